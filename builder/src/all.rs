@@ -11,6 +11,8 @@ use pldm_fw_pkg::{
     },
     FirmwareManifest,
 };
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha384};
 use std::{
     io::{Read, Write},
     path::{Path, PathBuf},
@@ -23,11 +25,25 @@ use zip::{
 
 use crate::CaliptraBuilder;
 use crate::PROJECT_ROOT;
-use crate::TARGET;
 use crate::{firmware, ImageCfg};
 
 use std::{env::var, sync::OnceLock};
 
+/// Entry of the build manifest describing one artifact.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BuildManifestEntry {
+    pub name: String,
+    pub size: usize,
+    /// Hex-encoded SHA-384 of the artifact.
+    pub sha384: String,
+}
+
+/// Record of the artifacts in a firmware bundle, written as JSON for provenance tracking.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BuildManifest {
+    pub artifacts: Vec<BuildManifestEntry>,
+}
+
 #[derive(Default)]
 pub struct FirmwareBinaries {
     pub caliptra_rom: Vec<u8>,
@@ -105,6 +121,53 @@ impl FirmwareBinaries {
         Ok(binaries)
     }
 
+    /// Returns the name and contents of every non-empty artifact in the bundle.
+    fn artifacts(&self) -> Vec<(&str, &[u8])> {
+        let mut artifacts: Vec<(&str, &[u8])> = [
+            (Self::CALIPTRA_ROM_NAME, &self.caliptra_rom),
+            (Self::CALIPTRA_FW_NAME, &self.caliptra_fw),
+            (Self::MCU_ROM_NAME, &self.mcu_rom),
+            (Self::MCU_RUNTIME_NAME, &self.mcu_runtime),
+            (Self::SOC_MANIFEST_NAME, &self.soc_manifest),
+        ]
+        .into_iter()
+        .map(|(name, data)| (name, data.as_slice()))
+        .collect();
+        for (name, data) in self
+            .test_roms
+            .iter()
+            .chain(self.caliptra_test_roms.iter())
+            .chain(self.test_soc_manifests.iter())
+            .chain(self.test_runtimes.iter())
+        {
+            artifacts.push((name.as_str(), data.as_slice()));
+        }
+        artifacts.retain(|(_, data)| !data.is_empty());
+        artifacts
+    }
+
+    /// Builds the manifest listing the size and SHA-384 of every artifact in the bundle.
+    pub fn manifest(&self) -> BuildManifest {
+        BuildManifest {
+            artifacts: self
+                .artifacts()
+                .into_iter()
+                .map(|(name, data)| BuildManifestEntry {
+                    name: name.to_string(),
+                    size: data.len(),
+                    sha384: hex::encode(Sha384::digest(data)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes the build manifest as JSON to `path`.
+    pub fn write_manifest(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, &self.manifest())?;
+        Ok(())
+    }
+
     pub fn vendor_pk_hash(&self) -> Option<[u8; 48]> {
         if let Ok((manifest, _)) = ImageManifest::ref_from_prefix(&self.caliptra_fw) {
             CaliptraBuilder::vendor_pk_hash(manifest).ok()
@@ -179,9 +242,14 @@ pub struct AllBuildArgs<'a> {
     pub soc_images: Option<Vec<ImageCfg>>,
     pub mcu_cfg: Option<ImageCfg>,
     pub pldm_manifest: Option<&'a str>,
+    /// Path to write a JSON manifest of the bundled artifacts to, if any.
+    pub manifest: Option<&'a str>,
 }
 
 /// Build Caliptra ROM and firmware bundle, MCU ROM and runtime, and SoC manifest, and package them all together in a ZIP file.
+///
+/// The ROMs are built concurrently with the runtime. Set `MCU_BUILD_SEQUENTIAL=1` to build them
+/// one after the other.
 pub fn all_build(args: AllBuildArgs) -> Result<()> {
     let AllBuildArgs {
         output,
@@ -195,45 +263,18 @@ pub fn all_build(args: AllBuildArgs) -> Result<()> {
         soc_images,
         mcu_cfg,
         pldm_manifest,
+        manifest,
     } = args;
 
     // TODO: use temp files
     let platform = platform.unwrap_or("emulator");
     let rom_features = rom_features.unwrap_or_default();
-    let mcu_rom = crate::rom_build(Some(platform), rom_features)?;
     let memory_map = match platform {
         "emulator" => &mcu_config_emulator::EMULATOR_MEMORY_MAP,
         "fpga" => &mcu_config_fpga::FPGA_MEMORY_MAP,
         _ => bail!("Unknown platform: {:?}", platform),
     };
 
-    let mut used_filenames = std::collections::HashSet::new();
-    let mut test_roms = vec![];
-    for fwid in firmware::REGISTERED_FW {
-        let bin_path = PathBuf::from(crate::test_rom_build(Some(platform), fwid)?);
-        let filename = bin_path.file_name().unwrap().to_str().unwrap().to_string();
-        if !used_filenames.insert(filename.clone()) {
-            panic!("Multiple fwids with filename {filename}")
-        }
-
-        test_roms.push((bin_path, filename));
-    }
-
-    for fwid in firmware::CPTRA_REGISTERED_FW {
-        let filename = format!("cptra-test-rom-{}-{}.bin", fwid.crate_name, fwid.bin_name);
-        if !used_filenames.insert(filename.clone()) {
-            panic!("Multiple fwids with filename {filename}")
-        }
-        let bin_path = PROJECT_ROOT
-            .join("target")
-            .join(TARGET)
-            .join("release")
-            .join(&filename);
-        let rom_bytes = caliptra_builder::build_firmware_rom(fwid)?;
-        std::fs::write(&bin_path, rom_bytes)?;
-        test_roms.push((bin_path, filename));
-    }
-
     if separate_runtimes && (runtime_features.is_none() || runtime_features.unwrap().is_empty()) {
         bail!("Must specify runtime features when building separate runtimes");
     }
@@ -256,18 +297,49 @@ pub fn all_build(args: AllBuildArgs) -> Result<()> {
     let base_runtime_file = tempfile::NamedTempFile::new().unwrap();
     let base_runtime_path = base_runtime_file.path().to_str().unwrap();
 
-    let mcu_runtime = &crate::runtime_build_with_apps_cached(
-        &base_runtime_features,
-        Some(base_runtime_path),
-        false,
-        Some(platform),
-        Some(memory_map),
-        use_dccm_for_stack,
-        dccm_offset,
-        dccm_size,
-        None,
-        None,
-    )?;
+    let build_base_runtime = || {
+        crate::runtime_build_with_apps_cached(
+            &base_runtime_features,
+            Some(base_runtime_path),
+            false,
+            Some(platform),
+            Some(memory_map),
+            use_dccm_for_stack,
+            dccm_offset,
+            dccm_size,
+            None,
+            None,
+        )
+    };
+
+    // The ROMs are built in their own target directory so that, when built concurrently with
+    // the runtime, the two cargo invocations don't serialize on the target directory lock. The
+    // same directory is used for sequential builds so the output paths don't depend on the mode.
+    let rom_target_dir = PROJECT_ROOT.join("target").join(ROM_TARGET_SUBDIR);
+
+    // The ROMs and the runtime don't depend on each other, so build them concurrently unless
+    // a sequential build is requested.
+    let ((mcu_rom, test_roms), mcu_runtime) = if sequential_build() {
+        let roms = build_roms(platform, rom_features, Some(rom_target_dir.as_path()))?;
+        (roms, build_base_runtime()?)
+    } else {
+        std::thread::scope(|s| {
+            let roms =
+                s.spawn(|| build_roms(platform, rom_features, Some(rom_target_dir.as_path())));
+            let runtime = build_base_runtime();
+            let roms = roms
+                .join()
+                .map_err(|_| anyhow::anyhow!("ROM build thread panicked"))?;
+            match (roms, runtime) {
+                (Ok(roms), Ok(runtime)) => Ok((roms, runtime)),
+                (Err(rom_err), Err(runtime_err)) => {
+                    bail!("ROM build failed: {rom_err:#}; runtime build failed: {runtime_err:#}")
+                }
+                (Err(err), _) | (_, Err(err)) => Err(err),
+            }
+        })?
+    };
+    let mcu_runtime = &mcu_runtime;
 
     let fpga = platform == "fpga";
     let mut caliptra_builder = crate::CaliptraBuilder::new(
@@ -507,9 +579,65 @@ pub fn all_build(args: AllBuildArgs) -> Result<()> {
 
     zip.finish()?;
 
+    if let Some(manifest) = manifest {
+        println!("Writing build manifest: {}", manifest);
+        FirmwareBinaries::read_from_zip(&path.to_path_buf())?
+            .write_manifest(Path::new(manifest))?;
+    }
+
     Ok(())
 }
 
+/// Environment variable that disables building the ROMs and runtime concurrently.
+const SEQUENTIAL_BUILD_ENV: &str = "MCU_BUILD_SEQUENTIAL";
+
+/// Subdirectory of the target directory used for the ROM builds of `all_build`.
+const ROM_TARGET_SUBDIR: &str = "all-build-rom";
+
+fn sequential_build() -> bool {
+    var(SEQUENTIAL_BUILD_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Builds the MCU ROM, the MCU test ROMs and the Caliptra test ROMs.
+///
+/// Returns the path of the MCU ROM and the path and bundle file name of every test ROM.
+fn build_roms(
+    platform: &str,
+    rom_features: &str,
+    target_dir: Option<&Path>,
+) -> Result<(String, Vec<(PathBuf, String)>)> {
+    let mcu_rom = crate::rom::rom_build_in(Some(platform), rom_features, target_dir)?;
+
+    let mut used_filenames = std::collections::HashSet::new();
+    let mut test_roms = vec![];
+    for fwid in firmware::REGISTERED_FW {
+        let bin_path = PathBuf::from(crate::rom::test_rom_build_in(
+            Some(platform),
+            fwid,
+            target_dir,
+        )?);
+        let filename = bin_path.file_name().unwrap().to_str().unwrap().to_string();
+        if !used_filenames.insert(filename.clone()) {
+            bail!("Multiple fwids with filename {filename}")
+        }
+
+        test_roms.push((bin_path, filename));
+    }
+
+    for fwid in firmware::CPTRA_REGISTERED_FW {
+        let filename = format!("cptra-test-rom-{}-{}.bin", fwid.crate_name, fwid.bin_name);
+        if !used_filenames.insert(filename.clone()) {
+            bail!("Multiple fwids with filename {filename}")
+        }
+        let bin_path = crate::rom::rom_release_dir(target_dir).join(&filename);
+        let rom_bytes = caliptra_builder::build_firmware_rom(fwid)?;
+        std::fs::write(&bin_path, rom_bytes)?;
+        test_roms.push((bin_path, filename));
+    }
+
+    Ok((mcu_rom, test_roms))
+}
+
 fn add_to_zip(
     input_file: &PathBuf,
     name: &str,
@@ -605,3 +733,51 @@ fn get_device_uuid() -> [u8; 16] {
         0x10,
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_manifest() {
+        let binaries = FirmwareBinaries {
+            caliptra_rom: b"caliptra rom".to_vec(),
+            mcu_rom: b"mcu rom".to_vec(),
+            test_runtimes: vec![(
+                "mcu-test-runtime-test-feature.bin".to_string(),
+                b"test runtime".to_vec(),
+            )],
+            ..Default::default()
+        };
+
+        let manifest_file = tempfile::NamedTempFile::new().unwrap();
+        binaries.write_manifest(manifest_file.path()).unwrap();
+
+        let data = std::fs::read_to_string(manifest_file.path()).unwrap();
+        let manifest: BuildManifest = serde_json::from_str(&data).unwrap();
+        assert_eq!(
+            manifest.artifacts,
+            vec![
+                BuildManifestEntry {
+                    name: FirmwareBinaries::CALIPTRA_ROM_NAME.to_string(),
+                    size: 12,
+                    sha384: hex::encode(Sha384::digest(b"caliptra rom")),
+                },
+                BuildManifestEntry {
+                    name: FirmwareBinaries::MCU_ROM_NAME.to_string(),
+                    size: 7,
+                    sha384: hex::encode(Sha384::digest(b"mcu rom")),
+                },
+                BuildManifestEntry {
+                    name: "mcu-test-runtime-test-feature.bin".to_string(),
+                    size: 12,
+                    sha384: hex::encode(Sha384::digest(b"test runtime")),
+                },
+            ]
+        );
+        assert!(manifest
+            .artifacts
+            .iter()
+            .all(|entry| entry.sha384.len() == 96));
+    }
+}
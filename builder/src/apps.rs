@@ -3,11 +3,11 @@
 use crate::runtime::bit_flags;
 use crate::tbf::TbfHeader;
 use crate::{objcopy, target_binary, OBJCOPY_FLAGS};
-use crate::{PROJECT_ROOT, TARGET};
+use crate::{target_triple, PROJECT_ROOT};
 use anyhow::{bail, Result};
 use std::process::Command;
 
-pub const EMULATOR_APPS: &[App] = &[
+pub const EMULATOR_APPS: &[App<'static>] = &[
     App {
         // Make sure this is the first app in the list
         name: "example-app",
@@ -21,7 +21,7 @@ pub const EMULATOR_APPS: &[App] = &[
     },
 ];
 
-pub const FPGA_APPS: &[App] = &[
+pub const FPGA_APPS: &[App<'static>] = &[
     App {
         // Make sure this is the first app in the list
         name: "example-app",
@@ -35,8 +35,11 @@ pub const FPGA_APPS: &[App] = &[
     },
 ];
 
-pub struct App {
-    pub name: &'static str,
+// Minimum RAM given to apps that are not listed in the platform's app table.
+pub const DEFAULT_APP_MINIMUM_RAM: u32 = 48 * 1024;
+
+pub struct App<'a> {
+    pub name: &'a str,
     pub permissions: Vec<(u32, u32)>, // pairs of (driver, command). All console and alarm commands are allowed by default.
     pub minimum_ram: u32,
 }
@@ -59,29 +62,51 @@ pub const BASE_PERMISSIONS: &[(u32, u32)] = &[
     (8, 3), // Low-level debug
 ];
 
-// Generates a single flat binary containing the apps built with their TBF headers.
+fn platform_apps(platform: &str) -> Result<&'static [App<'static>]> {
+    match platform {
+        "emulator" => Ok(EMULATOR_APPS),
+        "fpga" => Ok(FPGA_APPS),
+        _ => bail!("Unknown platform: {}", platform),
+    }
+}
+
+// Returns the names of the apps bundled by default for the platform.
 // If `example_app` is true, only the example app is included; otherwise, all other apps are included.
+pub fn default_app_names(platform: &str, example_app: bool) -> Result<Vec<&'static str>> {
+    Ok(platform_apps(platform)?
+        .iter()
+        .map(|app| app.name)
+        .filter(|name| (*name == "example-app") == example_app)
+        .collect())
+}
+
+// Generates a single flat binary containing the listed apps built with their TBF headers, in order.
+// Apps in the platform's app table use its RAM size and permissions; other app crates get
+// `DEFAULT_APP_MINIMUM_RAM` and the base permissions.
 pub fn apps_build_flat_tbf(
     platform: &str,
     start: usize,
     ram_start: usize,
     features: &[&str],
-    example_app: bool,
+    app_names: &[&str],
 ) -> Result<Vec<u8>> {
     let mut bin = vec![];
     let mut offset = start;
     let mut ram_start = ram_start;
-    let apps = match platform {
-        "emulator" => EMULATOR_APPS,
-        "fpga" => FPGA_APPS,
-        _ => {
-            bail!("Unknown platform: {}", platform);
-        }
-    };
-    for app in apps.iter() {
-        if app.name == "example-app" && !example_app {
-            continue;
-        }
+    let platform_apps = platform_apps(platform)?;
+    for name in app_names {
+        let custom_app;
+        let app = match platform_apps.iter().find(|app| app.name == *name) {
+            Some(app) => app,
+            None => {
+                custom_app = App {
+                    name: *name,
+                    permissions: vec![],
+                    minimum_ram: DEFAULT_APP_MINIMUM_RAM,
+                };
+                &custom_app
+            }
+        };
         println!("Building TBF for app {}", app.name);
         let app_bin = app_build_tbf(
             app,
@@ -94,13 +119,6 @@ pub fn apps_build_flat_tbf(
         bin.extend_from_slice(&app_bin);
         offset += app_bin.len();
         ram_start += app.minimum_ram as usize;
-
-        if app.name == "example-app" && example_app {
-            // example app is always the first app
-            // and we do not want to build any more apps after it
-            // so we can just break out of the loop
-            break;
-        }
     }
     // align to 4-byte boundary for PMP
     while bin.len() % 4 != 0 {
@@ -234,7 +252,7 @@ INCLUDE platforms/emulator/runtime/userspace/apps/app_layout.ld",
             "--features",
             &features_str,
             "--target",
-            TARGET,
+            target_triple(),
             "--",
         ])
         .args(bit_flags(platform).split(' '))
@@ -249,3 +267,27 @@ INCLUDE platforms/emulator/runtime/userspace/apps/app_layout.ld",
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_app_names() {
+        assert_eq!(
+            default_app_names("emulator", true).unwrap(),
+            vec!["example-app"]
+        );
+        assert_eq!(
+            default_app_names("emulator", false).unwrap(),
+            vec!["user-app"]
+        );
+        assert!(default_app_names("unknown", false).is_err());
+    }
+
+    #[test]
+    fn test_apps_build_flat_tbf_empty_list() {
+        let bin = apps_build_flat_tbf("emulator", 0x4000_0000, 0x5000_0000, &[], &[]).unwrap();
+        assert!(bin.is_empty());
+    }
+}
@@ -28,6 +28,7 @@ pub struct CaliptraBuilder {
     caliptra_rom: Option<PathBuf>,
     caliptra_firmware: Option<PathBuf>,
     soc_manifest: Option<PathBuf>,
+    soc_manifest_path: Option<PathBuf>,
     vendor_pk_hash: Option<String>,
     mcu_firmware: Option<PathBuf>,
     soc_images: Option<Vec<ImageCfg>>,
@@ -35,6 +36,7 @@ pub struct CaliptraBuilder {
     soc_manifest_svn: Option<u32>,
     vendor: String,
     model: String,
+    pqc_key_type: FwVerificationPqcKeyType,
 }
 
 impl CaliptraBuilder {
@@ -57,6 +59,7 @@ impl CaliptraBuilder {
             caliptra_rom,
             caliptra_firmware,
             soc_manifest,
+            soc_manifest_path: None,
             vendor_pk_hash,
             mcu_firmware,
             soc_images,
@@ -64,9 +67,29 @@ impl CaliptraBuilder {
             soc_manifest_svn,
             vendor: vendor.unwrap_or_else(|| "ChipsAlliance".to_string()),
             model: model.unwrap_or_else(|| "Caliptra-SS".to_string()),
+            pqc_key_type: FwVerificationPqcKeyType::LMS,
         }
     }
 
+    /// Sign the Caliptra firmware bundle and SoC manifest with `pqc_key_type` instead of LMS.
+    ///
+    /// The device must be provisioned with the same PQC key type (`--vendor-pqc-type`).
+    pub fn with_pqc_key_type(mut self, pqc_key_type: FwVerificationPqcKeyType) -> Self {
+        self.pqc_key_type = pqc_key_type;
+        self
+    }
+
+    /// Write generated SoC manifests to `path` instead of `target/soc-manifest`, unless
+    /// a name is passed to [`Self::get_soc_manifest`].
+    pub fn with_soc_manifest_path(mut self, path: PathBuf) -> Self {
+        self.soc_manifest_path = Some(path);
+        self
+    }
+
+    pub fn pqc_key_type(&self) -> FwVerificationPqcKeyType {
+        self.pqc_key_type
+    }
+
     pub fn get_caliptra_rom(&self) -> Result<PathBuf> {
         if let Some(caliptra_rom) = &self.caliptra_rom {
             if !caliptra_rom.exists() {
@@ -87,7 +110,8 @@ impl CaliptraBuilder {
                 bail!("Vendor public key hash is required for active mode if Caliptra FW is passed as an argument");
             }
         } else {
-            let (path, vendor_pk_hash) = Self::compile_caliptra_fw_cached(self.fpga)?;
+            let (path, vendor_pk_hash) =
+                Self::compile_caliptra_fw_cached(self.fpga, self.pqc_key_type)?;
             self.vendor_pk_hash = Some(vendor_pk_hash);
             self.caliptra_firmware = Some(path);
         }
@@ -126,7 +150,9 @@ impl CaliptraBuilder {
             let path = Self::write_soc_manifest(
                 metadata.clone(),
                 self.soc_manifest_svn.unwrap_or(0),
-                name,
+                self.pqc_key_type,
+                name.map(PathBuf::from)
+                    .or_else(|| self.soc_manifest_path.clone()),
             )?;
             self.write_fw_components_config(&metadata)?;
             self.soc_manifest = Some(path);
@@ -226,13 +252,12 @@ impl CaliptraBuilder {
     fn write_soc_manifest(
         metadata: Vec<AuthManifestImageMetadata>,
         svn: u32,
-        name: Option<&str>,
+        pqc_key_type: FwVerificationPqcKeyType,
+        path: Option<PathBuf>,
     ) -> Result<PathBuf> {
-        let manifest = Self::create_auth_manifest_with_metadata(metadata, svn);
+        let manifest = Self::create_auth_manifest(metadata, svn, pqc_key_type);
 
-        let path = name
-            .map(PathBuf::from)
-            .unwrap_or(target_dir().join("soc-manifest"));
+        let path = path.unwrap_or_else(|| target_dir().join("soc-manifest"));
         std::fs::write(&path, manifest.as_bytes())?;
         Ok(path)
     }
@@ -287,7 +312,10 @@ impl CaliptraBuilder {
         let generated_dir = target_dir().join("generated");
         std::fs::create_dir_all(&generated_dir)?;
         let relay_path = generated_dir.join("soc_env_config.rs");
-        std::fs::write(&relay_path, &consts_file)?;
+        // Replace the file atomically so a concurrent build never reads a partial file.
+        let mut relay_file = tempfile::NamedTempFile::new_in(&generated_dir)?;
+        std::io::Write::write_all(&mut relay_file, consts_file.as_bytes())?;
+        relay_file.persist(&relay_path)?;
         println!(
             "Generated FW components config relay file at: {}",
             relay_path.display()
@@ -359,11 +387,20 @@ impl CaliptraBuilder {
         Ok(path)
     }
 
-    fn compile_caliptra_fw_cached(fpga: bool) -> Result<(PathBuf, String)> {
+    fn compile_caliptra_fw_cached(
+        fpga: bool,
+        pqc_key_type: FwVerificationPqcKeyType,
+    ) -> Result<(PathBuf, String)> {
         let platform = if fpga { "fpga" } else { "emulator" };
+        let pqc = match pqc_key_type {
+            FwVerificationPqcKeyType::LMS => "lms",
+            FwVerificationPqcKeyType::MLDSA => "mldsa",
+        };
         if let Some(version) = Self::caliptra_version() {
-            let path =
-                target_dir().join(format!("caliptra-fw-bundle-{}-{}.bin", version, platform));
+            let path = target_dir().join(format!(
+                "caliptra-fw-bundle-{}-{}-{}.bin",
+                version, platform, pqc
+            ));
             if path.exists() {
                 println!("Using cached Caliptra FW bundle at {:?}", path);
                 return Self::parse_fw_bundle(path);
@@ -372,12 +409,12 @@ impl CaliptraBuilder {
                 "Caliptra FW bundle version {} not found in cache, compiling...",
                 version
             );
-            let compiled_fw_bundle = Self::compile_caliptra_fw_uncached(fpga)?.0;
+            let compiled_fw_bundle = Self::compile_caliptra_fw_uncached(fpga, pqc_key_type)?.0;
             std::fs::copy(compiled_fw_bundle, &path)?;
             Self::parse_fw_bundle(path)
         } else {
             println!("Caliptra version not found so cannot use cached FW bundle");
-            Self::compile_caliptra_fw_uncached(fpga)
+            Self::compile_caliptra_fw_uncached(fpga, pqc_key_type)
         }
     }
 
@@ -403,9 +440,12 @@ impl CaliptraBuilder {
         Ok(Self::vendor_pk_hash(&manifest)?.encode_hex())
     }
 
-    fn compile_caliptra_fw_uncached(fpga: bool) -> Result<(PathBuf, String)> {
+    fn compile_caliptra_fw_uncached(
+        fpga: bool,
+        pqc_key_type: FwVerificationPqcKeyType,
+    ) -> Result<(PathBuf, String)> {
         let opts = caliptra_builder::ImageOptions {
-            pqc_key_type: FwVerificationPqcKeyType::LMS,
+            pqc_key_type,
             ..Default::default()
         };
 
@@ -431,6 +471,14 @@ impl CaliptraBuilder {
     pub fn create_auth_manifest_with_metadata(
         image_metadata_list: Vec<AuthManifestImageMetadata>,
         svn: u32,
+    ) -> AuthorizationManifest {
+        Self::create_auth_manifest(image_metadata_list, svn, FwVerificationPqcKeyType::LMS)
+    }
+
+    fn create_auth_manifest(
+        image_metadata_list: Vec<AuthManifestImageMetadata>,
+        svn: u32,
+        pqc_key_type: FwVerificationPqcKeyType,
     ) -> AuthorizationManifest {
         let vendor_fw_key_info: AuthManifestGeneratorKeyConfig = AuthManifestGeneratorKeyConfig {
             pub_keys: AuthManifestPubKeysConfig {
@@ -494,7 +542,7 @@ impl CaliptraBuilder {
             image_metadata_list,
             version: 1,
             flags: AuthManifestFlags::VENDOR_SIGNATURE_REQUIRED,
-            pqc_key_type: FwVerificationPqcKeyType::LMS,
+            pqc_key_type,
             svn,
         };
 
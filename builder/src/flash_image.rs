@@ -2,8 +2,9 @@
 
 use anyhow::{anyhow, bail, Result};
 use flash_image::{
-    FlashHeader, ImageHeader, CALIPTRA_FMC_RT_IDENTIFIER, FLASH_IMAGE_MAGIC_NUMBER, HEADER_VERSION,
-    MCU_RT_IDENTIFIER, SOC_IMAGES_BASE_IDENTIFIER, SOC_MANIFEST_IDENTIFIER,
+    ChecksumAlgo, FlashHeader, ImageHeader, CALIPTRA_FMC_RT_IDENTIFIER, FLASH_IMAGE_MAGIC_NUMBER,
+    HEADER_VERSION, LEGACY_HEADER_VERSION, MCU_RT_IDENTIFIER, SOC_IMAGES_BASE_IDENTIFIER,
+    SOC_MANIFEST_IDENTIFIER,
 };
 use mcu_config_emulator::flash::PartitionTable;
 use std::fs::{File, OpenOptions};
@@ -39,19 +40,22 @@ impl<'a> FirmwareImage<'a> {
 }
 
 impl<'a> FlashImage<'a> {
-    pub fn new(images: &'a [FirmwareImage<'a>], image_info: &'a [ImageHeader]) -> Self {
+    pub fn new(
+        images: &'a [FirmwareImage<'a>],
+        image_info: &'a [ImageHeader],
+        checksum_algo: ChecksumAlgo,
+    ) -> Self {
         let mut header = FlashHeader {
             magic: FLASH_IMAGE_MAGIC_NUMBER.into(),
             version: HEADER_VERSION,
             image_count: image_info.len() as u16,
             image_headers_offset: core::mem::size_of::<FlashHeader>() as u32,
+            checksum_algo: checksum_algo as u16,
+            reserved: 0,
             header_checksum: 0,
         };
 
-        let header_checksum = calculate_checksum(
-            header.as_bytes()[..offset_of!(FlashHeader, header_checksum)].as_ref(),
-        );
-        header.header_checksum = header_checksum;
+        header.recompute_checksum();
         let payload = FlashImagePayload::new(image_info, images);
 
         Self { header, payload }
@@ -89,20 +93,20 @@ impl<'a> FlashImage<'a> {
         if image.len() < HEADER_SIZE {
             bail!("Image too small to contain the header.");
         }
-        let header = FlashHeader::read_from_bytes(&image[..HEADER_SIZE])
-            .map_err(|_| anyhow!("Failed to parse header: invalid format or size"))?;
+        let header = FlashHeader::parse(image)
+            .ok_or_else(|| anyhow!("Failed to parse header: invalid format or size"))?;
         if header.magic != FLASH_IMAGE_MAGIC_NUMBER {
             bail!("Invalid header: incorrect magic number or header version.");
         }
 
-        if header.version != HEADER_VERSION {
+        if header.version != HEADER_VERSION && header.version != LEGACY_HEADER_VERSION {
             bail!("Unsupported header version");
         }
-        // Parse and verify checksums
-        let calculated_header_checksum = calculate_checksum(
-            header.as_bytes()[..offset_of!(FlashHeader, header_checksum)].as_ref(),
-        );
-        if calculated_header_checksum != header.header_checksum {
+        let checksum_algo = header
+            .checksum_algo()
+            .ok_or_else(|| anyhow!("Unsupported checksum algorithm {}", header.checksum_algo))?;
+        // Parse and verify checksums; legacy headers are checked against their own layout
+        if !header.verify() {
             bail!("Header checksum mismatch.");
         }
 
@@ -111,16 +115,15 @@ impl<'a> FlashImage<'a> {
             let offset = header.image_headers_offset as usize + (IMAGE_INFO_SIZE * i);
             let info = ImageHeader::read_from_bytes(&image[offset..offset + IMAGE_INFO_SIZE])
                 .map_err(|_| anyhow!("Failed to read image info"))?;
-            let image_checksum = calculate_checksum(
-                &image[info.offset as usize..info.offset as usize + info.size as usize],
-            );
+            let image_checksum = checksum_algo
+                .checksum(&image[info.offset as usize..info.offset as usize + info.size as usize]);
             if image_checksum != info.image_checksum {
                 bail!(
                     "Image checksum mismatch for image with identifier: {}",
                     info.identifier
                 );
             }
-            let header_checksum = calculate_checksum(
+            let header_checksum = checksum_algo.checksum(
                 info.as_bytes()[..offset_of!(ImageHeader, image_header_checksum)].as_ref(),
             );
             if header_checksum != info.image_header_checksum {
@@ -137,13 +140,6 @@ impl<'a> FlashImage<'a> {
     }
 }
 
-pub fn calculate_checksum(data: &[u8]) -> u32 {
-    let sum = data
-        .iter()
-        .fold(0u32, |acc, &byte| acc.wrapping_add(byte as u32));
-    0u32.wrapping_sub(sum)
-}
-
 impl<'a> FlashImagePayload<'a> {
     pub fn new(image_info: &'a [ImageHeader], images: &'a [FirmwareImage<'a>]) -> Self {
         Self { image_info, images }
@@ -211,15 +207,18 @@ pub fn flash_image_create(
         soc_image_identifer += 1;
     }
 
-    let image_info = generate_image_info(images.clone());
+    let image_info = generate_image_info(images.clone(), ChecksumAlgo::Crc32);
 
-    let flash_image = FlashImage::new(&images, &image_info);
+    let flash_image = FlashImage::new(&images, &image_info, ChecksumAlgo::Crc32);
     flash_image.write_to_file(offset, output_path)?;
 
     Ok(())
 }
 
-pub fn generate_image_info(images: Vec<FirmwareImage>) -> Vec<ImageHeader> {
+pub fn generate_image_info(
+    images: Vec<FirmwareImage>,
+    checksum_algo: ChecksumAlgo,
+) -> Vec<ImageHeader> {
     let mut info = Vec::new();
     let mut offset = std::mem::size_of::<FlashHeader>() as u32
         + (std::mem::size_of::<ImageHeader>() * images.len()) as u32;
@@ -228,12 +227,10 @@ pub fn generate_image_info(images: Vec<FirmwareImage>) -> Vec<ImageHeader> {
             identifier: image.identifier,
             offset,
             size: image.data.len() as u32,
-            image_checksum: calculate_checksum(image.data),
+            image_checksum: checksum_algo.checksum(image.data),
             image_header_checksum: 0,
         };
-        header.image_header_checksum = calculate_checksum(
-            header.as_bytes()[..offset_of!(ImageHeader, image_header_checksum)].as_ref(),
-        );
+        header.recompute_checksum(checksum_algo);
         info.push(header);
         offset += image.data.len() as u32;
     }
@@ -259,6 +256,81 @@ pub fn flash_image_verify(image_file_path: &str, offset: u32) -> Result<()> {
     FlashImage::verify_flash_image(&data[offset as usize..])
 }
 
+/// Replace the data of the image with `identifier` in the flash image at `path` and write
+/// the result to `out`. Only that image's header and the flash header are updated, so the
+/// new data (padded to a multiple of 4 bytes) must fit in the image's slot, which extends
+/// to the start of the next image (or the end of the file).
+pub fn flash_image_replace_component(
+    path: &str,
+    identifier: u32,
+    new_data: &[u8],
+    out: &str,
+) -> Result<()> {
+    let mut image =
+        std::fs::read(path).map_err(|e| anyhow!(format!("Cannot read file '{}': {}", path, e)))?;
+    if image.len() < HEADER_SIZE {
+        bail!("Image too small to contain the header.");
+    }
+    let mut header = FlashHeader::read_from_bytes(&image[..HEADER_SIZE])
+        .map_err(|_| anyhow!("Failed to parse header: invalid format or size"))?;
+    if header.magic != FLASH_IMAGE_MAGIC_NUMBER {
+        bail!("Invalid header: incorrect magic number or header version.");
+    }
+    let checksum_algo = header
+        .checksum_algo()
+        .ok_or_else(|| anyhow!("Unsupported checksum algorithm {}", header.checksum_algo))?;
+
+    let mut data = new_data.to_vec();
+    data.resize(data.len().next_multiple_of(4), 0);
+
+    let infos: Vec<(usize, ImageHeader)> = (0..header.image_count as usize)
+        .map(|i| header.image_headers_offset as usize + (IMAGE_INFO_SIZE * i))
+        .filter_map(|offset| {
+            let info =
+                ImageHeader::read_from_bytes(image.get(offset..offset + IMAGE_INFO_SIZE)?).ok()?;
+            Some((offset, info))
+        })
+        .collect();
+    let (info_offset, mut info) = infos
+        .iter()
+        .find(|(_, info)| info.identifier == identifier)
+        .copied()
+        .ok_or_else(|| anyhow!("No image with identifier {:#x}", identifier))?;
+
+    // The slot capacity is independent of the current payload length, so an image shrunk
+    // by an earlier replacement can grow back into its original space.
+    let slot_start = info.offset as usize;
+    let slot_end = infos
+        .iter()
+        .map(|(_, other)| other.offset as usize)
+        .filter(|&offset| offset > slot_start)
+        .min()
+        .unwrap_or(image.len());
+    if data.len() > slot_end.saturating_sub(slot_start) {
+        bail!(
+            "New data for image {:#x} is {} bytes but only {} bytes are available",
+            identifier,
+            data.len(),
+            slot_end.saturating_sub(slot_start)
+        );
+    }
+    let slot = image
+        .get_mut(slot_start..slot_end)
+        .ok_or_else(|| anyhow!("Image {:#x} extends past the end of the file", identifier))?;
+    slot.fill(0);
+    slot[..data.len()].copy_from_slice(&data);
+
+    info.size = data.len() as u32;
+    info.image_checksum = checksum_algo.checksum(&data);
+    info.recompute_checksum(checksum_algo);
+    image[info_offset..info_offset + IMAGE_INFO_SIZE].copy_from_slice(info.as_bytes());
+
+    header.recompute_checksum();
+    image[..HEADER_SIZE].copy_from_slice(header.as_bytes());
+
+    std::fs::write(out, &image).map_err(|e| anyhow!(format!("Cannot write file '{}': {}", out, e)))
+}
+
 pub fn write_partition_table(
     partition_table: &PartitionTable,
     offset: usize,
@@ -356,10 +428,11 @@ mod tests {
         assert_eq!(header.magic, FLASH_IMAGE_MAGIC_NUMBER);
         assert_eq!(header.version, HEADER_VERSION);
         assert_eq!(header.image_count, 5); // 3 main images + 2 SoC images
+        assert_eq!(header.checksum_algo(), Some(ChecksumAlgo::Crc32));
 
         // Verify checksums
         let calculated_header_checksum =
-            calculate_checksum(&data[0..offset_of!(FlashHeader, header_checksum)]);
+            ChecksumAlgo::Crc32.checksum(&data[0..offset_of!(FlashHeader, header_checksum)]);
         assert_eq!(header.header_checksum, calculated_header_checksum);
 
         let expected_images: Vec<(u32, &[u8])> = vec![
@@ -434,24 +507,140 @@ mod tests {
                 data: b"Soc Image 2 Data - POIUYTREWQ",
             },
         ];
-        // Create a flash image from the mutable slice
-        let image_info = generate_image_info(expected_images.to_vec());
-        let flash_image = FlashImage::new(&expected_images, &image_info);
-        flash_image
-            .write_to_file(0, image_path)
-            .expect("Failed to write flash image");
-
-        // Verify the firmware image
-        let result = flash_image_verify(image_path, 0);
-        result.unwrap_or_else(|e| {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        });
+        // Create a flash image from the mutable slice, once per checksum algorithm
+        for checksum_algo in [ChecksumAlgo::Crc32, ChecksumAlgo::Sum] {
+            let image_info = generate_image_info(expected_images.to_vec(), checksum_algo);
+            let flash_image = FlashImage::new(&expected_images, &image_info, checksum_algo);
+            flash_image
+                .write_to_file(0, image_path)
+                .expect("Failed to write flash image");
+
+            // Verify the firmware image
+            let result = flash_image_verify(image_path, 0);
+            result.unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+        }
 
         // Cleanup
         fs::remove_file(image_path).expect("Failed to clean up test file");
     }
 
+    #[test]
+    fn test_flash_image_verify_legacy_header() {
+        use flash_image::LegacyFlashHeader;
+
+        // A v1 image: 16-byte header, one image header, then the image, all summed
+        let data = b"MCU Runtime Data - QWERTYUI\0";
+        let header_size = std::mem::size_of::<LegacyFlashHeader>();
+        let mut info = ImageHeader {
+            identifier: MCU_RT_IDENTIFIER,
+            offset: (header_size + IMAGE_INFO_SIZE) as u32,
+            size: data.len() as u32,
+            image_checksum: ChecksumAlgo::Sum.checksum(data),
+            image_header_checksum: 0,
+        };
+        info.recompute_checksum(ChecksumAlgo::Sum);
+        let mut header = LegacyFlashHeader {
+            magic: FLASH_IMAGE_MAGIC_NUMBER.into(),
+            version: LEGACY_HEADER_VERSION,
+            image_count: 1,
+            image_headers_offset: header_size as u32,
+            header_checksum: 0,
+        };
+        header.header_checksum = ChecksumAlgo::Sum
+            .checksum(&header.as_bytes()[..offset_of!(LegacyFlashHeader, header_checksum)]);
+
+        let mut image = header.as_bytes().to_vec();
+        image.extend_from_slice(info.as_bytes());
+        image.extend_from_slice(data);
+        FlashImage::verify_flash_image(&image).unwrap();
+
+        image[header_size + IMAGE_INFO_SIZE] ^= 1;
+        assert!(FlashImage::verify_flash_image(&image).is_err());
+    }
+
+    #[test]
+    fn test_flash_image_replace_component() {
+        let images = [
+            FirmwareImage {
+                identifier: CALIPTRA_FMC_RT_IDENTIFIER,
+                data: b"Caliptra Firmware Data - ABCDEFGH",
+            },
+            FirmwareImage {
+                identifier: MCU_RT_IDENTIFIER,
+                data: b"MCU Runtime Data - QWERTYUI",
+            },
+            FirmwareImage {
+                identifier: SOC_IMAGES_BASE_IDENTIFIER,
+                data: b"Soc Image 1 Data - ZXCVBNMLKJ",
+            },
+        ];
+        let input_file = NamedTempFile::new().expect("Failed to create temp file");
+        let input_path = input_file.path().to_str().unwrap();
+        let output_file = NamedTempFile::new().expect("Failed to create temp file");
+        let output_path = output_file.path().to_str().unwrap();
+
+        let image_info = generate_image_info(images.to_vec(), ChecksumAlgo::Crc32);
+        FlashImage::new(&images, &image_info, ChecksumAlgo::Crc32)
+            .write_to_file(0, input_path)
+            .expect("Failed to write flash image");
+
+        let new_runtime = b"New MCU Runtime";
+        flash_image_replace_component(input_path, MCU_RT_IDENTIFIER, new_runtime, output_path)
+            .expect("Failed to replace MCU runtime");
+        flash_image_verify(output_path, 0).expect("Updated image failed verification");
+
+        let data = fs::read(output_path).unwrap();
+        let offset = HEADER_SIZE + IMAGE_INFO_SIZE;
+        let info = ImageHeader::read_from_bytes(&data[offset..offset + IMAGE_INFO_SIZE]).unwrap();
+        assert_eq!(info.identifier, MCU_RT_IDENTIFIER);
+        assert_eq!(info.offset, image_info[1].offset);
+        assert_eq!(info.size as usize, new_runtime.len().next_multiple_of(4));
+        assert_eq!(
+            &data[info.offset as usize..info.offset as usize + new_runtime.len()],
+            new_runtime
+        );
+
+        // Other images are untouched
+        let soc_image = &images[2];
+        let soc_offset = image_info[2].offset as usize;
+        assert_eq!(
+            &data[soc_offset..soc_offset + soc_image.data.len()],
+            soc_image.data
+        );
+
+        // A later replacement can grow back into the whole slot
+        let regrown = [0x5a; 24];
+        flash_image_replace_component(output_path, MCU_RT_IDENTIFIER, &regrown, output_path)
+            .expect("Failed to grow MCU runtime back into its slot");
+        flash_image_verify(output_path, 0).expect("Regrown image failed verification");
+        let data = fs::read(output_path).unwrap();
+        let info = ImageHeader::read_from_bytes(&data[offset..offset + IMAGE_INFO_SIZE]).unwrap();
+        assert_eq!(info.size as usize, regrown.len());
+        assert_eq!(
+            &data[info.offset as usize..info.offset as usize + regrown.len()],
+            &regrown
+        );
+        assert_eq!(
+            &data[soc_offset..soc_offset + soc_image.data.len()],
+            soc_image.data
+        );
+
+        // Data that does not fit is rejected, as are unknown identifiers
+        assert!(flash_image_replace_component(
+            output_path,
+            MCU_RT_IDENTIFIER,
+            &[0xaa; 28],
+            output_path
+        )
+        .is_err());
+        assert!(
+            flash_image_replace_component(input_path, 0x1234, new_runtime, output_path).is_err()
+        );
+    }
+
     #[test]
     fn test_flash_image_verify_corrupted_case() {
         let image_path = PROJECT_ROOT
@@ -471,8 +660,8 @@ mod tests {
                 data: b"Valid SOC Manifest Data",
             },
         ];
-        let image_info = generate_image_info(images.to_vec());
-        let flash_image = FlashImage::new(&images, &image_info);
+        let image_info = generate_image_info(images.to_vec(), ChecksumAlgo::Crc32);
+        let flash_image = FlashImage::new(&images, &image_info, ChecksumAlgo::Crc32);
         flash_image
             .write_to_file(0, image_path)
             .expect("Failed to write flash image");
@@ -9,22 +9,57 @@ mod rom;
 mod runtime;
 mod tbf;
 
-pub use all::{all_build, AllBuildArgs, FirmwareBinaries};
+pub use all::{all_build, AllBuildArgs, BuildManifest, BuildManifestEntry, FirmwareBinaries};
 pub use caliptra::{CaliptraBuilder, ImageCfg};
 pub use rom::{rom_build, rom_ld_script, test_rom_build};
 pub use runtime::{
-    runtime_build_no_apps_uncached, runtime_build_with_apps_cached, runtime_ld_script,
+    runtime_build_no_apps_uncached, runtime_build_with_app_list, runtime_build_with_apps_cached,
+    runtime_ld_script,
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use std::{
     path::{Path, PathBuf},
     process::Command,
     sync::LazyLock,
+    time::UNIX_EPOCH,
 };
 
+/// Default RISC-V target triple for MCU firmware.
 pub const TARGET: &str = "riscv32imc-unknown-none-elf";
 
+/// Environment variable overriding the RISC-V target triple.
+pub const TARGET_ENV: &str = "MCU_TARGET";
+
+static TARGET_TRIPLE: LazyLock<String> = LazyLock::new(|| {
+    parse_target_triple(std::env::var(TARGET_ENV).ok()).unwrap_or_else(|e| panic!("{e}"))
+});
+
+/// Returns the RISC-V target triple MCU firmware is built for.
+///
+/// Defaults to [`TARGET`] and can be overridden with the `MCU_TARGET` environment variable, e.g.
+/// for cores with a different set of extensions. The supported triples are the bare-metal 32-bit
+/// RISC-V targets:
+/// - `riscv32imc-unknown-none-elf` (default)
+/// - `riscv32imac-unknown-none-elf`
+/// - `riscv32im-unknown-none-elf`
+/// - `riscv32i-unknown-none-elf`
+/// - `riscv32imafc-unknown-none-elf`
+///
+/// Panics if `MCU_TARGET` is not a 32-bit RISC-V triple, so that firmware is never accidentally
+/// built for the host.
+pub fn target_triple() -> &'static str {
+    &TARGET_TRIPLE
+}
+
+fn parse_target_triple(value: Option<String>) -> Result<String> {
+    match value {
+        None => Ok(TARGET.to_string()),
+        Some(triple) if triple.starts_with("riscv32") => Ok(triple),
+        Some(triple) => bail!("{TARGET_ENV}={triple:?} is not a 32-bit RISC-V target triple"),
+    }
+}
+
 pub static PROJECT_ROOT: LazyLock<PathBuf> = LazyLock::new(|| {
     let current_dir = std::env::current_dir().expect("Could not get current directory");
     option_env!("CARGO_MANIFEST_DIR")
@@ -96,21 +131,105 @@ pub(crate) fn find_file(dir: &str, name: &str) -> Option<PathBuf> {
     None
 }
 
+// Path to llvm-objcopy, resolved once per process and cached in the target directory so that
+// the sysroot doesn't have to be searched again by later invocations.
+static OBJCOPY: LazyLock<Result<String>> = LazyLock::new(|| {
+    cached_path(&PROJECT_ROOT.join("target").join("objcopy.txt"), || {
+        find_file(&SYSROOT, "llvm-objcopy")
+    })
+    .ok_or_else(|| anyhow!("Could not find llvm-objcopy; perhaps you need to run `rustup component add llvm-tools` or set the OBJCOPY environment variable to where to find objcopy"))
+});
+
 pub fn objcopy() -> Result<String> {
-    std::env::var("OBJCOPY").map(Ok).unwrap_or_else(|_| {
-        // We need to get the full path to llvm-objcopy, if it is installed.
-        if let Some(llvm_size) = find_file(&SYSROOT, "llvm-objcopy") {
-            Ok(llvm_size.to_str().unwrap().to_string())
-        } else {
-            Err(anyhow!("Could not find llvm-objcopy; perhaps you need to run `rustup component add llvm-tools` or set the OBJCOPY environment variable to where to find objcopy"))
+    std::env::var("OBJCOPY")
+        .map(Ok)
+        .unwrap_or_else(|_| match &*OBJCOPY {
+            Ok(path) => Ok(path.clone()),
+            Err(e) => Err(anyhow!("{e}")),
+        })
+}
+
+// Returns the path stored in `cache_file` if that file still has the modification time recorded
+// with it, so that a removed or reinstalled tool is searched for again; otherwise calls `find`
+// and caches its result.
+fn cached_path(cache_file: &Path, find: impl FnOnce() -> Option<PathBuf>) -> Option<String> {
+    if let Ok(cached) = std::fs::read_to_string(cache_file) {
+        if let Some((path, mtime)) = cached.split_once('\n') {
+            if modified_nanos(Path::new(path))
+                .map(|m| m.to_string())
+                .as_deref()
+                == Some(mtime)
+            {
+                return Some(path.to_string());
+            }
         }
-    })
+    }
+    let path = find()?.to_str()?.to_string();
+    if let Some(mtime) = modified_nanos(Path::new(&path)) {
+        // the cache is only an optimization, so ignore failures to write it
+        let _ = std::fs::write(cache_file, format!("{path}\n{mtime}"));
+    }
+    Some(path)
+}
+
+fn modified_nanos(path: &Path) -> Option<u128> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
 }
 
 pub(crate) fn target_binary(name: &str) -> PathBuf {
     PROJECT_ROOT
         .join("target")
-        .join(TARGET)
+        .join(target_triple())
         .join("release")
         .join(name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_file = dir.path().join("tool.txt");
+        let tool = dir.path().join("tool");
+        std::fs::write(&tool, b"").unwrap();
+
+        let searches = std::cell::Cell::new(0);
+        let find = || {
+            searches.set(searches.get() + 1);
+            Some(tool.clone())
+        };
+        let first = cached_path(&cache_file, find);
+        let second = cached_path(&cache_file, find);
+        assert_eq!(first, Some(tool.to_str().unwrap().to_string()));
+        assert_eq!(first, second);
+        assert_eq!(searches.get(), 1);
+
+        // a reinstalled tool is searched for again
+        std::fs::File::options()
+            .write(true)
+            .open(&tool)
+            .unwrap()
+            .set_modified(UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(cached_path(&cache_file, find), first);
+        assert_eq!(searches.get(), 2);
+
+        // and so is a removed one
+        std::fs::remove_file(&tool).unwrap();
+        assert_eq!(cached_path(&cache_file, || None), None);
+    }
+
+    #[test]
+    fn test_parse_target_triple() {
+        assert_eq!(parse_target_triple(None).unwrap(), TARGET);
+        assert_eq!(
+            parse_target_triple(Some("riscv32imac-unknown-none-elf".into())).unwrap(),
+            "riscv32imac-unknown-none-elf"
+        );
+        assert!(parse_target_triple(Some("x86_64-unknown-linux-gnu".into())).is_err());
+        assert!(parse_target_triple(Some("riscv64gc-unknown-none-elf".into())).is_err());
+    }
+}
@@ -1,13 +1,26 @@
 // Licensed under the Apache-2.0 license
 
 use crate::objcopy;
-use crate::{PROJECT_ROOT, TARGET};
+use crate::{target_triple, PROJECT_ROOT};
 use anyhow::{bail, Result};
 use caliptra_builder::FwId;
 use mcu_config::McuMemoryMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub fn rom_build(platform: Option<&str>, feature: &str) -> Result<String> {
+    rom_build_in(platform, feature, None)
+}
+
+/// Builds the ROM into `target_dir` instead of the default target directory.
+///
+/// A separate target directory allows the ROM to be built concurrently with other cargo builds
+/// without contending for the target directory lock.
+pub(crate) fn rom_build_in(
+    platform: Option<&str>,
+    feature: &str,
+    target_dir: Option<&Path>,
+) -> Result<String> {
     let platform = platform.unwrap_or("emulator");
     let platform_pkg = format!("mcu-rom-{}", platform);
     let feature_suffix = if feature.is_empty() {
@@ -24,26 +37,21 @@ pub fn rom_build(platform: Option<&str>, feature: &str) -> Result<String> {
         &platform_pkg,
         "--release",
         "--target",
-        TARGET,
+        target_triple(),
     ]);
     if !feature.is_empty() {
         cmd.args(["--features", feature]);
     }
+    if let Some(target_dir) = target_dir {
+        cmd.arg("--target-dir").arg(target_dir);
+    }
     let status = cmd.status()?;
     if !status.success() {
         bail!("build ROM binary failed");
     }
-    let rom_elf = PROJECT_ROOT
-        .join("target")
-        .join(TARGET)
-        .join("release")
-        .join(&platform_pkg);
-
-    let rom_binary = PROJECT_ROOT
-        .join("target")
-        .join(TARGET)
-        .join("release")
-        .join(&platform_bin);
+    let release_dir = rom_release_dir(target_dir);
+    let rom_elf = release_dir.join(&platform_pkg);
+    let rom_binary = release_dir.join(&platform_bin);
 
     let objcopy = objcopy()?;
     let objcopy_flags = "--strip-sections --strip-all";
@@ -67,6 +75,15 @@ pub fn rom_build(platform: Option<&str>, feature: &str) -> Result<String> {
 }
 
 pub fn test_rom_build(platform: Option<&str>, fwid: &FwId) -> Result<String> {
+    test_rom_build_in(platform, fwid, None)
+}
+
+/// Builds a test ROM into `target_dir` instead of the default target directory.
+pub(crate) fn test_rom_build_in(
+    platform: Option<&str>,
+    fwid: &FwId,
+    target_dir: Option<&Path>,
+) -> Result<String> {
     let platform = platform.unwrap_or("emulator");
 
     let platform_bin = format!("mcu-test-rom-{}-{}.bin", fwid.crate_name, fwid.bin_name);
@@ -77,7 +94,7 @@ pub fn test_rom_build(platform: Option<&str>, fwid: &FwId) -> Result<String> {
         fwid.crate_name,
         "--release",
         "--target",
-        TARGET,
+        target_triple(),
     ]);
 
     let mut features = fwid.features.to_vec();
@@ -88,23 +105,18 @@ pub fn test_rom_build(platform: Option<&str>, fwid: &FwId) -> Result<String> {
         features.push("fpga_realtime");
     }
     cmd.args(["--features", &features.join(",")]);
+    if let Some(target_dir) = target_dir {
+        cmd.arg("--target-dir").arg(target_dir);
+    }
 
     println!("Executing: {cmd:?}");
     let status = cmd.status()?;
     if !status.success() {
         bail!("build ROM binary failed");
     }
-    let rom_elf = PROJECT_ROOT
-        .join("target")
-        .join(TARGET)
-        .join("release")
-        .join(fwid.bin_name);
-
-    let rom_binary = PROJECT_ROOT
-        .join("target")
-        .join(TARGET)
-        .join("release")
-        .join(&platform_bin);
+    let release_dir = rom_release_dir(target_dir);
+    let rom_elf = release_dir.join(fwid.bin_name);
+    let rom_binary = release_dir.join(&platform_bin);
 
     let objcopy = objcopy()?;
     let objcopy_flags = "--strip-sections --strip-all";
@@ -127,6 +139,14 @@ pub fn test_rom_build(platform: Option<&str>, fwid: &FwId) -> Result<String> {
     Ok(rom_binary.to_string_lossy().to_string())
 }
 
+pub(crate) fn rom_release_dir(target_dir: Option<&Path>) -> PathBuf {
+    target_dir
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PROJECT_ROOT.join("target"))
+        .join(target_triple())
+        .join("release")
+}
+
 pub fn rom_ld_script(memory_map: &McuMemoryMap) -> String {
     subst::substitute(ROM_LD_TEMPLATE, &memory_map.hash_map()).unwrap()
 }
@@ -10,8 +10,10 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
 
-use crate::apps::apps_build_flat_tbf;
-use crate::{objcopy, target_binary, target_dir, OBJCOPY_FLAGS, PROJECT_ROOT, SYSROOT, TARGET};
+use crate::apps::{apps_build_flat_tbf, default_app_names};
+use crate::{
+    objcopy, target_binary, target_dir, target_triple, OBJCOPY_FLAGS, PROJECT_ROOT, SYSROOT,
+};
 use anyhow::{anyhow, bail, Result};
 use elf::endian::AnyEndian;
 use elf::ElfBytes;
@@ -162,18 +164,18 @@ pub fn runtime_build_no_apps_uncached(
             .stdout,
     )?
     .split('\n')
-    .any(|line| line.contains(TARGET))
+    .any(|line| line.contains(target_triple()))
     {
         println!("WARNING: Request to compile for a missing TARGET, will install in 5s");
         std::thread::sleep(std::time::Duration::from_secs(5));
         if !Command::new("rustup")
             .arg("target")
             .arg("add")
-            .arg(TARGET)
+            .arg(target_triple())
             .status()?
             .success()
         {
-            bail!(format!("Failed to install target {}", TARGET));
+            bail!(format!("Failed to install target {}", target_triple()));
         }
     }
 
@@ -197,7 +199,7 @@ pub fn runtime_build_no_apps_uncached(
     let bin = format!("mcu-runtime-{}", platform);
     let cargo_flags_tock = [
         "--verbose".into(),
-        format!("--target={}", TARGET),
+        format!("--target={}", target_triple()),
         format!("--package {}", bin),
         "-Z build-std=core,compiler_builtins".into(),
         "-Z build-std-features=core/optimize_for_size".into(),
@@ -303,6 +305,36 @@ pub fn runtime_build_with_apps_cached(
     dccm_size: Option<u32>,
     log_flash_config: Option<&LoggingFlashConfig>,
     mcu_image_header: Option<&[u8]>,
+) -> Result<String> {
+    let apps = default_app_names(platform.unwrap_or(DEFAULT_PLATFORM), example_app)?;
+    runtime_build_with_app_list(
+        &apps,
+        features,
+        output_name,
+        platform,
+        memory_map,
+        use_dccm_for_stack,
+        dccm_offset,
+        dccm_size,
+        log_flash_config,
+        mcu_image_header,
+    )
+}
+
+/// Builds the runtime with a TBF bundle assembled from the named app crates, in order.
+/// An empty list produces a runtime with no apps.
+#[allow(clippy::too_many_arguments)]
+pub fn runtime_build_with_app_list(
+    apps: &[&str],
+    features: &[&str],
+    output_name: Option<&str>,
+    platform: Option<&str>,
+    memory_map: Option<&McuMemoryMap>,
+    use_dccm_for_stack: bool,
+    dccm_offset: Option<u32>,
+    dccm_size: Option<u32>,
+    log_flash_config: Option<&LoggingFlashConfig>,
+    mcu_image_header: Option<&[u8]>,
 ) -> Result<String> {
     let memory_map = memory_map.unwrap_or(&mcu_config_emulator::EMULATOR_MEMORY_MAP);
     let mut app_offset = memory_map.sram_offset as usize;
@@ -373,13 +405,7 @@ pub fn runtime_build_with_apps_cached(
     let padding = apps_offset - runtime_end_offset;
 
     // build the apps with the data memory at some incorrect offset
-    let apps_bin = apps_build_flat_tbf(
-        platform,
-        apps_offset,
-        apps_memory_offset,
-        features,
-        example_app,
-    )?;
+    let apps_bin = apps_build_flat_tbf(platform, apps_offset, apps_memory_offset, features, apps)?;
     let apps_bin_len = apps_bin.len();
     println!("Apps built: {} bytes", apps_bin_len);
 
@@ -418,13 +444,8 @@ pub fn runtime_build_with_apps_cached(
         println!("Rebuilding apps with correct offsets");
 
         // re-link the applications with the correct data memory offsets
-        let apps_bin = apps_build_flat_tbf(
-            platform,
-            apps_offset,
-            apps_memory_offset,
-            features,
-            example_app,
-        )?;
+        let apps_bin =
+            apps_build_flat_tbf(platform, apps_offset, apps_memory_offset, features, apps)?;
         assert_eq!(
             apps_bin_len,
             apps_bin.len(),
@@ -4,9 +4,17 @@
 
 pub mod boot;
 
+#[cfg(not(target_arch = "riscv32"))]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 /// Configures the memory map for the MCU.
 /// These are the defaults that can be overridden and provided to the ROM and runtime builds.
 #[repr(C)]
+#[cfg_attr(
+    not(target_arch = "riscv32"),
+    derive(Debug, Serialize, Deserialize),
+    serde(default)
+)]
 pub struct McuMemoryMap {
     pub rom_offset: u32,
     pub rom_size: u32,
@@ -47,6 +55,11 @@ pub struct McuMemoryMap {
     pub lc_offset: u32,
     pub lc_size: u32,
     pub lc_properties: MemoryRegionType,
+
+    /// When set, `mrac_override` is used as the MRAC value instead of computing it
+    /// from the region properties. Kept as a flag plus value so the struct stays FFI-safe.
+    pub mrac_override_valid: bool,
+    pub mrac_override: u32,
 }
 
 impl Default for McuMemoryMap {
@@ -91,6 +104,9 @@ impl Default for McuMemoryMap {
             lc_offset: 0x7000_0400,
             lc_size: 0x8c,
             lc_properties: MemoryRegionType::MMIO,
+
+            mrac_override_valid: false,
+            mrac_override: 0,
         }
     }
 }
@@ -100,6 +116,8 @@ impl Default for McuMemoryMap {
 #[repr(C)]
 pub struct McuStraps {
     pub i3c_static_addr: u8,
+    /// AXI user that the ROM grants access to Caliptra mailbox user 0, the fuse
+    /// registers, and the TRNG. There is only one; all platform configs set this field.
     pub axi_user: u32,
     pub cptra_wdt_cfg0: u32,
     pub cptra_wdt_cfg1: u32,
@@ -118,6 +136,55 @@ impl McuStraps {
             mcu_wdt_cfg1: 1,
         }
     }
+
+    /// Start from [`McuStraps::default()`] and override individual straps.
+    pub const fn builder() -> McuStrapsBuilder {
+        McuStrapsBuilder {
+            straps: McuStraps::default(),
+        }
+    }
+}
+
+/// Builder for [`McuStraps`]. All methods are `const` so platform configs can use it in
+/// `const` items.
+pub struct McuStrapsBuilder {
+    straps: McuStraps,
+}
+
+impl McuStrapsBuilder {
+    pub const fn with_i3c_static_addr(mut self, i3c_static_addr: u8) -> Self {
+        self.straps.i3c_static_addr = i3c_static_addr;
+        self
+    }
+
+    pub const fn with_axi_user(mut self, axi_user: u32) -> Self {
+        self.straps.axi_user = axi_user;
+        self
+    }
+
+    pub const fn with_cptra_wdt_cfg0(mut self, cptra_wdt_cfg0: u32) -> Self {
+        self.straps.cptra_wdt_cfg0 = cptra_wdt_cfg0;
+        self
+    }
+
+    pub const fn with_cptra_wdt_cfg1(mut self, cptra_wdt_cfg1: u32) -> Self {
+        self.straps.cptra_wdt_cfg1 = cptra_wdt_cfg1;
+        self
+    }
+
+    pub const fn with_mcu_wdt_cfg0(mut self, mcu_wdt_cfg0: u32) -> Self {
+        self.straps.mcu_wdt_cfg0 = mcu_wdt_cfg0;
+        self
+    }
+
+    pub const fn with_mcu_wdt_cfg1(mut self, mcu_wdt_cfg1: u32) -> Self {
+        self.straps.mcu_wdt_cfg1 = mcu_wdt_cfg1;
+        self
+    }
+
+    pub const fn build(self) -> McuStraps {
+        self.straps
+    }
 }
 
 /// Represents the properties of a memory region for MRAC computation
@@ -141,11 +208,68 @@ impl MemoryRegionType {
         side_effect: true,
         cacheable: false,
     };
+    /// Uncached memory regions (no side effects, not cacheable)
+    pub const UNCACHED: Self = Self {
+        side_effect: false,
+        cacheable: false,
+    };
     /// Default for unmapped regions (side effects, not cacheable)
     pub const UNMAPPED: Self = Self {
         side_effect: true,
         cacheable: false,
     };
+
+    /// The 2-bit MRAC field for this region type: bit 1 is side effect, bit 0 is cacheable.
+    pub const fn mrac_bits(&self) -> u8 {
+        ((self.side_effect as u8) << 1) | self.cacheable as u8
+    }
+
+    /// Decode a 2-bit MRAC field; only the low two bits of `bits` are used. `0b11` decodes to
+    /// a region that both has side effects and is cacheable, which is not a valid combination
+    /// and matches none of the named region types.
+    pub const fn from_mrac_bits(bits: u8) -> Self {
+        Self {
+            side_effect: bits & 0b10 != 0,
+            cacheable: bits & 0b01 != 0,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+impl MemoryRegionType {
+    /// Names of the valid MRAC combinations, as used in TOML memory maps.
+    const NAMES: [(&'static str, Self); 3] = [
+        ("memory", Self::MEMORY),
+        ("mmio", Self::MMIO),
+        ("uncached", Self::UNCACHED),
+    ];
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+impl Serialize for MemoryRegionType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match Self::NAMES
+            .iter()
+            .find(|(_, region_type)| region_type == self)
+        {
+            Some((name, _)) => serializer.serialize_str(name),
+            None => Err(serde::ser::Error::custom(
+                "memory region type can't both have side effects and be cacheable",
+            )),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+impl<'de> Deserialize<'de> for MemoryRegionType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, region_type)| *region_type)
+            .ok_or_else(|| serde::de::Error::unknown_variant(&s, &["memory", "mmio", "uncached"]))
+    }
 }
 
 impl McuMemoryMap {
@@ -166,6 +290,15 @@ impl McuMemoryMap {
         region
     }
 
+    /// The forced MRAC value, if `mrac_override_valid` is set.
+    pub const fn get_mrac_override(&self) -> Option<u32> {
+        if self.mrac_override_valid {
+            Some(self.mrac_override)
+        } else {
+            None
+        }
+    }
+
     /// Compute the MRAC register value based on the memory map
     ///
     /// MRAC is a 32-bit register controlling 16 regions of 256MB each.
@@ -239,14 +372,70 @@ impl McuMemoryMap {
         // Build the 32-bit MRAC value
         let mut mrac_value = 0u32;
         for (i, region_type) in region_types.iter().enumerate() {
-            let bits = (if region_type.side_effect { 2 } else { 0 })
-                | (if region_type.cacheable { 1 } else { 0 });
-            mrac_value |= bits << (i * 2);
+            mrac_value |= (region_type.mrac_bits() as u32) << (i * 2);
         }
 
         mrac_value
     }
 
+    /// Named address ranges of the memory map as (name, offset, size)
+    #[cfg(not(target_arch = "riscv32"))]
+    pub fn named_regions(&self) -> [(&'static str, u32, u32); 10] {
+        [
+            ("rom", self.rom_offset, self.rom_size),
+            ("sram", self.sram_offset, self.sram_size),
+            ("dccm", self.dccm_offset, self.dccm_size),
+            ("pic", self.pic_offset, 0x1000), // PIC doesn't have explicit size, use 4KB
+            ("i3c", self.i3c_offset, self.i3c_size),
+            ("mci", self.mci_offset, self.mci_size),
+            ("mbox", self.mbox_offset, self.mbox_size),
+            ("soc", self.soc_offset, self.soc_size),
+            ("otp", self.otp_offset, self.otp_size),
+            ("lc", self.lc_offset, self.lc_size),
+        ]
+    }
+
+    /// Check that no two named regions of the memory map overlap
+    ///
+    /// Returns the names of every pair of overlapping regions on failure.
+    #[cfg(not(target_arch = "riscv32"))]
+    pub fn validate(&self) -> Result<(), Vec<(String, String)>> {
+        let regions = self.named_regions();
+        let mut overlaps = Vec::new();
+
+        for (i, &(name_a, offset_a, size_a)) in regions.iter().enumerate() {
+            for &(name_b, offset_b, size_b) in regions.iter().skip(i + 1) {
+                if size_a == 0 || size_b == 0 {
+                    continue;
+                }
+                let end_a = offset_a as u64 + size_a as u64;
+                let end_b = offset_b as u64 + size_b as u64;
+                if (offset_a as u64) < end_b && (offset_b as u64) < end_a {
+                    overlaps.push((name_a.to_string(), name_b.to_string()));
+                }
+            }
+        }
+
+        if overlaps.is_empty() {
+            Ok(())
+        } else {
+            Err(overlaps)
+        }
+    }
+
+    /// Parse a memory map from TOML. Fields that are not present keep their default values.
+    #[cfg(not(target_arch = "riscv32"))]
+    pub fn from_toml(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Serialize the memory map to TOML. Fails if a region both has side effects and is
+    /// cacheable, which has no MRAC encoding.
+    #[cfg(not(target_arch = "riscv32"))]
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
     #[cfg(not(target_arch = "riscv32"))]
     pub fn hash_map(&self) -> std::collections::HashMap<String, String> {
         let mut map = std::collections::HashMap::new();
@@ -265,11 +454,11 @@ impl McuMemoryMap {
         );
         map.insert("DCCM_SIZE".to_string(), format!("0x{:x}", self.dccm_size));
 
-        // The computed MRAC value (derived from all memory region properties)
-        map.insert(
-            "MRAC_VALUE".to_string(),
-            format!("0x{:x}", self.compute_mrac()),
-        );
+        // The MRAC value (derived from all memory region properties unless overridden)
+        let mrac_value = self
+            .get_mrac_override()
+            .unwrap_or_else(|| self.compute_mrac());
+        map.insert("MRAC_VALUE".to_string(), format!("0x{:x}", mrac_value));
 
         map
     }
@@ -279,6 +468,33 @@ impl McuMemoryMap {
 mod tests {
     use super::*;
 
+    // Spells out every McuStraps field so that a change to the struct's shape is caught
+    // here, next to the definition, rather than only in the platform crates.
+    const _CANONICAL_STRAPS: McuStraps = McuStraps {
+        i3c_static_addr: 0x3a,
+        axi_user: 0x1,
+        cptra_wdt_cfg0: 200_000_000,
+        cptra_wdt_cfg1: 200_000_000,
+        mcu_wdt_cfg0: 800_000_000,
+        mcu_wdt_cfg1: 1,
+    };
+
+    #[test]
+    fn test_straps_builder() {
+        const STRAPS: McuStraps = McuStraps::builder()
+            .with_axi_user(0x1)
+            .with_mcu_wdt_cfg0(800_000_000)
+            .build();
+        let default = McuStraps::default();
+
+        assert_eq!(STRAPS.axi_user, 0x1);
+        assert_eq!(STRAPS.mcu_wdt_cfg0, 800_000_000);
+        assert_eq!(STRAPS.i3c_static_addr, default.i3c_static_addr);
+        assert_eq!(STRAPS.cptra_wdt_cfg0, default.cptra_wdt_cfg0);
+        assert_eq!(STRAPS.cptra_wdt_cfg1, default.cptra_wdt_cfg1);
+        assert_eq!(STRAPS.mcu_wdt_cfg1, default.mcu_wdt_cfg1);
+    }
+
     #[test]
     fn test_mrac_computation() {
         let memory_map = McuMemoryMap::default();
@@ -331,6 +547,148 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mrac_bits() {
+        assert_eq!(MemoryRegionType::MEMORY.mrac_bits(), 0b01);
+        assert_eq!(MemoryRegionType::MMIO.mrac_bits(), 0b10);
+
+        let uncached = MemoryRegionType {
+            side_effect: false,
+            cacheable: false,
+        };
+        assert_eq!(uncached.mrac_bits(), 0b00);
+        assert_eq!(MemoryRegionType::from_mrac_bits(0b00), uncached);
+        assert_eq!(
+            MemoryRegionType::from_mrac_bits(0b01),
+            MemoryRegionType::MEMORY
+        );
+        assert_eq!(
+            MemoryRegionType::from_mrac_bits(0b10),
+            MemoryRegionType::MMIO
+        );
+        // Only the low two bits are decoded.
+        assert_eq!(
+            MemoryRegionType::from_mrac_bits(0b101),
+            MemoryRegionType::MEMORY
+        );
+
+        for bits in 0..=0b11 {
+            assert_eq!(MemoryRegionType::from_mrac_bits(bits).mrac_bits(), bits);
+        }
+    }
+
+    #[test]
+    fn test_mrac_bits_invalid_combination() {
+        // Side effect and cacheable together is invalid, and must not alias a valid type.
+        let invalid = MemoryRegionType::from_mrac_bits(0b11);
+        assert!(invalid.side_effect && invalid.cacheable);
+        for valid in [
+            MemoryRegionType::MEMORY,
+            MemoryRegionType::MMIO,
+            MemoryRegionType::UNCACHED,
+            MemoryRegionType::UNMAPPED,
+        ] {
+            assert_ne!(invalid, valid);
+        }
+    }
+
+    #[test]
+    fn test_mrac_override() {
+        let memory_map = McuMemoryMap::default();
+        assert_eq!(memory_map.get_mrac_override(), None);
+        assert_eq!(
+            memory_map.hash_map()["MRAC_VALUE"],
+            format!("0x{:x}", memory_map.compute_mrac())
+        );
+
+        let memory_map = McuMemoryMap {
+            mrac_override_valid: true,
+            mrac_override: 0xaaaa_aaaa,
+            ..Default::default()
+        };
+        assert_eq!(memory_map.get_mrac_override(), Some(0xaaaa_aaaa));
+        assert_eq!(memory_map.hash_map()["MRAC_VALUE"], "0xaaaaaaaa");
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let memory_map = McuMemoryMap::default();
+        let toml = memory_map.to_toml().unwrap();
+        assert!(toml.contains("rom_properties = \"memory\""));
+        assert!(toml.contains("i3c_properties = \"mmio\""));
+
+        let parsed = McuMemoryMap::from_toml(&toml).unwrap();
+        assert_eq!(parsed.to_toml().unwrap(), toml);
+        assert_eq!(parsed.rom_offset, memory_map.rom_offset);
+        assert_eq!(parsed.lc_properties, memory_map.lc_properties);
+        assert_eq!(parsed.get_mrac_override(), None);
+    }
+
+    #[test]
+    fn test_from_toml_partial() {
+        let memory_map =
+            McuMemoryMap::from_toml("sram_offset = 0x1000\nsram_properties = \"mmio\"\n").unwrap();
+        assert_eq!(memory_map.sram_offset, 0x1000);
+        assert_eq!(memory_map.sram_properties, MemoryRegionType::MMIO);
+        assert_eq!(memory_map.rom_offset, McuMemoryMap::default().rom_offset);
+
+        assert!(McuMemoryMap::from_toml("rom_properties = \"rom\"").is_err());
+    }
+
+    #[test]
+    fn test_toml_region_types() {
+        for bits in 0..=0b10 {
+            let memory_map = McuMemoryMap {
+                sram_properties: MemoryRegionType::from_mrac_bits(bits),
+                ..Default::default()
+            };
+            let parsed = McuMemoryMap::from_toml(&memory_map.to_toml().unwrap()).unwrap();
+            assert_eq!(parsed.sram_properties, memory_map.sram_properties);
+        }
+        assert_eq!(
+            McuMemoryMap::from_toml("sram_properties = \"uncached\"")
+                .unwrap()
+                .sram_properties,
+            MemoryRegionType::UNCACHED
+        );
+
+        let invalid = McuMemoryMap {
+            sram_properties: MemoryRegionType {
+                side_effect: true,
+                cacheable: true,
+            },
+            ..Default::default()
+        };
+        assert!(invalid.to_toml().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(McuMemoryMap::default().validate(), Ok(()));
+
+        let memory_map = McuMemoryMap {
+            sram_offset: 0x5000_0000,
+            sram_size: 0x1000,
+            dccm_offset: 0x5000_0800,
+            dccm_size: 0x1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            memory_map.validate(),
+            Err(vec![("sram".to_string(), "dccm".to_string())])
+        );
+
+        // Adjacent regions do not overlap
+        let memory_map = McuMemoryMap {
+            sram_offset: 0x5000_0000,
+            sram_size: 0x1000,
+            dccm_offset: 0x5000_1000,
+            dccm_size: 0x1000,
+            ..Default::default()
+        };
+        assert_eq!(memory_map.validate(), Ok(()));
+    }
+
     #[test]
     fn test_mrac_region_mapping() {
         // Test the 256MB region boundaries
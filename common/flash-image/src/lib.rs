@@ -10,8 +10,94 @@ pub const SOC_MANIFEST_IDENTIFIER: u32 = 0x00000001;
 pub const MCU_RT_IDENTIFIER: u32 = 0x00000002;
 pub const SOC_IMAGES_BASE_IDENTIFIER: u32 = 0x00001000;
 
+/// Identifiers at or above this value are not assigned.
+pub const SOC_IMAGES_END_IDENTIFIER: u32 = 0x0001_0000;
+
+/// Typed view of an [`ImageHeader::identifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashImageType {
+    CaliptraFmcRt,
+    SocManifest,
+    McuRt,
+    /// Vendor-defined SoC image `n`, with identifier `SOC_IMAGES_BASE_IDENTIFIER + n`.
+    SocImage(u16),
+}
+
+impl FlashImageType {
+    pub fn from_identifier(identifier: u32) -> Option<Self> {
+        match identifier {
+            CALIPTRA_FMC_RT_IDENTIFIER => Some(FlashImageType::CaliptraFmcRt),
+            SOC_MANIFEST_IDENTIFIER => Some(FlashImageType::SocManifest),
+            MCU_RT_IDENTIFIER => Some(FlashImageType::McuRt),
+            SOC_IMAGES_BASE_IDENTIFIER..SOC_IMAGES_END_IDENTIFIER => Some(
+                FlashImageType::SocImage((identifier - SOC_IMAGES_BASE_IDENTIFIER) as u16),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for a SoC image index beyond the identifier range.
+    pub fn to_identifier(self) -> Option<u32> {
+        match self {
+            FlashImageType::CaliptraFmcRt => Some(CALIPTRA_FMC_RT_IDENTIFIER),
+            FlashImageType::SocManifest => Some(SOC_MANIFEST_IDENTIFIER),
+            FlashImageType::McuRt => Some(MCU_RT_IDENTIFIER),
+            FlashImageType::SocImage(index) => {
+                let identifier = SOC_IMAGES_BASE_IDENTIFIER + index as u32;
+                (identifier < SOC_IMAGES_END_IDENTIFIER).then_some(identifier)
+            }
+        }
+    }
+}
+
 pub const FLASH_IMAGE_MAGIC_NUMBER: u32 = u32::from_be_bytes(*b"FLSH");
-pub const HEADER_VERSION: u16 = 0x0001;
+pub const HEADER_VERSION: u16 = 0x0002;
+
+/// Version of the original header, laid out as [`LegacyFlashHeader`]. These images
+/// have no `checksum_algo` field and always use [`ChecksumAlgo::Sum`].
+pub const LEGACY_HEADER_VERSION: u16 = 0x0001;
+
+/// Algorithm used for the header and image checksums of a flash image.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgo {
+    /// Two's complement of the byte sum
+    Sum = 0,
+    /// CRC-32 (IEEE 802.3)
+    Crc32 = 1,
+}
+
+impl ChecksumAlgo {
+    pub fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(ChecksumAlgo::Sum),
+            1 => Some(ChecksumAlgo::Crc32),
+            _ => None,
+        }
+    }
+
+    /// Compute the checksum of `data` with this algorithm.
+    pub fn checksum(self, data: &[u8]) -> u32 {
+        let mut state = ChecksumState::new(self);
+        state.update(data);
+        state.finish()
+    }
+}
+
+/// Feed `data` into a running (non-finalized) CRC-32 value.
+pub fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
 
 #[repr(C)]
 #[derive(Debug, FromBytes, IntoBytes, Immutable, KnownLayout)]
@@ -20,29 +106,146 @@ pub struct FlashHeader {
     pub version: u16,
     pub image_count: u16,
     pub image_headers_offset: u32,
+    /// A [`ChecksumAlgo`] value, applied to this header and all image headers and images.
+    pub checksum_algo: u16,
+    pub reserved: u16,
     pub header_checksum: u32,
 }
 
+/// Flash header of a [`LEGACY_HEADER_VERSION`] image.
+#[repr(C)]
+#[derive(Debug, FromBytes, IntoBytes, Immutable, KnownLayout)]
+pub struct LegacyFlashHeader {
+    pub magic: U32<zerocopy::byteorder::BigEndian>,
+    pub version: u16,
+    pub image_count: u16,
+    pub image_headers_offset: u32,
+    pub header_checksum: u32,
+}
+
+impl From<&LegacyFlashHeader> for FlashHeader {
+    fn from(legacy: &LegacyFlashHeader) -> Self {
+        FlashHeader {
+            magic: legacy.magic,
+            version: legacy.version,
+            image_count: legacy.image_count,
+            image_headers_offset: legacy.image_headers_offset,
+            checksum_algo: ChecksumAlgo::Sum as u16,
+            reserved: 0,
+            header_checksum: legacy.header_checksum,
+        }
+    }
+}
+
 impl FlashHeader {
+    /// Parse the flash header at the start of `image`, which may use either the
+    /// current layout or the [`LegacyFlashHeader`] layout.
+    pub fn parse(image: &[u8]) -> Option<FlashHeader> {
+        let (legacy, _) = LegacyFlashHeader::ref_from_prefix(image).ok()?;
+        if legacy.version == LEGACY_HEADER_VERSION {
+            return Some(FlashHeader::from(legacy));
+        }
+        FlashHeader::read_from_prefix(image)
+            .ok()
+            .map(|(header, _)| header)
+    }
+
     pub fn verify(&self) -> bool {
         if self.magic.get() != FLASH_IMAGE_MAGIC_NUMBER {
             return false;
         }
-        if self.version != HEADER_VERSION {
+        let Some(header_size) = self.header_size() else {
             return false;
-        }
+        };
         if self.image_count == 0 {
             return false;
         }
-        if self.image_headers_offset < core::mem::size_of::<FlashHeader>() as u32 {
+        if self.image_headers_offset < header_size as u32 {
             return false;
         }
+        let Some(algo) = self.checksum_algo() else {
+            return false;
+        };
 
-        0u32.wrapping_sub(
-            self.as_bytes()[..offset_of!(FlashHeader, header_checksum)]
-                .iter()
-                .fold(0u32, |acc, &byte| acc.wrapping_add(byte as u32)),
-        ) == self.header_checksum
+        algo.checksum(self.checksummed_bytes()) == self.header_checksum
+    }
+
+    /// Set `header_checksum` to match the other fields, using this header's
+    /// checksum algorithm. Does nothing if `checksum_algo` is not recognized.
+    pub fn recompute_checksum(&mut self) {
+        if let Some(algo) = self.checksum_algo() {
+            self.header_checksum = algo.checksum(self.checksummed_bytes());
+        }
+    }
+
+    /// Size of this header in flash, or `None` for an unsupported version.
+    ///
+    /// Legacy headers must use [`ChecksumAlgo::Sum`], which is all they can express.
+    pub fn header_size(&self) -> Option<usize> {
+        match self.version {
+            HEADER_VERSION => Some(core::mem::size_of::<FlashHeader>()),
+            LEGACY_HEADER_VERSION if self.checksum_algo() == Some(ChecksumAlgo::Sum) => {
+                Some(core::mem::size_of::<LegacyFlashHeader>())
+            }
+            _ => None,
+        }
+    }
+
+    /// The fields covered by `header_checksum`, as laid out for this header's version.
+    fn checksummed_bytes(&self) -> &[u8] {
+        let end = if self.version == LEGACY_HEADER_VERSION {
+            offset_of!(LegacyFlashHeader, header_checksum)
+        } else {
+            offset_of!(FlashHeader, header_checksum)
+        };
+        &self.as_bytes()[..end]
+    }
+
+    /// The checksum algorithm of this image, or `None` if it is not recognized.
+    pub fn checksum_algo(&self) -> Option<ChecksumAlgo> {
+        ChecksumAlgo::from_u16(self.checksum_algo)
+    }
+
+    /// Iterate over the image headers of `image`, which must start with this header.
+    ///
+    /// Iteration stops early if `image` is too short to hold all `image_count` headers.
+    pub fn image_headers<'a>(&self, image: &'a [u8]) -> ImageHeaderIter<'a> {
+        ImageHeaderIter {
+            image,
+            offset: self.image_headers_offset as usize,
+            remaining: self.image_count,
+        }
+    }
+
+    /// Find the image header for `image_type` in `image`, which must start with this header.
+    pub fn find_image(&self, image: &[u8], image_type: FlashImageType) -> Option<ImageHeader> {
+        self.image_headers(image)
+            .find(|(ty, _)| *ty == Some(image_type))
+            .map(|(_, header)| header)
+    }
+}
+
+/// Iterator over the [`ImageHeader`]s of a flash image, see [`FlashHeader::image_headers`].
+pub struct ImageHeaderIter<'a> {
+    image: &'a [u8],
+    offset: usize,
+    remaining: u16,
+}
+
+impl Iterator for ImageHeaderIter<'_> {
+    type Item = (Option<FlashImageType>, ImageHeader);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let bytes = self
+            .image
+            .get(self.offset..self.offset + core::mem::size_of::<ImageHeader>())?;
+        let header = ImageHeader::read_from_bytes(bytes).ok()?;
+        self.offset += core::mem::size_of::<ImageHeader>();
+        self.remaining -= 1;
+        Some((header.image_type(), header))
     }
 }
 
@@ -57,11 +260,401 @@ pub struct ImageHeader {
 }
 
 impl ImageHeader {
-    pub fn verify(&self) -> bool {
-        0u32.wrapping_sub(
-            self.as_bytes()[..offset_of!(ImageHeader, image_header_checksum)]
+    pub fn image_type(&self) -> Option<FlashImageType> {
+        FlashImageType::from_identifier(self.identifier)
+    }
+
+    /// Verify the header checksum using the algorithm from the enclosing [`FlashHeader`].
+    pub fn verify(&self, algo: ChecksumAlgo) -> bool {
+        algo.checksum(&self.as_bytes()[..offset_of!(ImageHeader, image_header_checksum)])
+            == self.image_header_checksum
+    }
+
+    /// Set `image_header_checksum` to match the other fields, so that [`Self::verify`]
+    /// with the same `algo` passes.
+    pub fn recompute_checksum(&mut self, algo: ChecksumAlgo) {
+        self.image_header_checksum =
+            algo.checksum(&self.as_bytes()[..offset_of!(ImageHeader, image_header_checksum)]);
+    }
+}
+
+/// Running checksum of a byte stream.
+#[derive(Debug, Clone, Copy)]
+struct ChecksumState {
+    algo: ChecksumAlgo,
+    acc: u32,
+}
+
+impl ChecksumState {
+    fn new(algo: ChecksumAlgo) -> Self {
+        let acc = match algo {
+            ChecksumAlgo::Sum => 0,
+            ChecksumAlgo::Crc32 => !0,
+        };
+        Self { algo, acc }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.acc = match self.algo {
+            ChecksumAlgo::Sum => data
                 .iter()
-                .fold(0u32, |acc, &byte| acc.wrapping_add(byte as u32)),
-        ) == self.image_header_checksum
+                .fold(self.acc, |acc, &b| acc.wrapping_add(b as u32)),
+            ChecksumAlgo::Crc32 => crc32_update(self.acc, data),
+        };
+    }
+
+    fn finish(self) -> u32 {
+        match self.algo {
+            ChecksumAlgo::Sum => 0u32.wrapping_sub(self.acc),
+            ChecksumAlgo::Crc32 => !self.acc,
+        }
+    }
+}
+
+const IMAGE_HEADER_SIZE: usize = core::mem::size_of::<ImageHeader>();
+
+/// Incrementally verifies an [`ImageHeader`] followed by its image body.
+///
+/// Bytes may be fed in chunks of any size as they are read from flash; the
+/// header is buffered until complete so chunks may split any of its fields.
+pub struct FlashImageVerifier {
+    algo: ChecksumAlgo,
+    header: [u8; IMAGE_HEADER_SIZE],
+    header_len: usize,
+    body: ChecksumState,
+    body_len: usize,
+    overflow: bool,
+}
+
+impl FlashImageVerifier {
+    pub fn new(algo: ChecksumAlgo) -> Self {
+        Self {
+            algo,
+            header: [0; IMAGE_HEADER_SIZE],
+            header_len: 0,
+            body: ChecksumState::new(algo),
+            body_len: 0,
+            overflow: false,
+        }
+    }
+
+    pub fn update(&mut self, mut chunk: &[u8]) {
+        if self.header_len < IMAGE_HEADER_SIZE {
+            let n = chunk.len().min(IMAGE_HEADER_SIZE - self.header_len);
+            self.header[self.header_len..self.header_len + n].copy_from_slice(&chunk[..n]);
+            self.header_len += n;
+            chunk = &chunk[n..];
+        }
+        if chunk.is_empty() {
+            return;
+        }
+
+        // The header is complete at this point, so the body size is known
+        let size = self.image_header().size as usize;
+        let n = chunk.len().min(size.saturating_sub(self.body_len));
+        if n < chunk.len() {
+            self.overflow = true;
+        }
+        self.body.update(&chunk[..n]);
+        self.body_len += n;
+    }
+
+    /// Returns true if the header and the complete image body were received and both
+    /// checksums match.
+    pub fn finalize(self) -> bool {
+        if self.header_len < IMAGE_HEADER_SIZE || self.overflow {
+            return false;
+        }
+        let header = self.image_header();
+        header.verify(self.algo)
+            && self.body_len == header.size as usize
+            && self.body.finish() == header.image_checksum
+    }
+
+    fn image_header(&self) -> ImageHeader {
+        ImageHeader::read_from_bytes(&self.header).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        // Standard CRC-32 check value
+        assert_eq!(ChecksumAlgo::Crc32.checksum(b"123456789"), 0xcbf4_3926);
+        assert_eq!(ChecksumAlgo::Crc32.checksum(&[]), 0);
+    }
+
+    #[test]
+    fn test_sum() {
+        assert_eq!(ChecksumAlgo::Sum.checksum(&[1, 2, 3]), 0u32.wrapping_sub(6));
+    }
+
+    fn header(algo: ChecksumAlgo) -> FlashHeader {
+        let mut header = FlashHeader {
+            magic: FLASH_IMAGE_MAGIC_NUMBER.into(),
+            version: HEADER_VERSION,
+            image_count: 1,
+            image_headers_offset: core::mem::size_of::<FlashHeader>() as u32,
+            checksum_algo: algo as u16,
+            reserved: 0,
+            header_checksum: 0,
+        };
+        header.recompute_checksum();
+        header
+    }
+
+    #[test]
+    fn test_flash_header_verify() {
+        assert!(header(ChecksumAlgo::Sum).verify());
+        assert!(header(ChecksumAlgo::Crc32).verify());
+
+        // A checksum computed with the wrong algorithm is rejected
+        let mut bad = header(ChecksumAlgo::Sum);
+        bad.checksum_algo = ChecksumAlgo::Crc32 as u16;
+        assert!(!bad.verify());
+
+        let mut unknown = header(ChecksumAlgo::Crc32);
+        unknown.checksum_algo = 7;
+        assert!(!unknown.verify());
+    }
+
+    #[test]
+    fn test_flash_header_round_trip() {
+        for algo in [ChecksumAlgo::Sum, ChecksumAlgo::Crc32] {
+            let original = header(algo);
+            let parsed = FlashHeader::read_from_bytes(original.as_bytes()).unwrap();
+            assert!(parsed.verify());
+            assert_eq!(parsed.checksum_algo(), Some(algo));
+        }
+    }
+
+    #[test]
+    fn test_legacy_flash_header() {
+        // v1 image: magic, version, image_count, image_headers_offset, header_checksum,
+        // followed directly by the image header and the image
+        let mut image = [0u8; 16 + IMAGE_HEADER_SIZE + 4];
+        image[..4].copy_from_slice(&FLASH_IMAGE_MAGIC_NUMBER.to_be_bytes());
+        image[4..6].copy_from_slice(&LEGACY_HEADER_VERSION.to_le_bytes());
+        image[6..8].copy_from_slice(&1u16.to_le_bytes());
+        image[8..12].copy_from_slice(&16u32.to_le_bytes());
+        let checksum = ChecksumAlgo::Sum.checksum(&image[..12]);
+        image[12..16].copy_from_slice(&checksum.to_le_bytes());
+        let body = [1, 2, 3, 4];
+        let mut image_header = ImageHeader {
+            identifier: MCU_RT_IDENTIFIER,
+            offset: (16 + IMAGE_HEADER_SIZE) as u32,
+            size: body.len() as u32,
+            image_checksum: ChecksumAlgo::Sum.checksum(&body),
+            image_header_checksum: 0,
+        };
+        image_header.recompute_checksum(ChecksumAlgo::Sum);
+        image[16..16 + IMAGE_HEADER_SIZE].copy_from_slice(image_header.as_bytes());
+        image[16 + IMAGE_HEADER_SIZE..].copy_from_slice(&body);
+
+        let parsed = FlashHeader::parse(&image).unwrap();
+        assert!(parsed.verify());
+        assert_eq!(parsed.header_size(), Some(16));
+        assert_eq!(parsed.checksum_algo(), Some(ChecksumAlgo::Sum));
+        let found = parsed.find_image(&image, FlashImageType::McuRt).unwrap();
+        assert!(found.verify(ChecksumAlgo::Sum));
+        assert_eq!(found.size, 4);
+
+        // Recomputing the checksum of a legacy header keeps the legacy layout
+        let mut recomputed = FlashHeader::parse(&image).unwrap();
+        recomputed.header_checksum = 0;
+        recomputed.recompute_checksum();
+        assert_eq!(recomputed.header_checksum, checksum);
+
+        // A corrupted legacy header is rejected
+        image[6] = 2;
+        assert!(!FlashHeader::parse(&image).unwrap().verify());
+
+        // Legacy images cannot use CRC-32
+        let mut crc = header(ChecksumAlgo::Crc32);
+        crc.version = LEGACY_HEADER_VERSION;
+        crc.recompute_checksum();
+        assert!(!crc.verify());
+    }
+
+    #[test]
+    fn test_parse_current_header() {
+        let original = header(ChecksumAlgo::Crc32);
+        let parsed = FlashHeader::parse(original.as_bytes()).unwrap();
+        assert!(parsed.verify());
+        assert_eq!(
+            parsed.header_size(),
+            Some(core::mem::size_of::<FlashHeader>())
+        );
+        assert!(FlashHeader::parse(&original.as_bytes()[..8]).is_none());
+    }
+
+    #[test]
+    fn test_recompute_checksum() {
+        for algo in [ChecksumAlgo::Sum, ChecksumAlgo::Crc32] {
+            let mut flash_header = header(algo);
+            flash_header.image_count = 3;
+            assert!(!flash_header.verify());
+            flash_header.recompute_checksum();
+            assert!(flash_header.verify());
+
+            let mut image_header = ImageHeader {
+                identifier: MCU_RT_IDENTIFIER,
+                offset: 0x100,
+                size: 0x40,
+                image_checksum: 0x1234_5678,
+                image_header_checksum: 0,
+            };
+            image_header.recompute_checksum(algo);
+            assert!(image_header.verify(algo));
+            image_header.offset = 0x200;
+            assert!(!image_header.verify(algo));
+            image_header.recompute_checksum(algo);
+            assert!(image_header.verify(algo));
+        }
+
+        // An unrecognized algorithm leaves the checksum unchanged
+        let mut unknown = header(ChecksumAlgo::Crc32);
+        unknown.checksum_algo = 7;
+        let checksum = unknown.header_checksum;
+        unknown.recompute_checksum();
+        assert_eq!(unknown.header_checksum, checksum);
+    }
+
+    #[test]
+    fn test_flash_image_type() {
+        assert_eq!(
+            FlashImageType::from_identifier(0x1003),
+            Some(FlashImageType::SocImage(3))
+        );
+        assert_eq!(FlashImageType::SocImage(3).to_identifier(), Some(0x1003));
+        assert_eq!(
+            FlashImageType::from_identifier(SOC_IMAGES_BASE_IDENTIFIER),
+            Some(FlashImageType::SocImage(0))
+        );
+        assert_eq!(
+            FlashImageType::from_identifier(0xffff),
+            Some(FlashImageType::SocImage(0xefff))
+        );
+        assert_eq!(FlashImageType::SocImage(0xf000).to_identifier(), None);
+
+        for (ty, id) in [
+            (FlashImageType::CaliptraFmcRt, CALIPTRA_FMC_RT_IDENTIFIER),
+            (FlashImageType::SocManifest, SOC_MANIFEST_IDENTIFIER),
+            (FlashImageType::McuRt, MCU_RT_IDENTIFIER),
+        ] {
+            assert_eq!(FlashImageType::from_identifier(id), Some(ty));
+            assert_eq!(ty.to_identifier(), Some(id));
+        }
+
+        assert_eq!(FlashImageType::from_identifier(0x3), None);
+        assert_eq!(FlashImageType::from_identifier(0x0fff), None);
+        assert_eq!(
+            FlashImageType::from_identifier(SOC_IMAGES_END_IDENTIFIER),
+            None
+        );
+    }
+
+    #[test]
+    fn test_image_headers() {
+        let ids = [MCU_RT_IDENTIFIER, 0x1003, 0x42];
+        let mut flash_header = header(ChecksumAlgo::Crc32);
+        flash_header.image_count = ids.len() as u16;
+
+        let mut buf = [0u8; 128];
+        buf[..core::mem::size_of::<FlashHeader>()].copy_from_slice(flash_header.as_bytes());
+        for (i, &identifier) in ids.iter().enumerate() {
+            let image_header = ImageHeader {
+                identifier,
+                offset: 0,
+                size: i as u32,
+                image_checksum: 0,
+                image_header_checksum: 0,
+            };
+            let offset = flash_header.image_headers_offset as usize + i * IMAGE_HEADER_SIZE;
+            buf[offset..offset + IMAGE_HEADER_SIZE].copy_from_slice(image_header.as_bytes());
+        }
+
+        let types: [Option<FlashImageType>; 3] = [
+            Some(FlashImageType::McuRt),
+            Some(FlashImageType::SocImage(3)),
+            None,
+        ];
+        let mut count = 0;
+        for (i, (ty, image_header)) in flash_header.image_headers(&buf).enumerate() {
+            assert_eq!(ty, types[i]);
+            assert_eq!(image_header.identifier, ids[i]);
+            count += 1;
+        }
+        assert_eq!(count, ids.len());
+
+        let found = flash_header
+            .find_image(&buf, FlashImageType::SocImage(3))
+            .unwrap();
+        assert_eq!(found.size, 1);
+        assert!(flash_header
+            .find_image(&buf, FlashImageType::SocManifest)
+            .is_none());
+
+        // A truncated image yields only the complete headers
+        let end = flash_header.image_headers_offset as usize + IMAGE_HEADER_SIZE + 4;
+        assert_eq!(flash_header.image_headers(&buf[..end]).count(), 1);
+    }
+
+    fn image(algo: ChecksumAlgo, body: &[u8]) -> [u8; 128] {
+        let mut header = ImageHeader {
+            identifier: MCU_RT_IDENTIFIER,
+            offset: 0x100,
+            size: body.len() as u32,
+            image_checksum: algo.checksum(body),
+            image_header_checksum: 0,
+        };
+        header.recompute_checksum(algo);
+
+        let mut buf = [0u8; 128];
+        buf[..IMAGE_HEADER_SIZE].copy_from_slice(header.as_bytes());
+        buf[IMAGE_HEADER_SIZE..IMAGE_HEADER_SIZE + body.len()].copy_from_slice(body);
+        buf
+    }
+
+    fn verify_chunked(algo: ChecksumAlgo, data: &[u8], chunk_size: usize) -> bool {
+        let mut verifier = FlashImageVerifier::new(algo);
+        for chunk in data.chunks(chunk_size) {
+            verifier.update(chunk);
+        }
+        verifier.finalize()
+    }
+
+    #[test]
+    fn test_flash_image_verifier() {
+        let body = b"MCU Runtime Data - QWERTYUIOPASDFGHJKLZXCVBNM0123456789";
+        let len = IMAGE_HEADER_SIZE + body.len();
+
+        for algo in [ChecksumAlgo::Sum, ChecksumAlgo::Crc32] {
+            let buf = image(algo, body);
+            for chunk_size in [1, 7, len] {
+                assert!(verify_chunked(algo, &buf[..len], chunk_size));
+            }
+
+            // Corrupt the image checksum field
+            let mut bad = buf;
+            bad[offset_of!(ImageHeader, image_checksum) + 1] ^= 1;
+            for chunk_size in [1, 7, len] {
+                assert!(!verify_chunked(algo, &bad[..len], chunk_size));
+            }
+
+            // Corrupt the body
+            let mut bad = buf;
+            bad[len - 1] ^= 0x80;
+            for chunk_size in [1, 7, len] {
+                assert!(!verify_chunked(algo, &bad[..len], chunk_size));
+            }
+
+            // Truncated and oversized streams
+            assert!(!verify_chunked(algo, &buf[..len - 1], 7));
+            assert!(!verify_chunked(algo, &buf[..len + 1], 7));
+        }
     }
 }
@@ -46,7 +46,7 @@ where
     const MIN_SIZE: usize = core::mem::size_of::<Self>();
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CommandId(pub u32);
 
 impl CommandId {
@@ -8,6 +8,7 @@ pub enum I3cError {
     NoMoreAddresses,
     DeviceAttachedWithoutAddress,
     InvalidAddress,
+    AddressCollision,
     TargetNotFound,
     TargetNoResponseReady,
     InvalidTcriCommand,
@@ -34,8 +34,10 @@ Abstract:
 --*/
 
 use crate::i3c::{
-    I3cBusCommand, I3cBusResponse, I3cTcriCommand, I3cTcriCommandXfer, ResponseDescriptor,
+    DynamicI3cAddress, I3cBusCommand, I3cBusResponse, I3cError, I3cTcriCommand, I3cTcriCommandXfer,
+    ResponseDescriptor,
 };
+use mcu_config::McuStraps;
 use std::io::{ErrorKind, Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -47,12 +49,78 @@ use zerocopy::{transmute, FromBytes, IntoBytes};
 
 pub const CRC8_SMBUS: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_SMBUS);
 
+/// Exit code used by the emulator when the I3C socket port is already in use.
+///
+/// This is sysexits' `EX_TEMPFAIL`, and the emulator reserves it: a firmware exit code that
+/// would produce the same process status is reported as 1 instead, so test runners can retry
+/// on another port without mistaking a firmware failure for a port collision.
+pub const I3C_PORT_IN_USE_EXIT_CODE: i32 = 75;
+
+/// The emulator's I3C socket: the port it listens on and how the controller addresses the
+/// MCU target.
+#[derive(Clone, Copy, Debug)]
+pub struct I3cSocket {
+    port: u16,
+    fixed_dynamic_address: Option<DynamicI3cAddress>,
+}
+
+impl I3cSocket {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            fixed_dynamic_address: None,
+        }
+    }
+
+    /// Makes the controller assign `addr` to the MCU target during ENTDAA instead of the
+    /// first free address, so tests can rely on a known target address.
+    ///
+    /// Fails if `addr` is not a valid dynamic address, or if it is the target's static
+    /// address in `straps`, the straps the platform was configured with.
+    pub fn with_fixed_dynamic_address(
+        mut self,
+        addr: u8,
+        straps: &McuStraps,
+    ) -> Result<Self, I3cError> {
+        if addr == straps.i3c_static_addr {
+            return Err(I3cError::AddressCollision);
+        }
+        self.fixed_dynamic_address = Some(DynamicI3cAddress::new(addr)?);
+        Ok(self)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// The address set with [`I3cSocket::with_fixed_dynamic_address`], if any.
+    pub fn fixed_dynamic_address(&self) -> Option<DynamicI3cAddress> {
+        self.fixed_dynamic_address
+    }
+
+    /// Binds the port and starts serving it, as [`try_start_i3c_socket`] does.
+    pub fn start(
+        &self,
+        running: &'static AtomicBool,
+    ) -> std::io::Result<(Receiver<I3cBusCommand>, Sender<I3cBusResponse>)> {
+        try_start_i3c_socket(running, self.port)
+    }
+}
+
 pub fn start_i3c_socket(
     running: &'static AtomicBool,
     port: u16,
 ) -> (Receiver<I3cBusCommand>, Sender<I3cBusResponse>) {
-    let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
-        .expect("Failed to bind TCP socket for port");
+    try_start_i3c_socket(running, port).expect("Failed to bind TCP socket for port")
+}
+
+/// Same as [`start_i3c_socket`], but returns an error instead of panicking if the port
+/// cannot be bound.
+pub fn try_start_i3c_socket(
+    running: &'static AtomicBool,
+    port: u16,
+) -> std::io::Result<(Receiver<I3cBusCommand>, Sender<I3cBusResponse>)> {
+    let listener = TcpListener::bind(format!("127.0.0.1:{}", port))?;
 
     let (bus_command_tx, bus_command_rx) = mpsc::channel::<I3cBusCommand>();
     let (bus_response_tx, bus_response_rx) = mpsc::channel::<I3cBusResponse>();
@@ -60,7 +128,7 @@ pub fn start_i3c_socket(
         handle_i3c_socket_loop(running, listener, bus_response_rx, bus_command_tx)
     });
 
-    (bus_command_rx, bus_response_tx)
+    Ok((bus_command_rx, bus_response_tx))
 }
 
 pub fn handle_i3c_socket_loop(
@@ -164,3 +232,30 @@ fn handle_i3c_socket_connection(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_dynamic_address() {
+        let straps = McuStraps::builder().with_i3c_static_addr(0x20).build();
+        let socket = I3cSocket::new(0)
+            .with_fixed_dynamic_address(0x3a, &straps)
+            .unwrap();
+        assert_eq!(
+            socket.fixed_dynamic_address(),
+            Some(DynamicI3cAddress::new(0x3a).unwrap())
+        );
+        assert_eq!(I3cSocket::new(0).fixed_dynamic_address(), None);
+
+        assert!(matches!(
+            I3cSocket::new(0).with_fixed_dynamic_address(0x20, &straps),
+            Err(I3cError::AddressCollision)
+        ));
+        assert!(matches!(
+            I3cSocket::new(0).with_fixed_dynamic_address(0x7e, &straps),
+            Err(I3cError::InvalidAddress)
+        ));
+    }
+}
@@ -19,6 +19,22 @@ use std::sync::{Arc, Condvar, Mutex};
 
 pub const MCTP_TAG_MASK: u8 = 0x07;
 
+/// Parameters controlling how messages are split into MCTP packets.
+#[derive(Debug, Clone, Copy)]
+pub struct MctpTransportConfig {
+    /// Maximum number of message bytes carried by a single MCTP packet.
+    /// Larger messages are fragmented across several packets.
+    pub max_packet_payload: usize,
+}
+
+impl Default for MctpTransportConfig {
+    fn default() -> Self {
+        Self {
+            max_packet_payload: MAX_PLDM_PAYLOAD_SIZE,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 enum MctpPldmSocketState {
     Idle,
@@ -31,6 +47,7 @@ pub struct MctpPldmSocket {
     dest: EndpointId,
     target_addr: u8,
     msg_tag: u8,
+    config: MctpTransportConfig,
     context: Arc<(Mutex<MctpPldmSocketData>, Condvar)>,
     stream: BufferedStream,
     response_msg_tag: Arc<Mutex<u8>>,
@@ -44,7 +61,7 @@ struct MctpPldmSocketData {
 impl PldmSocket for MctpPldmSocket {
     fn send(&self, payload: &[u8]) -> Result<(), PldmTransportError> {
         let mut mctp_util = MctpUtil::new();
-        mctp_util.set_pkt_payload_size(MAX_PLDM_PAYLOAD_SIZE);
+        mctp_util.set_pkt_payload_size(self.config.max_packet_payload);
         let mut mctp_common_header = MctpCommonHeader(0);
         mctp_common_header.set_ic(0);
         mctp_common_header.set_msg_type(MCTP_PLDM_MSG_TYPE);
@@ -128,7 +145,7 @@ impl PldmSocket for MctpPldmSocket {
         // We are in duplex mode, so we can receive packets
         // without waiting for the first response
         let mut mctp_util = MctpUtil::new();
-        mctp_util.set_pkt_payload_size(MAX_PLDM_PAYLOAD_SIZE);
+        mctp_util.set_pkt_payload_size(self.config.max_packet_payload);
         let mut stream = self
             .stream
             .try_clone()
@@ -164,6 +181,7 @@ impl PldmSocket for MctpPldmSocket {
             dest: self.dest,
             target_addr: self.target_addr,
             msg_tag: self.msg_tag,
+            config: self.config,
             context: self.context.clone(),
             stream: self.stream.try_clone().unwrap(),
             response_msg_tag: self.response_msg_tag.clone(),
@@ -175,11 +193,28 @@ impl PldmSocket for MctpPldmSocket {
 pub struct MctpTransport {
     port: u16,
     target_addr: DynamicI3cAddress,
+    config: MctpTransportConfig,
 }
 
 impl MctpTransport {
     pub fn new(port: u16, target_addr: DynamicI3cAddress) -> Self {
-        Self { port, target_addr }
+        Self::with_config(port, target_addr, MctpTransportConfig::default())
+    }
+
+    pub fn with_config(
+        port: u16,
+        target_addr: DynamicI3cAddress,
+        config: MctpTransportConfig,
+    ) -> Self {
+        assert!(
+            config.max_packet_payload > 0,
+            "MCTP packet payload size must be non-zero"
+        );
+        Self {
+            port,
+            target_addr,
+            config,
+        }
     }
 }
 
@@ -198,6 +233,7 @@ impl PldmTransport<MctpPldmSocket> for MctpTransport {
             dest,
             target_addr: self.target_addr.into(),
             msg_tag,
+            config: self.config,
             stream,
             context: Arc::new((
                 Mutex::new(MctpPldmSocketData {
@@ -210,3 +246,95 @@ impl PldmTransport<MctpPldmSocket> for MctpTransport {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i3c::{I3cTcriCommand, ResponseDescriptor};
+    use crate::i3c_socket_server::{IncomingHeader, OutgoingHeader, CRC8_SMBUS};
+    use crate::mctp_util::base_protocol::{MCTPHdr, MCTP_HDR_SIZE};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use zerocopy::{transmute, FromBytes};
+
+    const TARGET_ADDR: u8 = 0x3a;
+
+    // Reads a private write issued by the socket and returns the MCTP packet without the PEC.
+    fn read_private_write(stream: &mut TcpStream) -> Vec<u8> {
+        let mut header_bytes = [0u8; 9];
+        stream.read_exact(&mut header_bytes).unwrap();
+        let header: IncomingHeader = transmute!(header_bytes);
+        assert_eq!(header.to_addr, TARGET_ADDR);
+        let command = header.command;
+        let cmd: I3cTcriCommand = command.try_into().unwrap();
+        let mut data = vec![0u8; cmd.data_len()];
+        stream.read_exact(&mut data).unwrap();
+        data.pop();
+        data
+    }
+
+    // Hands a packet back to the socket the way a target does: an IBI followed by the private read data.
+    fn write_private_read(stream: &mut TcpStream, pkt: &[u8]) {
+        let mut digest = CRC8_SMBUS.digest();
+        digest.update(&[(TARGET_ADDR << 1) | 1]);
+        digest.update(pkt);
+        let mut data = pkt.to_vec();
+        data.push(digest.finalize());
+
+        let mut resp = ResponseDescriptor(0);
+        for (ibi, payload) in [(0xae, &[][..]), (0, &data[..])] {
+            resp.set_data_length(payload.len() as u16);
+            let header = OutgoingHeader {
+                ibi,
+                from_addr: TARGET_ADDR,
+                response_descriptor: resp,
+            };
+            let header_bytes: [u8; 6] = transmute!(header);
+            stream.write_all(&header_bytes).unwrap();
+            stream.write_all(payload).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_fragmented_loopback() {
+        let config = MctpTransportConfig {
+            max_packet_payload: 16,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let transport = MctpTransport::with_config(port, TARGET_ADDR.into(), config);
+        let socket = transport
+            .create_socket(EndpointId(8), EndpointId(0))
+            .unwrap();
+        let (mut peer, _) = listener.accept().unwrap();
+        // Skip the responder handshake so the message is sent as a plain request
+        socket.context.0.lock().unwrap().state = MctpPldmSocketState::DuplexReady;
+
+        let mut msg: Vec<u8> = (0..100).collect();
+        msg[0] = 0x80;
+        socket.send(&msg).unwrap();
+
+        // The MCTP common header byte is prepended to the message
+        let num_pkts = (msg.len() + 1).div_ceil(config.max_packet_payload);
+        let pkts: Vec<Vec<u8>> = (0..num_pkts)
+            .map(|_| read_private_write(&mut peer))
+            .collect();
+        let mut reassembled = Vec::new();
+        for (i, pkt) in pkts.iter().enumerate() {
+            let hdr =
+                MCTPHdr::<[u8; MCTP_HDR_SIZE]>::read_from_bytes(&pkt[..MCTP_HDR_SIZE]).unwrap();
+            assert!(pkt.len() - MCTP_HDR_SIZE <= config.max_packet_payload);
+            assert_eq!(hdr.som(), (i == 0) as u8);
+            assert_eq!(hdr.eom(), (i == num_pkts - 1) as u8);
+            assert_eq!(hdr.pkt_seq(), (i % 4) as u8);
+            reassembled.extend_from_slice(&pkt[MCTP_HDR_SIZE..]);
+        }
+        assert_eq!(&reassembled[1..], &msg[..]);
+
+        for pkt in &pkts {
+            write_private_read(&mut peer, pkt);
+        }
+        let rx = socket.receive(None).unwrap();
+        assert_eq!(&rx.payload.data[..rx.payload.len], &msg[..]);
+    }
+}
@@ -107,7 +107,7 @@ impl MctpUtil {
         self.msg_tag
     }
 
-    /// Sends a single packet message to the target address and waits for a response.
+    /// Sends a message to the target address and waits for a single packet response.
     /// Retries up to 10 times if no response is received.
     /// This function will block until a response is received or the retry limit is reached.
     ///
@@ -128,9 +128,10 @@ impl MctpUtil {
     ) -> Option<Vec<u8>> {
         self.new_req(msg_tag);
         let pkts = self.packetize(msg);
-        assert!(pkts.len() == 1, "Only one packet is expected in message");
         let mut i3c_state = I3cControllerState::Start;
         let msg_type = msg[0];
+        // Index of the first packet not yet written
+        let mut next_pkt = 0;
 
         let mut retry = 100;
 
@@ -144,8 +145,14 @@ impl MctpUtil {
                 }
 
                 I3cControllerState::SendPrivateWrite => {
-                    let write_pkt = pkts.front().unwrap().clone();
-                    if stream.send_private_write(target_addr, write_pkt) {
+                    // A failed write is retried on the next pass without resending the
+                    // packets before it.
+                    while next_pkt < pkts.len()
+                        && stream.send_private_write(target_addr, pkts[next_pkt].clone())
+                    {
+                        next_pkt += 1;
+                    }
+                    if next_pkt == pkts.len() {
                         i3c_state = I3cControllerState::WaitForIbi;
                         sleep_emulator_ticks(100_000);
                     }
@@ -156,6 +163,7 @@ impl MctpUtil {
                     } else {
                         retry -= 1;
                         println!("MCTP_UTIL: IBI not received. Retrying {}...", retry);
+                        next_pkt = 0;
                         i3c_state = I3cControllerState::SendPrivateWrite;
                     }
                 }
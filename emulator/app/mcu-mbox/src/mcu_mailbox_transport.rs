@@ -21,10 +21,9 @@ impl McuMailboxTransport {
         }
 
         // Sender attempts to lock mailbox by reading MBOX_LOCK register
-        if self.mbox.regs.lock().unwrap().is_locked() {
+        if !self.mbox.acquire_lock() {
             return Err(McuMailboxError::Locked);
         }
-        self.mbox.regs.lock().unwrap().lock();
 
         // Sender writes data to MBOX_SRAM
         for (index, chunk) in payload.chunks(4).enumerate() {
@@ -4,6 +4,34 @@
 mod test {
     use crate::dis::*;
 
+    #[test]
+    fn test_trace_line() {
+        use crate::emulator::trace_line;
+        use crate::TraceFormat;
+
+        assert_eq!(
+            trace_line(TraceFormat::Text, 4, 0x10018193, Some((3, 256))),
+            "0x00000004   10018193          addi          gp,gp,256"
+        );
+        let json: serde_json::Value = serde_json::from_str(&trace_line(
+            TraceFormat::JsonLines,
+            4,
+            0x10018193,
+            Some((3, 256)),
+        ))
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "pc": 4,
+                "insn": 0x10018193u32,
+                "mnemonic": "addi gp,gp,256",
+                "rd": 3,
+                "rd_val": 256,
+            })
+        );
+    }
+
     #[test]
     fn test_addi() {
         let dis = disasm_inst(RvIsa::Rv32, 4, 0x10018193);
@@ -5,12 +5,22 @@ use elf::endian::AnyEndian;
 use elf::ElfBytes;
 use std::io::{Error, ErrorKind};
 
+/// A PT_LOAD segment as it appears in memory, with the bss tail (`p_memsz - p_filesz`)
+/// zero-filled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElfSegment {
+    pub addr: u32,
+    pub data: Vec<u8>,
+    /// Number of leading bytes of `data` that come from the file (`p_filesz`)
+    pub file_size: usize,
+}
+
 /// ELF Executable
 #[derive(Default)]
 pub struct ElfExecutable {
     load_addr: u32,
     entry_point: u32,
-    content: Vec<u8>,
+    segments: Vec<ElfSegment>,
 }
 
 pub fn load_into_image(
@@ -34,7 +44,7 @@ pub fn load_into_image(
 impl ElfExecutable {
     /// Create new instance of `ElfExecutable`.
     pub fn new(elf_bytes: &[u8]) -> Result<Self, Error> {
-        let mut content = vec![];
+        let mut loaded_segments = vec![];
 
         let elf_file = ElfBytes::<AnyEndian>::minimal_parse(elf_bytes).map_err(|e| {
             Error::new(
@@ -69,15 +79,27 @@ impl ElfExecutable {
             let segment_data = elf_file
                 .segment_data(&segment)
                 .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
-            if segment_data.is_empty() {
-                continue;
+            if segment.p_memsz > 0 {
+                let memsz = usize::try_from(segment.p_memsz)
+                    .ok()
+                    .filter(|&memsz| memsz >= segment_data.len())
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Segment at 0x{:08x} has p_memsz smaller than p_filesz",
+                                segment.p_paddr
+                            ),
+                        )
+                    })?;
+                let mut data = segment_data.to_vec();
+                data.resize(memsz, 0);
+                loaded_segments.push(ElfSegment {
+                    addr: segment.p_paddr as u32,
+                    data,
+                    file_size: segment_data.len(),
+                });
             }
-            load_into_image(
-                &mut content,
-                load_addr,
-                segment.p_paddr as u32,
-                segment_data,
-            )?;
         }
 
         let entry_point = elf_file.ehdr.e_entry as u32;
@@ -85,9 +107,43 @@ impl ElfExecutable {
         Ok(Self {
             load_addr,
             entry_point,
-            content,
+            segments: loaded_segments,
         })
     }
+
+    /// Write every PT_LOAD segment at its physical address into the region that contains it.
+    /// `regions` are `(base address, memory)` pairs. Fails without writing anything if a
+    /// segment does not fit entirely within a single region. Segments with no file contents
+    /// (pure bss) outside every region are skipped, as the program's startup code clears them.
+    pub fn load_into_regions(&self, regions: &mut [(u32, &mut [u8])]) -> Result<(), Error> {
+        let find_region = |segment: &ElfSegment, regions: &[(u32, &mut [u8])]| {
+            regions.iter().position(|(base, memory)| {
+                segment.addr >= *base
+                    && (segment.addr - base) as usize + segment.data.len() <= memory.len()
+            })
+        };
+        for segment in &self.segments {
+            if segment.file_size > 0 && find_region(segment, regions).is_none() {
+                Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Segment 0x{:08x}..0x{:08x} is outside mapped memory",
+                        segment.addr,
+                        segment.addr as u64 + segment.data.len() as u64
+                    ),
+                ))?;
+            }
+        }
+        for segment in &self.segments {
+            let Some(index) = find_region(segment, regions) else {
+                continue;
+            };
+            let (base, memory) = &mut regions[index];
+            let offset = (segment.addr - *base) as usize;
+            memory[offset..][..segment.data.len()].copy_from_slice(&segment.data);
+        }
+        Ok(())
+    }
 }
 
 impl ElfExecutable {
@@ -101,15 +157,160 @@ impl ElfExecutable {
         self.entry_point
     }
 
-    /// Executable content
-    pub fn content(&self) -> &Vec<u8> {
-        &self.content
+    /// Executable content: the file contents of every segment, placed at its offset from
+    /// the load address. Built on demand, as segments in distant regions make it large.
+    pub fn content(&self) -> Vec<u8> {
+        let mut content = vec![];
+        for segment in self.segments.iter().filter(|s| s.file_size > 0) {
+            // Segment addresses are never below the load address, the lowest of them
+            let _ = load_into_image(
+                &mut content,
+                self.load_addr,
+                segment.addr,
+                &segment.data[..segment.file_size],
+            );
+        }
+        content
+    }
+
+    /// Loadable segments, in program header order
+    pub fn segments(&self) -> &[ElfSegment] {
+        &self.segments
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::elf::load_into_image;
+    use crate::elf::{load_into_image, ElfExecutable, ElfSegment};
+
+    /// Build a minimal little-endian RV32 executable with one PT_LOAD segment per
+    /// `(paddr, file data, memsz)` entry.
+    fn build_elf(entry: u32, segments: &[(u32, &[u8], u32)]) -> Vec<u8> {
+        const EHDR_SIZE: u32 = 52;
+        const PHDR_SIZE: u32 = 32;
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1];
+        elf.resize(16, 0);
+        elf.extend_from_slice(&2u16.to_le_bytes()); // e_type = ET_EXEC
+        elf.extend_from_slice(&0xf3u16.to_le_bytes()); // e_machine = EM_RISCV
+        elf.extend_from_slice(&1u32.to_le_bytes()); // e_version
+        elf.extend_from_slice(&entry.to_le_bytes());
+        elf.extend_from_slice(&EHDR_SIZE.to_le_bytes()); // e_phoff
+        elf.extend_from_slice(&0u32.to_le_bytes()); // e_shoff
+        elf.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+        elf.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+        elf.extend_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        elf.extend_from_slice(&(segments.len() as u16).to_le_bytes());
+        elf.extend_from_slice(&40u16.to_le_bytes()); // e_shentsize
+        elf.extend_from_slice(&0u16.to_le_bytes()); // e_shnum
+        elf.extend_from_slice(&0u16.to_le_bytes()); // e_shstrndx
+
+        let mut offset = EHDR_SIZE + PHDR_SIZE * segments.len() as u32;
+        for (paddr, data, memsz) in segments {
+            for field in [1, offset, *paddr, *paddr, data.len() as u32, *memsz, 7, 4] {
+                elf.extend_from_slice(&field.to_le_bytes());
+            }
+            offset += data.len() as u32;
+        }
+        for (_, data, _) in segments {
+            elf.extend_from_slice(data);
+        }
+        elf
+    }
+
+    #[test]
+    fn test_load_segments_with_gap() {
+        let elf = build_elf(
+            0x4000_0000,
+            &[
+                (0x4000_0000, b"codecode", 8),
+                // .data followed by a bss tail, well past the end of the first segment.
+                (0x4000_1010, b"data", 16),
+            ],
+        );
+        let elf = ElfExecutable::new(&elf).unwrap();
+        assert_eq!(elf.load_addr(), 0x4000_0000);
+        assert_eq!(
+            elf.segments(),
+            &[
+                ElfSegment {
+                    addr: 0x4000_0000,
+                    data: b"codecode".to_vec(),
+                    file_size: 8,
+                },
+                ElfSegment {
+                    addr: 0x4000_1010,
+                    data: b"data\0\0\0\0\0\0\0\0\0\0\0\0".to_vec(),
+                    file_size: 4,
+                },
+            ]
+        );
+
+        let mut code = [0xaa; 0x100];
+        let mut data = [0xaa; 0x100];
+        elf.load_into_regions(&mut [(0x4000_0000, &mut code), (0x4000_1000, &mut data)])
+            .unwrap();
+        assert_eq!(&code[..8], b"codecode");
+        assert_eq!(code[8], 0xaa);
+        assert_eq!(&data[..0x10], &[0xaa; 0x10]);
+        assert_eq!(&data[0x10..0x14], b"data");
+        assert_eq!(&data[0x14..0x20], &[0; 12]);
+        assert_eq!(data[0x20], 0xaa);
+
+        // The flat content covers the file contents only, without the bss tail
+        let content = elf.content();
+        assert_eq!(content.len(), 0x1014);
+        assert_eq!(&content[..8], b"codecode");
+        assert_eq!(&content[0x1010..], b"data");
+    }
+
+    #[test]
+    fn test_load_into_rom_sram_and_dccm() {
+        // ROM code, .data at a DCCM address, and a table in SRAM, out of address order
+        let elf = build_elf(
+            0x0000_0000,
+            &[
+                (0x0000_0000, b"romcode!", 8),
+                (0x5000_0008, b"dccmdata", 12),
+                (0x4000_0004, b"sram", 4),
+            ],
+        );
+        let elf = ElfExecutable::new(&elf).unwrap();
+        let mut rom = [0xaa; 0x40];
+        let mut sram = [0xaa; 0x40];
+        let mut dccm = [0xaa; 0x40];
+        elf.load_into_regions(&mut [
+            (0x0000_0000, &mut rom),
+            (0x4000_0000, &mut sram),
+            (0x5000_0000, &mut dccm),
+        ])
+        .unwrap();
+        assert_eq!(&rom[..8], b"romcode!");
+        assert_eq!(rom[8], 0xaa);
+        assert_eq!(&sram[..4], &[0xaa; 4]);
+        assert_eq!(&sram[4..8], b"sram");
+        assert_eq!(&dccm[..8], &[0xaa; 8]);
+        assert_eq!(&dccm[8..16], b"dccmdata");
+        assert_eq!(&dccm[16..20], &[0; 4]);
+        assert_eq!(dccm[20], 0xaa);
+    }
+
+    #[test]
+    fn test_load_segment_outside_regions() {
+        let elf = build_elf(
+            0x4000_0000,
+            &[(0x4000_0000, b"code", 4), (0x4000_00fe, b"da", 4)],
+        );
+        let elf = ElfExecutable::new(&elf).unwrap();
+        let mut memory = [0xaa; 0x100];
+        assert_eq!(
+            elf.load_into_regions(&mut [(0x4000_0000, &mut memory)])
+                .unwrap_err()
+                .to_string(),
+            "Segment 0x400000fe..0x40000102 is outside mapped memory"
+        );
+        // Nothing is written when any segment is rejected.
+        assert_eq!(memory, [0xaa; 0x100]);
+    }
 
     #[test]
     fn test_load_into_image() {
@@ -130,4 +331,18 @@ mod test {
             "Section address 0x3fffffff is below image base address 0x40000000"
         );
     }
+
+    #[test]
+    fn test_load_skips_unmapped_bss() {
+        let elf = build_elf(
+            0x4000_0000,
+            &[(0x4000_0000, b"code", 4), (0x5000_0000, b"", 0x20)],
+        );
+        let elf = ElfExecutable::new(&elf).unwrap();
+        let mut memory = [0xaa; 0x100];
+        elf.load_into_regions(&mut [(0x4000_0000, &mut memory)])
+            .unwrap();
+        assert_eq!(&memory[..4], b"code");
+        assert_eq!(memory[4], 0xaa);
+    }
 }
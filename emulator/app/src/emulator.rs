@@ -15,35 +15,44 @@ Abstract:
 use crate::dis;
 use crate::doe_mbox_fsm;
 use crate::elf;
+use crate::lockstep::{written_register, Lockstep};
 use crate::tests;
-use caliptra_emu_bus::{Bus, Clock, Timer};
+use caliptra_emu_bus::{Bus, BusError, Clock, Timer};
+use caliptra_emu_cpu::xreg_file::XReg;
 use caliptra_emu_cpu::{Cpu, Pic, RvInstr, StepAction};
 use caliptra_emu_periph::CaliptraRootBus as CaliptraMainRootBus;
+use caliptra_emu_types::RvSize;
 use caliptra_image_types::FwVerificationPqcKeyType;
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, ValueEnum};
 use clap_num::maybe_hex;
 use crossterm::event::{Event, KeyCode, KeyEvent};
 use emulator_bmc::Bmc;
 use emulator_caliptra::BytesOrPath;
 use emulator_caliptra::{start_caliptra, StartCaliptraArgs};
-use emulator_consts::{DEFAULT_CPU_ARGS, RAM_ORG, ROM_SIZE};
-#[allow(unused_imports)]
-use emulator_periph::MciMailboxRequester;
+use emulator_consts::{DEFAULT_CPU_ARGS, RAM_ORG, RAM_SIZE, ROM_SIZE};
 use emulator_periph::{
-    CaliptraToExtBus, DoeMboxPeriph, DummyDoeMbox, DummyFlashCtrl, I3c, I3cController, LcCtrl, Mci,
-    McuRootBus, McuRootBusArgs, McuRootBusOffsets, Otp, OtpArgs,
+    AccessCountingBus, BusAccessStats, BusPeripheral, CaliptraToExtBus, DoeMboxPeriph,
+    DummyDoeMbox, DummyFlashCtrl, EccInjectingBus, EmuCtrlExit, I3c, I3cController, LcCtrl, Mci,
+    MciMailboxRequester, McuMailbox0External, McuRootBus, McuRootBusArgs, McuRootBusOffsets, Otp,
+    OtpArgs, WatchHit, WatchingBus,
 };
 use emulator_registers_generated::axicdma::AxicdmaPeripheral;
 use emulator_registers_generated::root_bus::{AutoRootBus, AutoRootBusOffsets};
+use mcu_config::McuMemoryMap;
+use mcu_config_emulator::EMULATOR_MCU_STRAPS;
+use mcu_testing_common::i3c::{
+    I3cError, I3cTcriCommand, I3cTcriCommandXfer, ReguDataTransferCommand,
+};
 use mcu_testing_common::i3c_socket;
-use mcu_testing_common::i3c_socket_server::start_i3c_socket;
+use mcu_testing_common::i3c_socket_server::I3cSocket;
 use mcu_testing_common::mctp_transport::MctpTransport;
 use mcu_testing_common::mctp_util::base_protocol::LOCAL_TEST_ENDPOINT_EID;
 use mcu_testing_common::{MCU_RUNNING, MCU_RUNTIME_STARTED, MCU_TICKS, TICK_COND};
 use pldm_fw_pkg::FirmwareManifest;
 use pldm_ua::daemon::PldmDaemon;
 use pldm_ua::transport::{EndpointId, PldmTransport};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::fmt::Write as _;
 use std::fs::File;
 use std::io::{self, IsTerminal, Read, Write};
 use std::ops::Range;
@@ -54,6 +63,7 @@ use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 use tests::pldm_request_response_test::PldmRequestResponseTest;
+use zerocopy::FromBytes;
 
 // Type aliases for external shim callbacks
 pub type ExternalReadCallback =
@@ -65,6 +75,12 @@ pub type ExternalWriteCallback = Box<
         caliptra_emu_types::RvData,
     ) -> bool,
 >;
+/// Called with (mcause, mepc) when the MCU CPU enters its trap handler. Returning false
+/// requests a halt: the current `step` returns `StepAction::Break`.
+pub type ExternalTrapCallback = Box<dyn FnMut(u32, u32) -> bool>;
+
+/// The MCU CPU's view of the root bus: access counting, write watchpoints and ECC injection.
+pub type McuBus = AccessCountingBus<WatchingBus<EccInjectingBus<AutoRootBus>>>;
 
 fn parse_vendor_pqc_type(s: &str) -> Result<FwVerificationPqcKeyType, String> {
     match s.to_lowercase().trim() {
@@ -77,6 +93,16 @@ fn parse_vendor_pqc_type(s: &str) -> Result<FwVerificationPqcKeyType, String> {
     }
 }
 
+/// Output format of the instruction trace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    /// Human-readable disassembly, one instruction per line.
+    #[default]
+    Text,
+    /// One JSON object per line with `pc`, `insn` and `mnemonic` fields.
+    JsonLines,
+}
+
 #[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None, name = "Caliptra MCU Emulator")]
 pub struct EmulatorArgs {
@@ -103,6 +129,41 @@ pub struct EmulatorArgs {
     #[arg(short, long, default_value_t = false)]
     pub trace_instr: bool,
 
+    /// Format of the MCU instruction trace.
+    #[arg(long, value_enum, default_value_t = TraceFormat::Text)]
+    pub trace_format: TraceFormat,
+
+    /// Only trace MCU instructions at or above this PC.
+    #[arg(long, value_parser=maybe_hex::<u32>)]
+    pub trace_pc_start: Option<u32>,
+
+    /// Only trace MCU instructions below this PC.
+    #[arg(long, value_parser=maybe_hex::<u32>)]
+    pub trace_pc_end: Option<u32>,
+
+    /// Reference MCU instruction trace (in either `--trace-format`) to compare execution
+    /// against. The emulator stops with a fatal error at the first divergent instruction.
+    #[arg(long)]
+    pub lockstep_trace: Option<PathBuf>,
+
+    /// Seed for the Caliptra TRNG sources. Two runs with the same seed and
+    /// inputs produce identical traces. If not set, entropy is used.
+    #[arg(long)]
+    pub trng_seed: Option<u64>,
+
+    /// Keep only the most recent N bytes of captured UART output. Unbounded if not set.
+    #[arg(long)]
+    pub uart_capture_max_bytes: Option<usize>,
+
+    /// Print the MCU registers, PC, and trap CSRs alongside the UART output when the MCU CPU
+    /// stops with a fatal error.
+    #[arg(long, default_value_t = false)]
+    pub dump_state_on_exit: bool,
+
+    /// Exit with code 124 once the MCU clock reaches this many cycles. Unbounded if not set.
+    #[arg(long)]
+    pub max_cycles: Option<u64>,
+
     // These look backwards, but this is necessary so that the default is to capture stdin.
     /// Pass stdin to the MCU UART Rx.
     #[arg(long = "no-stdin-uart", action = ArgAction::SetFalse)]
@@ -130,6 +191,11 @@ pub struct EmulatorArgs {
     #[arg(long)]
     pub i3c_port: Option<u16>,
 
+    /// Dynamic address to assign to the MCU I3C target instead of the first free one.
+    /// Requires --i3c-port.
+    #[arg(long, value_parser=maybe_hex::<u8>, requires = "i3c_port")]
+    pub i3c_dynamic_address: Option<u8>,
+
     /// This is only needed if the IDevID CSR needed to be generated in the Caliptra Core.
     #[arg(long)]
     pub manufacturing_mode: bool,
@@ -154,10 +220,18 @@ pub struct EmulatorArgs {
     #[arg(long)]
     pub secondary_flash_image: Option<PathBuf>,
 
+    /// Cycles a flash controller read, program or erase takes to complete (default 200).
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub flash_op_latency: Option<u64>,
+
     /// HW revision in semver format (e.g., "2.0.0")
     #[arg(long, value_parser = semver::Version::parse, default_value = "2.0.0")]
     pub hw_revision: semver::Version,
 
+    /// TOML file describing the MCU memory map. Individual offset/size overrides take precedence.
+    #[arg(long)]
+    pub memory_map_file: Option<PathBuf>,
+
     /// Override ROM offset
     #[arg(long, value_parser=maybe_hex::<u32>)]
     pub rom_offset: Option<u32>,
@@ -260,14 +334,83 @@ pub struct EmulatorArgs {
     pub fuse_vendor_hashes_prod_partition: Option<String>,
 }
 
+/// Number of addressable CSRs in the RISC-V CSR address space.
+const CSR_COUNT: u32 = 4096;
+
+/// Writable trap setup and handling CSRs captured by [`Emulator::snapshot`], along with
+/// [`SNAPSHOT_PMP_CSRS`]. Read-only ID registers never change, and the cycle/instruction
+/// counters are left running with the shared clock.
+const SNAPSHOT_CSRS: &[u32] = &[
+    0x300, // mstatus
+    0x304, // mie
+    0x305, // mtvec
+    0x340, // mscratch
+    0x341, // mepc
+    0x342, // mcause
+    0x343, // mtval
+];
+
+/// `pmpcfg0`..`pmpcfg15` followed by `pmpaddr0`..`pmpaddr63`.
+const SNAPSHOT_PMP_CSRS: Range<u32> = 0x3a0..0x3f0;
+
+/// Checkpoint of the MCU CPU register file and writable RAM.
+///
+/// Peripherals with external side effects (I3C, UART, mailboxes) and the
+/// Caliptra core are not captured.
+#[derive(Clone)]
+pub struct EmulatorSnapshot {
+    xregs: [u32; 32],
+    csrs: Vec<(u32, u32)>,
+    pc: u32,
+    sram: Vec<u32>,
+    dccm: Vec<u32>,
+}
+
+/// Reset values of the MCU CPU CSRs and the reset vector.
+///
+/// Captured from a freshly constructed CPU, since the CPU model has no reset
+/// operation of its own.
+#[derive(Clone)]
+pub struct CpuResetState {
+    csrs: Vec<(u32, u32)>,
+    pc: u32,
+}
+
+impl CpuResetState {
+    /// Record the current CSR values and PC of `cpu` as its reset state.
+    pub fn capture<B: Bus>(cpu: &Cpu<B>) -> Self {
+        let csrs = (0..CSR_COUNT)
+            .filter_map(|addr| cpu.read_csr_machine(addr).ok().map(|val| (addr, val)))
+            .collect();
+        Self {
+            csrs,
+            pc: cpu.read_pc(),
+        }
+    }
+
+    /// Restore the CSRs, clear the general-purpose registers, and move the PC
+    /// back to the reset vector. Bus contents are not touched.
+    pub fn apply<B: Bus>(&self, cpu: &mut Cpu<B>) {
+        // Read-only CSRs reject the write; their value cannot have changed anyway.
+        for &(addr, val) in self.csrs.iter() {
+            let _ = cpu.write_csr_machine(addr, val);
+        }
+        for i in 1..32u32 {
+            let _ = cpu.write_xreg(XReg::from(i), 0);
+        }
+        cpu.write_pc(self.pc);
+    }
+}
+
 pub struct Emulator {
-    pub mcu_cpu: Cpu<AutoRootBus>,
+    pub mcu_cpu: Cpu<McuBus>,
     pub caliptra_cpu: Cpu<CaliptraMainRootBus>,
     pub bmc: Option<Bmc>,
     pub timer: Timer,
     pub trace_file: Option<File>,
     pub stdin_uart: Option<Arc<Mutex<Option<u8>>>>,
     pub sram_range: Range<u32>,
+    pub dccm_range: Range<u32>,
     #[allow(dead_code)]
     pub clock: Rc<Clock>,
     #[allow(dead_code)]
@@ -280,12 +423,62 @@ pub struct Emulator {
     pub doe_mbox_fsm: doe_mbox_fsm::DoeMboxFsm,
     pub i3c_address: Option<u8>,
     pub i3c_controller_join_handle: Option<JoinHandle<()>>,
+    /// Only MCU instructions with a PC in `[trace_pc_start, trace_pc_end)` are traced.
+    pub trace_pc_start: Option<u32>,
+    pub trace_pc_end: Option<u32>,
+    pub trace_format: TraceFormat,
+    /// MCU CPU state restored by [`Emulator::warm_reset`].
+    pub cpu_reset_state: CpuResetState,
+    /// Base address of the MCI block on the MCU bus.
+    pub mci_offset: u32,
+    /// Memory map of the MCU bus, advertised to GDB.
+    pub memory_map: McuMemoryMap,
+    /// MCI generic input wires as seen by the MCU; see [`Emulator::set_mcu_generic_input_wires`].
+    pub mci_generic_input_wires: Rc<RefCell<[u32; 2]>>,
+    /// Write [`Emulator::state_dump`] to the UART output when the MCU CPU stops with
+    /// `StepAction::Fatal`.
+    pub dump_state_on_exit: bool,
+    /// When the captured UART output ends with this marker, the marker is removed from the
+    /// buffer and [`Emulator::step`] returns `StepAction::Break`. Requires captured UART output.
+    pub exit_on_uart_marker: Option<String>,
+    /// Set when a step stopped because of `exit_on_uart_marker`; see
+    /// [`Emulator::take_uart_exit_marker_seen`].
+    pub uart_exit_marker_seen: bool,
+    /// Caliptra's CPU only steps while this is set (CPTRA_BOOT_GO); it is set by default.
+    pub caliptra_boot_go: bool,
+    /// iTRNG clock divider. Recorded for parity with the FPGA model; the emulated iTRNG
+    /// returns nibbles on demand and does not model its sampling rate.
+    pub itrng_divider: u32,
+    /// Invoked whenever the MCU CPU takes a trap; see [`ExternalTrapCallback`].
+    pub trap_callback: Option<ExternalTrapCallback>,
+    /// Once the MCU clock reaches this value, [`Emulator::step`] requests an exit with
+    /// [`CYCLE_BUDGET_EXIT_CODE`] and returns `StepAction::Fatal`.
+    pub max_cycles: Option<u64>,
+    /// Reference trace each executed MCU instruction is checked against; see
+    /// [`EmulatorArgs::lockstep_trace`].
+    pub lockstep: Option<Lockstep>,
+    /// Set once Caliptra reports it is ready for firmware; see [`Emulator::ready_for_fw`].
+    pub caliptra_ready_for_fw: Rc<Cell<bool>>,
+    /// Exit request the firmware wrote to the emulation control register.
+    pub emu_ctrl_exit: Option<EmuCtrlExit>,
+    /// The SoC's views of MCU mailboxes 0 and 1; see [`Emulator::set_axi_user`].
+    pub soc_mcu_mailboxes: Vec<McuMailbox0External>,
 }
 
+/// Offset of the RESET_REASON register within the MCI block.
+const MCI_RESET_REASON_OFFSET: u32 = 0x38;
+/// Offset of CPTRA_GENERIC_INPUT_WIRES within the Caliptra SoC interface.
+const SOC_GENERIC_INPUT_WIRES_OFFSET: u32 = 0xc4;
+/// Default AXI user of the SoC agent driving the MCU mailboxes.
+const DEFAULT_SOC_AXI_USER: u32 = 0x1;
+
+/// Exit code requested when the MCU clock reaches [`Emulator::max_cycles`] (as `timeout(1)`).
+pub const CYCLE_BUDGET_EXIT_CODE: u32 = 124;
+
 impl Emulator {
     /// Create an Emulator from command line arguments without external callbacks
     pub fn from_args(cli: EmulatorArgs, capture_uart_output: bool) -> std::io::Result<Self> {
-        Self::from_args_with_callbacks(cli, capture_uart_output, None, None)
+        Self::from_args_with_callbacks(cli, capture_uart_output, None, None, None)
     }
 
     /// Create an Emulator from command line arguments with optional external callbacks
@@ -294,6 +487,7 @@ impl Emulator {
         capture_uart_output: bool,
         external_read_callback: Option<ExternalReadCallback>,
         external_write_callback: Option<ExternalWriteCallback>,
+        external_trap_callback: Option<ExternalTrapCallback>,
     ) -> std::io::Result<Self> {
         #[cfg(feature = "test-flash-based-boot")]
         let is_flash_based_boot = true;
@@ -323,27 +517,17 @@ impl Emulator {
 
         let use_mcu_recovery_interface = is_flash_based_boot;
 
+        let caliptra_ready_for_fw = Rc::new(Cell::new(false));
         let (mut caliptra_cpu, soc_to_caliptra, ext_mci) = start_caliptra(&StartCaliptraArgs {
             rom: BytesOrPath::Path(cli.caliptra_rom),
             device_lifecycle,
             req_idevid_csr,
             use_mcu_recovery_interface,
+            trng_seed: cli.trng_seed,
+            ready_for_fw: Some(caliptra_ready_for_fw.clone()),
         })
         .expect("Failed to start Caliptra CPU");
 
-        let rom_buffer = read_binary(args_rom, 0)?;
-        if rom_buffer.len() > ROM_SIZE as usize {
-            println!("ROM File Size must not exceed {} bytes", ROM_SIZE);
-            exit(-1);
-        }
-        println!(
-            "Loaded ROM File {:?} of size {}",
-            args_rom,
-            rom_buffer.len(),
-        );
-
-        let mcu_firmware = read_binary(&cli.firmware, 0x4000_0000)?;
-
         let clock = Rc::new(Clock::new());
 
         let uart_output = if capture_uart_output {
@@ -362,6 +546,40 @@ impl Emulator {
         let mut mcu_root_bus_offsets = McuRootBusOffsets::default();
         let mut auto_root_bus_offsets = AutoRootBusOffsets::default();
 
+        if let Some(memory_map_file) = cli.memory_map_file.as_ref() {
+            let contents = std::fs::read_to_string(memory_map_file)?;
+            let memory_map = McuMemoryMap::from_toml(&contents).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Invalid memory map file {}: {}",
+                        memory_map_file.display(),
+                        e
+                    ),
+                )
+            })?;
+            mcu_root_bus_offsets.rom_offset = memory_map.rom_offset;
+            mcu_root_bus_offsets.rom_size = memory_map.rom_size;
+            mcu_root_bus_offsets.ram_offset = memory_map.sram_offset;
+            mcu_root_bus_offsets.ram_size = memory_map.sram_size;
+            mcu_root_bus_offsets.rom_dedicated_ram_offset = memory_map.dccm_offset;
+            mcu_root_bus_offsets.rom_dedicated_ram_size = memory_map.dccm_size;
+            mcu_root_bus_offsets.pic_offset = memory_map.pic_offset;
+            auto_root_bus_offsets.el2_pic_offset = memory_map.pic_offset;
+            auto_root_bus_offsets.i3c_offset = memory_map.i3c_offset;
+            auto_root_bus_offsets.i3c_size = memory_map.i3c_size;
+            auto_root_bus_offsets.mci_offset = memory_map.mci_offset;
+            auto_root_bus_offsets.mci_size = memory_map.mci_size;
+            auto_root_bus_offsets.mbox_offset = memory_map.mbox_offset;
+            auto_root_bus_offsets.mbox_size = memory_map.mbox_size;
+            auto_root_bus_offsets.soc_offset = memory_map.soc_offset;
+            auto_root_bus_offsets.soc_size = memory_map.soc_size;
+            auto_root_bus_offsets.otp_offset = memory_map.otp_offset;
+            auto_root_bus_offsets.otp_size = memory_map.otp_size;
+            auto_root_bus_offsets.lc_offset = memory_map.lc_offset;
+            auto_root_bus_offsets.lc_size = memory_map.lc_size;
+        }
+
         // Apply all the CLI offset overrides
         if let Some(rom_offset) = cli.rom_offset {
             mcu_root_bus_offsets.rom_offset = rom_offset;
@@ -452,16 +670,96 @@ impl Emulator {
             auto_root_bus_offsets.lc_size = lc_size;
         }
 
+        // Fail fast if the overrides produce overlapping regions
+        let memory_map = McuMemoryMap {
+            rom_offset: mcu_root_bus_offsets.rom_offset,
+            rom_size: mcu_root_bus_offsets.rom_size,
+            sram_offset: mcu_root_bus_offsets.ram_offset,
+            sram_size: mcu_root_bus_offsets.ram_size,
+            pic_offset: mcu_root_bus_offsets.pic_offset,
+            dccm_offset: mcu_root_bus_offsets.rom_dedicated_ram_offset,
+            dccm_size: mcu_root_bus_offsets.rom_dedicated_ram_size,
+            i3c_offset: auto_root_bus_offsets.i3c_offset,
+            i3c_size: auto_root_bus_offsets.i3c_size,
+            mci_offset: auto_root_bus_offsets.mci_offset,
+            mci_size: auto_root_bus_offsets.mci_size,
+            mbox_offset: auto_root_bus_offsets.mbox_offset,
+            mbox_size: auto_root_bus_offsets.mbox_size,
+            soc_offset: auto_root_bus_offsets.soc_offset,
+            soc_size: auto_root_bus_offsets.soc_size,
+            otp_offset: auto_root_bus_offsets.otp_offset,
+            otp_size: auto_root_bus_offsets.otp_size,
+            lc_offset: auto_root_bus_offsets.lc_offset,
+            lc_size: auto_root_bus_offsets.lc_size,
+            ..Default::default()
+        };
+        if let Err(overlaps) = memory_map.validate() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Overlapping memory regions: {:?}", overlaps),
+            ))?;
+        }
+
+        // ELF segments outside the ROM and firmware images (e.g. .data in DCCM) are
+        // preloaded into the RAM that contains them.
+        let mut sram_preload = vec![0u8; mcu_root_bus_offsets.ram_size as usize];
+        let mut dccm_preload = vec![0u8; mcu_root_bus_offsets.rom_dedicated_ram_size as usize];
+        let rom_buffer = read_binary(
+            args_rom,
+            0,
+            ROM_SIZE as usize,
+            &mut [
+                (mcu_root_bus_offsets.ram_offset, &mut sram_preload),
+                (
+                    mcu_root_bus_offsets.rom_dedicated_ram_offset,
+                    &mut dccm_preload,
+                ),
+            ],
+        )?;
+        if rom_buffer.len() > ROM_SIZE as usize {
+            println!("ROM File Size must not exceed {} bytes", ROM_SIZE);
+            exit(-1);
+        }
+        println!(
+            "Loaded ROM File {:?} of size {}",
+            args_rom,
+            rom_buffer.len(),
+        );
+
+        let mcu_firmware = read_binary(
+            &cli.firmware,
+            RAM_ORG,
+            RAM_SIZE as usize,
+            &mut [(
+                mcu_root_bus_offsets.rom_dedicated_ram_offset,
+                &mut dccm_preload,
+            )],
+        )?;
+
+        let bus_regions = bus_stats_regions(&mcu_root_bus_offsets, &auto_root_bus_offsets);
+
         let bus_args = McuRootBusArgs {
             offsets: mcu_root_bus_offsets.clone(),
             rom: rom_buffer,
             log_dir: args_log_dir.clone(),
             uart_output: uart_output.clone(),
+            uart_output_max_bytes: cli.uart_capture_max_bytes,
             uart_rx: stdin_uart.clone(),
             pic: pic.clone(),
             clock: clock.clone(),
         };
         let root_bus = McuRootBus::new(bus_args).unwrap();
+        root_bus
+            .ram
+            .borrow_mut()
+            .data_mut()
+            .copy_from_slice(&sram_preload);
+        root_bus
+            .rom_sram
+            .borrow_mut()
+            .data_mut()
+            .copy_from_slice(&dccm_preload);
+        let emu_ctrl_exit = root_bus.ctrl.exit_handle();
 
         // Create external communication bus
         let mut caliptra_to_ext = CaliptraToExtBus::new();
@@ -483,8 +781,23 @@ impl Emulator {
         println!("Starting I3C Socket, port {}", cli.i3c_port.unwrap_or(0));
 
         let mut i3c_controller = if let Some(i3c_port) = cli.i3c_port {
-            let (rx, tx) = start_i3c_socket(&MCU_RUNNING, i3c_port);
-            I3cController::new(rx, tx)
+            let mut socket = I3cSocket::new(i3c_port);
+            if let Some(addr) = cli.i3c_dynamic_address {
+                socket = socket
+                    .with_fixed_dynamic_address(addr, &EMULATOR_MCU_STRAPS)
+                    .map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("Invalid I3C dynamic address {:#x}: {:?}", addr, e),
+                        )
+                    })?;
+            }
+            I3cController::with_socket(&MCU_RUNNING, &socket).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("Failed to bind I3C socket to port {}: {}", i3c_port, e),
+                )
+            })?
         } else {
             I3cController::default()
         };
@@ -592,6 +905,7 @@ impl Emulator {
             PldmRequestResponseTest::run(pldm_socket);
         }
 
+        let flash_op_latency = cli.flash_op_latency;
         let create_flash_controller =
             |default_path: &str,
              error_irq: u8,
@@ -621,7 +935,7 @@ impl Emulator {
                     Some(PathBuf::from(default_path))
                 };
 
-                DummyFlashCtrl::new(
+                let mut flash_ctrl = DummyFlashCtrl::new(
                     &clock.clone(),
                     direct_read_region,
                     flash_file,
@@ -629,7 +943,11 @@ impl Emulator {
                     pic.register_irq(event_irq),
                     initial_content,
                 )
-                .unwrap()
+                .unwrap();
+                if let Some(cycles) = flash_op_latency {
+                    flash_ctrl.set_op_latency(cycles);
+                }
+                flash_ctrl
             };
 
         let primary_flash_initial_content = if cli.primary_flash_image.is_some() {
@@ -698,6 +1016,10 @@ impl Emulator {
 
         let mcu_mailbox0 = root_bus.mcu_mailbox0.clone();
         let mcu_mailbox1 = root_bus.mcu_mailbox1.clone();
+        let soc_mcu_mailboxes = vec![
+            mcu_mailbox0.as_external(MciMailboxRequester::SocAgent(DEFAULT_SOC_AXI_USER)),
+            mcu_mailbox1.as_external(MciMailboxRequester::SocAgent(DEFAULT_SOC_AXI_USER)),
+        ];
 
         let delegates: Vec<Box<dyn Bus>> = vec![
             Box::new(root_bus),
@@ -737,7 +1059,7 @@ impl Emulator {
             feature = "test-mcu-mbox-usermode",
             feature = "test-mcu-mbox-cmds",
         ))]
-        let ext_mcu_mailbox0 = mcu_mailbox0.as_external(MciMailboxRequester::SocAgent(1));
+        let ext_mcu_mailbox0 = soc_mcu_mailboxes[0].clone();
         let mci = Mci::new(
             &clock.clone(),
             ext_mci,
@@ -745,7 +1067,10 @@ impl Emulator {
             Some(mcu_mailbox0),
             Some(mcu_mailbox1),
         );
+        let mci_generic_input_wires = mci.generic_input_wires();
+        let sram_ecc_cor_count = mci.sram_ecc_cor_count();
 
+        let mci_offset = auto_root_bus_offsets.mci_offset;
         let mut auto_root_bus = AutoRootBus::new(
             delegates,
             Some(auto_root_bus_offsets),
@@ -797,7 +1122,15 @@ impl Emulator {
 
         let cpu_args = DEFAULT_CPU_ARGS;
 
-        let mut cpu = Cpu::new(auto_root_bus, clock.clone(), pic.clone(), cpu_args);
+        let mut cpu = Cpu::new(
+            AccessCountingBus::new(
+                WatchingBus::new(EccInjectingBus::new(auto_root_bus, sram_ecc_cor_count)),
+                bus_regions,
+            ),
+            clock.clone(),
+            pic.clone(),
+            cpu_args,
+        );
         cpu.write_pc(mcu_root_bus_offsets.rom_offset);
         cpu.register_events();
 
@@ -808,6 +1141,7 @@ impl Emulator {
             let (caliptra_event_sender, caliptra_event_receiver) = caliptra_cpu.register_events();
             let (mcu_event_sender, mcu_event_receiver) = cpu.register_events();
             cpu.bus
+                .bus
                 .i3c_periph
                 .as_mut()
                 .unwrap()
@@ -830,8 +1164,10 @@ impl Emulator {
             ));
 
             // load the firmware images and SoC manifest into the recovery interface emulator
-            let caliptra_firmware = read_binary(&cli.caliptra_firmware, RAM_ORG).unwrap();
-            let soc_manifest = read_binary(&cli.soc_manifest, 0).unwrap();
+            let caliptra_firmware =
+                read_binary(&cli.caliptra_firmware, RAM_ORG, RAM_SIZE as usize, &mut []).unwrap();
+            let soc_manifest =
+                read_binary(&cli.soc_manifest, 0, RAM_SIZE as usize, &mut []).unwrap();
             let bmc = bmc.as_mut().unwrap();
             bmc.push_recovery_image(caliptra_firmware);
             bmc.push_recovery_image(soc_manifest);
@@ -911,15 +1247,19 @@ impl Emulator {
 
         let sram_range = mcu_root_bus_offsets.ram_offset
             ..mcu_root_bus_offsets.ram_offset + mcu_root_bus_offsets.ram_size;
+        let dccm_range = mcu_root_bus_offsets.rom_dedicated_ram_offset
+            ..mcu_root_bus_offsets.rom_dedicated_ram_offset
+                + mcu_root_bus_offsets.rom_dedicated_ram_size;
 
         // Create the emulator instance
-        Ok(Self::new(
+        let mut emulator = Self::new(
             cpu,
             caliptra_cpu,
             instr_trace,
             stdin_uart,
             bmc,
             sram_range,
+            dccm_range,
             clock,
             pic,
             uart_output,
@@ -927,17 +1267,37 @@ impl Emulator {
             doe_mbox_fsm,
             Some(i3c_dynamic_address.into()),
             i3c_controller_join_handle,
-        ))
+        );
+        emulator.trace_pc_start = cli.trace_pc_start;
+        emulator.trace_pc_end = cli.trace_pc_end;
+        emulator.trace_format = cli.trace_format;
+        emulator.mci_offset = mci_offset;
+        emulator.memory_map = memory_map;
+        emulator.mci_generic_input_wires = mci_generic_input_wires;
+        emulator.soc_mcu_mailboxes = soc_mcu_mailboxes;
+        emulator.dump_state_on_exit = cli.dump_state_on_exit;
+        emulator.trap_callback = external_trap_callback;
+        emulator.max_cycles = cli.max_cycles;
+        emulator.lockstep = cli
+            .lockstep_trace
+            .as_deref()
+            .map(Lockstep::open)
+            .transpose()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        emulator.caliptra_ready_for_fw = caliptra_ready_for_fw;
+        emulator.emu_ctrl_exit = Some(emu_ctrl_exit);
+        Ok(emulator)
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        mcu_cpu: Cpu<AutoRootBus>,
+        mcu_cpu: Cpu<McuBus>,
         caliptra_cpu: Cpu<CaliptraMainRootBus>,
         trace_path: Option<PathBuf>,
         stdin_uart: Option<Arc<Mutex<Option<u8>>>>,
         bmc: Option<Bmc>,
         sram_range: Range<u32>,
+        dccm_range: Range<u32>,
         clock: Rc<Clock>,
         pic: Rc<Pic>,
         uart_output: Option<Rc<RefCell<Vec<u8>>>>,
@@ -951,6 +1311,7 @@ impl Emulator {
         std::thread::spawn(move || read_console(stdin_uart_clone));
 
         let timer = Timer::new(&mcu_cpu.clock.clone());
+        let cpu_reset_state = CpuResetState::capture(&mcu_cpu);
         let trace_file = trace_path.map(|path| File::create(path).unwrap());
 
         Self {
@@ -961,6 +1322,7 @@ impl Emulator {
             trace_file,
             stdin_uart,
             sram_range,
+            dccm_range,
             clock,
             pic,
             uart_output,
@@ -968,6 +1330,24 @@ impl Emulator {
             doe_mbox_fsm,
             i3c_address,
             i3c_controller_join_handle,
+            trace_pc_start: None,
+            trace_pc_end: None,
+            trace_format: TraceFormat::Text,
+            cpu_reset_state,
+            mci_offset: AutoRootBusOffsets::default().mci_offset,
+            memory_map: McuMemoryMap::default(),
+            mci_generic_input_wires: Rc::new(RefCell::new([0; 2])),
+            dump_state_on_exit: false,
+            exit_on_uart_marker: None,
+            uart_exit_marker_seen: false,
+            caliptra_boot_go: true,
+            itrng_divider: 1,
+            trap_callback: None,
+            max_cycles: None,
+            lockstep: None,
+            caliptra_ready_for_fw: Rc::new(Cell::new(false)),
+            emu_ctrl_exit: None,
+            soc_mcu_mailboxes: vec![],
         }
     }
 
@@ -975,6 +1355,38 @@ impl Emulator {
         self.i3c_address
     }
 
+    /// Exit code the firmware wrote to the emulation control register, if any.
+    /// 0 means the firmware passed.
+    pub fn exit_code(&self) -> Option<u32> {
+        self.emu_ctrl_exit.as_ref().and_then(|exit| exit.code())
+    }
+
+    /// Queue a private write of `data` to the MCU I3C target without going through the
+    /// I3C socket. The firmware sees it on a following `step()`.
+    pub fn i3c_send(&mut self, data: &[u8]) -> Result<(), I3cError> {
+        let addr = self.i3c_address.ok_or(I3cError::TargetNotFound)?;
+        let data_len = u16::try_from(data.len()).map_err(|_| I3cError::InvalidTcriCommand)?;
+        let mut write_cmd = ReguDataTransferCommand::read_from_bytes(&[0; 8]).unwrap();
+        write_cmd.set_rnw(0);
+        write_cmd.set_data_length(data_len);
+        self.i3c_controller.tcri_send(
+            addr.into(),
+            I3cTcriCommandXfer {
+                cmd: I3cTcriCommand::Regular(write_cmd),
+                data: data.to_vec(),
+            },
+        )
+    }
+
+    /// Take the next response the firmware wrote to the MCU I3C target, if any, without
+    /// going through the I3C socket.
+    pub fn i3c_recv(&mut self) -> Option<Vec<u8>> {
+        let addr = self.i3c_address?;
+        let mut resp = self.i3c_controller.tcri_receive(addr.into()).ok()?;
+        resp.data.truncate(resp.resp.data_length() as usize);
+        Some(resp.data)
+    }
+
     pub fn start_i3c_controller(&mut self) {
         if self.i3c_controller_join_handle.is_none() {
             self.i3c_controller_join_handle = Some(self.i3c_controller.start());
@@ -992,37 +1404,96 @@ impl Emulator {
             TICK_COND.notify_all();
         }
 
+        if self.max_cycles.is_some_and(|max| now >= max) {
+            println!("cycle budget exceeded ({} cycles)", now);
+            if self.dump_state_on_exit {
+                self.write_state_dump();
+            }
+            if let Some(exit) = &self.emu_ctrl_exit {
+                exit.request_exit(CYCLE_BUDGET_EXIT_CODE);
+            }
+            return StepAction::Fatal;
+        }
+
         if let Some(ref stdin_uart) = self.stdin_uart {
             if stdin_uart.lock().unwrap().is_some() {
                 self.timer.schedule_poll_in(1);
             }
         }
 
-        let action = if let Some(ref mut trace_file) = self.trace_file {
-            let trace_fn: &mut dyn FnMut(u32, RvInstr) = &mut |pc, instr| match instr {
-                RvInstr::Instr32(instr32) => {
-                    let _ = writeln!(trace_file, "{}", disassemble(pc, instr32));
-                    println!("{{mcu cpu}}      {}", disassemble(pc, instr32));
-                }
-                RvInstr::Instr16(instr16) => {
-                    let _ = writeln!(trace_file, "{}", disassemble(pc, instr16 as u32));
-                    println!("{{mcu cpu}}      {}", disassemble(pc, instr16 as u32));
-                }
+        let trap_csrs_before = self.trap_callback.as_ref().and_then(|_| self.trap_csrs());
+        let mut executed = None;
+        let traced =
+            self.trace_file.is_some() || self.lockstep.is_some() || self.trap_callback.is_some();
+        let action = if traced {
+            let trace_fn: &mut dyn FnMut(u32, RvInstr) = &mut |pc, instr| {
+                let instr = match instr {
+                    RvInstr::Instr32(instr32) => instr32,
+                    RvInstr::Instr16(instr16) => instr16 as u32,
+                };
+                executed = Some((pc, instr));
             };
             self.mcu_cpu.step(Some(trace_fn))
         } else {
             self.mcu_cpu.step(None)
         };
 
+        // The tracer runs before the instruction executes, so the line is written here, once
+        // the value of the destination register is known.
+        let executed = executed.map(|(pc, instr)| {
+            let rd = written_register(instr).map(|rd| {
+                let val = self.mcu_cpu.read_xreg(XReg::from(rd as u32)).unwrap_or(0);
+                (rd, val)
+            });
+            (pc, instr, rd)
+        });
+        if let (Some(trace_file), Some((pc, instr, rd))) = (self.trace_file.as_mut(), executed) {
+            if !(self.trace_pc_start.is_some_and(|start| pc < start)
+                || self.trace_pc_end.is_some_and(|end| pc >= end))
+            {
+                let line = trace_line(self.trace_format, pc, instr, rd);
+                let _ = writeln!(trace_file, "{}", line);
+                println!("{{mcu cpu}}      {}", line);
+            }
+        }
+
+        if let (Some(lockstep), Some((pc, instr, rd))) = (self.lockstep.as_mut(), executed) {
+            if let Err(divergence) = lockstep.check(pc, instr, rd) {
+                println!("lockstep divergence: {}", divergence);
+                if self.dump_state_on_exit {
+                    self.write_state_dump();
+                }
+                return StepAction::Fatal;
+            }
+        }
+
         if action != StepAction::Continue {
+            if action == StepAction::Fatal && self.dump_state_on_exit {
+                self.write_state_dump();
+            }
             return action;
         }
 
+        if let Some(before) = trap_csrs_before {
+            let csr_write = executed.is_some_and(|(_, instr, _)| writes_trap_csr(instr));
+            match self.trap_csrs() {
+                Some((mcause, mepc)) if !csr_write && (mcause, mepc) != before => {
+                    let callback = self.trap_callback.as_mut().unwrap();
+                    if !callback(mcause, mepc) {
+                        return StepAction::Break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
         if self.sram_range.contains(&self.mcu_cpu.read_pc()) {
             MCU_RUNTIME_STARTED.store(true, Ordering::Relaxed);
         }
 
-        let caliptra_action = if self.trace_file.is_some() {
+        let caliptra_action = if !self.caliptra_boot_go {
+            StepAction::Continue
+        } else if self.trace_file.is_some() {
             let caliptra_trace_fn: &mut dyn FnMut(u32, caliptra_emu_cpu::RvInstr) =
                 &mut |pc, instr| match instr {
                     caliptra_emu_cpu::RvInstr::Instr32(instr32) => {
@@ -1048,13 +1519,269 @@ impl Emulator {
             bmc.step();
         }
 
+        if self.match_uart_exit_marker() {
+            return StepAction::Break;
+        }
+
         action
     }
 
+    /// Returns true if the captured UART output ends with `exit_on_uart_marker`, removing
+    /// the marker from the buffer so it is only matched once.
+    fn match_uart_exit_marker(&mut self) -> bool {
+        let (Some(marker), Some(uart_output)) = (&self.exit_on_uart_marker, &self.uart_output)
+        else {
+            return false;
+        };
+        let mut uart_output = uart_output.borrow_mut();
+        if marker.is_empty() || !uart_output.ends_with(marker.as_bytes()) {
+            return false;
+        }
+        let len = uart_output.len() - marker.len();
+        uart_output.truncate(len);
+        self.uart_exit_marker_seen = true;
+        true
+    }
+
+    /// Returns whether the last `StepAction::Break` was caused by `exit_on_uart_marker`,
+    /// clearing the flag.
+    pub fn take_uart_exit_marker_seen(&mut self) -> bool {
+        std::mem::take(&mut self.uart_exit_marker_seen)
+    }
+
+    /// Start or stop counting MCU bus accesses per peripheral. Enabling resets the counters.
+    pub fn set_bus_access_stats_enabled(&mut self, enabled: bool) {
+        self.mcu_cpu.bus.set_enabled(enabled);
+    }
+
+    /// Inject an ECC error into the SRAM or DCCM word containing `addr`. The next MCU read
+    /// of that word fails with a load access fault for a double-bit error, or is corrected
+    /// for a single-bit error, which the MCI reports through its SRAM ECC correctable
+    /// counter and notif0 status. The injection is cleared after it fires once.
+    ///
+    /// Returns false if `addr` is not in SRAM or DCCM.
+    pub fn inject_ecc_error(&mut self, addr: u32, double_bit: bool) -> bool {
+        if !self.sram_range.contains(&addr) && !self.dccm_range.contains(&addr) {
+            return false;
+        }
+        self.mcu_cpu.bus.bus.bus.inject_ecc_error(addr, double_bit);
+        true
+    }
+
+    /// Record MCU writes that overlap any of `ranges`, replacing the previous ranges.
+    pub fn set_watch_ranges(&mut self, ranges: Vec<Range<u32>>) {
+        self.mcu_cpu.bus.bus.set_watch_ranges(ranges);
+    }
+
+    /// MCU writes to watched ranges since the last call, oldest first.
+    pub fn take_watch_hits(&mut self) -> Vec<WatchHit> {
+        self.mcu_cpu.bus.bus.take_watch_hits()
+    }
+
+    /// MCU bus accesses per peripheral since counting was enabled (all zero if disabled).
+    pub fn bus_access_stats(&self) -> BusAccessStats {
+        self.mcu_cpu.bus.stats().copied().unwrap_or_default()
+    }
+
     /// Get the current program counter (PC) of the MCU CPU
     pub fn get_pc(&self) -> u32 {
         self.mcu_cpu.read_pc()
     }
+
+    /// Current (mcause, mepc) of the MCU CPU.
+    ///
+    /// The CPU writes both when it takes a trap, so `step` reports a trap when either changes
+    /// and the instruction was not a CSR write to them. A trap that repeats the previous
+    /// trap's cause at the same mepc leaves both unchanged and is not reported again.
+    fn trap_csrs(&self) -> Option<(u32, u32)> {
+        let mcause = self.mcu_cpu.read_csr_machine(0x342).ok()?;
+        let mepc = self.mcu_cpu.read_csr_machine(0x341).ok()?;
+        Some((mcause, mepc))
+    }
+
+    /// Write [`Emulator::state_dump`] where the firmware's UART output goes: the captured
+    /// output buffer when there is one, stderr otherwise.
+    fn write_state_dump(&self) {
+        let dump = self.state_dump();
+        match &self.uart_output {
+            Some(output) => output.borrow_mut().extend_from_slice(dump.as_bytes()),
+            None => eprint!("{}", dump),
+        }
+    }
+
+    /// Human-readable dump of the MCU xregs, PC, and trap-related CSRs.
+    pub fn state_dump(&self) -> String {
+        const CSRS: [(&str, u32); 4] = [
+            ("mcause", 0x342),
+            ("mepc", 0x341),
+            ("mstatus", 0x300),
+            ("mtval", 0x343),
+        ];
+
+        let mut out = String::from("MCU CPU state:\n");
+        let _ = writeln!(out, "  pc      = 0x{:08x}", self.mcu_cpu.read_pc());
+        for (name, addr) in CSRS {
+            let val = self.mcu_cpu.read_csr_machine(addr).unwrap_or(0);
+            let _ = writeln!(out, "  {:<7} = 0x{:08x}", name, val);
+        }
+        for i in 0..32u32 {
+            let val = self.mcu_cpu.read_xreg(XReg::from(i)).unwrap_or(0);
+            let _ = write!(out, "  x{:<2} = 0x{:08x}", i, val);
+            if i % 4 == 3 {
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Whether Caliptra has set CPTRA_FLOW_STATUS.ready_for_mb_processing (bit 28), which
+    /// is the condition `McuHwModel::ready_for_fw` reports.
+    pub fn ready_for_fw(&self) -> bool {
+        self.caliptra_ready_for_fw.get()
+    }
+
+    /// Step until [`Emulator::ready_for_fw`] or for at most `max_cycles` MCU clock cycles.
+    ///
+    /// Returns false if the budget runs out or a step returns anything other than
+    /// `StepAction::Continue` first.
+    pub fn run_until_ready_for_fw(&mut self, max_cycles: u64) -> bool {
+        let start = self.mcu_cpu.clock.now();
+        while !self.ready_for_fw() {
+            if self.mcu_cpu.clock.now() - start >= max_cycles {
+                return false;
+            }
+            if self.step() != StepAction::Continue {
+                return self.ready_for_fw();
+            }
+        }
+        true
+    }
+
+    /// Write the MCI RESET_REASON register through the MCU bus. The ROM reads it on the
+    /// next boot to choose between the cold-boot, warm-boot, and firmware-update flows.
+    pub fn set_reset_reason(&mut self, reason: u32) -> Result<(), BusError> {
+        self.mcu_cpu.bus.write(
+            RvSize::Word,
+            self.mci_offset + MCI_RESET_REASON_OFFSET,
+            reason,
+        )
+    }
+
+    /// Drive the Caliptra generic input wires by writing CPTRA_GENERIC_INPUT_WIRES through
+    /// the SoC interface on the MCU bus.
+    pub fn set_generic_input_wires(&mut self, value: &[u32; 2]) -> Result<(), BusError> {
+        for (i, wire) in value.iter().copied().enumerate() {
+            self.mcu_cpu.bus.write(
+                RvSize::Word,
+                self.memory_map.soc_offset + SOC_GENERIC_INPUT_WIRES_OFFSET + 4 * i as u32,
+                wire,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Drive the MCI generic input wires that the MCU firmware reads.
+    pub fn set_mcu_generic_input_wires(&mut self, value: &[u32; 2]) {
+        *self.mci_generic_input_wires.borrow_mut() = *value;
+    }
+
+    /// Set the AXI user the SoC presents to the MCU mailboxes. It is shared by every SoC view
+    /// of a mailbox, including the one MCI's mailbox transport uses; the MCU's own mailbox
+    /// accesses are not affected.
+    pub fn set_axi_user(&mut self, axi_user: u32) {
+        for mailbox in &self.soc_mcu_mailboxes {
+            mailbox.set_axi_user(axi_user);
+        }
+    }
+
+    /// Re-run the MCU reset sequence: reset the bus peripherals, return the CPU registers
+    /// and CSRs to their reset values, and restart at the reset vector. RAM, flash, and OTP
+    /// contents are kept.
+    pub fn warm_reset(&mut self) {
+        self.mcu_cpu.bus.warm_reset();
+        self.cpu_reset_state.apply(&mut self.mcu_cpu);
+    }
+
+    /// Capture the MCU CPU registers, writable CSRs, PC, and the contents of SRAM (ICCM)
+    /// and DCCM.
+    pub fn snapshot(&mut self) -> io::Result<EmulatorSnapshot> {
+        let mut xregs = [0u32; 32];
+        for (i, xreg) in xregs.iter_mut().enumerate().skip(1) {
+            *xreg = self
+                .mcu_cpu
+                .read_xreg(XReg::from(i as u32))
+                .map_err(|e| io::Error::other(format!("Failed to read x{}: {:?}", i, e)))?;
+        }
+        let csrs = SNAPSHOT_CSRS
+            .iter()
+            .copied()
+            .chain(SNAPSHOT_PMP_CSRS)
+            .map(|addr| {
+                self.mcu_cpu
+                    .read_csr_machine(addr)
+                    .map(|val| (addr, val))
+                    .map_err(|e| {
+                        io::Error::other(format!("Failed to read CSR 0x{:03x}: {:?}", addr, e))
+                    })
+            })
+            .collect::<io::Result<_>>()?;
+        let sram = self.read_ram_region(self.sram_range.clone())?;
+        let dccm = self.read_ram_region(self.dccm_range.clone())?;
+
+        Ok(EmulatorSnapshot {
+            xregs,
+            csrs,
+            pc: self.mcu_cpu.read_pc(),
+            sram,
+            dccm,
+        })
+    }
+
+    /// Restore the state captured by [`Emulator::snapshot`].
+    pub fn restore(&mut self, snap: &EmulatorSnapshot) -> io::Result<()> {
+        for &(addr, val) in snap.csrs.iter() {
+            // Skip unchanged CSRs so that locked PMP entries are not rewritten.
+            if self.mcu_cpu.read_csr_machine(addr).ok() == Some(val) {
+                continue;
+            }
+            self.mcu_cpu.write_csr_machine(addr, val).map_err(|e| {
+                io::Error::other(format!("Failed to restore CSR 0x{:03x}: {:?}", addr, e))
+            })?;
+        }
+        for (i, &val) in snap.xregs.iter().enumerate().skip(1) {
+            self.mcu_cpu
+                .write_xreg(XReg::from(i as u32), val)
+                .map_err(|e| io::Error::other(format!("Failed to restore x{}: {:?}", i, e)))?;
+        }
+        self.mcu_cpu.write_pc(snap.pc);
+        self.write_ram_region(self.sram_range.start, &snap.sram)?;
+        self.write_ram_region(self.dccm_range.start, &snap.dccm)?;
+        Ok(())
+    }
+
+    fn read_ram_region(&mut self, range: Range<u32>) -> io::Result<Vec<u32>> {
+        range
+            .step_by(4)
+            .map(|addr| {
+                self.mcu_cpu.bus.read(RvSize::Word, addr).map_err(|e| {
+                    io::Error::other(format!("Failed to read 0x{:08x}: {:?}", addr, e))
+                })
+            })
+            .collect()
+    }
+
+    fn write_ram_region(&mut self, start: u32, words: &[u32]) -> io::Result<()> {
+        for (i, &word) in words.iter().enumerate() {
+            let addr = start + (i as u32) * 4;
+            self.mcu_cpu
+                .bus
+                .write(RvSize::Word, addr, word)
+                .map_err(|e| {
+                    io::Error::other(format!("Failed to write 0x{:08x}: {:?}", addr, e))
+                })?;
+        }
+        Ok(())
+    }
 }
 
 fn disassemble(pc: u32, instr: u32) -> String {
@@ -1066,6 +1793,66 @@ fn disassemble(pc: u32, instr: u32) -> String {
     String::from_utf8(out).unwrap()
 }
 
+/// Address ranges of the peripherals tracked by [`AccessCountingBus`].
+fn bus_stats_regions(
+    mcu: &McuRootBusOffsets,
+    auto: &AutoRootBusOffsets,
+) -> Vec<(Range<u32>, BusPeripheral)> {
+    let region = |offset: u32, size: u32| offset..offset.saturating_add(size);
+    vec![
+        (region(auto.i3c_offset, auto.i3c_size), BusPeripheral::I3c),
+        (region(auto.mci_offset, auto.mci_size), BusPeripheral::Mci),
+        (
+            region(auto.mbox_offset, auto.mbox_size),
+            BusPeripheral::Mailbox,
+        ),
+        (
+            region(auto.doe_mbox_offset, auto.doe_mbox_size),
+            BusPeripheral::Mailbox,
+        ),
+        (
+            region(auto.primary_flash_offset, auto.primary_flash_size),
+            BusPeripheral::Flash,
+        ),
+        (
+            region(auto.secondary_flash_offset, auto.secondary_flash_size),
+            BusPeripheral::Flash,
+        ),
+        (region(auto.otp_offset, auto.otp_size), BusPeripheral::Otp),
+        (region(mcu.uart_offset, mcu.uart_size), BusPeripheral::Uart),
+    ]
+}
+
+/// Whether `instr` is a Zicsr instruction targeting mepc or mcause.
+fn writes_trap_csr(instr: u32) -> bool {
+    instr & 0x7f == 0x73 && (instr >> 12) & 0x7 != 0 && matches!(instr >> 20, 0x341 | 0x342)
+}
+
+/// Format a single traced instruction. `rd` is the destination register the instruction
+/// wrote and its new value; JSON lines record it as `rd` and `rd_val`, which is what
+/// [`crate::lockstep::Lockstep`] compares.
+pub(crate) fn trace_line(
+    format: TraceFormat,
+    pc: u32,
+    instr: u32,
+    rd: Option<(u8, u32)>,
+) -> String {
+    match format {
+        TraceFormat::Text => disassemble(pc, instr),
+        TraceFormat::JsonLines => {
+            let dis = dis::disasm_inst(dis::RvIsa::Rv32, pc as u64, instr as u64);
+            // The disassembly starts with the instruction bytes, which are emitted separately.
+            let mnemonic = dis.split_whitespace().skip(1).collect::<Vec<_>>().join(" ");
+            let mut json = serde_json::json!({ "pc": pc, "insn": instr, "mnemonic": mnemonic });
+            if let Some((rd, val)) = rd {
+                json["rd"] = rd.into();
+                json["rd_val"] = val.into();
+            }
+            json.to_string()
+        }
+    }
+}
+
 fn read_console(stdin_uart: Option<Arc<Mutex<Option<u8>>>>) {
     let mut buffer = vec![];
     if let Some(ref stdin_uart) = stdin_uart {
@@ -1107,7 +1894,17 @@ fn read_console(stdin_uart: Option<Arc<Mutex<Option<u8>>>>) {
     }
 }
 
-fn read_binary(path: &PathBuf, expect_load_addr: u32) -> io::Result<Vec<u8>> {
+/// Read a raw binary, or an ELF executable loaded at `expect_load_addr`.
+///
+/// ELF segments are written to whichever region contains them: the returned image of up
+/// to `max_size` bytes from `expect_load_addr`, or one of `other_regions`, given as
+/// `(base address, memory)` pairs.
+fn read_binary(
+    path: &PathBuf,
+    expect_load_addr: u32,
+    max_size: usize,
+    other_regions: &mut [(u32, &mut Vec<u8>)],
+) -> io::Result<Vec<u8>> {
     let mut file = File::open(path)?;
     let mut buffer = Vec::new();
     file.read_to_end(&mut buffer)?;
@@ -1132,7 +1929,26 @@ fn read_binary(path: &PathBuf, expect_load_addr: u32) -> io::Result<Vec<u8>> {
                 format!("ELF executable has non-0x{:x} entry point, which is not supported (got 0x{:x})", expect_load_addr, elf.entry_point()),
             ))?;
         }
-        buffer = elf.content().clone();
+        // Place every segment, bss tails included, into its region. The image ends after
+        // the last byte in it that came from the file.
+        let mut image = vec![0; max_size];
+        let mut regions = vec![(expect_load_addr, image.as_mut_slice())];
+        regions.extend(
+            other_regions
+                .iter_mut()
+                .map(|(base, memory)| (*base, memory.as_mut_slice())),
+        );
+        elf.load_into_regions(&mut regions)?;
+        let image_range = expect_load_addr as u64..expect_load_addr as u64 + max_size as u64;
+        let len = elf
+            .segments()
+            .iter()
+            .filter(|segment| segment.file_size > 0 && image_range.contains(&(segment.addr as u64)))
+            .map(|segment| (segment.addr - expect_load_addr) as usize + segment.file_size)
+            .max()
+            .unwrap_or(0);
+        image.truncate(len);
+        buffer = image;
     }
 
     Ok(buffer)
@@ -25,6 +25,7 @@ use gdbstub::target::ext::breakpoints::WatchKind;
 use gdbstub::target::Target;
 use gdbstub::target::TargetResult;
 use gdbstub_arch;
+use mcu_config::McuMemoryMap;
 
 use crate::emulator::Emulator;
 use caliptra_emu_cpu::StepAction as SystemStepAction;
@@ -146,6 +147,35 @@ impl Target for GdbTarget {
     ) -> Option<target::ext::breakpoints::BreakpointsOps<'_, Self>> {
         Some(self)
     }
+
+    fn support_memory_map(&mut self) -> Option<target::ext::memory_map::MemoryMapOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+// Build the GDB memory map XML for the MCU bus. ROM is reported as read-only so that
+// GDB uses hardware breakpoints there; everything else (including MMIO) is read-write.
+pub fn memory_map_xml(memory_map: &McuMemoryMap) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?>\n\
+         <!DOCTYPE memory-map PUBLIC \"+//IDN gnu.org//DTD GDB Memory Map V1.0//EN\" \
+         \"http://sourceware.org/gdb/gdb-memory-map.dtd\">\n\
+         <memory-map>\n",
+    );
+    let mut regions = memory_map.named_regions();
+    regions.sort_by_key(|&(_, offset, _)| offset);
+    for (name, offset, size) in regions {
+        if size == 0 {
+            continue;
+        }
+        let kind = if name == "rom" { "rom" } else { "ram" };
+        xml.push_str(&format!(
+            "  <memory type=\"{}\" start=\"{:#x}\" length=\"{:#x}\"/>\n",
+            kind, offset, size
+        ));
+    }
+    xml.push_str("</memory-map>\n");
+    xml
 }
 
 impl SingleThreadBase for GdbTarget {
@@ -281,6 +311,25 @@ impl SingleThreadResume for GdbTarget {
     }
 }
 
+impl target::ext::memory_map::MemoryMap for GdbTarget {
+    fn memory_map_xml(
+        &self,
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self> {
+        let xml = memory_map_xml(&self.emulator.memory_map);
+        let xml = xml.as_bytes();
+        let start = (offset as usize).min(xml.len());
+        let end = start
+            .saturating_add(length)
+            .min(xml.len())
+            .min(start + buf.len());
+        buf[..end - start].copy_from_slice(&xml[start..end]);
+        Ok(end - start)
+    }
+}
+
 impl target::ext::breakpoints::Breakpoints for GdbTarget {
     #[inline(always)]
     fn support_sw_breakpoint(
@@ -352,3 +401,17 @@ impl target::ext::breakpoints::HwWatchpoint for GdbTarget {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_map_xml_rom_read_only() {
+        let xml = memory_map_xml(&McuMemoryMap::default());
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<memory type=\"rom\" start=\"0x80000000\" length=\"0x8000\"/>"));
+        assert!(xml.contains("<memory type=\"ram\" start=\"0x40000000\" length=\"0x80000\"/>"));
+        assert!(xml.trim_end().ends_with("</memory-map>"));
+    }
+}
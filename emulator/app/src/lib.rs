@@ -18,6 +18,10 @@ pub mod doe_mbox_fsm;
 pub mod elf;
 pub mod emulator;
 pub mod gdb;
+pub mod lockstep;
 pub mod tests;
 
-pub use emulator::{Emulator, EmulatorArgs, ExternalReadCallback, ExternalWriteCallback};
+pub use emulator::{
+    CpuResetState, Emulator, EmulatorArgs, EmulatorSnapshot, ExternalReadCallback,
+    ExternalTrapCallback, ExternalWriteCallback, TraceFormat, CYCLE_BUDGET_EXIT_CODE,
+};
@@ -0,0 +1,255 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    lockstep.rs
+
+Abstract:
+
+    Lockstep comparison of MCU execution against a reference instruction trace.
+
+--*/
+
+use std::fmt;
+use std::path::Path;
+
+/// One retired instruction from a reference trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub pc: u32,
+    pub insn: u32,
+    /// Destination register and the value written to it, if the trace records one.
+    pub rd: Option<(u8, u32)>,
+}
+
+/// The first point at which execution differs from the reference trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Zero-based index of the instruction in the reference trace.
+    pub index: usize,
+    pub expected: Option<TraceEntry>,
+    pub actual: TraceEntry,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let actual = self.actual;
+        let Some(expected) = self.expected else {
+            return write!(
+                f,
+                "instruction {}: reference trace ended, executed pc=0x{:08x} insn=0x{:08x}",
+                self.index, actual.pc, actual.insn
+            );
+        };
+        if expected.pc != actual.pc || expected.insn != actual.insn {
+            return write!(
+                f,
+                "instruction {}: expected pc=0x{:08x} insn=0x{:08x}, executed pc=0x{:08x} insn=0x{:08x}",
+                self.index, expected.pc, expected.insn, actual.pc, actual.insn
+            );
+        }
+        let (rd, expected_val) = expected.rd.unwrap_or_default();
+        let actual_val = actual.rd.map_or(0, |(_, val)| val);
+        write!(
+            f,
+            "instruction {} at pc=0x{:08x}: expected x{} = 0x{:08x}, got 0x{:08x}",
+            self.index, actual.pc, rd, expected_val, actual_val
+        )
+    }
+}
+
+/// Compares each executed MCU instruction with the next entry of a reference trace.
+pub struct Lockstep {
+    entries: Vec<TraceEntry>,
+    index: usize,
+}
+
+impl Lockstep {
+    /// Parse a reference trace in either of the `--trace-format` layouts. Text traces only
+    /// carry PC and instruction; JSON lines may additionally carry `rd` and `rd_val`.
+    pub fn parse(trace: &str) -> Result<Self, String> {
+        let entries = trace
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| parse_line(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { entries, index: 0 })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let trace = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        Self::parse(&trace)
+    }
+
+    /// Check one executed instruction. `rd` is the destination register the instruction wrote
+    /// and its new value; the register is only compared when the reference records it.
+    pub fn check(&mut self, pc: u32, insn: u32, rd: Option<(u8, u32)>) -> Result<(), Divergence> {
+        let index = self.index;
+        let actual = TraceEntry { pc, insn, rd };
+        let expected = self.entries.get(index).copied();
+        self.index += 1;
+        let matches = expected.is_some_and(|expected| {
+            expected.pc == pc
+                && expected.insn == insn
+                && expected
+                    .rd
+                    .is_none_or(|(reg, val)| reg == 0 || rd == Some((reg, val)))
+        });
+        if matches {
+            Ok(())
+        } else {
+            Err(Divergence {
+                index,
+                expected,
+                actual,
+            })
+        }
+    }
+}
+
+/// Destination register of a 32-bit instruction, or `None` if it does not write one.
+/// Compressed instructions are not decoded.
+pub fn written_register(insn: u32) -> Option<u8> {
+    if insn & 0b11 != 0b11 {
+        return None;
+    }
+    let rd = ((insn >> 7) & 0x1f) as u8;
+    let writes_rd = match insn & 0x7f {
+        // LOAD, OP-IMM, AUIPC, AMO, OP, LUI, JALR, JAL
+        0x03 | 0x13 | 0x17 | 0x2f | 0x33 | 0x37 | 0x67 | 0x6f => true,
+        // Zicsr; funct3 == 0 covers ecall, ebreak, mret and wfi.
+        0x73 => (insn >> 12) & 0x7 != 0,
+        _ => false,
+    };
+    (writes_rd && rd != 0).then_some(rd)
+}
+
+fn parse_hex(s: &str) -> Result<u32, String> {
+    u32::from_str_radix(s.trim_start_matches("0x"), 16).map_err(|e| format!("{s:?}: {e}"))
+}
+
+fn parse_line(line: &str) -> Result<TraceEntry, String> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        let json: serde_json::Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        let field = |name: &str| {
+            json.get(name)
+                .map(|v| {
+                    v.as_u64()
+                        .and_then(|v| u32::try_from(v).ok())
+                        .ok_or_else(|| format!("invalid {name}"))
+                })
+                .transpose()
+        };
+        let pc = field("pc")?.ok_or("missing pc")?;
+        let insn = field("insn")?.ok_or("missing insn")?;
+        let rd = match (field("rd")?, field("rd_val")?) {
+            (Some(rd), Some(val)) if rd < 32 => Some((rd as u8, val)),
+            (None, None) => None,
+            _ => return Err("rd and rd_val must be given together".into()),
+        };
+        return Ok(TraceEntry { pc, insn, rd });
+    }
+
+    // Text format: "0x<pc>   <insn>   <disassembly>".
+    let mut fields = line.split_whitespace();
+    let pc = parse_hex(fields.next().ok_or("missing pc")?)?;
+    let insn = parse_hex(fields.next().ok_or("missing insn")?)?;
+    Ok(TraceEntry { pc, insn, rd: None })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::emulator::trace_line;
+    use crate::TraceFormat;
+
+    #[test]
+    fn test_written_register() {
+        // addi gp,gp,256
+        assert_eq!(written_register(0x10018193), Some(3));
+        // sw a0,0(sp)
+        assert_eq!(written_register(0x00a12023), None);
+        // csrr a0,mcause
+        assert_eq!(written_register(0x34202573), Some(10));
+        // ecall
+        assert_eq!(written_register(0x00000073), None);
+        // c.addi a0,1
+        assert_eq!(written_register(0x0505), None);
+    }
+
+    #[test]
+    fn test_lockstep_matching_trace() {
+        let trace = [
+            trace_line(TraceFormat::Text, 0, 0x10018193, Some((3, 256))),
+            trace_line(TraceFormat::JsonLines, 4, 0x00a12023, None),
+        ]
+        .join("\n")
+            + "\n{\"pc\":8,\"insn\":268534163,\"rd\":3,\"rd_val\":512}\n";
+        let mut lockstep = Lockstep::parse(&trace).unwrap();
+        assert_eq!(lockstep.check(0, 0x10018193, Some((3, 256))), Ok(()));
+        assert_eq!(lockstep.check(4, 0x00a12023, None), Ok(()));
+        assert_eq!(lockstep.check(8, 0x10018193, Some((3, 512))), Ok(()));
+    }
+
+    #[test]
+    fn test_lockstep_mismatching_trace() {
+        let trace = "{\"pc\":0,\"insn\":268534163,\"rd\":3,\"rd_val\":256}\n\
+                     {\"pc\":4,\"insn\":268534163,\"rd\":3,\"rd_val\":512}\n";
+
+        // Wrong register value.
+        let mut lockstep = Lockstep::parse(trace).unwrap();
+        assert_eq!(lockstep.check(0, 0x10018193, Some((3, 256))), Ok(()));
+        let err = lockstep.check(4, 0x10018193, Some((3, 511))).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(
+            err.to_string(),
+            "instruction 1 at pc=0x00000004: expected x3 = 0x00000200, got 0x000001ff"
+        );
+
+        // Wrong PC.
+        let mut lockstep = Lockstep::parse(trace).unwrap();
+        let err = lockstep.check(2, 0x10018193, Some((3, 256))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "instruction 0: expected pc=0x00000000 insn=0x10018193, executed pc=0x00000002 insn=0x10018193"
+        );
+
+        // Execution runs past the end of the reference.
+        let mut lockstep = Lockstep::parse(trace).unwrap();
+        assert!(lockstep.check(0, 0x10018193, Some((3, 256))).is_ok());
+        assert!(lockstep.check(4, 0x10018193, Some((3, 512))).is_ok());
+        assert_eq!(lockstep.check(8, 0x13, None).unwrap_err().expected, None);
+    }
+
+    #[test]
+    fn test_lockstep_emitted_register_mismatch() {
+        // A JSON trace written by the emulator itself records the register each instruction
+        // wrote, so a later run that computes a different value diverges there.
+        let trace = [
+            trace_line(TraceFormat::JsonLines, 0, 0x10018193, Some((3, 256))),
+            trace_line(TraceFormat::JsonLines, 4, 0x00a12023, None),
+            trace_line(TraceFormat::JsonLines, 8, 0x10018193, Some((3, 512))),
+        ]
+        .join("\n");
+        let mut lockstep = Lockstep::parse(&trace).unwrap();
+        assert_eq!(lockstep.check(0, 0x10018193, Some((3, 256))), Ok(()));
+        assert_eq!(lockstep.check(4, 0x00a12023, None), Ok(()));
+        let err = lockstep.check(8, 0x10018193, Some((3, 257))).unwrap_err();
+        assert_eq!(err.index, 2);
+        assert_eq!(
+            err.to_string(),
+            "instruction 2 at pc=0x00000008: expected x3 = 0x00000200, got 0x00000101"
+        );
+    }
+
+    #[test]
+    fn test_lockstep_parse_error() {
+        assert!(Lockstep::parse("{\"pc\":0}\n").is_err());
+        assert!(Lockstep::parse("not a trace\n").is_err());
+    }
+}
@@ -15,6 +15,7 @@ Abstract:
 use caliptra_emu_cpu::StepAction;
 use clap::Parser;
 use emulator::{gdb, Emulator, EmulatorArgs};
+use mcu_testing_common::i3c_socket_server::I3C_PORT_IN_USE_EXIT_CODE;
 use mcu_testing_common::MCU_RUNNING;
 use std::cell::RefCell;
 use std::io;
@@ -32,9 +33,19 @@ fn free_run(mut emulator: Emulator) {
     }
 }
 
+// Firmware exits never produce the port-in-use status.
+const _: () = assert!(I3C_PORT_IN_USE_EXIT_CODE == emulator_periph::RESERVED_EXIT_STATUS);
+
 fn main() -> io::Result<()> {
     let cli = EmulatorArgs::parse();
-    run(cli, false).map(|_| ())
+    match run(cli, false) {
+        // Use a distinct exit code so test runners can retry on another port
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+            eprintln!("{}", e);
+            std::process::exit(I3C_PORT_IN_USE_EXIT_CODE);
+        }
+        result => result.map(|_| ()),
+    }
 }
 
 fn run(cli: EmulatorArgs, capture_uart_output: bool) -> io::Result<Vec<u8>> {
@@ -20,6 +20,9 @@ use caliptra_emu_periph::{
     CaliptraRootBus, CaliptraRootBusArgs, DownloadIdevidCsrCb, MailboxInternal, MailboxRequester,
     Mci, ReadyForFwCb, SocToCaliptraBus, TbServicesCb, UploadUpdateFwCb,
 };
+use caliptra_hw_model_types::{RandomEtrngResponses, RandomNibbles};
+use rand::{rngs::StdRng, SeedableRng};
+use std::cell::Cell;
 use std::io::{self, ErrorKind, Write};
 use std::path::PathBuf;
 use std::process::exit;
@@ -65,6 +68,12 @@ pub struct StartCaliptraArgs {
     pub req_idevid_csr: Option<bool>,
     pub device_lifecycle: Option<String>,
     pub use_mcu_recovery_interface: bool,
+    /// Seed for the internal and external TRNG sources. If `None`, the default
+    /// entropy sources are used.
+    pub trng_seed: Option<u64>,
+    /// Set when Caliptra reports it is ready for firmware
+    /// (CPTRA_FLOW_STATUS.ready_for_mb_processing).
+    pub ready_for_fw: Option<Rc<Cell<bool>>>,
 }
 
 register_bitfields! [
@@ -130,11 +139,16 @@ pub fn start_caliptra(
     );
 
     // in active mode, we don't upload the firmware here, as MCU ROM will trigger it
-    let ready_for_fw_cb = ReadyForFwCb::new(|_| {});
+    let ready_for_fw = args.ready_for_fw.clone();
+    let ready_for_fw_cb = ReadyForFwCb::new(move |_| {
+        if let Some(ready_for_fw) = &ready_for_fw {
+            ready_for_fw.set(true);
+        }
+    });
     // in active mode, we don't update firmware here, as MCU will trigger it
     let upload_update_fw = UploadUpdateFwCb::new(|_| {});
 
-    let bus_args = CaliptraRootBusArgs {
+    let mut bus_args = CaliptraRootBusArgs {
         clock: clock.clone(),
         pic: pic.clone(),
         rom: rom_buffer,
@@ -160,6 +174,10 @@ pub fn start_caliptra(
         use_mcu_recovery_interface: args_use_mcu_recovery_interface,
         ..Default::default()
     };
+    if let Some(seed) = args.trng_seed {
+        bus_args.itrng_nibbles = Some(Box::new(RandomNibbles(StdRng::seed_from_u64(seed))));
+        bus_args.etrng_responses = Box::new(RandomEtrngResponses(StdRng::seed_from_u64(seed)));
+    }
 
     let root_bus = CaliptraRootBus::new(bus_args);
     let soc_ifc = unsafe {
@@ -375,6 +375,7 @@ void print_usage(const char* program_name) {
     printf("  -t, --trace-instr                    Trace instructions\n");
     printf("      --no-stdin-uart                  Don't pass stdin to the MCU UART Rx\n");
     printf("      --i3c-port <I3C_PORT>            I3C socket port\n");
+    printf("      --i3c-dynamic-address <ADDR>     Dynamic address for the MCU I3C target\n");
     printf("      --manufacturing-mode             Enable manufacturing mode\n");
     printf("      --vendor-pk-hash <VENDOR_PK_HASH>\n");
     printf("                                       Vendor public key hash\n");
@@ -573,6 +574,15 @@ int main(int argc, char *argv[]) {
         .otp_size = -1,
         .lc_offset = -1,
         .lc_size = -1,
+        .trace_pc_start = -1,
+        .trace_pc_end = -1,
+        .trng_seed = -1,
+        .uart_capture_max_bytes = -1,
+        .dump_state_on_exit = 0,
+        .allow_unaligned_bus = 0,
+        .exit_on_uart_marker = NULL,
+        .max_cycles = -1,
+        .i3c_dynamic_address = -1,
         .external_read_callback = NULL,
         .external_write_callback = NULL,
         .callback_context = NULL,
@@ -623,6 +633,7 @@ int main(int argc, char *argv[]) {
         {"lc-size", required_argument, 0, 162},
         {"mbox-offset", required_argument, 0, 163},
         {"mbox-size", required_argument, 0, 164},
+        {"i3c-dynamic-address", required_argument, 0, 165},
         {"help", no_argument, 0, 'h'},
         {"version", no_argument, 0, 'V'},
         {0, 0, 0, 0}
@@ -767,6 +778,9 @@ int main(int argc, char *argv[]) {
             case 164: // --mbox-size
                 config.mbox_size = parse_hex_or_decimal(optarg);
                 break;
+            case 165: // --i3c-dynamic-address
+                config.i3c_dynamic_address = (int)parse_hex_or_decimal(optarg);
+                break;
             case 'h':
                 print_usage(argv[0]);
                 return 0;
@@ -858,6 +872,13 @@ int main(int argc, char *argv[]) {
 #endif
             return 1;
         }
+
+        unsigned char i3c_addr;
+        if (emulator_get_i3c_address((struct CEmulator*)memory, &i3c_addr) == 1) {
+            printf("MCU I3C target dynamic address: 0x%02x\n", i3c_addr);
+        } else {
+            printf("MCU I3C target has no dynamic address yet\n");
+        }
     }
 
     global_emulator = (struct CEmulator*)memory;
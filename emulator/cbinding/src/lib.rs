@@ -11,20 +11,33 @@ Abstract:
     C bindings for the Caliptra MCU Emulator.
 
 --*/
-use caliptra_emu_bus::Bus;
+use caliptra_emu_bus::{Bus, BusError};
 use caliptra_emu_cpu::xreg_file::XReg;
 use caliptra_emu_cpu::StepAction;
 use caliptra_emu_types::{RvAddr, RvSize};
-use emulator::{gdb, Emulator, EmulatorArgs, ExternalReadCallback, ExternalWriteCallback};
+use emulator::{
+    gdb, Emulator, EmulatorArgs, EmulatorSnapshot, ExternalReadCallback, ExternalTrapCallback,
+    ExternalWriteCallback, TraceFormat,
+};
+use emulator_periph::{AccessCount, BusAccessStats, MciMailboxRequester, WatchHit};
 use mcu_testing_common::MCU_RUNNING;
+use std::collections::HashSet;
 use std::ffi::CStr;
-use std::os::raw::{c_char, c_int, c_longlong, c_uchar, c_uint};
+use std::ops::Range;
+use std::os::raw::{c_char, c_int, c_longlong, c_uchar, c_uint, c_ulonglong};
 use std::ptr;
 use std::sync::atomic::Ordering;
 
 #[cfg(test)]
 mod simple_test;
 
+/// CSR address of the lower 32 bits of the retired instruction counter
+const CSR_MINSTRET: RvAddr = 0xb02;
+/// CSR address of the upper 32 bits of the retired instruction counter
+const CSR_MINSTRETH: RvAddr = 0xb82;
+/// WARM_RESET bit of the MCI RESET_REASON register
+const RESET_REASON_WARM_RESET: u32 = 1 << 2;
+
 /// Internal emulator wrapper that can be in normal or GDB mode
 enum EmulatorWrapper {
     Normal(Emulator),
@@ -35,6 +48,115 @@ enum EmulatorWrapper {
 struct CEmulatorState {
     wrapper: EmulatorWrapper,
     gdb_port: Option<u16>, // Store GDB port for later use
+    breakpoints: HashSet<u32>,
+    // PC of the breakpoint last reported, so that the next step executes it
+    stopped_at_breakpoint: Option<u32>,
+    uart_rx_ready_callback: Option<(CUartRxReadyCallback, *const std::ffi::c_void)>,
+    allow_unaligned_bus: bool,
+    watchpoints: Vec<Watchpoint>,
+}
+
+/// A watched address range and the C callback to notify of writes to it
+struct Watchpoint {
+    range: Range<u32>,
+    callback: CWatchpointCallback,
+    context: *const std::ffi::c_void,
+}
+
+/// Invoke the callback of every watchpoint each hit overlaps, in write order.
+/// Returns false if any callback asked to halt.
+fn fire_watchpoints(watchpoints: &[Watchpoint], hits: &[WatchHit]) -> bool {
+    let mut keep_running = true;
+    for hit in hits {
+        let end = hit.addr.saturating_add(hit.size as u32);
+        for watchpoint in watchpoints {
+            if hit.addr < watchpoint.range.end && watchpoint.range.start < end {
+                let ret = unsafe {
+                    (watchpoint.callback)(watchpoint.context, hit.addr, hit.size as c_uint, hit.val)
+                };
+                keep_running &= ret != 0;
+            }
+        }
+    }
+    keep_running
+}
+
+impl CEmulatorState {
+    fn emulator_mut(&mut self) -> &mut Emulator {
+        match &mut self.wrapper {
+            EmulatorWrapper::Normal(emulator) => emulator,
+            EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut(),
+        }
+    }
+
+    /// Report writes to watched ranges since the last call to the watchpoint callbacks.
+    /// Returns false if a callback asked to halt.
+    fn check_watchpoints(&mut self) -> bool {
+        if self.watchpoints.is_empty() {
+            return true;
+        }
+        let hits = self.emulator_mut().take_watch_hits();
+        fire_watchpoints(&self.watchpoints, &hits)
+    }
+
+    /// Tell the bus which ranges to record writes for.
+    fn update_watch_ranges(&mut self) {
+        let ranges = self.watchpoints.iter().map(|w| w.range.clone()).collect();
+        self.emulator_mut().set_watch_ranges(ranges);
+    }
+
+    /// Returns true if the PC is at a breakpoint that has not been reported yet.
+    /// A reported breakpoint is stepped over by the next step.
+    fn at_breakpoint(&mut self) -> bool {
+        let pc = self.emulator_mut().get_pc();
+        if self.breakpoints.contains(&pc) && self.stopped_at_breakpoint != Some(pc) {
+            self.stopped_at_breakpoint = Some(pc);
+            return true;
+        }
+        false
+    }
+
+    /// Step the underlying emulator once, stopping with `Break` if the new PC
+    /// hits a breakpoint. The instruction at the breakpoint is not executed.
+    fn step(&mut self) -> CStepAction {
+        let uart_rx_ready_callback = self.uart_rx_ready_callback;
+        self.stopped_at_breakpoint = None;
+        let emulator = self.emulator_mut();
+        let uart_rx_was_full = uart_rx_ready_callback.is_some() && uart_rx_full(emulator);
+        let action = emulator.step();
+        if uart_rx_was_full && !uart_rx_full(emulator) {
+            if let Some((callback, context)) = uart_rx_ready_callback {
+                // The return value is informational only; there is nothing to retry.
+                let _ = unsafe { callback(context) };
+            }
+        }
+        let uart_exit = action == StepAction::Break && emulator.take_uart_exit_marker_seen();
+        let exit_code = emulator.exit_code();
+        let watchpoints_continue = self.check_watchpoints();
+        if uart_exit {
+            return CStepAction::ExitSuccess;
+        }
+        match exit_code {
+            Some(0) => return CStepAction::ExitSuccess,
+            Some(_) => return CStepAction::ExitFailure,
+            None => {}
+        }
+        if action == StepAction::Continue && !watchpoints_continue {
+            return CStepAction::Break;
+        }
+        if action == StepAction::Continue && self.at_breakpoint() {
+            return CStepAction::Break;
+        }
+        action.into()
+    }
+}
+
+/// Returns true if the UART RX buffer holds a byte the firmware has not read yet
+fn uart_rx_full(emulator: &Emulator) -> bool {
+    emulator
+        .stdin_uart
+        .as_ref()
+        .is_some_and(|uart_rx| uart_rx.lock().unwrap().is_some())
 }
 
 /// Error codes for C API
@@ -50,6 +172,7 @@ pub enum EmulatorError {
     BusStoreAccessFault = -6,
     BusLoadAddrMisaligned = -7,
     BusStoreAddrMisaligned = -8,
+    RestoreFailed = -9,
 }
 
 /// Step action results for C API
@@ -106,6 +229,93 @@ pub type CExternalWriteCallback = unsafe extern "C" fn(
     data: c_uint,                     // RvData as u32
 ) -> c_int;
 
+/// C function pointer type for MCU trap notifications
+///
+/// # Arguments
+/// * `context` - Context pointer passed to the callback
+/// * `mcause` - Value of mcause when the trap was taken
+/// * `mepc` - Value of mepc when the trap was taken
+///
+/// # Returns
+/// * 1 to continue, 0 to halt (the current step returns `CStepAction::Break`)
+pub type CTrapCallback = unsafe extern "C" fn(
+    context: *const std::ffi::c_void, // Context pointer
+    mcause: c_uint,                   // mcause CSR
+    mepc: c_uint,                     // mepc CSR
+) -> c_int;
+
+/// C function pointer type for watchpoint notifications
+///
+/// # Arguments
+/// * `context` - Context pointer passed to the callback
+/// * `addr` - Address of the write
+/// * `size` - Size of the write in bytes (1, 2 or 4)
+/// * `value` - Value written
+///
+/// # Returns
+/// * 1 to continue, 0 to halt (the current step returns `CStepAction::Break`)
+pub type CWatchpointCallback = unsafe extern "C" fn(
+    context: *const std::ffi::c_void, // Context pointer
+    addr: c_uint,                     // Write address
+    size: c_uint,                     // Write size in bytes
+    value: c_uint,                    // Value written
+) -> c_int;
+
+/// C function pointer type for UART RX ready notifications
+///
+/// # Arguments
+/// * `context` - Context pointer passed to the callback
+///
+/// # Returns
+/// * 1 for success, 0 for failure
+pub type CUartRxReadyCallback = unsafe extern "C" fn(
+    context: *const std::ffi::c_void, // Context pointer
+) -> c_int;
+
+/// Read and write counts for one peripheral
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CAccessCount {
+    pub reads: c_ulonglong,
+    pub writes: c_ulonglong,
+}
+
+impl From<AccessCount> for CAccessCount {
+    fn from(count: AccessCount) -> Self {
+        CAccessCount {
+            reads: count.reads,
+            writes: count.writes,
+        }
+    }
+}
+
+/// MCU bus accesses per peripheral, see emulator_get_bus_stats()
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CBusAccessStats {
+    pub i3c: CAccessCount,
+    pub mci: CAccessCount,
+    pub mailbox: CAccessCount,
+    pub flash: CAccessCount,
+    pub otp: CAccessCount,
+    pub uart: CAccessCount,
+    pub other: CAccessCount,
+}
+
+impl From<BusAccessStats> for CBusAccessStats {
+    fn from(stats: BusAccessStats) -> Self {
+        CBusAccessStats {
+            i3c: stats.i3c.into(),
+            mci: stats.mci.into(),
+            mailbox: stats.mailbox.into(),
+            flash: stats.flash.into(),
+            otp: stats.otp.into(),
+            uart: stats.uart.into(),
+            other: stats.other.into(),
+        }
+    }
+}
+
 /// Opaque structure representing the emulator
 /// C code should allocate memory for this structure
 #[repr(C)]
@@ -113,6 +323,13 @@ pub struct CEmulator {
     _private: [u8; 0],
 }
 
+/// Opaque handle to an emulator snapshot
+/// Allocated by emulator_snapshot() and released with emulator_free_snapshot()
+#[repr(C)]
+pub struct CEmulatorSnapshot {
+    _private: [u8; 0],
+}
+
 /// Configuration structure for emulator initialization
 ///
 /// Memory layout override parameters use int64_t values where:
@@ -196,6 +413,36 @@ pub struct CEmulatorConfig {
     pub fuse_soc_manifest_max_svn: c_longlong,
     pub fuse_vendor_hashes_prod_partition: *const c_char, // Optional, can be null
 
+    // Only trace MCU instructions with a PC in [trace_pc_start, trace_pc_end) (-1 means unbounded)
+    pub trace_pc_start: c_longlong,
+    pub trace_pc_end: c_longlong,
+
+    // Seed for the Caliptra TRNG sources (-1 means use entropy)
+    pub trng_seed: c_longlong,
+
+    // Keep only the most recent N bytes of captured UART output (-1 means unbounded)
+    pub uart_capture_max_bytes: c_longlong,
+
+    // Write the MCU registers, PC, and trap CSRs to the UART output when the MCU
+    // CPU stops with a fatal error (0 = false, 1 = true)
+    pub dump_state_on_exit: c_uchar,
+
+    // Split unaligned auto_root_bus reads/writes into byte accesses instead of
+    // failing with a misaligned error (0 = false, 1 = true)
+    pub allow_unaligned_bus: c_uchar,
+
+    // Stop with CStepAction::ExitSuccess once the captured UART output ends with this
+    // string; the marker is removed from the output (optional, can be null)
+    pub exit_on_uart_marker: *const c_char,
+
+    // Stop with CStepAction::ExitFailure once the MCU clock reaches this many cycles
+    // (-1 means unbounded)
+    pub max_cycles: c_longlong,
+
+    // Dynamic address the I3C controller assigns to the MCU target (-1 means the first
+    // free address); requires i3c_port
+    pub i3c_dynamic_address: c_int,
+
     // External device callbacks (can be null)
     pub external_read_callback: *const std::ffi::c_void,
     pub external_write_callback: *const std::ffi::c_void,
@@ -217,6 +464,9 @@ pub extern "C" fn emulator_get_alignment() -> usize {
 
 /// Initialize an emulator in the provided memory location
 ///
+/// The firmware's exit request does not exit the host process; emulator_step()
+/// reports it instead (see emulator_get_exit_status()).
+///
 /// # Arguments
 /// * `emulator_memory` - Pointer to allocated memory (must be at least emulator_get_size() bytes)
 /// * `config` - Configuration for the emulator
@@ -267,6 +517,14 @@ pub unsafe extern "C" fn emulator_init(
         Err(_) => return EmulatorError::InvalidArgs,
     };
 
+    let i3c_dynamic_address = match config.i3c_dynamic_address {
+        -1 => None,
+        addr => match u8::try_from(addr) {
+            Ok(addr) if config.i3c_port != 0 => Some(addr),
+            _ => return EmulatorError::InvalidArgs,
+        },
+    };
+
     // Build EmulatorArgs
     let args = EmulatorArgs {
         rom: rom_path.into(),
@@ -282,6 +540,26 @@ pub unsafe extern "C" fn emulator_init(
         },
         log_dir: convert_optional_c_string(config.log_dir_path).map(|s| s.into()),
         trace_instr: config.trace_instr != 0,
+        trace_format: TraceFormat::Text,
+        trace_pc_start: convert_optional_offset_size(config.trace_pc_start),
+        trace_pc_end: convert_optional_offset_size(config.trace_pc_end),
+        lockstep_trace: None,
+        trng_seed: if config.trng_seed < 0 {
+            None
+        } else {
+            Some(config.trng_seed as u64)
+        },
+        uart_capture_max_bytes: if config.uart_capture_max_bytes < 0 {
+            None
+        } else {
+            Some(config.uart_capture_max_bytes as usize)
+        },
+        dump_state_on_exit: config.dump_state_on_exit != 0,
+        max_cycles: if config.max_cycles < 0 {
+            None
+        } else {
+            Some(config.max_cycles as u64)
+        },
         stdin_uart: config.stdin_uart != 0,
         _no_stdin_uart: false,
         i3c_port: if config.i3c_port == 0 {
@@ -289,6 +567,7 @@ pub unsafe extern "C" fn emulator_init(
         } else {
             Some(config.i3c_port as u16)
         },
+        i3c_dynamic_address,
         manufacturing_mode: config.manufacturing_mode != 0,
         vendor_pk_hash: convert_optional_c_string(config.vendor_pk_hash),
         vendor_pqc_type: caliptra_image_types::FwVerificationPqcKeyType::from_u8(
@@ -301,12 +580,14 @@ pub unsafe extern "C" fn emulator_init(
             .map(|s| s.into()),
         secondary_flash_image: convert_optional_c_string(config.secondary_flash_image_path)
             .map(|s| s.into()),
+        flash_op_latency: None,
         hw_revision: semver::Version::new(
             config.hw_revision_major as u64,
             config.hw_revision_minor as u64,
             config.hw_revision_patch as u64,
         ),
         flash_based_boot: config.flash_based_boot != 0,
+        memory_map_file: None,
         // Use provided offset and size override parameters (-1 means use default)
         rom_offset: convert_optional_offset_size(config.rom_offset),
         rom_size: convert_optional_offset_size(config.rom_size),
@@ -366,15 +647,21 @@ pub unsafe extern "C" fn emulator_init(
     };
 
     // Create the emulator with callbacks
-    let emulator = match Emulator::from_args_with_callbacks(
+    let mut emulator = match Emulator::from_args_with_callbacks(
         args,
         config.capture_uart_output != 0,
         read_callback,
         write_callback,
+        None,
     ) {
         Ok(emu) => emu,
         Err(_) => return EmulatorError::InitializationFailed,
     };
+    // Report firmware exits through emulator_step() rather than exiting the host process.
+    if let Some(exit) = &emulator.emu_ctrl_exit {
+        exit.set_exit_process(false);
+    }
+    emulator.exit_on_uart_marker = convert_optional_c_string(config.exit_on_uart_marker);
 
     // Determine if we should be in GDB mode based on config
     let gdb_port = if config.gdb_port == 0 {
@@ -388,11 +675,21 @@ pub unsafe extern "C" fn emulator_init(
         CEmulatorState {
             wrapper: EmulatorWrapper::Gdb(gdb::gdb_target::GdbTarget::new(emulator)),
             gdb_port: Some(port),
+            breakpoints: HashSet::new(),
+            stopped_at_breakpoint: None,
+            uart_rx_ready_callback: None,
+            allow_unaligned_bus: config.allow_unaligned_bus != 0,
+            watchpoints: Vec::new(),
         }
     } else {
         CEmulatorState {
             wrapper: EmulatorWrapper::Normal(emulator),
             gdb_port: None,
+            breakpoints: HashSet::new(),
+            stopped_at_breakpoint: None,
+            uart_rx_ready_callback: None,
+            allow_unaligned_bus: config.allow_unaligned_bus != 0,
+            watchpoints: Vec::new(),
         }
     };
 
@@ -405,6 +702,13 @@ pub unsafe extern "C" fn emulator_init(
 
 /// Step the emulator once
 ///
+/// Returns `CStepAction::Break` without executing anything if the current PC
+/// matches a breakpoint added with `emulator_add_breakpoint()`, or after the
+/// step if the new PC matches one. Either way the instruction at the breakpoint
+/// has not yet been executed; the next step executes it.
+/// Returns `CStepAction::ExitSuccess` once the captured UART output ends with the
+/// configured `exit_on_uart_marker`.
+///
 /// This function works in both normal and GDB modes:
 /// - **Normal mode**: Steps the emulator directly
 /// - **GDB mode**: Steps the underlying emulator, allowing C to control execution
@@ -427,331 +731,375 @@ pub unsafe extern "C" fn emulator_step(emulator_memory: *mut CEmulator) -> CStep
     let emulator_ptr = emulator_memory as *mut CEmulatorState;
     let emulator_state = &mut *emulator_ptr;
 
-    match &mut emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => {
-            let action = emulator.step();
-            action.into()
-        }
-        EmulatorWrapper::Gdb(gdb_target) => {
-            // In GDB mode, step the underlying emulator directly
-            let action = gdb_target.emulator_mut().step();
-            action.into()
-        }
+    if emulator_state.at_breakpoint() {
+        return CStepAction::Break;
     }
-}
 
-/// Destroy the emulator and clean up resources
-///
-/// # Arguments
-/// * `emulator_memory` - Pointer to the initialized emulator
-///
-/// # Safety
-/// * `emulator_memory` must point to a valid, initialized emulator
-/// * After calling this function, the emulator memory should not be used
-#[no_mangle]
-pub unsafe extern "C" fn emulator_destroy(emulator_memory: *mut CEmulator) {
-    if !emulator_memory.is_null() {
-        let emulator_ptr = emulator_memory as *mut CEmulatorState;
-        ptr::drop_in_place(emulator_ptr);
-    }
+    // In GDB mode, this steps the underlying emulator directly
+    emulator_state.step()
 }
 
-/// Get UART output if it was captured
+/// Step the emulator up to `count` times
+///
+/// Stepping stops early when a step returns anything other than
+/// `CStepAction::Continue` (including hitting a breakpoint), or when an exit has been requested through
+/// `emulator_trigger_exit()`. Works in both normal and GDB modes.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `output_buffer` - Buffer to write the output to
-/// * `buffer_size` - Size of the output buffer
+/// * `count` - Maximum number of steps to execute
+/// * `out_action` - Pointer to store the action of the last step
+/// * `out_steps` - Pointer to store the number of steps actually executed
 ///
 /// # Returns
-/// * Number of bytes written to the buffer, or -1 if no output available
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
-/// * `output_buffer` must be a valid buffer of at least `buffer_size` bytes
+/// * `out_action` must be a valid pointer to a CStepAction
+/// * `out_steps` must be a valid pointer to a u64
 #[no_mangle]
-pub unsafe extern "C" fn emulator_get_uart_output(
+pub unsafe extern "C" fn emulator_step_n(
     emulator_memory: *mut CEmulator,
-    output_buffer: *mut c_char,
-    buffer_size: usize,
-) -> c_int {
-    if emulator_memory.is_null() || output_buffer.is_null() || buffer_size == 0 {
-        return -1;
+    count: c_ulonglong,
+    out_action: *mut CStepAction,
+    out_steps: *mut c_ulonglong,
+) -> EmulatorError {
+    if emulator_memory.is_null() || out_action.is_null() || out_steps.is_null() {
+        return EmulatorError::NullPointer;
     }
 
     let emulator_ptr = emulator_memory as *mut CEmulatorState;
     let emulator_state = &mut *emulator_ptr;
 
-    let uart_output = match &emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => &emulator.uart_output,
-        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().uart_output,
-    };
-
-    if let Some(ref uart_output_rc) = uart_output {
-        let uart_data = uart_output_rc.borrow();
-        let copy_len = std::cmp::min(uart_data.len(), buffer_size - 1);
+    let mut action = CStepAction::Continue;
+    let mut steps: c_ulonglong = 0;
+    while steps < count {
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            action = CStepAction::Break;
+            break;
+        }
 
-        if copy_len > 0 {
-            ptr::copy_nonoverlapping(uart_data.as_ptr() as *const c_char, output_buffer, copy_len);
+        // Stopping at a breakpoint before executing does not count as a step
+        if emulator_state.at_breakpoint() {
+            action = CStepAction::Break;
+            break;
         }
 
-        // Null terminate
-        *output_buffer.add(copy_len) = 0;
-        copy_len as c_int
-    } else {
-        -1
+        action = emulator_state.step();
+        steps += 1;
+
+        if action != CStepAction::Continue {
+            break;
+        }
     }
+
+    ptr::write(out_action, action);
+    *out_steps = steps;
+    EmulatorError::Success
 }
 
-/// Send a character to the emulator's UART RX (for console input simulation)
+/// Add a breakpoint at the given instruction address
+///
+/// Breakpoints are only checked by emulator_step() and emulator_step_n(); they
+/// are independent of any breakpoints set from a GDB session.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `character` - Character to send to UART RX
+/// * `addr` - Instruction address to break at
 ///
 /// # Returns
-/// * 1 if character was queued successfully, 0 if UART RX buffer is full, -1 on error
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_send_uart_char(
+pub unsafe extern "C" fn emulator_add_breakpoint(
     emulator_memory: *mut CEmulator,
-    character: c_char,
-) -> c_int {
+    addr: c_uint,
+) -> EmulatorError {
     if emulator_memory.is_null() {
-        return -1;
+        return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &mut *emulator_ptr;
-
-    let stdin_uart = match &emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => &emulator.stdin_uart,
-        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().stdin_uart,
-    };
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    state.breakpoints.insert(addr);
 
-    if let Some(ref stdin_uart_arc) = stdin_uart {
-        let mut uart_rx = stdin_uart_arc.lock().unwrap();
-        if uart_rx.is_none() {
-            *uart_rx = Some(character as u8);
-            1
-        } else {
-            0 // Buffer full
-        }
-    } else {
-        -1 // UART RX not enabled
-    }
+    EmulatorError::Success
 }
 
-/// Check if UART RX is ready to accept a new character
+/// Remove a breakpoint previously added with emulator_add_breakpoint()
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
+/// * `addr` - Instruction address of the breakpoint
 ///
 /// # Returns
-/// * 1 if UART RX is ready for input, 0 if busy/full, -1 on error
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if no breakpoint exists at `addr`
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_uart_rx_ready(emulator_memory: *mut CEmulator) -> c_int {
+pub unsafe extern "C" fn emulator_remove_breakpoint(
+    emulator_memory: *mut CEmulator,
+    addr: c_uint,
+) -> EmulatorError {
     if emulator_memory.is_null() {
-        return -1;
+        return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &mut *emulator_ptr;
-
-    let stdin_uart = match &emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => &emulator.stdin_uart,
-        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().stdin_uart,
-    };
-
-    if let Some(ref stdin_uart_arc) = stdin_uart {
-        let uart_rx = stdin_uart_arc.lock().unwrap();
-        if uart_rx.is_none() {
-            1
-        } else {
-            0
-        }
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    if state.breakpoints.remove(&addr) {
+        EmulatorError::Success
     } else {
-        -1 // UART RX not enabled
+        EmulatorError::InvalidArgs
     }
 }
 
-/// Get the most recent UART output (streaming mode)
-/// This function returns only the new output since the last call and clears the buffer.
+/// Add a watchpoint on the address range `[addr, addr + len)`
+///
+/// `on_write` is called with the address, size and value of every MCU bus write
+/// that touches the range, after the write has been committed. Writes by the
+/// firmware are reported from emulator_step() and emulator_step_n(); writes made
+/// with emulator_write_auto_root_bus() and emulator_write_auto_root_bus_block()
+/// are reported before those functions return. Returning 0 from the callback
+/// makes the current emulator_step() return `CStepAction::Break`.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `output_buffer` - Buffer to write the output to
-/// * `buffer_size` - Size of the output buffer
+/// * `addr` - Start address of the watched range
+/// * `len` - Length of the watched range in bytes
+/// * `on_write` - Function to call for each write
+/// * `context` - Context pointer passed to the callback
 ///
 /// # Returns
-/// * Number of bytes written to the buffer, or -1 if no output available
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `len` is 0 or the range wraps around
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
-/// * `output_buffer` must be a valid buffer of at least `buffer_size` bytes
+/// * `context` must remain valid for as long as the watchpoint is registered
 #[no_mangle]
-pub unsafe extern "C" fn emulator_get_uart_output_streaming(
+pub unsafe extern "C" fn emulator_add_watchpoint(
     emulator_memory: *mut CEmulator,
-    output_buffer: *mut c_char,
-    buffer_size: usize,
-) -> c_int {
-    if emulator_memory.is_null() || output_buffer.is_null() || buffer_size == 0 {
-        return -1;
+    addr: c_uint,
+    len: c_uint,
+    on_write: Option<CWatchpointCallback>,
+    context: *const std::ffi::c_void,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
     }
-
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &mut *emulator_ptr;
-
-    let uart_output = match &emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => &emulator.uart_output,
-        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().uart_output,
+    let Some(callback) = on_write else {
+        return EmulatorError::NullPointer;
+    };
+    let Some(end) = addr.checked_add(len).filter(|_| len != 0) else {
+        return EmulatorError::InvalidArgs;
     };
 
-    if let Some(ref uart_output_rc) = uart_output {
-        let mut uart_data = uart_output_rc.borrow_mut();
-        let copy_len = std::cmp::min(uart_data.len(), buffer_size - 1);
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    state.watchpoints.push(Watchpoint {
+        range: addr..end,
+        callback,
+        context,
+    });
+    state.update_watch_ranges();
 
-        if copy_len > 0 {
-            ptr::copy_nonoverlapping(uart_data.as_ptr() as *const c_char, output_buffer, copy_len);
-            // Clear the buffer after reading
-            uart_data.clear();
-        }
+    EmulatorError::Success
+}
 
-        // Null terminate
-        *output_buffer.add(copy_len) = 0;
-        copy_len as c_int
-    } else {
-        -1
+/// Remove all watchpoints starting at `addr` added with emulator_add_watchpoint()
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `addr` - Start address of the watched range
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if no watchpoint starts at `addr`
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_remove_watchpoint(
+    emulator_memory: *mut CEmulator,
+    addr: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let count = state.watchpoints.len();
+    state.watchpoints.retain(|w| w.range.start != addr);
+    if state.watchpoints.len() == count {
+        return EmulatorError::InvalidArgs;
     }
+    state.update_watch_ranges();
+
+    EmulatorError::Success
 }
 
-/// Start GDB server and wait for connection (blocking)
-/// This function should only be called if the emulator was initialized with a GDB port.
-///
-/// IMPORTANT: There are two ways to use GDB mode:
-///
-/// 1. **GDB-controlled execution**: Call this function and let GDB control all stepping.
-///    The GDB server will handle all emulator execution and stepping commands.
-///    Do NOT call emulator_step() while this function is running.
+/// Register a callback invoked when the UART RX buffer becomes ready for input
 ///
-/// 2. **C-controlled execution with GDB debugging**: DON'T call this function.
-///    Instead, call emulator_step() normally to control execution from C.
-///    Connect GDB to the port and use GDB for debugging/inspection only.
-///    In this mode, GDB can inspect state but C controls when steps happen.
+/// The callback runs from emulator_step() and emulator_step_n() whenever the
+/// firmware consumes the pending RX byte, so C harnesses can queue the next byte
+/// without polling emulator_uart_rx_ready().
 ///
 /// # Arguments
-/// * `emulator_memory` - Pointer to the initialized emulator in GDB mode
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `callback` - Function to call, or NULL to remove the current callback
+/// * `context` - Context pointer passed to the callback
 ///
 /// # Returns
-/// * `EmulatorError::Success` when GDB session ends normally
+/// * `EmulatorError::Success` on success
 /// * Appropriate error code on failure
 ///
 /// # Safety
-/// * `emulator_memory` must point to a valid, initialized emulator in GDB mode
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `context` must remain valid for as long as the callback is registered
 #[no_mangle]
-pub unsafe extern "C" fn emulator_run_gdb_server(emulator_memory: *mut CEmulator) -> EmulatorError {
+pub unsafe extern "C" fn emulator_set_uart_rx_ready_callback(
+    emulator_memory: *mut CEmulator,
+    callback: Option<CUartRxReadyCallback>,
+    context: *const std::ffi::c_void,
+) -> EmulatorError {
     if emulator_memory.is_null() {
         return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &mut *emulator_ptr;
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    state.uart_rx_ready_callback = callback.map(|callback| (callback, context));
 
-    match (&mut emulator_state.wrapper, emulator_state.gdb_port) {
-        (EmulatorWrapper::Gdb(gdb_target), Some(port)) => {
-            gdb::gdb_state::wait_for_gdb_run(gdb_target, port);
-            EmulatorError::Success
-        }
-        (EmulatorWrapper::Normal(_), _) => EmulatorError::InvalidArgs,
-        (EmulatorWrapper::Gdb(_), None) => EmulatorError::InvalidArgs,
-    }
+    EmulatorError::Success
 }
 
-/// Check if the emulator is in GDB mode
+/// Enable or disable counting MCU bus accesses per peripheral
+///
+/// Counting is disabled by default. Enabling it resets the counters.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
+/// * `enable` - 1 to enable counting, 0 to disable it
 ///
 /// # Returns
-/// * 1 if in GDB mode, 0 if in normal mode
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_is_gdb_mode(emulator_memory: *mut CEmulator) -> c_int {
+pub unsafe extern "C" fn emulator_set_bus_stats_enabled(
+    emulator_memory: *mut CEmulator,
+    enable: c_uchar,
+) -> EmulatorError {
     if emulator_memory.is_null() {
-        return 0;
+        return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &*emulator_ptr;
-
-    match emulator_state.wrapper {
-        EmulatorWrapper::Gdb(_) => 1,
-        EmulatorWrapper::Normal(_) => 0,
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.set_bus_access_stats_enabled(enable != 0),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target
+            .emulator_mut()
+            .set_bus_access_stats_enabled(enable != 0),
     }
+
+    EmulatorError::Success
 }
 
-/// Get the GDB port if the emulator is in GDB mode
+/// Get the MCU bus access counts per peripheral
+///
+/// All counts are zero if counting has not been enabled with
+/// emulator_set_bus_stats_enabled().
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
+/// * `out_stats` - Receives the access counts
 ///
 /// # Returns
-/// * GDB port number, or 0 if not in GDB mode
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
+/// * `out_stats` must be a valid pointer to a CBusAccessStats
 #[no_mangle]
-pub unsafe extern "C" fn emulator_get_gdb_port(emulator_memory: *mut CEmulator) -> c_uint {
-    if emulator_memory.is_null() {
-        return 0;
+pub unsafe extern "C" fn emulator_get_bus_stats(
+    emulator_memory: *mut CEmulator,
+    out_stats: *mut CBusAccessStats,
+) -> EmulatorError {
+    if emulator_memory.is_null() || out_stats.is_null() {
+        return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &*emulator_ptr;
+    let emulator_state = &*(emulator_memory as *const CEmulatorState);
+    let stats = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.bus_access_stats(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().bus_access_stats(),
+    };
+    ptr::write(out_stats, stats.into());
 
-    emulator_state.gdb_port.unwrap_or(0) as c_uint
+    EmulatorError::Success
 }
 
-/// Get the current program counter (PC) of the MCU CPU
+/// Inject an ECC error into the SRAM or DCCM word containing `addr`
+///
+/// The next MCU read of that word fails with a load access fault for a
+/// double-bit error, or is corrected for a single-bit error. Corrected errors
+/// increment the MCI SRAM ECC correctable counter and set its notif0 status.
+/// The injection is cleared after it fires once.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
+/// * `addr` - Address in SRAM or DCCM
+/// * `double_bit` - 1 for an uncorrectable double-bit error, 0 for a single-bit error
 ///
 /// # Returns
-/// * Current PC value of the MCU CPU
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `addr` is not in SRAM or DCCM
+/// * Appropriate error code on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_get_pc(emulator_memory: *mut CEmulator) -> c_uint {
+pub unsafe extern "C" fn emulator_inject_ecc_error(
+    emulator_memory: *mut CEmulator,
+    addr: c_uint,
+    double_bit: c_uchar,
+) -> EmulatorError {
     if emulator_memory.is_null() {
-        return 0;
+        return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &*emulator_ptr;
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    let injected = match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.inject_ecc_error(addr, double_bit != 0),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target
+            .emulator_mut()
+            .inject_ecc_error(addr, double_bit != 0),
+    };
 
-    match &emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.get_pc(),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().get_pc(),
+    if injected {
+        EmulatorError::Success
+    } else {
+        EmulatorError::InvalidArgs
     }
 }
 
-/// Start the I3C controller thread
+/// Register a callback invoked whenever the MCU CPU enters its trap handler
 ///
-/// This function starts the I3C controller's background thread that processes
-/// incoming commands and sends responses. Should be called after emulator_init
-/// if I3C functionality is needed.
+/// The callback receives mcause and mepc. Returning 0 halts the emulator: the
+/// `emulator_step()` that took the trap returns `CStepAction::Break`.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
+/// * `callback` - Trap callback, or null to remove the current one
+/// * `context` - Context pointer passed to the callback
 ///
 /// # Returns
 /// * `EmulatorError::Success` on success
@@ -759,384 +1107,1426 @@ pub unsafe extern "C" fn emulator_get_pc(emulator_memory: *mut CEmulator) -> c_u
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
+/// * `callback` and `context` must remain valid until replaced or the emulator is destroyed
 #[no_mangle]
-pub unsafe extern "C" fn emulator_start_i3c_controller(
+pub unsafe extern "C" fn emulator_set_trap_callback(
     emulator_memory: *mut CEmulator,
+    callback: Option<CTrapCallback>,
+    context: *const std::ffi::c_void,
 ) -> EmulatorError {
     if emulator_memory.is_null() {
         return EmulatorError::NullPointer;
     }
 
-    let emulator_ptr = emulator_memory as *mut CEmulatorState;
-    let emulator_state = &mut *emulator_ptr;
-
+    let trap_callback = callback.map(|c_callback| convert_c_trap_callback(c_callback, context));
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
     match &mut emulator_state.wrapper {
-        EmulatorWrapper::Normal(emulator) => {
-            emulator.i3c_controller.start();
-            EmulatorError::Success
-        }
-        EmulatorWrapper::Gdb(gdb_target) => {
-            gdb_target.emulator_mut().i3c_controller.start();
-            EmulatorError::Success
-        }
+        EmulatorWrapper::Normal(emulator) => emulator.trap_callback = trap_callback,
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().trap_callback = trap_callback,
     }
-}
 
-/// Trigger an exit request by setting EMULATOR_RUNNING to false
-/// This will cause any loops waiting on EMULATOR_RUNNING to exit
-///
-/// # Returns
-/// * `EmulatorError::Success` on success
-#[no_mangle]
-pub extern "C" fn emulator_trigger_exit() -> EmulatorError {
-    MCU_RUNNING.store(false, Ordering::Relaxed);
     EmulatorError::Success
 }
 
-/// Example external read callback that returns the address as data
-/// This is a simple test callback that C code can use for testing
+/// Destroy the emulator and clean up resources
 ///
 /// # Arguments
-/// * `context` - Context pointer (unused in this example)
-/// * `size` - Size of the read operation (1, 2, or 4 bytes)
-/// * `addr` - Address being read from
-/// * `buffer` - Pointer to write the read data to
-///
-/// # Returns
-/// * 1 for success
+/// * `emulator_memory` - Pointer to the initialized emulator
 ///
 /// # Safety
-/// * `buffer` must be a valid pointer to a u32 that can be written to
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * After calling this function, the emulator memory should not be used
 #[no_mangle]
-pub unsafe extern "C" fn example_external_read_callback(
-    _context: *const std::ffi::c_void,
-    _size: c_uint,
-    addr: c_uint,
-    buffer: *mut c_uint,
-) -> c_int {
-    if buffer.is_null() {
-        return 0;
+pub unsafe extern "C" fn emulator_destroy(emulator_memory: *mut CEmulator) {
+    if !emulator_memory.is_null() {
+        let emulator_ptr = emulator_memory as *mut CEmulatorState;
+        ptr::drop_in_place(emulator_ptr);
     }
-
-    // Simple example: return the address as the read data
-    *buffer = addr;
-    1 // Success
 }
 
-/// Example external write callback that logs the operation
-/// This is a simple test callback that C code can use for testing
+/// Get UART output if it was captured
 ///
 /// # Arguments
-/// * `context` - Context pointer (unused in this example)
-/// * `size` - Size of the write operation (1, 2, or 4 bytes)
-/// * `addr` - Address being written to
-/// * `data` - Data being written
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `output_buffer` - Buffer to write the output to
+/// * `buffer_size` - Size of the output buffer
 ///
 /// # Returns
-/// * 1 for success
+/// * Number of bytes written to the buffer, or -1 if no output available
 ///
 /// # Safety
-/// * This function only reads the provided parameters and performs no unsafe operations
-/// * All parameters are passed by value, so no pointer safety concerns
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `output_buffer` must be a valid buffer of at least `buffer_size` bytes
 #[no_mangle]
-pub unsafe extern "C" fn example_external_write_callback(
-    _context: *const std::ffi::c_void,
-    size: c_uint,
-    addr: c_uint,
-    data: c_uint,
+pub unsafe extern "C" fn emulator_get_uart_output(
+    emulator_memory: *mut CEmulator,
+    output_buffer: *mut c_char,
+    buffer_size: usize,
 ) -> c_int {
-    println!(
-        "External write: size={}, addr=0x{:08x}, data=0x{:08x}",
-        size, addr, data
-    );
-    1 // Success
-}
+    if emulator_memory.is_null() || output_buffer.is_null() || buffer_size == 0 {
+        return -1;
+    }
 
-// Helper functions
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-/// Read a general purpose register (X0-X31)
+    let uart_output = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.uart_output,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().uart_output,
+    };
+
+    if let Some(ref uart_output_rc) = uart_output {
+        let uart_data = uart_output_rc.borrow();
+        let copy_len = std::cmp::min(uart_data.len(), buffer_size - 1);
+
+        if copy_len > 0 {
+            ptr::copy_nonoverlapping(uart_data.as_ptr() as *const c_char, output_buffer, copy_len);
+        }
+
+        // Null terminate
+        *output_buffer.add(copy_len) = 0;
+        copy_len as c_int
+    } else {
+        -1
+    }
+}
+
+/// Send a character to the emulator's UART RX (for console input simulation)
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `reg_num` - Register number (0-31)
-/// * `value` - Pointer to store the register value
+/// * `character` - Character to send to UART RX
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * 1 if character was queued successfully, 0 if UART RX buffer is full, -1 on error
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
-/// * `value` must be a valid pointer to a u32
 #[no_mangle]
-pub unsafe extern "C" fn emulator_read_xreg(
+pub unsafe extern "C" fn emulator_send_uart_char(
     emulator_memory: *mut CEmulator,
-    reg_num: c_uint,
-    value: *mut c_uint,
-) -> EmulatorError {
-    if emulator_memory.is_null() || value.is_null() {
-        return EmulatorError::NullPointer;
+    character: c_char,
+) -> c_int {
+    if emulator_memory.is_null() {
+        return -1;
     }
 
-    if reg_num > 31 {
-        return EmulatorError::InvalidArgs;
-    }
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
-
-    let result = match &state.wrapper {
-        EmulatorWrapper::Normal(emulator) => {
-            let xreg = XReg::from(reg_num);
-            emulator.mcu_cpu.read_xreg(xreg)
-        }
-        EmulatorWrapper::Gdb(gdb_target) => {
-            let xreg = XReg::from(reg_num);
-            gdb_target.emulator().mcu_cpu.read_xreg(xreg)
-        }
+    let stdin_uart = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.stdin_uart,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().stdin_uart,
     };
 
-    match result {
-        Ok(val) => {
-            *value = val;
-            EmulatorError::Success
+    if let Some(ref stdin_uart_arc) = stdin_uart {
+        let mut uart_rx = stdin_uart_arc.lock().unwrap();
+        if uart_rx.is_none() {
+            *uart_rx = Some(character as u8);
+            1
+        } else {
+            0 // Buffer full
         }
-        Err(_) => EmulatorError::InvalidArgs,
+    } else {
+        -1 // UART RX not enabled
     }
 }
 
-/// Write a general purpose register (X0-X31)
+/// Send a string of bytes to the emulator's UART RX (for console input simulation)
+///
+/// The UART RX buffer only holds as many bytes as are free at the time of the call,
+/// so this queues what fits and returns the count. The caller should step the
+/// emulator and call again with the remaining bytes.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `reg_num` - Register number (0-31)
-/// * `value` - Value to write to the register
+/// * `data` - Bytes to send to UART RX
+/// * `len` - Number of bytes in `data`
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * Number of bytes queued (0 if the UART RX buffer is full or `len` is 0), -1 on error
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
+/// * `data` must point to at least `len` readable bytes
 #[no_mangle]
-pub unsafe extern "C" fn emulator_write_xreg(
+pub unsafe extern "C" fn emulator_send_uart_str(
     emulator_memory: *mut CEmulator,
-    reg_num: c_uint,
-    value: c_uint,
-) -> EmulatorError {
-    if emulator_memory.is_null() {
-        return EmulatorError::NullPointer;
+    data: *const c_char,
+    len: usize,
+) -> c_int {
+    if emulator_memory.is_null() || data.is_null() {
+        return -1;
     }
-
-    if reg_num > 31 {
-        return EmulatorError::InvalidArgs;
+    if len == 0 {
+        return 0;
     }
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-    let result = match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => {
-            let xreg = XReg::from(reg_num);
-            emulator.mcu_cpu.write_xreg(xreg, value)
-        }
-        EmulatorWrapper::Gdb(gdb_target) => {
-            let xreg = XReg::from(reg_num);
-            gdb_target.emulator_mut().mcu_cpu.write_xreg(xreg, value)
-        }
+    let stdin_uart = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.stdin_uart,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().stdin_uart,
     };
 
-    match result {
-        Ok(_) => EmulatorError::Success,
-        Err(_) => EmulatorError::InvalidArgs,
+    let Some(stdin_uart_arc) = stdin_uart else {
+        return -1; // UART RX not enabled
+    };
+
+    let data = std::slice::from_raw_parts(data as *const u8, len);
+    let mut uart_rx = stdin_uart_arc.lock().unwrap();
+    let mut queued = 0;
+    for &byte in data {
+        if uart_rx.is_some() {
+            break; // Buffer full
+        }
+        *uart_rx = Some(byte);
+        queued += 1;
     }
+    queued
 }
 
-/// Read a Control and Status Register (CSR)
+/// Check if UART RX is ready to accept a new character
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `csr_addr` - CSR address (e.g., 0x300 for MSTATUS)
-/// * `value` - Pointer to store the CSR value
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * 1 if UART RX is ready for input, 0 if busy/full, -1 on error
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
-/// * `value` must be a valid pointer to a u32
 #[no_mangle]
-pub unsafe extern "C" fn emulator_read_csr(
-    emulator_memory: *mut CEmulator,
-    csr_addr: c_uint,
-    value: *mut c_uint,
-) -> EmulatorError {
-    if emulator_memory.is_null() || value.is_null() {
-        return EmulatorError::NullPointer;
+pub unsafe extern "C" fn emulator_uart_rx_ready(emulator_memory: *mut CEmulator) -> c_int {
+    if emulator_memory.is_null() {
+        return -1;
     }
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-    let result = match &state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.read_csr_machine(csr_addr as RvAddr),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target
-            .emulator()
-            .mcu_cpu
-            .read_csr_machine(csr_addr as RvAddr),
+    let stdin_uart = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.stdin_uart,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().stdin_uart,
     };
 
-    match result {
-        Ok(val) => {
-            *value = val;
-            EmulatorError::Success
+    if let Some(ref stdin_uart_arc) = stdin_uart {
+        let uart_rx = stdin_uart_arc.lock().unwrap();
+        if uart_rx.is_none() {
+            1
+        } else {
+            0
         }
-        Err(_) => EmulatorError::InvalidArgs,
+    } else {
+        -1 // UART RX not enabled
     }
 }
 
-/// Write a Control and Status Register (CSR)
+/// Get the most recent UART output (streaming mode)
+/// This function returns only the new output since the last call and clears the buffer.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `csr_addr` - CSR address (e.g., 0x300 for MSTATUS)
-/// * `value` - Value to write to the CSR
+/// * `output_buffer` - Buffer to write the output to
+/// * `buffer_size` - Size of the output buffer
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * Number of bytes written to the buffer, or -1 if no output available
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
+/// * `output_buffer` must be a valid buffer of at least `buffer_size` bytes
 #[no_mangle]
-pub unsafe extern "C" fn emulator_write_csr(
+pub unsafe extern "C" fn emulator_get_uart_output_streaming(
     emulator_memory: *mut CEmulator,
-    csr_addr: c_uint,
-    value: c_uint,
-) -> EmulatorError {
-    if emulator_memory.is_null() {
-        return EmulatorError::NullPointer;
+    output_buffer: *mut c_char,
+    buffer_size: usize,
+) -> c_int {
+    if emulator_memory.is_null() || output_buffer.is_null() || buffer_size == 0 {
+        return -1;
     }
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-    let result = match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator
-            .mcu_cpu
-            .write_csr_machine(csr_addr as RvAddr, value),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target
-            .emulator_mut()
-            .mcu_cpu
-            .write_csr_machine(csr_addr as RvAddr, value),
+    let uart_output = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.uart_output,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().uart_output,
     };
 
-    match result {
-        Ok(_) => EmulatorError::Success,
-        Err(_) => EmulatorError::InvalidArgs,
+    if let Some(ref uart_output_rc) = uart_output {
+        let mut uart_data = uart_output_rc.borrow_mut();
+        let copy_len = std::cmp::min(uart_data.len(), buffer_size - 1);
+
+        if copy_len > 0 {
+            ptr::copy_nonoverlapping(uart_data.as_ptr() as *const c_char, output_buffer, copy_len);
+            // Clear the buffer after reading
+            uart_data.clear();
+        }
+
+        // Null terminate
+        *output_buffer.add(copy_len) = 0;
+        copy_len as c_int
+    } else {
+        -1
     }
 }
 
-/// Read the program counter (PC)
+/// Start GDB server and wait for connection (blocking)
+/// This function should only be called if the emulator was initialized with a GDB port.
+///
+/// IMPORTANT: There are two ways to use GDB mode:
+///
+/// 1. **GDB-controlled execution**: Call this function and let GDB control all stepping.
+///    The GDB server will handle all emulator execution and stepping commands.
+///    Do NOT call emulator_step() while this function is running.
+///
+/// 2. **C-controlled execution with GDB debugging**: DON'T call this function.
+///    Instead, call emulator_step() normally to control execution from C.
+///    Connect GDB to the port and use GDB for debugging/inspection only.
+///    In this mode, GDB can inspect state but C controls when steps happen.
 ///
 /// # Arguments
-/// * `emulator_memory` - Pointer to the initialized emulator
-/// * `value` - Pointer to store the PC value
+/// * `emulator_memory` - Pointer to the initialized emulator in GDB mode
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
+/// * `EmulatorError::Success` when GDB session ends normally
 /// * Appropriate error code on failure
 ///
 /// # Safety
-/// * `emulator_memory` must point to a valid, initialized emulator
-/// * `value` must be a valid pointer to a u32
+/// * `emulator_memory` must point to a valid, initialized emulator in GDB mode
 #[no_mangle]
-pub unsafe extern "C" fn emulator_read_pc(
-    emulator_memory: *mut CEmulator,
-    value: *mut c_uint,
-) -> EmulatorError {
-    if emulator_memory.is_null() || value.is_null() {
+pub unsafe extern "C" fn emulator_run_gdb_server(emulator_memory: *mut CEmulator) -> EmulatorError {
+    if emulator_memory.is_null() {
         return EmulatorError::NullPointer;
     }
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
-
-    let pc = match &state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.read_pc(),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().mcu_cpu.read_pc(),
-    };
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
 
-    *value = pc;
-    EmulatorError::Success
+    match (&mut emulator_state.wrapper, emulator_state.gdb_port) {
+        (EmulatorWrapper::Gdb(gdb_target), Some(port)) => {
+            gdb::gdb_state::wait_for_gdb_run(gdb_target, port);
+            EmulatorError::Success
+        }
+        (EmulatorWrapper::Normal(_), _) => EmulatorError::InvalidArgs,
+        (EmulatorWrapper::Gdb(_), None) => EmulatorError::InvalidArgs,
+    }
 }
 
-/// Write the program counter (PC)
+/// Check if the emulator is in GDB mode
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `value` - Value to write to the PC
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * 1 if in GDB mode, 0 if in normal mode
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_write_pc(
-    emulator_memory: *mut CEmulator,
-    value: c_uint,
-) -> EmulatorError {
+pub unsafe extern "C" fn emulator_is_gdb_mode(emulator_memory: *mut CEmulator) -> c_int {
     if emulator_memory.is_null() {
-        return EmulatorError::NullPointer;
+        return 0;
     }
 
-    let state = &mut *(emulator_memory as *mut CEmulatorState);
-
-    match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.write_pc(value),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().mcu_cpu.write_pc(value),
-    };
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &*emulator_ptr;
 
-    EmulatorError::Success
+    match emulator_state.wrapper {
+        EmulatorWrapper::Gdb(_) => 1,
+        EmulatorWrapper::Normal(_) => 0,
+    }
 }
 
-/// Set an external interrupt level
+/// Get the GDB port if the emulator is in GDB mode
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `irq_num` - IRQ number to set (0-31)
-/// * `is_high` - 1 to set interrupt high, 0 to set interrupt low
 ///
 /// # Returns
-/// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * GDB port number, or 0 if not in GDB mode
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
 #[no_mangle]
-pub unsafe extern "C" fn emulator_set_external_interrupt(
-    emulator_memory: *mut CEmulator,
-    irq_num: c_uint,
-    is_high: c_uchar,
-) -> EmulatorError {
+pub unsafe extern "C" fn emulator_get_gdb_port(emulator_memory: *mut CEmulator) -> c_uint {
     if emulator_memory.is_null() {
-        return EmulatorError::NullPointer;
+        return 0;
     }
 
-    if irq_num > 31 {
-        return EmulatorError::InvalidArgs;
-    }
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &*emulator_ptr;
+
+    emulator_state.gdb_port.unwrap_or(0) as c_uint
+}
+
+/// Get the current program counter (PC) of the MCU CPU
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * Current PC value of the MCU CPU
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_get_pc(emulator_memory: *mut CEmulator) -> c_uint {
+    if emulator_memory.is_null() {
+        return 0;
+    }
+
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &*emulator_ptr;
+
+    match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.get_pc(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().get_pc(),
+    }
+}
+
+/// Get the number of clock cycles the MCU CPU has executed
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * Current cycle count, or -1 if the emulator pointer is null
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_get_cycle_count(emulator_memory: *mut CEmulator) -> c_longlong {
+    if emulator_memory.is_null() {
+        return -1;
+    }
+
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &*emulator_ptr;
+
+    let now = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.clock.now(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().mcu_cpu.clock.now(),
+    };
+    now as c_longlong
+}
+
+/// Get the number of instructions retired by the MCU CPU
+///
+/// The value is read from the `minstret`/`minstreth` CSRs.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * Retired instruction count, or -1 if the emulator pointer is null or the
+///   counter cannot be read
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_get_instret(emulator_memory: *mut CEmulator) -> c_longlong {
+    if emulator_memory.is_null() {
+        return -1;
+    }
+
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &*emulator_ptr;
+
+    let cpu = match &emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.mcu_cpu,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().mcu_cpu,
+    };
+
+    match (
+        cpu.read_csr_machine(CSR_MINSTRET),
+        cpu.read_csr_machine(CSR_MINSTRETH),
+    ) {
+        (Ok(lo), Ok(hi)) => (((hi as u64) << 32) | lo as u64) as c_longlong,
+        _ => -1,
+    }
+}
+
+/// Start the I3C controller thread
+///
+/// This function starts the I3C controller's background thread that processes
+/// incoming commands and sends responses. Should be called after emulator_init
+/// if I3C functionality is needed.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_start_i3c_controller(
+    emulator_memory: *mut CEmulator,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let emulator_ptr = emulator_memory as *mut CEmulatorState;
+    let emulator_state = &mut *emulator_ptr;
+
+    match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => {
+            emulator.i3c_controller.start();
+            EmulatorError::Success
+        }
+        EmulatorWrapper::Gdb(gdb_target) => {
+            gdb_target.emulator_mut().i3c_controller.start();
+            EmulatorError::Success
+        }
+    }
+}
+
+/// Get the dynamic address assigned to the MCU I3C target
+///
+/// This is the same address `McuHwModel::i3c_address()` reports, and the one
+/// in-process I3C traffic (`emulator_i3c_send()`/`emulator_i3c_recv()`) is
+/// addressed to.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `out_addr` - Receives the address if one has been assigned
+///
+/// # Returns
+/// * 1 if an address has been assigned and was written to `out_addr`
+/// * 0 if no address has been assigned yet
+/// * -1 on error
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `out_addr` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn emulator_get_i3c_address(
+    emulator_memory: *mut CEmulator,
+    out_addr: *mut c_uchar,
+) -> c_int {
+    if emulator_memory.is_null() || out_addr.is_null() {
+        return -1;
+    }
+
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    let addr = match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.get_i3c_addr(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().get_i3c_addr(),
+    };
+
+    match addr {
+        Some(addr) => {
+            *out_addr = addr;
+            1
+        }
+        None => 0,
+    }
+}
+
+/// Map a firmware exit code to the value returned by emulator_get_exit_status()
+fn exit_status(exit_code: Option<u32>) -> c_int {
+    match exit_code {
+        None => 0,
+        Some(0) => 1,
+        Some(_) => 2,
+    }
+}
+
+/// Get whether the firmware has signalled test completion
+///
+/// The firmware signals completion by writing an exit code to the emulation
+/// control register: 0 for a pass, anything else for a failure. Once it has,
+/// emulator_step() returns `CStepAction::ExitSuccess` or
+/// `CStepAction::ExitFailure` accordingly instead of exiting the process.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * 0 if the firmware has not exited
+/// * 1 if the firmware passed
+/// * 2 if the firmware failed
+/// * -1 on error
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_get_exit_status(emulator_memory: *mut CEmulator) -> c_int {
+    if emulator_memory.is_null() {
+        return -1;
+    }
+
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    exit_status(emulator_state.emulator_mut().exit_code())
+}
+
+/// Send a private write to the MCU I3C target without using the I3C socket
+///
+/// The data is queued for the target directly and is sent verbatim, so a
+/// trailing PEC byte must be included if the firmware expects one. The firmware
+/// sees the write on a following `emulator_step()`. Do not combine with
+/// `emulator_start_i3c_controller()`, whose thread also consumes the target queues.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `data` - Bytes to write
+/// * `len` - Number of bytes in `data`
+///
+/// # Returns
+/// * Number of bytes queued, or -1 on error
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `data` must be valid for reads of `len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn emulator_i3c_send(
+    emulator_memory: *mut CEmulator,
+    data: *const c_uchar,
+    len: usize,
+) -> c_int {
+    if emulator_memory.is_null() || (data.is_null() && len > 0) {
+        return -1;
+    }
+
+    let data = if len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    };
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    let result = match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.i3c_send(data),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().i3c_send(data),
+    };
+
+    match result {
+        Ok(()) => len as c_int,
+        Err(_) => -1,
+    }
+}
+
+/// Receive the next response the firmware wrote to the MCU I3C target
+///
+/// Responses become available after the `emulator_step()` in which the
+/// firmware queues them, in the order they were queued. A response larger than
+/// `cap` is discarded and -1 is returned.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `buffer` - Buffer to store the response data
+/// * `cap` - Size of `buffer`
+///
+/// # Returns
+/// * Number of bytes written to `buffer` (0 if no response is ready), or -1 on error
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `buffer` must be valid for writes of `cap` bytes
+#[no_mangle]
+pub unsafe extern "C" fn emulator_i3c_recv(
+    emulator_memory: *mut CEmulator,
+    buffer: *mut c_uchar,
+    cap: usize,
+) -> c_int {
+    if emulator_memory.is_null() || buffer.is_null() {
+        return -1;
+    }
+
+    let emulator_state = &mut *(emulator_memory as *mut CEmulatorState);
+    let resp = match &mut emulator_state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.i3c_recv(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().i3c_recv(),
+    };
+
+    match resp {
+        None => 0,
+        Some(data) if data.len() > cap => -1,
+        Some(data) => {
+            ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+            data.len() as c_int
+        }
+    }
+}
+
+/// Trigger an exit request by setting EMULATOR_RUNNING to false
+/// This will cause any loops waiting on EMULATOR_RUNNING to exit
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+#[no_mangle]
+pub extern "C" fn emulator_trigger_exit() -> EmulatorError {
+    MCU_RUNNING.store(false, Ordering::Relaxed);
+    EmulatorError::Success
+}
+
+/// Get a human-readable description of an `EmulatorError` code
+///
+/// # Arguments
+/// * `code` - Error code returned by one of the emulator functions
+///
+/// # Returns
+/// * Pointer to a static null-terminated string describing the error.
+///   The string has static lifetime and must not be freed.
+#[no_mangle]
+pub extern "C" fn emulator_error_string(code: c_int) -> *const c_char {
+    let msg: &'static CStr = match code {
+        x if x == EmulatorError::Success as c_int => c"success",
+        x if x == EmulatorError::InvalidArgs as c_int => c"invalid arguments",
+        x if x == EmulatorError::InitializationFailed as c_int => c"initialization failed",
+        x if x == EmulatorError::NullPointer as c_int => c"null pointer",
+        x if x == EmulatorError::InvalidEmulator as c_int => c"invalid emulator",
+        x if x == EmulatorError::BusLoadAccessFault as c_int => c"bus load access fault",
+        x if x == EmulatorError::BusStoreAccessFault as c_int => c"bus store access fault",
+        x if x == EmulatorError::BusLoadAddrMisaligned as c_int => c"bus load address misaligned",
+        x if x == EmulatorError::BusStoreAddrMisaligned as c_int => c"bus store address misaligned",
+        x if x == EmulatorError::RestoreFailed as c_int => c"snapshot restore failed",
+        _ => c"unknown error",
+    };
+    msg.as_ptr()
+}
+
+/// Example external read callback that returns the address as data
+/// This is a simple test callback that C code can use for testing
+///
+/// # Arguments
+/// * `context` - Context pointer (unused in this example)
+/// * `size` - Size of the read operation (1, 2, or 4 bytes)
+/// * `addr` - Address being read from
+/// * `buffer` - Pointer to write the read data to
+///
+/// # Returns
+/// * 1 for success
+///
+/// # Safety
+/// * `buffer` must be a valid pointer to a u32 that can be written to
+#[no_mangle]
+pub unsafe extern "C" fn example_external_read_callback(
+    _context: *const std::ffi::c_void,
+    _size: c_uint,
+    addr: c_uint,
+    buffer: *mut c_uint,
+) -> c_int {
+    if buffer.is_null() {
+        return 0;
+    }
+
+    // Simple example: return the address as the read data
+    *buffer = addr;
+    1 // Success
+}
+
+/// Example external write callback that logs the operation
+/// This is a simple test callback that C code can use for testing
+///
+/// # Arguments
+/// * `context` - Context pointer (unused in this example)
+/// * `size` - Size of the write operation (1, 2, or 4 bytes)
+/// * `addr` - Address being written to
+/// * `data` - Data being written
+///
+/// # Returns
+/// * 1 for success
+///
+/// # Safety
+/// * This function only reads the provided parameters and performs no unsafe operations
+/// * All parameters are passed by value, so no pointer safety concerns
+#[no_mangle]
+pub unsafe extern "C" fn example_external_write_callback(
+    _context: *const std::ffi::c_void,
+    size: c_uint,
+    addr: c_uint,
+    data: c_uint,
+) -> c_int {
+    println!(
+        "External write: size={}, addr=0x{:08x}, data=0x{:08x}",
+        size, addr, data
+    );
+    1 // Success
+}
+
+// Helper functions
+
+/// Read a general purpose register (X0-X31)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `reg_num` - Register number (0-31)
+/// * `value` - Pointer to store the register value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `value` must be a valid pointer to a u32
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_xreg(
+    emulator_memory: *mut CEmulator,
+    reg_num: c_uint,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    if reg_num > 31 {
+        return EmulatorError::InvalidArgs;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let result = match &state.wrapper {
+        EmulatorWrapper::Normal(emulator) => {
+            let xreg = XReg::from(reg_num);
+            emulator.mcu_cpu.read_xreg(xreg)
+        }
+        EmulatorWrapper::Gdb(gdb_target) => {
+            let xreg = XReg::from(reg_num);
+            gdb_target.emulator().mcu_cpu.read_xreg(xreg)
+        }
+    };
+
+    match result {
+        Ok(val) => {
+            *value = val;
+            EmulatorError::Success
+        }
+        Err(_) => EmulatorError::InvalidArgs,
+    }
+}
+
+/// Write a general purpose register (X0-X31)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `reg_num` - Register number (0-31)
+/// * `value` - Value to write to the register
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_write_xreg(
+    emulator_memory: *mut CEmulator,
+    reg_num: c_uint,
+    value: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    if reg_num > 31 {
+        return EmulatorError::InvalidArgs;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let result = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => {
+            let xreg = XReg::from(reg_num);
+            emulator.mcu_cpu.write_xreg(xreg, value)
+        }
+        EmulatorWrapper::Gdb(gdb_target) => {
+            let xreg = XReg::from(reg_num);
+            gdb_target.emulator_mut().mcu_cpu.write_xreg(xreg, value)
+        }
+    };
+
+    match result {
+        Ok(_) => EmulatorError::Success,
+        Err(_) => EmulatorError::InvalidArgs,
+    }
+}
+
+/// Read a Control and Status Register (CSR)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `csr_addr` - CSR address (e.g., 0x300 for MSTATUS)
+/// * `value` - Pointer to store the CSR value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `value` must be a valid pointer to a u32
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_csr(
+    emulator_memory: *mut CEmulator,
+    csr_addr: c_uint,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let result = match &state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.read_csr_machine(csr_addr as RvAddr),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target
+            .emulator()
+            .mcu_cpu
+            .read_csr_machine(csr_addr as RvAddr),
+    };
+
+    match result {
+        Ok(val) => {
+            *value = val;
+            EmulatorError::Success
+        }
+        Err(_) => EmulatorError::InvalidArgs,
+    }
+}
+
+/// Write a Control and Status Register (CSR)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `csr_addr` - CSR address (e.g., 0x300 for MSTATUS)
+/// * `value` - Value to write to the CSR
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_write_csr(
+    emulator_memory: *mut CEmulator,
+    csr_addr: c_uint,
+    value: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let result = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator
+            .mcu_cpu
+            .write_csr_machine(csr_addr as RvAddr, value),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target
+            .emulator_mut()
+            .mcu_cpu
+            .write_csr_machine(csr_addr as RvAddr, value),
+    };
+
+    match result {
+        Ok(_) => EmulatorError::Success,
+        Err(_) => EmulatorError::InvalidArgs,
+    }
+}
+
+/// Machine-mode CSRs that can be accessed by name with `emulator_read_csr_by_name()`
+const CSR_NAMES: &[(&str, RvAddr)] = &[
+    ("mstatus", 0x300),
+    ("mie", 0x304),
+    ("mtvec", 0x305),
+    ("mepc", 0x341),
+    ("mcause", 0x342),
+    ("mip", 0x344),
+];
+
+/// Look up the address of a CSR in `CSR_NAMES`
+fn csr_addr_from_name(name: &str) -> Option<RvAddr> {
+    CSR_NAMES
+        .iter()
+        .find(|(csr_name, _)| *csr_name == name)
+        .map(|&(_, addr)| addr)
+}
+
+/// Read a Control and Status Register (CSR) by name
+///
+/// Supported names are "mstatus", "mepc", "mcause", "mtvec", "mie" and "mip".
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `name` - Null-terminated CSR name
+/// * `value` - Pointer to store the CSR value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if the name is not recognized
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `name` must be a valid null-terminated string
+/// * `value` must be a valid pointer to a u32
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_csr_by_name(
+    emulator_memory: *mut CEmulator,
+    name: *const c_char,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || name.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let Some(csr_addr) = CStr::from_ptr(name)
+        .to_str()
+        .ok()
+        .and_then(csr_addr_from_name)
+    else {
+        return EmulatorError::InvalidArgs;
+    };
+
+    emulator_read_csr(emulator_memory, csr_addr, value)
+}
+
+/// Read the program counter (PC)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `value` - Pointer to store the PC value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `value` must be a valid pointer to a u32
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_pc(
+    emulator_memory: *mut CEmulator,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let pc = match &state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.read_pc(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator().mcu_cpu.read_pc(),
+    };
+
+    *value = pc;
+    EmulatorError::Success
+}
+
+/// Write the program counter (PC)
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `value` - Value to write to the PC
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_write_pc(
+    emulator_memory: *mut CEmulator,
+    value: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.write_pc(value),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().mcu_cpu.write_pc(value),
+    };
+
+    EmulatorError::Success
+}
+
+/// Set an external interrupt level
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `irq_num` - IRQ number to set (0-31)
+/// * `is_high` - 1 to set interrupt high, 0 to set interrupt low
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_external_interrupt(
+    emulator_memory: *mut CEmulator,
+    irq_num: c_uint,
+    is_high: c_uchar,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    if irq_num > 31 {
+        return EmulatorError::InvalidArgs;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    // Use the PIC to set the external interrupt level
+    // We need to register an IRQ and then set its level
+    let pic: &std::rc::Rc<caliptra_emu_cpu::Pic> = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &emulator.pic,
+        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().pic,
+    };
+
+    // Register the IRQ and set its level
+    let irq = pic.register_irq(irq_num as u8);
+    irq.set_level(is_high != 0);
+
+    EmulatorError::Success
+}
+
+/// Set the MCI RESET_REASON register that the MCU ROM reads to select its boot flow
+///
+/// Exactly one of `warm` and `cold` must be set. A warm reason sets WARM_RESET so
+/// the ROM takes the warm-boot path; a cold reason clears the register so the ROM
+/// takes the cold-boot path. Call before stepping, or before `emulator_warm_reset()`.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `warm` - 1 to select a warm reset
+/// * `cold` - 1 to select a cold boot
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if both or neither of `warm` and `cold` are set
+/// * Appropriate bus error code if the register write fails
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_reset_reason(
+    emulator_memory: *mut CEmulator,
+    warm: c_uchar,
+    cold: c_uchar,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let reason = match (warm != 0, cold != 0) {
+        (true, false) => RESET_REASON_WARM_RESET,
+        (false, true) => 0,
+        _ => return EmulatorError::InvalidArgs,
+    };
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let result = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.set_reset_reason(reason),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().set_reset_reason(reason),
+    };
+
+    match result {
+        Ok(()) => EmulatorError::Success,
+        Err(bus_error) => convert_bus_store_error(bus_error),
+    }
+}
+
+/// Set the Caliptra iTRNG clock divider
+///
+/// The emulated iTRNG does not model its sampling rate, so the divider is only
+/// recorded; it is accepted for parity with FPGA-based harnesses.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `divider` - Clock divider (must be non-zero)
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `divider` is 0
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_itrng_divider(
+    emulator_memory: *mut CEmulator,
+    divider: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+    if divider == 0 {
+        return EmulatorError::InvalidArgs;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.itrng_divider = divider,
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().itrng_divider = divider,
+    }
+
+    EmulatorError::Success
+}
+
+/// Set or clear CPTRA_BOOT_GO
+///
+/// Caliptra does not leave its boot FSM (its CPU is not stepped) while boot_go
+/// is clear. It is set at init, so clear it before the first `emulator_step()`
+/// to hold Caliptra and set it again to release it.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `value` - 1 to set boot_go, 0 to clear it
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `value` is not 0 or 1
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_caliptra_boot_go(
+    emulator_memory: *mut CEmulator,
+    value: c_uchar,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+    let go = match value {
+        0 => false,
+        1 => true,
+        _ => return EmulatorError::InvalidArgs,
+    };
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.caliptra_boot_go = go,
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().caliptra_boot_go = go,
+    }
+
+    EmulatorError::Success
+}
+
+/// Step until Caliptra is ready for firmware
+///
+/// Polls CPTRA_FLOW_STATUS.ready_for_mb_processing (bit 28), the condition the
+/// hardware model reports as `ready_for_fw`, after every step. Stepping stops
+/// early if a step returns anything other than `CStepAction::Continue`.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `max_cycles` - Maximum number of MCU clock cycles to run
+///
+/// # Returns
+/// * 1 if Caliptra became ready for firmware within `max_cycles`
+/// * 0 otherwise, or if `emulator_memory` is null
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_run_until_ready_for_fw(
+    emulator_memory: *mut CEmulator,
+    max_cycles: c_ulonglong,
+) -> c_int {
+    if emulator_memory.is_null() {
+        return 0;
+    }
 
     let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let ready = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.run_until_ready_for_fw(max_cycles),
+        EmulatorWrapper::Gdb(gdb_target) => {
+            gdb_target.emulator_mut().run_until_ready_for_fw(max_cycles)
+        }
+    };
+    ready as c_int
+}
 
-    // Use the PIC to set the external interrupt level
-    // We need to register an IRQ and then set its level
-    let pic: &std::rc::Rc<caliptra_emu_cpu::Pic> = match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => &emulator.pic,
-        EmulatorWrapper::Gdb(gdb_target) => &gdb_target.emulator().pic,
+/// Drive the Caliptra generic input wires (CPTRA_GENERIC_INPUT_WIRES)
+///
+/// The values are written through the Caliptra SoC interface on the MCU bus.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `w0` - Value of generic input wire 0
+/// * `w1` - Value of generic input wire 1
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate bus error code if the register write fails
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_generic_input_wires(
+    emulator_memory: *mut CEmulator,
+    w0: c_uint,
+    w1: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let result = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.set_generic_input_wires(&[w0, w1]),
+        EmulatorWrapper::Gdb(gdb_target) => {
+            gdb_target.emulator_mut().set_generic_input_wires(&[w0, w1])
+        }
     };
 
-    // Register the IRQ and set its level
-    let irq = pic.register_irq(irq_num as u8);
-    irq.set_level(is_high != 0);
+    match result {
+        Ok(()) => EmulatorError::Success,
+        Err(bus_error) => convert_bus_store_error(bus_error),
+    }
+}
+
+/// Drive the MCI generic input wires read by the MCU firmware
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `w0` - Value of generic input wire 0
+/// * `w1` - Value of generic input wire 1
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_mcu_generic_input_wires(
+    emulator_memory: *mut CEmulator,
+    w0: c_uint,
+    w1: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.set_mcu_generic_input_wires(&[w0, w1]),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target
+            .emulator_mut()
+            .set_mcu_generic_input_wires(&[w0, w1]),
+    }
+
+    EmulatorError::Success
+}
+
+/// Set the AXI user presented by SoC accesses to the MCU mailboxes
+///
+/// Every SoC view of the mailboxes, including the one MCI's mailbox transport
+/// uses, presents this AXI user; accesses made by the MCU keep identifying as
+/// the MCU. Once the MCU
+/// has locked an MBOXx_VALID_AXI_USER entry in MCI, MBOX_LOCK reads from any
+/// other AXI user return 0 without acquiring the mailbox.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `axi_user` - AXI user of the SoC agent
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `axi_user` is 0xFFFFFFFF, which identifies the MCU
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_set_axi_user(
+    emulator_memory: *mut CEmulator,
+    axi_user: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+    if axi_user == u32::from(MciMailboxRequester::Mcu) {
+        return EmulatorError::InvalidArgs;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    state.emulator_mut().set_axi_user(axi_user);
+
+    EmulatorError::Success
+}
+
+/// Read an MCU mailbox's MBOX_LOCK register from the SoC side
+///
+/// The read is made as the AXI user set with `emulator_set_axi_user()`. As on
+/// hardware, reading 0 acquires the lock if it was free; a read from an AXI
+/// user MCI does not allow is dropped and also returns 0.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `mailbox` - MCU mailbox index (0 or 1)
+/// * `value` - Receives the MBOX_LOCK value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * `EmulatorError::InvalidArgs` if `mailbox` is not 0 or 1
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `value` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn emulator_soc_read_mbox_lock(
+    emulator_memory: *mut CEmulator,
+    mailbox: c_uint,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    match state.emulator_mut().soc_mcu_mailboxes.get(mailbox as usize) {
+        Some(mbox) => {
+            *value = mbox.read_mbox_lock();
+            EmulatorError::Success
+        }
+        None => EmulatorError::InvalidArgs,
+    }
+}
+
+/// Re-run the MCU reset sequence without rebuilding the emulator
+///
+/// Bus peripherals receive a warm reset, the general-purpose registers are
+/// cleared, the CSRs return to their reset values, and the CPU restarts at the
+/// reset vector. RAM, flash, and OTP contents are kept, so the ROM sees the
+/// reset reason set with `emulator_set_reset_reason()`. Works in both normal
+/// and GDB mode.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_warm_reset(emulator_memory: *mut CEmulator) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.warm_reset(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().warm_reset(),
+    }
 
     EmulatorError::Success
 }
@@ -1190,139 +2580,569 @@ fn convert_c_write_callback(
             RvSize::Invalid => return false, // Invalid size
         };
 
-        let result = unsafe { c_callback(context, size_u32, addr, data) };
-        result != 0
-    })
+        let result = unsafe { c_callback(context, size_u32, addr, data) };
+        result != 0
+    })
+}
+
+/// Convert C trap callback to Rust callback
+fn convert_c_trap_callback(
+    c_callback: CTrapCallback,
+    context: *const std::ffi::c_void,
+) -> ExternalTrapCallback {
+    Box::new(move |mcause, mepc| unsafe { c_callback(context, mcause, mepc) } != 0)
+}
+
+/// Convert a C access size in bytes (1, 2, or 4) to an RvSize
+fn convert_rv_size(size: c_uint) -> Option<RvSize> {
+    match size {
+        1 => Some(RvSize::Byte),
+        2 => Some(RvSize::HalfWord),
+        4 => Some(RvSize::Word),
+        _ => None,
+    }
+}
+
+/// Map a bus error from a load to the C API error code
+fn convert_bus_load_error(bus_error: BusError) -> EmulatorError {
+    match bus_error {
+        BusError::LoadAccessFault => EmulatorError::BusLoadAccessFault,
+        BusError::LoadAddrMisaligned => EmulatorError::BusLoadAddrMisaligned,
+        _ => EmulatorError::InvalidArgs, // Fallback for other bus errors
+    }
+}
+
+/// Map a bus error from a store to the C API error code
+fn convert_bus_store_error(bus_error: BusError) -> EmulatorError {
+    match bus_error {
+        BusError::StoreAccessFault => EmulatorError::BusStoreAccessFault,
+        BusError::StoreAddrMisaligned => EmulatorError::BusStoreAddrMisaligned,
+        _ => EmulatorError::InvalidArgs, // Fallback for other bus errors
+    }
+}
+
+/// Read from the bus, splitting an unaligned access into byte reads if
+/// `allow_unaligned` is set. Aligned accesses are always passed through as-is.
+fn bus_read<B: Bus + ?Sized>(
+    bus: &mut B,
+    size: RvSize,
+    addr: RvAddr,
+    allow_unaligned: bool,
+) -> Result<u32, BusError> {
+    let len = size as u32;
+    if !allow_unaligned || addr % len == 0 {
+        return bus.read(size, addr);
+    }
+    let mut val = 0;
+    for i in 0..len {
+        val |= bus.read(RvSize::Byte, addr.wrapping_add(i))? << (8 * i);
+    }
+    Ok(val)
+}
+
+/// Write to the bus, splitting an unaligned access into byte writes if
+/// `allow_unaligned` is set. Aligned accesses are always passed through as-is.
+fn bus_write<B: Bus + ?Sized>(
+    bus: &mut B,
+    size: RvSize,
+    addr: RvAddr,
+    value: u32,
+    allow_unaligned: bool,
+) -> Result<(), BusError> {
+    let len = size as u32;
+    if !allow_unaligned || addr % len == 0 {
+        return bus.write(size, addr, value);
+    }
+    for i in 0..len {
+        bus.write(
+            RvSize::Byte,
+            addr.wrapping_add(i),
+            (value >> (8 * i)) & 0xff,
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn convert_optional_offset_size(value: c_longlong) -> Option<u32> {
+    if value == -1 {
+        None
+    } else {
+        // Convert to u32, but validate range
+        if value < 0 || value > u32::MAX as c_longlong {
+            None // Invalid range, treat as default
+        } else {
+            Some(value as u32)
+        }
+    }
+}
+
+/// Read from the auto_root_bus at the specified address
+///
+/// If the emulator was configured with `allow_unaligned_bus`, an unaligned
+/// 2- or 4-byte read is performed as consecutive byte reads (little-endian)
+/// instead of failing with `BusLoadAddrMisaligned`.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `size` - Size of the read operation (1, 2, or 4 bytes)
+/// * `addr` - Address to read from
+/// * `value` - Pointer to store the read value
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `value` must be a valid pointer to a u32
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_auto_root_bus(
+    emulator_memory: *mut CEmulator,
+    size: c_uint,
+    addr: c_uint,
+    value: *mut c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() || value.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let rv_size = match convert_rv_size(size) {
+        Some(rv_size) => rv_size,
+        None => return EmulatorError::InvalidArgs,
+    };
+
+    let allow_unaligned = state.allow_unaligned_bus;
+    let bus = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &mut emulator.mcu_cpu.bus,
+        EmulatorWrapper::Gdb(gdb_target) => &mut gdb_target.emulator_mut().mcu_cpu.bus,
+    };
+    let result = bus_read(bus, rv_size, addr, allow_unaligned);
+
+    match result {
+        Ok(val) => {
+            *value = val;
+            EmulatorError::Success
+        }
+        Err(bus_error) => convert_bus_load_error(bus_error),
+    }
+}
+
+/// Write to the auto_root_bus at the specified address
+///
+/// If the emulator was configured with `allow_unaligned_bus`, an unaligned
+/// 2- or 4-byte write is performed as consecutive byte writes (little-endian)
+/// instead of failing with `BusStoreAddrMisaligned`. A bus error part way
+/// through leaves the earlier bytes written.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `size` - Size of the write operation (1, 2, or 4 bytes)
+/// * `addr` - Address to write to
+/// * `value` - Value to write
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Appropriate error code on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_write_auto_root_bus(
+    emulator_memory: *mut CEmulator,
+    size: c_uint,
+    addr: c_uint,
+    value: c_uint,
+) -> EmulatorError {
+    if emulator_memory.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let rv_size = match convert_rv_size(size) {
+        Some(rv_size) => rv_size,
+        None => return EmulatorError::InvalidArgs,
+    };
+
+    let allow_unaligned = state.allow_unaligned_bus;
+    let bus = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &mut emulator.mcu_cpu.bus,
+        EmulatorWrapper::Gdb(gdb_target) => &mut gdb_target.emulator_mut().mcu_cpu.bus,
+    };
+    let result = bus_write(bus, rv_size, addr, value, allow_unaligned);
+    // Outside of a step there is nothing to halt, so the callback's return value is ignored.
+    state.check_watchpoints();
+
+    match result {
+        Ok(_) => EmulatorError::Success,
+        Err(bus_error) => convert_bus_store_error(bus_error),
+    }
 }
 
-pub(crate) fn convert_optional_offset_size(value: c_longlong) -> Option<u32> {
-    if value == -1 {
-        None
-    } else {
-        // Convert to u32, but validate range
-        if value < 0 || value > u32::MAX as c_longlong {
-            None // Invalid range, treat as default
+/// Read a block of memory from the auto_root_bus
+///
+/// Aligned words are read with word-sized accesses; any unaligned head or tail
+/// is read one byte at a time. Reading stops at the first bus error.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+/// * `addr` - Address to start reading from
+/// * `buffer` - Buffer to store the read data
+/// * `len` - Number of bytes to read
+/// * `bytes_read` - Pointer to store the number of bytes successfully read
+///
+/// # Returns
+/// * `EmulatorError::Success` on success
+/// * Error code of the first failing bus access on failure
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+/// * `buffer` must be a valid buffer of at least `len` bytes
+/// * `bytes_read` must be a valid pointer to a usize
+#[no_mangle]
+pub unsafe extern "C" fn emulator_read_auto_root_bus_block(
+    emulator_memory: *mut CEmulator,
+    addr: c_uint,
+    buffer: *mut c_uchar,
+    len: usize,
+    bytes_read: *mut usize,
+) -> EmulatorError {
+    if emulator_memory.is_null() || buffer.is_null() || bytes_read.is_null() {
+        return EmulatorError::NullPointer;
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+    let bus = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &mut emulator.mcu_cpu.bus,
+        EmulatorWrapper::Gdb(gdb_target) => &mut gdb_target.emulator_mut().mcu_cpu.bus,
+    };
+    let buffer = std::slice::from_raw_parts_mut(buffer, len);
+
+    let mut offset = 0;
+    while offset < len {
+        let cur_addr = addr.wrapping_add(offset as u32);
+        let rv_size = if cur_addr % 4 == 0 && len - offset >= 4 {
+            RvSize::Word
         } else {
-            Some(value as u32)
+            RvSize::Byte
+        };
+
+        match bus.read(rv_size, cur_addr) {
+            Ok(val) => {
+                let n = rv_size as usize;
+                buffer[offset..offset + n].copy_from_slice(&val.to_le_bytes()[..n]);
+                offset += n;
+            }
+            Err(bus_error) => {
+                *bytes_read = offset;
+                return convert_bus_load_error(bus_error);
+            }
         }
     }
+
+    *bytes_read = offset;
+    EmulatorError::Success
 }
 
-/// Read from the auto_root_bus at the specified address
+/// Write a block of memory to the auto_root_bus
+///
+/// Aligned words are written with word-sized accesses; any unaligned head or
+/// tail is written one byte at a time. Writing stops at the first bus error.
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `size` - Size of the read operation (1, 2, or 4 bytes)
-/// * `addr` - Address to read from
-/// * `value` - Pointer to store the read value
+/// * `addr` - Address to start writing to
+/// * `buffer` - Data to write
+/// * `len` - Number of bytes to write
+/// * `bytes_written` - Pointer to store the number of bytes successfully written
 ///
 /// # Returns
 /// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * Error code of the first failing bus access on failure
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
-/// * `value` must be a valid pointer to a u32
+/// * `buffer` must be a valid buffer of at least `len` bytes
+/// * `bytes_written` must be a valid pointer to a usize
 #[no_mangle]
-pub unsafe extern "C" fn emulator_read_auto_root_bus(
+pub unsafe extern "C" fn emulator_write_auto_root_bus_block(
     emulator_memory: *mut CEmulator,
-    size: c_uint,
     addr: c_uint,
-    value: *mut c_uint,
+    buffer: *const c_uchar,
+    len: usize,
+    bytes_written: *mut usize,
 ) -> EmulatorError {
-    if emulator_memory.is_null() || value.is_null() {
+    if emulator_memory.is_null() || buffer.is_null() || bytes_written.is_null() {
         return EmulatorError::NullPointer;
     }
 
     let state = &mut *(emulator_memory as *mut CEmulatorState);
-
-    // Convert size to RvSize
-    let rv_size = match size {
-        1 => caliptra_emu_types::RvSize::Byte,
-        2 => caliptra_emu_types::RvSize::HalfWord,
-        4 => caliptra_emu_types::RvSize::Word,
-        _ => return EmulatorError::InvalidArgs,
+    let bus = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => &mut emulator.mcu_cpu.bus,
+        EmulatorWrapper::Gdb(gdb_target) => &mut gdb_target.emulator_mut().mcu_cpu.bus,
     };
+    let buffer = std::slice::from_raw_parts(buffer, len);
+
+    let mut offset = 0;
+    while offset < len {
+        let cur_addr = addr.wrapping_add(offset as u32);
+        let (rv_size, val) = if cur_addr % 4 == 0 && len - offset >= 4 {
+            let mut word = [0u8; 4];
+            word.copy_from_slice(&buffer[offset..offset + 4]);
+            (RvSize::Word, u32::from_le_bytes(word))
+        } else {
+            (RvSize::Byte, buffer[offset] as u32)
+        };
 
-    let result = match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.bus.read(rv_size, addr),
-        EmulatorWrapper::Gdb(gdb_target) => {
-            gdb_target.emulator_mut().mcu_cpu.bus.read(rv_size, addr)
+        match bus.write(rv_size, cur_addr, val) {
+            Ok(_) => offset += rv_size as usize,
+            Err(bus_error) => {
+                *bytes_written = offset;
+                state.check_watchpoints();
+                return convert_bus_store_error(bus_error);
+            }
         }
+    }
+
+    *bytes_written = offset;
+    state.check_watchpoints();
+    EmulatorError::Success
+}
+
+/// Capture a snapshot of the MCU CPU registers, writable CSRs, PC, and writable RAM
+///
+/// Peripherals with external side effects (I3C, UART, mailboxes) and the
+/// Caliptra core are not captured.
+///
+/// # Arguments
+/// * `emulator_memory` - Pointer to the initialized emulator
+///
+/// # Returns
+/// * Handle to the snapshot, or null if `emulator_memory` is null or a register or RAM
+///   read failed. The cause of a failure is not reported. The handle must be released
+///   with emulator_free_snapshot()
+///
+/// # Safety
+/// * `emulator_memory` must point to a valid, initialized emulator
+#[no_mangle]
+pub unsafe extern "C" fn emulator_snapshot(
+    emulator_memory: *mut CEmulator,
+) -> *mut CEmulatorSnapshot {
+    if emulator_memory.is_null() {
+        return ptr::null_mut();
+    }
+
+    let state = &mut *(emulator_memory as *mut CEmulatorState);
+
+    let snapshot = match &mut state.wrapper {
+        EmulatorWrapper::Normal(emulator) => emulator.snapshot(),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().snapshot(),
     };
 
-    match result {
-        Ok(val) => {
-            *value = val;
-            EmulatorError::Success
-        }
-        Err(bus_error) => match bus_error {
-            caliptra_emu_bus::BusError::LoadAccessFault => EmulatorError::BusLoadAccessFault,
-            caliptra_emu_bus::BusError::LoadAddrMisaligned => EmulatorError::BusLoadAddrMisaligned,
-            _ => EmulatorError::InvalidArgs, // Fallback for other bus errors
-        },
+    match snapshot {
+        Ok(snapshot) => Box::into_raw(Box::new(snapshot)) as *mut CEmulatorSnapshot,
+        Err(_) => ptr::null_mut(),
     }
 }
 
-/// Write to the auto_root_bus at the specified address
+/// Restore the emulator to a previously captured snapshot
 ///
 /// # Arguments
 /// * `emulator_memory` - Pointer to the initialized emulator
-/// * `size` - Size of the write operation (1, 2, or 4 bytes)
-/// * `addr` - Address to write to
-/// * `value` - Value to write
+/// * `snapshot` - Snapshot handle returned by emulator_snapshot()
 ///
 /// # Returns
 /// * `EmulatorError::Success` on success
-/// * Appropriate error code on failure
+/// * `EmulatorError::NullPointer` if either pointer is null
+/// * `EmulatorError::RestoreFailed` if a register or RAM write was rejected. The emulator
+///   may be left partly restored.
 ///
 /// # Safety
 /// * `emulator_memory` must point to a valid, initialized emulator
+/// * `snapshot` must be a handle returned by emulator_snapshot() that has not been freed
 #[no_mangle]
-pub unsafe extern "C" fn emulator_write_auto_root_bus(
+pub unsafe extern "C" fn emulator_restore(
     emulator_memory: *mut CEmulator,
-    size: c_uint,
-    addr: c_uint,
-    value: c_uint,
+    snapshot: *const CEmulatorSnapshot,
 ) -> EmulatorError {
-    if emulator_memory.is_null() {
+    if emulator_memory.is_null() || snapshot.is_null() {
         return EmulatorError::NullPointer;
     }
 
     let state = &mut *(emulator_memory as *mut CEmulatorState);
-
-    // Convert size to RvSize
-    let rv_size = match size {
-        1 => caliptra_emu_types::RvSize::Byte,
-        2 => caliptra_emu_types::RvSize::HalfWord,
-        4 => caliptra_emu_types::RvSize::Word,
-        _ => return EmulatorError::InvalidArgs,
-    };
+    let snapshot = &*(snapshot as *const EmulatorSnapshot);
 
     let result = match &mut state.wrapper {
-        EmulatorWrapper::Normal(emulator) => emulator.mcu_cpu.bus.write(rv_size, addr, value),
-        EmulatorWrapper::Gdb(gdb_target) => gdb_target
-            .emulator_mut()
-            .mcu_cpu
-            .bus
-            .write(rv_size, addr, value),
+        EmulatorWrapper::Normal(emulator) => emulator.restore(snapshot),
+        EmulatorWrapper::Gdb(gdb_target) => gdb_target.emulator_mut().restore(snapshot),
     };
 
     match result {
-        Ok(_) => EmulatorError::Success,
-        Err(bus_error) => match bus_error {
-            caliptra_emu_bus::BusError::StoreAccessFault => EmulatorError::BusStoreAccessFault,
-            caliptra_emu_bus::BusError::StoreAddrMisaligned => {
-                EmulatorError::BusStoreAddrMisaligned
-            }
-            _ => EmulatorError::InvalidArgs, // Fallback for other bus errors
-        },
+        Ok(()) => EmulatorError::Success,
+        Err(_) => EmulatorError::RestoreFailed,
+    }
+}
+
+/// Release a snapshot returned by emulator_snapshot()
+///
+/// # Arguments
+/// * `snapshot` - Snapshot handle to release (null is ignored)
+///
+/// # Safety
+/// * `snapshot` must be null or a handle returned by emulator_snapshot() that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn emulator_free_snapshot(snapshot: *mut CEmulatorSnapshot) {
+    if !snapshot.is_null() {
+        drop(Box::from_raw(snapshot as *mut EmulatorSnapshot));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::CString;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// `jal x0, 0`: spin in place.
+    const SPIN: u32 = 0x0000_006f;
+
+    /// Serializes tests that build a real emulator: the emulator creates its flash
+    /// storage in the working directory, which these tests switch to a temporary one.
+    static EMULATOR_LOCK: Mutex<()> = Mutex::new(());
+
+    /// A real emulator created through `emulator_init`, whose MCU ROM is `rom`.
+    struct TestEmulator {
+        memory: Vec<u64>,
+        _dir: tempfile::TempDir,
+        _guard: MutexGuard<'static, ()>,
+    }
+
+    impl TestEmulator {
+        fn new(rom: &[u32]) -> Self {
+            Self::with_config(rom, |_| {})
+        }
+
+        /// Like [`TestEmulator::new`], with `configure` applied to the config first.
+        fn with_config(rom: &[u32], configure: impl FnOnce(&mut CEmulatorConfig)) -> Self {
+            let guard = EMULATOR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let dir = tempfile::tempdir().unwrap();
+            let write = |name: &str, words: &[u32]| {
+                let path = dir.path().join(name);
+                let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+                std::fs::write(&path, bytes).unwrap();
+                CString::new(path.to_str().unwrap()).unwrap()
+            };
+            let rom_path = write("mcu_rom.bin", rom);
+            let firmware_path = write("mcu_firmware.bin", &[SPIN]);
+            let caliptra_rom_path = write("caliptra_rom.bin", &[SPIN]);
+            let caliptra_firmware_path = write("caliptra_firmware.bin", &[0]);
+            let soc_manifest_path = write("soc_manifest.bin", &[0]);
+            let log_dir = CString::new(dir.path().to_str().unwrap()).unwrap();
+
+            let mut config = CEmulatorConfig {
+                rom_path: rom_path.as_ptr(),
+                firmware_path: firmware_path.as_ptr(),
+                caliptra_rom_path: caliptra_rom_path.as_ptr(),
+                caliptra_firmware_path: caliptra_firmware_path.as_ptr(),
+                soc_manifest_path: soc_manifest_path.as_ptr(),
+                otp_path: ptr::null(),
+                log_dir_path: log_dir.as_ptr(),
+                gdb_port: 0,
+                i3c_port: 0,
+                trace_instr: 0,
+                stdin_uart: 0,
+                manufacturing_mode: 0,
+                capture_uart_output: 1,
+                vendor_pk_hash: ptr::null(),
+                vendor_pqc_type: 1,
+                owner_pk_hash: ptr::null(),
+                streaming_boot_path: ptr::null(),
+                primary_flash_image_path: ptr::null(),
+                secondary_flash_image_path: ptr::null(),
+                hw_revision_major: 2,
+                hw_revision_minor: 0,
+                hw_revision_patch: 0,
+                flash_based_boot: 0,
+                rom_offset: -1,
+                rom_size: -1,
+                uart_offset: -1,
+                uart_size: -1,
+                ctrl_offset: -1,
+                ctrl_size: -1,
+                sram_offset: -1,
+                sram_size: -1,
+                pic_offset: -1,
+                external_test_sram_offset: -1,
+                external_test_sram_size: -1,
+                dccm_offset: -1,
+                dccm_size: -1,
+                i3c_offset: -1,
+                i3c_size: -1,
+                primary_flash_offset: -1,
+                primary_flash_size: -1,
+                secondary_flash_offset: -1,
+                secondary_flash_size: -1,
+                mci_offset: -1,
+                mci_size: -1,
+                dma_offset: -1,
+                dma_size: -1,
+                mbox_offset: -1,
+                mbox_size: -1,
+                soc_offset: -1,
+                soc_size: -1,
+                otp_offset: -1,
+                otp_size: -1,
+                lc_offset: -1,
+                lc_size: -1,
+                fuse_soc_manifest_svn: -1,
+                fuse_soc_manifest_max_svn: -1,
+                fuse_vendor_hashes_prod_partition: ptr::null(),
+                trace_pc_start: -1,
+                trace_pc_end: -1,
+                trng_seed: 0,
+                uart_capture_max_bytes: -1,
+                dump_state_on_exit: 0,
+                allow_unaligned_bus: 0,
+                exit_on_uart_marker: ptr::null(),
+                max_cycles: -1,
+                i3c_dynamic_address: -1,
+                external_read_callback: ptr::null(),
+                external_write_callback: ptr::null(),
+                callback_context: ptr::null(),
+            };
+            configure(&mut config);
+
+            let mut memory = vec![0u64; emulator_get_size().div_ceil(8)];
+            assert!(emulator_get_alignment() <= std::mem::align_of::<u64>());
+            let cwd = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir.path()).unwrap();
+            let result = unsafe { emulator_init(memory.as_mut_ptr() as *mut CEmulator, &config) };
+            std::env::set_current_dir(cwd).unwrap();
+            assert_eq!(result, EmulatorError::Success);
+
+            Self {
+                memory,
+                _dir: dir,
+                _guard: guard,
+            }
+        }
+
+        fn ptr(&mut self) -> *mut CEmulator {
+            self.memory.as_mut_ptr() as *mut CEmulator
+        }
+
+        fn emulator(&mut self) -> &mut Emulator {
+            unsafe { &mut *(self.ptr() as *mut CEmulatorState) }.emulator_mut()
+        }
+    }
+
+    impl Drop for TestEmulator {
+        fn drop(&mut self) {
+            unsafe { emulator_destroy(self.ptr()) };
+        }
+    }
 
     #[test]
     fn test_size_and_alignment() {
@@ -1334,4 +3154,763 @@ mod tests {
         assert!(align > 0);
         assert!(align.is_power_of_two());
     }
+
+    #[test]
+    fn test_error_string() {
+        let errors = [
+            EmulatorError::Success,
+            EmulatorError::InvalidArgs,
+            EmulatorError::InitializationFailed,
+            EmulatorError::NullPointer,
+            EmulatorError::InvalidEmulator,
+            EmulatorError::BusLoadAccessFault,
+            EmulatorError::BusStoreAccessFault,
+            EmulatorError::BusLoadAddrMisaligned,
+            EmulatorError::BusStoreAddrMisaligned,
+            EmulatorError::RestoreFailed,
+        ];
+        for error in errors {
+            let msg = emulator_error_string(error as c_int);
+            assert!(!msg.is_null());
+            let msg = unsafe { CStr::from_ptr(msg) }.to_str().unwrap();
+            assert!(!msg.is_empty());
+            assert_ne!(msg, "unknown error");
+        }
+        let msg = unsafe { CStr::from_ptr(emulator_error_string(1234)) };
+        assert_eq!(msg.to_str().unwrap(), "unknown error");
+    }
+
+    #[test]
+    fn test_unaligned_word_read() {
+        let mut ram =
+            caliptra_emu_bus::Ram::new(vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]);
+
+        assert_eq!(bus_read(&mut ram, RvSize::Word, 1, true), Ok(0x4433_2211));
+        assert_eq!(bus_read(&mut ram, RvSize::HalfWord, 3, true), Ok(0x4433));
+        // Aligned accesses are unaffected by the flag
+        assert_eq!(bus_read(&mut ram, RvSize::Word, 4, true), Ok(0x7766_5544));
+    }
+
+    #[test]
+    fn test_unaligned_word_write() {
+        let mut ram = caliptra_emu_bus::Ram::new(vec![0; 8]);
+
+        assert_eq!(
+            bus_write(&mut ram, RvSize::Word, 3, 0xddcc_bbaa, true),
+            Ok(())
+        );
+        assert_eq!(bus_read(&mut ram, RvSize::Word, 0, false), Ok(0xaa00_0000));
+        assert_eq!(bus_read(&mut ram, RvSize::Word, 4, false), Ok(0x00dd_ccbb));
+    }
+
+    #[test]
+    fn test_allow_unaligned_bus() {
+        for allow_unaligned_bus in [0, 1] {
+            let mut emu = TestEmulator::with_config(&[SPIN], |config| {
+                config.allow_unaligned_bus = allow_unaligned_bus
+            });
+            let addr = emu.emulator().dccm_range.start + 3;
+            let mut value = 0;
+            unsafe {
+                let write = emulator_write_auto_root_bus(emu.ptr(), 4, addr, 0xddcc_bbaa);
+                let read = emulator_read_auto_root_bus(emu.ptr(), 4, addr, &mut value);
+                if allow_unaligned_bus == 0 {
+                    assert_eq!(write, EmulatorError::BusStoreAddrMisaligned);
+                    assert_eq!(read, EmulatorError::BusLoadAddrMisaligned);
+                } else {
+                    assert_eq!(write, EmulatorError::Success);
+                    assert_eq!(read, EmulatorError::Success);
+                    assert_eq!(value, 0xddcc_bbaa);
+                    // The bytes landed little-endian across the two words.
+                    assert_eq!(
+                        emulator_read_auto_root_bus(emu.ptr(), 4, addr - 3, &mut value),
+                        EmulatorError::Success
+                    );
+                    assert_eq!(value & 0xff00_0000, 0xaa00_0000);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_i3c_address_null_pointers() {
+        let mut addr = 0xffu8;
+        assert_eq!(
+            unsafe { emulator_get_i3c_address(std::ptr::null_mut(), &mut addr) },
+            -1
+        );
+        assert_eq!(addr, 0xff);
+    }
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn test_get_i3c_address() {
+        let port = free_port();
+        let mut emu = TestEmulator::with_config(&[SPIN], |config| config.i3c_port = port.into());
+        assert_eq!(
+            unsafe { emulator_start_i3c_controller(ptr::null_mut()) },
+            EmulatorError::NullPointer
+        );
+        assert_eq!(
+            unsafe { emulator_start_i3c_controller(emu.ptr()) },
+            EmulatorError::Success
+        );
+
+        // The MCU target is the only one on the bus, so it gets the first dynamic address.
+        let mut addr = 0;
+        assert_eq!(unsafe { emulator_get_i3c_address(emu.ptr(), &mut addr) }, 1);
+        assert_eq!(addr, 0x08);
+
+        emu.emulator().i3c_address = None;
+        addr = 0xff;
+        assert_eq!(unsafe { emulator_get_i3c_address(emu.ptr(), &mut addr) }, 0);
+        assert_eq!(addr, 0xff);
+    }
+
+    #[test]
+    fn test_fixed_i3c_dynamic_address() {
+        let port = free_port();
+        let mut emu = TestEmulator::with_config(&[SPIN], |config| {
+            config.i3c_port = port.into();
+            config.i3c_dynamic_address = 0x20;
+        });
+        let mut addr = 0;
+        assert_eq!(unsafe { emulator_get_i3c_address(emu.ptr(), &mut addr) }, 1);
+        assert_eq!(addr, 0x20);
+    }
+
+    unsafe extern "C" fn record_watch_hit(
+        context: *const std::ffi::c_void,
+        addr: c_uint,
+        size: c_uint,
+        value: c_uint,
+    ) -> c_int {
+        let hits = &*(context as *const std::cell::RefCell<Vec<(u32, u32, u32)>>);
+        hits.borrow_mut().push((addr, size, value));
+        // Halt on writes of zero
+        (value != 0) as c_int
+    }
+
+    #[test]
+    fn test_fire_watchpoints() {
+        let seen = std::cell::RefCell::new(Vec::<(u32, u32, u32)>::new());
+        let watchpoints = [Watchpoint {
+            range: 0x100..0x108,
+            callback: record_watch_hit,
+            context: &seen as *const _ as *const std::ffi::c_void,
+        }];
+        let mut bus = emulator_periph::WatchingBus::new(caliptra_emu_bus::Ram::new(vec![0; 0x200]));
+        bus.set_watch_ranges(watchpoints.iter().map(|w| w.range.clone()).collect());
+
+        bus.write(RvSize::Word, 0xfc, 0x1111_1111).unwrap();
+        bus.write(RvSize::Word, 0x104, 0xcafe_f00d).unwrap();
+        bus.write(RvSize::Byte, 0x108, 0x22).unwrap();
+        assert!(fire_watchpoints(&watchpoints, &bus.take_watch_hits()));
+        assert_eq!(*seen.borrow(), vec![(0x104, 4, 0xcafe_f00d)]);
+
+        bus.write(RvSize::HalfWord, 0x106, 0).unwrap();
+        assert!(!fire_watchpoints(&watchpoints, &bus.take_watch_hits()));
+        assert_eq!(seen.borrow().last(), Some(&(0x106, 2, 0)));
+    }
+
+    #[test]
+    fn test_watchpoint_firmware_store() {
+        unsafe extern "C" fn on_write(
+            context: *const std::ffi::c_void,
+            addr: c_uint,
+            size: c_uint,
+            value: c_uint,
+        ) -> c_int {
+            let seen = &*(context as *const std::cell::RefCell<Vec<(u32, u32, u32)>>);
+            seen.borrow_mut().push((addr, size, value));
+            0
+        }
+
+        // t0 holds the DCCM address; only the first store lands in the watched word.
+        let mut emu = TestEmulator::new(&[
+            0x1230_0313, // li t1, 0x123
+            0x0062_a023, // sw t1, 0(t0)
+            0x0062_a423, // sw t1, 8(t0)
+            SPIN,
+        ]);
+        let dccm = emu.emulator().dccm_range.start;
+        let seen = std::cell::RefCell::new(Vec::new());
+        let context = &seen as *const _ as *const std::ffi::c_void;
+        unsafe {
+            assert_eq!(
+                emulator_write_xreg(emu.ptr(), 5, dccm),
+                EmulatorError::Success
+            );
+            assert_eq!(
+                emulator_add_watchpoint(emu.ptr(), dccm, 4, Some(on_write), context),
+                EmulatorError::Success
+            );
+
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert!(seen.borrow().is_empty());
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Break);
+            assert_eq!(*seen.borrow(), [(dccm, 4, 0x123)]);
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert_eq!(seen.borrow().len(), 1);
+
+            assert_eq!(
+                emulator_remove_watchpoint(emu.ptr(), dccm),
+                EmulatorError::Success
+            );
+        }
+    }
+
+    #[test]
+    fn test_breakpoint_at_reset_vector() {
+        let mut emu = TestEmulator::new(&[
+            0x0000_0013, // nop
+            0x0000_0013, // nop
+            SPIN,
+        ]);
+        unsafe {
+            let reset = emulator_get_pc(emu.ptr());
+            assert_eq!(
+                emulator_add_breakpoint(emu.ptr(), reset),
+                EmulatorError::Success
+            );
+
+            // The breakpoint fires before the first instruction executes.
+            let mut action = CStepAction::Continue;
+            let mut steps = 0;
+            assert_eq!(
+                emulator_step_n(emu.ptr(), 10, &mut action, &mut steps),
+                EmulatorError::Success
+            );
+            assert_eq!((action, steps), (CStepAction::Break, 0));
+            assert_eq!(emulator_get_pc(emu.ptr()), reset);
+
+            // Stepping again executes the instruction at the breakpoint.
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert_eq!(emulator_get_pc(emu.ptr()), reset + 4);
+
+            // Landing on a breakpoint stops after the step that reached it, and a loop at
+            // the breakpoint stops on every iteration.
+            assert_eq!(
+                emulator_add_breakpoint(emu.ptr(), reset + 8),
+                EmulatorError::Success
+            );
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Break);
+            assert_eq!(emulator_get_pc(emu.ptr()), reset + 8);
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Break);
+            assert_eq!(emulator_get_pc(emu.ptr()), reset + 8);
+
+            assert_eq!(
+                emulator_remove_breakpoint(emu.ptr(), reset + 8),
+                EmulatorError::Success
+            );
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+        }
+    }
+
+    #[test]
+    fn test_exit_status() {
+        let mut ctrl = emulator_periph::EmuCtrl::new();
+        let exit = ctrl.exit_handle();
+        exit.set_exit_process(false);
+        assert_eq!(exit_status(exit.code()), 0);
+
+        // The firmware writes the pass code to the exit register
+        ctrl.write(RvSize::Word, 0, 0).unwrap();
+        assert_eq!(exit_status(exit.code()), 1);
+        ctrl.write(RvSize::Word, 0, 1).unwrap();
+        assert_eq!(exit_status(exit.code()), 2);
+
+        assert_eq!(
+            unsafe { emulator_get_exit_status(std::ptr::null_mut()) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_firmware_exit_status() {
+        const CTRL_OFFSET: u32 = 0x1000_2000;
+
+        for (code, action, status) in [
+            (0, CStepAction::ExitSuccess, 1),
+            (1, CStepAction::ExitFailure, 2),
+        ] {
+            // Write the exit code to the emulation control register.
+            let mut emu = TestEmulator::with_config(
+                &[
+                    0x1000_22b7,                // lui t0, 0x10002
+                    0x0000_0313 | (code << 20), // li t1, code
+                    0x0062_a023,                // sw t1, 0(t0)
+                    SPIN,
+                ],
+                |config| config.ctrl_offset = CTRL_OFFSET.into(),
+            );
+            unsafe {
+                assert_eq!(emulator_get_exit_status(emu.ptr()), 0);
+                assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+                assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+                assert_eq!(emulator_get_exit_status(emu.ptr()), 0);
+                // The host process keeps running; the step reports the exit.
+                assert_eq!(emulator_step(emu.ptr()), action);
+                assert_eq!(emulator_get_exit_status(emu.ptr()), status);
+            }
+        }
+    }
+
+    #[test]
+    fn test_csr_addr_from_name() {
+        assert_eq!(csr_addr_from_name("mstatus"), Some(0x300));
+        assert_eq!(csr_addr_from_name("mcause"), Some(0x342));
+        assert_eq!(csr_addr_from_name("MSTATUS"), None);
+        assert_eq!(csr_addr_from_name("bogus"), None);
+    }
+
+    #[test]
+    fn test_max_cycles() {
+        let mut emu = TestEmulator::with_config(&[SPIN], |config| config.max_cycles = 100);
+        let mut action = CStepAction::Continue;
+        for _ in 0..1000 {
+            action = unsafe { emulator_step(emu.ptr()) };
+            if action != CStepAction::Continue {
+                break;
+            }
+        }
+        assert_eq!(action, CStepAction::ExitFailure);
+        assert_eq!(
+            emu.emulator().exit_code(),
+            Some(emulator::CYCLE_BUDGET_EXIT_CODE)
+        );
+        assert_eq!(unsafe { emulator_get_exit_status(emu.ptr()) }, 2);
+        assert!(emu.emulator().mcu_cpu.clock.now() >= 100);
+    }
+
+    #[test]
+    fn test_dump_state_on_exit() {
+        let mut emu = TestEmulator::with_config(&[SPIN], |config| {
+            config.max_cycles = 100;
+            config.dump_state_on_exit = 1;
+        });
+        let mut action = CStepAction::Continue;
+        let mut steps = 0;
+        unsafe { emulator_step_n(emu.ptr(), 1000, &mut action, &mut steps) };
+        assert_eq!(action, CStepAction::ExitFailure);
+
+        // The dump lands in the captured UART output rather than on stdout.
+        let output = emu.emulator().uart_output.clone().unwrap();
+        let output = String::from_utf8(output.borrow().clone()).unwrap();
+        assert!(output.starts_with("MCU CPU state:\n"));
+        assert!(output.contains("  pc      = 0x"));
+    }
+
+    #[test]
+    fn test_trap_callback() {
+        unsafe extern "C" fn on_trap(
+            context: *const std::ffi::c_void,
+            mcause: c_uint,
+            mepc: c_uint,
+        ) -> c_int {
+            let seen = &*(context as *const std::cell::RefCell<Vec<(u32, u32)>>);
+            seen.borrow_mut().push((mcause, mepc));
+            0
+        }
+
+        // Point mtvec at an mret handler, write mepc directly and jump into the handler
+        // without trapping; only the ecall it returns to takes a trap.
+        let mut emu = TestEmulator::new(&[
+            0x0000_0297, // auipc t0, 0
+            0x0202_8293, // addi t0, t0, 0x20
+            0x3052_9073, // csrw mtvec, t0
+            0xff82_8313, // addi t1, t0, -8
+            0x3413_1073, // csrw mepc, t1
+            0x0002_8067, // jr t0
+            0x0000_0073, // ecall
+            SPIN,
+            0x3020_0073, // mret
+        ]);
+        let seen = std::cell::RefCell::new(Vec::new());
+        let context = &seen as *const _ as *const std::ffi::c_void;
+        assert_eq!(
+            unsafe { emulator_set_trap_callback(emu.ptr(), Some(on_trap), context) },
+            EmulatorError::Success
+        );
+
+        let rom = emu.emulator().mcu_cpu.read_pc();
+        for _ in 0..7 {
+            assert_eq!(unsafe { emulator_step(emu.ptr()) }, CStepAction::Continue);
+        }
+        assert!(seen.borrow().is_empty());
+        assert_eq!(unsafe { emulator_step(emu.ptr()) }, CStepAction::Break);
+        // mcause 11: environment call from M-mode
+        assert_eq!(*seen.borrow(), [(11, rom + 0x18)]);
+
+        assert_eq!(
+            unsafe { emulator_set_trap_callback(emu.ptr(), None, ptr::null()) },
+            EmulatorError::Success
+        );
+    }
+
+    #[test]
+    fn test_set_generic_input_wires() {
+        const MCI_GENERIC_INPUT_WIRES_OFFSET: u32 = 0x400;
+        const SOC_GENERIC_INPUT_WIRES_OFFSET: u32 = 0xc4;
+
+        let mut emu = TestEmulator::new(&[SPIN]);
+        let mci_offset = emu.emulator().mci_offset;
+        let soc_offset = emu.emulator().memory_map.soc_offset;
+        let read = |emu: &mut TestEmulator, addr: u32| {
+            let mut value = 0;
+            assert_eq!(
+                unsafe { emulator_read_auto_root_bus(emu.ptr(), 4, addr, &mut value) },
+                EmulatorError::Success
+            );
+            value
+        };
+
+        unsafe {
+            assert_eq!(
+                emulator_set_generic_input_wires(std::ptr::null_mut(), 0, 0),
+                EmulatorError::NullPointer
+            );
+            assert_eq!(
+                emulator_set_mcu_generic_input_wires(std::ptr::null_mut(), 0, 0),
+                EmulatorError::NullPointer
+            );
+
+            assert_eq!(
+                emulator_set_mcu_generic_input_wires(emu.ptr(), 0x1234_5678, 0x9abc_def0),
+                EmulatorError::Success
+            );
+            assert_eq!(
+                emulator_set_generic_input_wires(emu.ptr(), 0x0bad_f00d, 0xcafe_babe),
+                EmulatorError::Success
+            );
+        }
+
+        // The MCU reads its wires from MCI, and the Caliptra wires from the SoC interface.
+        let mci_wires = mci_offset + MCI_GENERIC_INPUT_WIRES_OFFSET;
+        assert_eq!(read(&mut emu, mci_wires), 0x1234_5678);
+        assert_eq!(read(&mut emu, mci_wires + 4), 0x9abc_def0);
+        let soc_wires = soc_offset + SOC_GENERIC_INPUT_WIRES_OFFSET;
+        assert_eq!(read(&mut emu, soc_wires), 0x0bad_f00d);
+        assert_eq!(read(&mut emu, soc_wires + 4), 0xcafe_babe);
+    }
+
+    #[test]
+    fn test_set_axi_user() {
+        const MBOX0_LOCK_OFFSET: u32 = 0x60_0000;
+        const MBOX0_USER_OFFSET: u32 = 0x60_0004;
+
+        assert_eq!(
+            unsafe { emulator_set_axi_user(ptr::null_mut(), 0x42) },
+            EmulatorError::NullPointer
+        );
+
+        let mut emu = TestEmulator::new(&[SPIN]);
+        unsafe {
+            assert_eq!(
+                emulator_set_axi_user(emu.ptr(), u32::from(MciMailboxRequester::Mcu)),
+                EmulatorError::InvalidArgs
+            );
+            assert_eq!(
+                emulator_set_axi_user(emu.ptr(), 0x42),
+                EmulatorError::Success
+            );
+        }
+
+        // The SoC takes the lock as AXI user 0x42
+        let mci_offset = emu.emulator().mci_offset;
+        let mut value = 1;
+        assert_eq!(
+            unsafe { emulator_soc_read_mbox_lock(emu.ptr(), 0, &mut value) },
+            EmulatorError::Success
+        );
+        assert_eq!(value, 0);
+        assert_eq!(
+            unsafe { emulator_soc_read_mbox_lock(emu.ptr(), 2, &mut value) },
+            EmulatorError::InvalidArgs
+        );
+
+        // The MCU sees the lock held by the SoC agent and does not take it over
+        for (offset, expected) in [
+            (MBOX0_USER_OFFSET, 0x42),
+            (MBOX0_LOCK_OFFSET, 1),
+            (MBOX0_USER_OFFSET, 0x42),
+        ] {
+            assert_eq!(
+                unsafe {
+                    emulator_read_auto_root_bus(emu.ptr(), 4, mci_offset + offset, &mut value)
+                },
+                EmulatorError::Success
+            );
+            assert_eq!(value, expected);
+        }
+    }
+
+    #[test]
+    fn test_set_axi_user_valid_axi_user_lock() {
+        const MBOX0_VALID_AXI_USER_OFFSET: u32 = 0x180;
+        const MBOX0_AXI_USER_LOCK_OFFSET: u32 = 0x1a0;
+        const MBOX0_USER_OFFSET: u32 = 0x60_0004;
+
+        let mut emu = TestEmulator::new(&[SPIN]);
+        let mci_offset = emu.emulator().mci_offset;
+        let mut user = 0;
+        let mut lock = 0;
+        unsafe {
+            // The MCU only lets AXI user 0x42 use mailbox 0
+            for (offset, value) in [
+                (MBOX0_VALID_AXI_USER_OFFSET, 0x42),
+                (MBOX0_AXI_USER_LOCK_OFFSET, 1),
+            ] {
+                assert_eq!(
+                    emulator_write_auto_root_bus(emu.ptr(), 4, mci_offset + offset, value),
+                    EmulatorError::Success
+                );
+            }
+
+            assert_eq!(
+                emulator_set_axi_user(emu.ptr(), 0x43),
+                EmulatorError::Success
+            );
+            assert_eq!(
+                emulator_soc_read_mbox_lock(emu.ptr(), 0, &mut lock),
+                EmulatorError::Success
+            );
+            assert_eq!(lock, 0);
+            assert_eq!(
+                emulator_read_auto_root_bus(
+                    emu.ptr(),
+                    4,
+                    mci_offset + MBOX0_USER_OFFSET,
+                    &mut user
+                ),
+                EmulatorError::Success
+            );
+            assert_eq!(user, 0, "a disallowed AXI user must not get the lock");
+
+            assert_eq!(
+                emulator_set_axi_user(emu.ptr(), 0x42),
+                EmulatorError::Success
+            );
+            lock = 1;
+            assert_eq!(
+                emulator_soc_read_mbox_lock(emu.ptr(), 0, &mut lock),
+                EmulatorError::Success
+            );
+            assert_eq!(lock, 0);
+            assert_eq!(
+                emulator_read_auto_root_bus(
+                    emu.ptr(),
+                    4,
+                    mci_offset + MBOX0_USER_OFFSET,
+                    &mut user
+                ),
+                EmulatorError::Success
+            );
+            assert_eq!(user, 0x42);
+        }
+    }
+
+    #[test]
+    fn test_inject_ecc_error() {
+        const NOTIF0_INTR_EN_OFFSET: u32 = 0x100c;
+        const NOTIF0_INTERNAL_INTR_R_OFFSET: u32 = 0x1024;
+        const NOTIF_MCU_SRAM_ECC_COR_INTR_COUNT_OFFSET: u32 = 0x1200;
+        const NOTIF_MCU_SRAM_ECC_COR_STS: u32 = 1;
+
+        let mut emu = TestEmulator::new(&[SPIN]);
+        let sram = emu.emulator().sram_range.start;
+        let mci = emu.emulator().mci_offset;
+        unsafe {
+            assert_eq!(
+                emulator_inject_ecc_error(emu.ptr(), 0, 0),
+                EmulatorError::InvalidArgs
+            );
+            assert_eq!(
+                emulator_inject_ecc_error(emu.ptr(), sram + 0x100, 1),
+                EmulatorError::Success
+            );
+            assert_eq!(
+                emulator_inject_ecc_error(emu.ptr(), sram + 0x200, 0),
+                EmulatorError::Success
+            );
+        }
+
+        let bus = &mut emu.emulator().mcu_cpu.bus;
+        assert_eq!(
+            bus.read(RvSize::Word, sram + 0x100),
+            Err(caliptra_emu_bus::BusError::LoadAccessFault)
+        );
+        assert!(bus.read(RvSize::Word, sram + 0x100).is_ok());
+
+        bus.write(
+            RvSize::Word,
+            mci + NOTIF0_INTR_EN_OFFSET,
+            NOTIF_MCU_SRAM_ECC_COR_STS,
+        )
+        .unwrap();
+        assert!(bus.read(RvSize::Word, sram + 0x200).is_ok());
+        bus.poll();
+        assert_eq!(
+            bus.read(RvSize::Word, mci + NOTIF_MCU_SRAM_ECC_COR_INTR_COUNT_OFFSET),
+            Ok(1)
+        );
+        assert_eq!(
+            bus.read(RvSize::Word, mci + NOTIF0_INTERNAL_INTR_R_OFFSET)
+                .unwrap()
+                & NOTIF_MCU_SRAM_ECC_COR_STS,
+            NOTIF_MCU_SRAM_ECC_COR_STS
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        const CSR_MSCRATCH: RvAddr = 0x340;
+        // addi t0, t0, 1; csrw mscratch, t0; j -8
+        let mut emu = TestEmulator::new(&[0x0012_8293, 0x3402_9073, 0xff9f_f06f]);
+        let step = |emu: &mut TestEmulator, count: usize| {
+            for _ in 0..count {
+                assert_eq!(unsafe { emulator_step(emu.ptr()) }, CStepAction::Continue);
+            }
+        };
+        let state = |emu: &mut TestEmulator| {
+            let emulator = emu.emulator();
+            let dccm = emulator.dccm_range.start;
+            (
+                emulator.mcu_cpu.read_pc(),
+                emulator.mcu_cpu.read_xreg(XReg::from(5u32)).unwrap(),
+                emulator.mcu_cpu.read_csr_machine(CSR_MSCRATCH).unwrap(),
+                emulator.mcu_cpu.bus.read(RvSize::Word, dccm).unwrap(),
+            )
+        };
+
+        step(&mut emu, 10);
+        let dccm = emu.emulator().dccm_range.start;
+        emu.emulator()
+            .mcu_cpu
+            .bus
+            .write(RvSize::Word, dccm, 0x1234_5678)
+            .unwrap();
+        let snapshot = unsafe { emulator_snapshot(emu.ptr()) };
+        assert!(!snapshot.is_null());
+        let saved = state(&mut emu);
+        assert_ne!(saved.1, 0);
+
+        step(&mut emu, 10);
+        emu.emulator()
+            .mcu_cpu
+            .bus
+            .write(RvSize::Word, dccm, 0xdead_beef)
+            .unwrap();
+        assert_ne!(state(&mut emu), saved);
+
+        assert_eq!(
+            unsafe { emulator_restore(emu.ptr(), snapshot) },
+            EmulatorError::Success
+        );
+        assert_eq!(state(&mut emu), saved);
+
+        // Execution continues from the restored state.
+        step(&mut emu, 3);
+        assert_eq!(state(&mut emu).1, saved.1 + 1);
+
+        unsafe { emulator_free_snapshot(snapshot) };
+    }
+
+    #[test]
+    fn test_warm_reset_preserves_ram() {
+        use caliptra_emu_bus::{Clock, Ram};
+        use caliptra_emu_cpu::{Cpu, Pic};
+        use emulator::CpuResetState;
+        use std::rc::Rc;
+
+        const RESET_VECTOR: u32 = 0x40;
+        const CSR_MSCRATCH: RvAddr = 0x340;
+
+        let mut cpu = Cpu::new(
+            Ram::new(vec![0; 0x100]),
+            Rc::new(Clock::new()),
+            Rc::new(Pic::new()),
+            emulator_consts::DEFAULT_CPU_ARGS,
+        );
+        cpu.write_pc(RESET_VECTOR);
+        let reset_state = CpuResetState::capture(&cpu);
+        let mscratch = cpu.read_csr_machine(CSR_MSCRATCH).unwrap();
+
+        cpu.bus.write(RvSize::Word, 0x80, 0xdead_beef).unwrap();
+        cpu.write_xreg(XReg::from(5u32), 0x1234).unwrap();
+        cpu.write_csr_machine(CSR_MSCRATCH, !mscratch).unwrap();
+        cpu.write_pc(0xc0);
+
+        reset_state.apply(&mut cpu);
+
+        assert_eq!(cpu.read_pc(), RESET_VECTOR);
+        assert_eq!(cpu.read_xreg(XReg::from(5u32)).unwrap(), 0);
+        assert_eq!(cpu.read_csr_machine(CSR_MSCRATCH).unwrap(), mscratch);
+        assert_eq!(cpu.bus.read(RvSize::Word, 0x80).unwrap(), 0xdead_beef);
+    }
+
+    #[test]
+    fn test_emulator_warm_reset() {
+        const CSR_MSCRATCH: c_uint = 0x340;
+
+        // t0 holds the DCCM address.
+        let mut emu = TestEmulator::new(&[
+            0x1230_0313, // li t1, 0x123
+            0x0062_a023, // sw t1, 0(t0)
+            SPIN,
+        ]);
+        let dccm = emu.emulator().dccm_range.start;
+        let mut value = 0;
+        unsafe {
+            let reset = emulator_get_pc(emu.ptr());
+            assert_eq!(
+                emulator_read_csr(emu.ptr(), CSR_MSCRATCH, &mut value),
+                EmulatorError::Success
+            );
+            let mscratch = value;
+
+            assert_eq!(
+                emulator_write_xreg(emu.ptr(), 5, dccm),
+                EmulatorError::Success
+            );
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert_eq!(
+                emulator_write_csr(emu.ptr(), CSR_MSCRATCH, !mscratch),
+                EmulatorError::Success
+            );
+
+            assert_eq!(emulator_warm_reset(emu.ptr()), EmulatorError::Success);
+
+            assert_eq!(emulator_get_pc(emu.ptr()), reset);
+            for reg in [5, 6] {
+                assert_eq!(
+                    emulator_read_xreg(emu.ptr(), reg, &mut value),
+                    EmulatorError::Success
+                );
+                assert_eq!(value, 0);
+            }
+            assert_eq!(
+                emulator_read_csr(emu.ptr(), CSR_MSCRATCH, &mut value),
+                EmulatorError::Success
+            );
+            assert_eq!(value, mscratch);
+            // RAM survives the reset.
+            assert_eq!(
+                emulator_read_auto_root_bus(emu.ptr(), 4, dccm, &mut value),
+                EmulatorError::Success
+            );
+            assert_eq!(value, 0x123);
+
+            // Execution restarts at the reset vector.
+            assert_eq!(emulator_step(emu.ptr()), CStepAction::Continue);
+            assert_eq!(emulator_get_pc(emu.ptr()), reset + 4);
+        }
+    }
 }
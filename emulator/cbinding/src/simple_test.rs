@@ -13,7 +13,7 @@ Abstract:
 --*/
 
 use caliptra_image_types::FwVerificationPqcKeyType;
-use emulator::{Emulator, EmulatorArgs};
+use emulator::{Emulator, EmulatorArgs, TraceFormat};
 
 #[test]
 fn test_can_import_emulator() {
@@ -42,10 +42,19 @@ fn test_emulator_args_creation() {
         gdb_port: None,
         log_dir: None,
         trace_instr: false,
+        trace_format: TraceFormat::Text,
+        trace_pc_start: None,
+        trace_pc_end: None,
+        lockstep_trace: None,
+        trng_seed: None,
+        uart_capture_max_bytes: None,
+        dump_state_on_exit: false,
+        max_cycles: None,
         stdin_uart: false,
         _no_stdin_uart: false,
         flash_based_boot: false,
         i3c_port: None,
+        i3c_dynamic_address: None,
         manufacturing_mode: false,
         vendor_pk_hash: None,
         vendor_pqc_type: FwVerificationPqcKeyType::LMS,
@@ -53,7 +62,9 @@ fn test_emulator_args_creation() {
         streaming_boot: None,
         primary_flash_image: None,
         secondary_flash_image: None,
+        flash_op_latency: None,
         hw_revision: semver::Version::new(2, 0, 0),
+        memory_map_file: None,
         rom_offset: None,
         rom_size: None,
         uart_offset: None,
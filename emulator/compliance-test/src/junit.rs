@@ -0,0 +1,117 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    junit.rs
+
+Abstract:
+
+    JUnit XML report of compliance test results.
+
+--*/
+
+use crate::TestInfo;
+use std::fmt::Write;
+
+/// Outcome of a single compliance test.
+pub struct TestResult {
+    pub test: &'static TestInfo,
+    /// `None` if the test passed, otherwise the failure message.
+    pub failure: Option<String>,
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render `results` as a JUnit XML document with one `<testcase>` per result. The test's
+/// extension is used as the class name.
+pub fn to_junit_xml(results: &[TestResult]) -> String {
+    let failures = results.iter().filter(|r| r.failure.is_some()).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuite name=\"riscv-compliance\" tests=\"{}\" failures=\"{}\">",
+        results.len(),
+        failures
+    );
+    for result in results {
+        let _ = write!(
+            xml,
+            "  <testcase classname=\"{}\" name=\"{}\"",
+            escape(result.test.extension),
+            escape(result.test.name)
+        );
+        match &result.failure {
+            None => xml.push_str("/>\n"),
+            Some(message) => {
+                let message = escape(message);
+                let _ = writeln!(
+                    xml,
+                    ">\n    <failure message=\"{message}\">{message}</failure>\n  </testcase>"
+                );
+            }
+        }
+    }
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ADD: TestInfo = TestInfo {
+        extension: "I",
+        name: "add-01",
+    };
+    static CLMUL: TestInfo = TestInfo {
+        extension: "B",
+        name: "clmul-01",
+    };
+
+    #[test]
+    fn test_to_junit_xml() {
+        let results = [
+            TestResult {
+                test: &ADD,
+                failure: None,
+            },
+            TestResult {
+                test: &CLMUL,
+                failure: Some("At addr 0x1000, expected 0x03050100 but was 0x03020100".into()),
+            },
+        ];
+        let xml = to_junit_xml(&results);
+        assert_eq!(xml.matches("<testcase ").count(), 2);
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuite name=\"riscv-compliance\" tests=\"2\" failures=\"1\">\n\
+             \x20 <testcase classname=\"I\" name=\"add-01\"/>\n\
+             \x20 <testcase classname=\"B\" name=\"clmul-01\">\n\
+             \x20   <failure message=\"At addr 0x1000, expected 0x03050100 but was 0x03020100\">\
+             At addr 0x1000, expected 0x03050100 but was 0x03020100</failure>\n\
+             \x20 </testcase>\n\
+             </testsuite>\n"
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
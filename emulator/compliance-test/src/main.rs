@@ -18,14 +18,16 @@ use caliptra_emu_types::RvSize;
 use clap::{arg, value_parser};
 use emulator_consts::DEFAULT_CPU_ARGS;
 use fs::TempDir;
+use junit::TestResult;
 use std::error::Error;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{env::set_var, rc::Rc};
 use test_data::{get_binary_data, get_signature_data, run_riscof};
 
 mod exec;
 mod fs;
+mod junit;
 mod test_data;
 
 pub struct TestInfo {
@@ -160,6 +162,16 @@ static TESTS_TO_RUN: &[TestInfo] = &[
     TestInfo { extension: "B", name: "zext.h_32-01", },
 ];
 
+/// Tests from `TESTS_TO_RUN` whose name contains `filter` and whose extension matches
+/// `extension` (case-insensitively). `None` matches everything.
+fn select_tests(filter: Option<&str>, extension: Option<&str>) -> Vec<&'static TestInfo> {
+    TESTS_TO_RUN
+        .iter()
+        .filter(|test| filter.is_none_or(|filter| test.name.contains(filter)))
+        .filter(|test| extension.is_none_or(|ext| test.extension.eq_ignore_ascii_case(ext)))
+        .collect()
+}
+
 fn into_io_error(err: impl Into<Box<dyn Error + Send + Sync>>) -> std::io::Error {
     std::io::Error::new(ErrorKind::Other, err)
 }
@@ -195,18 +207,61 @@ fn is_test_complete(bus: &mut impl Bus) -> bool {
     bus.read(RvSize::Word, 0x0).unwrap() != 0
 }
 
+fn run_test(test: &TestInfo, work_dir: &Path) -> std::io::Result<()> {
+    let binary = get_binary_data(test, work_dir.to_owned())?;
+    let reference_txt = get_signature_data(test, work_dir.to_owned())?;
+
+    let clock = Rc::new(Clock::new());
+    let pic = Rc::new(Pic::new());
+    let args = DEFAULT_CPU_ARGS;
+    let mut cpu = Cpu::new(Ram::new(binary), clock, pic, args);
+    cpu.write_pc(0x3000);
+    while !is_test_complete(&mut cpu.bus) {
+        match cpu.step(None) {
+            StepAction::Continue => continue,
+            _ => break,
+        }
+    }
+    if !is_test_complete(&mut cpu.bus) {
+        Err(std::io::Error::new(
+            ErrorKind::Other,
+            "test did not complete",
+        ))?;
+    }
+
+    check_reference_data(&reference_txt, &mut cpu.bus)
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let args = clap::Command::new("compliance-test")
         .about("RISC-V compliance suite runner")
-        .arg(arg!(--test_root_path <DIR> "Path to directory containing https://github.com/riscv-non-isa/riscv-arch-test").value_parser(value_parser!(PathBuf)))
+        .arg(arg!(--test_root_path <DIR> "Path to directory containing https://github.com/riscv-non-isa/riscv-arch-test").required_unless_present("list").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--compiler <FILE> "Path to risc-v build of gcc").required(false).default_value("riscv64-unknown-elf-gcc").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--objcopy <FILE> "Path to risc-v build of objcopy").required(false).default_value("riscv64-unknown-elf-objcopy").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--objdump <FILE> "Path to risc-v build of objdump").required(false).default_value("riscv64-unknown-elf-objdump").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--riscof <FILE> "Path to riscof").required(false).default_value("riscof").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--riscv_sim_rv32 <FILE> "Path to riscv_sim_RV32").required(false).default_value("riscv_sim_RV32").value_parser(value_parser!(PathBuf)))
         .arg(arg!(--spike <FILE> "Path to spike").required(false).default_value("spike").value_parser(value_parser!(PathBuf)))
+        .arg(arg!(--filter <SUBSTR> "Only run tests whose name contains this string").required(false))
+        .arg(arg!(--extension <EXT> "Only run tests for this extension (e.g. B)").required(false))
+        .arg(arg!(--list "Print the selected tests and exit"))
+        .arg(arg!(--junit <FILE> "Write JUnit XML results to this file and keep going after failures").required(false).value_parser(value_parser!(PathBuf)))
         .get_matches();
 
+    let tests = select_tests(
+        args.get_one::<String>("filter").map(String::as_str),
+        args.get_one::<String>("extension").map(String::as_str),
+    );
+    if args.get_flag("list") {
+        for test in tests {
+            println!("{}/{}", test.extension, test.name);
+        }
+        return Ok(());
+    }
+    if tests.is_empty() {
+        Err(into_io_error("no tests match the given filter"))?;
+    }
+
     set_var("RISCV_CC", args.get_one::<PathBuf>("compiler").unwrap());
     set_var("RISCV_OBJCOPY", args.get_one::<PathBuf>("objcopy").unwrap());
     set_var("RISCV_OBJDUMP", args.get_one::<PathBuf>("objdump").unwrap());
@@ -224,33 +279,36 @@ fn main() -> Result<(), Box<dyn Error>> {
         temp_dir.path().to_owned(),
     )?;
 
-    for test in TESTS_TO_RUN.iter() {
+    let junit_path = args.get_one::<PathBuf>("junit");
+    let mut results = vec![];
+    for test in tests {
         println!("Running test {}/{}", test.extension, test.name);
 
-        let binary = get_binary_data(test, temp_dir.path().to_owned())?;
-        let reference_txt = get_signature_data(test, temp_dir.path().to_owned())?;
-
-        let clock = Rc::new(Clock::new());
-        let pic = Rc::new(Pic::new());
-        let args = DEFAULT_CPU_ARGS;
-        let mut cpu = Cpu::new(Ram::new(binary), clock, pic, args);
-        cpu.write_pc(0x3000);
-        while !is_test_complete(&mut cpu.bus) {
-            match cpu.step(None) {
-                StepAction::Continue => continue,
-                _ => break,
-            }
+        let result = run_test(test, temp_dir.path());
+        match &result {
+            Ok(()) => println!("PASSED"),
+            Err(err) => println!("FAILED: {}", err),
         }
-        if !is_test_complete(&mut cpu.bus) {
-            Err(std::io::Error::new(
-                ErrorKind::Other,
-                "test did not complete",
-            ))?;
+        if junit_path.is_none() {
+            result?;
+            continue;
         }
+        results.push(TestResult {
+            test,
+            failure: result.err().map(|err| err.to_string()),
+        });
+    }
 
-        check_reference_data(&reference_txt, &mut cpu.bus)?;
-        println!("PASSED");
-        drop(cpu);
+    if let Some(junit_path) = junit_path {
+        std::fs::write(junit_path, junit::to_junit_xml(&results))?;
+        let failures = results.iter().filter(|r| r.failure.is_some()).count();
+        if failures > 0 {
+            Err(into_io_error(format!(
+                "{} of {} tests failed",
+                failures,
+                results.len()
+            )))?;
+        }
     }
     Ok(())
 }
@@ -261,6 +319,25 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_select_tests() {
+        assert_eq!(select_tests(None, None).len(), TESTS_TO_RUN.len());
+
+        let names = |tests: Vec<&TestInfo>| tests.iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(select_tests(Some("clmul"), None)),
+            ["clmul-01", "clmulh-01", "clmulr-01"]
+        );
+        assert_eq!(
+            names(select_tests(Some("add"), Some("b"))),
+            ["sh1add-01", "sh2add-01", "sh3add-01"]
+        );
+        assert!(select_tests(Some("clmul"), Some("I")).is_empty());
+        assert!(select_tests(None, Some("M"))
+            .iter()
+            .all(|test| test.extension == "M"));
+    }
+
     #[test]
     fn test_check_reference_data() {
         let mut ram_bytes = vec![0u8; 4096];
@@ -1,12 +1,13 @@
 // Licensed under the Apache-2.0 license
 
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::Rc;
 
 use crate::McuMailbox0Internal;
 use caliptra_emu_bus::{ActionHandle, Clock, Ram, ReadWriteRegister, Timer};
 use caliptra_emu_cpu::Irq;
-use emulator_consts::{RAM_ORG, RAM_SIZE};
+use emulator_consts::{MCU_MAILBOX0_SRAM_SIZE, RAM_ORG, RAM_SIZE};
 use emulator_registers_generated::axicdma::AxicdmaPeripheral;
 use registers_generated::axicdma::bits::{AxicdmaBytesToTransfer, AxicdmaControl, AxicdmaStatus};
 use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
@@ -140,7 +141,10 @@ impl AxiCDMA {
                 self.raise_interrupt(DmaCtrlIntType::Event);
             }
             Err(_) => {
-                self.status.reg.modify(AxicdmaStatus::Idle::SET);
+                // The transfer touched an address with no backing memory behind it
+                self.status
+                    .reg
+                    .modify(AxicdmaStatus::Idle::SET + AxicdmaStatus::ErrDecode::SET);
                 self.raise_interrupt(DmaCtrlIntType::Error);
             }
         }
@@ -206,66 +210,59 @@ impl AxiCDMA {
         let source_addr = Self::ram_address_to_offset(source_addr).unwrap() as usize;
         let dest_addr = Self::ram_address_to_offset(dest_addr).unwrap() as usize;
 
-        if dest_ram == Some(AXIPeripheral::McuMboxSram0) {
-            let source_ram = self.get_axi_ram(source_ram.unwrap()).unwrap();
-            let source_ram = source_ram.borrow_mut();
-            let source_data = &source_ram.data()[source_addr..source_addr + xfer_size];
-
-            if let Some(mbox0) = &self.mcu_mailbox0 {
-                for (index, chunk) in source_data.chunks(4).enumerate() {
-                    let mut data = [0u8; 4];
-                    data[..chunk.len()].copy_from_slice(chunk);
-                    let value = u32::from_le_bytes(data);
-                    let regs = &mbox0.regs;
-                    regs.lock()
-                        .unwrap()
-                        .write_mcu_mbox0_csr_mbox_sram(value, index + dest_addr);
-                }
-                return Ok(());
-            } else {
+        // Transfers that reach past the end of the backing memory fail with an error
+        // rather than touch memory outside it.
+        let source_ram = self
+            .get_axi_ram(source_ram.unwrap())
+            .ok_or(DmaOpError::ReadError)?;
+        let source_data: Vec<u8> = {
+            let source_ram = source_ram.borrow();
+            let range = Self::ram_range(&source_ram, source_addr, xfer_size)
+                .ok_or(DmaOpError::ReadError)?;
+            source_ram.data()[range].to_vec()
+        };
+
+        let mailbox = match dest_ram {
+            Some(AXIPeripheral::McuMboxSram0) => Some((&self.mcu_mailbox0, dest_addr)),
+            Some(AXIPeripheral::McuMboxSram1) => Some((&self.mcu_mailbox1, dest_addr.div_ceil(4))),
+            _ => None,
+        };
+        if let Some((mbox, dest_index)) = mailbox {
+            let mbox = mbox.as_ref().ok_or(DmaOpError::WriteError)?;
+            let mut regs = mbox.regs.lock().unwrap();
+            let words = xfer_size.div_ceil(4);
+            if !regs.is_locked()
+                || dest_index
+                    .checked_add(words)
+                    .is_none_or(|end| end > MCU_MAILBOX0_SRAM_SIZE as usize / 4)
+            {
                 return Err(DmaOpError::WriteError);
             }
-        } else if dest_ram == Some(AXIPeripheral::McuMboxSram1) {
-            let source_ram = self.get_axi_ram(source_ram.unwrap()).unwrap();
-            let source_ram = source_ram.borrow_mut();
-            let source_data = &source_ram.data()[source_addr..source_addr + xfer_size];
-
-            if let Some(mbox1) = &self.mcu_mailbox1 {
-                for (index, chunk) in source_data.chunks(4).enumerate() {
-                    let mut data = [0u8; 4];
-                    data[..chunk.len()].copy_from_slice(chunk);
-                    let value = u32::from_le_bytes(data);
-                    let regs = &mbox1.regs;
-                    regs.lock()
-                        .unwrap()
-                        .write_mcu_mbox0_csr_mbox_sram(value, index + dest_addr.div_ceil(4));
-                }
-                return Ok(());
-            } else {
-                return Err(DmaOpError::WriteError);
+            for (index, chunk) in source_data.chunks(4).enumerate() {
+                let mut data = [0u8; 4];
+                data[..chunk.len()].copy_from_slice(chunk);
+                regs.write_mcu_mbox0_csr_mbox_sram(u32::from_le_bytes(data), index + dest_index);
             }
+            return Ok(());
         }
 
-        if source_ram == dest_ram {
-            let ram = self.get_axi_ram(source_ram.unwrap()).unwrap();
-            let mut ram = ram.borrow_mut();
-            let source_data: Vec<u8> = ram.data()[source_addr..source_addr + xfer_size].to_vec();
-
-            ram.data_mut()[dest_addr..dest_addr + xfer_size].copy_from_slice(&source_data);
-        } else {
-            let source_ram = self.get_axi_ram(source_ram.unwrap()).unwrap();
-            let source_ram = source_ram.borrow_mut();
-            let dest_ram = self.get_axi_ram(dest_ram.unwrap()).unwrap();
-            let mut dest_ram = dest_ram.borrow_mut();
-
-            let source_data = &source_ram.data()[source_addr..source_addr + xfer_size];
-
-            dest_ram.data_mut()[dest_addr..dest_addr + xfer_size].copy_from_slice(source_data);
-        }
+        let dest_ram = self
+            .get_axi_ram(dest_ram.unwrap())
+            .ok_or(DmaOpError::WriteError)?;
+        let mut dest_ram = dest_ram.borrow_mut();
+        let range =
+            Self::ram_range(&dest_ram, dest_addr, xfer_size).ok_or(DmaOpError::WriteError)?;
+        dest_ram.data_mut()[range].copy_from_slice(&source_data);
 
         Ok(())
     }
 
+    /// The byte range `offset..offset + len` of `ram`, if it lies entirely within it.
+    fn ram_range(ram: &Ram, offset: usize, len: usize) -> Option<Range<usize>> {
+        let end = offset.checked_add(len)?;
+        (end <= ram.data().len()).then_some(offset..end)
+    }
+
     fn process_io(&mut self) {
         if !self.btt.reg.is_set(AxicdmaBytesToTransfer::Btt) {
             return;
@@ -614,4 +611,64 @@ mod test {
             AxicdmaStatus::Idle::SET.value
         );
     }
+
+    #[test]
+    fn test_main_dma_out_of_range_descriptor() {
+        let dummy_clock = Clock::new();
+        let dummy_mcu_sram = test_helper_setup_dummy_mcu_sram();
+        // Smaller than its AXI address window
+        let dummy_external_sram = Rc::new(RefCell::new(Ram::new(vec![0u8; 0x1000])));
+        let mut bus = test_helper_setup_autobus(
+            &dummy_clock,
+            Some(dummy_mcu_sram.clone()),
+            Some(dummy_external_sram.clone()),
+        );
+
+        let dma_ctrl_base_addr: u32 = AXICDMA_ADDR;
+        dummy_mcu_sram.borrow_mut().data_mut().fill(0x55);
+
+        // The source runs off the end of MCU SRAM, then the destination off the end of
+        // the external SRAM.
+        for (source_addr, dest_addr) in [
+            (RAM_ORG + RAM_SIZE - 0x10, EXTERNAL_SRAM_START_ADDR as u32),
+            (RAM_ORG, EXTERNAL_SRAM_START_ADDR as u32 + 0x1000 - 0x10),
+        ] {
+            bus.write(
+                RvSize::Word,
+                dma_ctrl_base_addr + AXICDMA_CONTROL_OFFSET,
+                AxicdmaControl::Reset::SET.value,
+            )
+            .unwrap();
+            bus.write(
+                RvSize::Word,
+                dma_ctrl_base_addr + AXICDMA_CONTROL_OFFSET,
+                AxicdmaControl::ErrIrqEn::SET.value,
+            )
+            .unwrap();
+            for (offset, value) in [
+                (AXICDMA_SRC_ADDR_OFFSET, source_addr),
+                (AXICDMA_SRC_ADDR_MSB_OFFSET, 0),
+                (AXICDMA_DST_ADDR_OFFSET, dest_addr),
+                (AXICDMA_DST_ADDR_MSB_OFFSET, 0),
+                (AXICDMA_BYTES_TO_TRANSFER_OFFSET, 0x100),
+            ] {
+                bus.write(RvSize::Word, dma_ctrl_base_addr + offset, value)
+                    .unwrap();
+            }
+
+            for _ in 0..1000 {
+                dummy_clock.increment_and_process_timer_actions(1, &mut bus);
+            }
+            bus.poll();
+
+            assert_eq!(
+                bus.read(RvSize::Word, dma_ctrl_base_addr + AXICDMA_STATUS_OFFSET)
+                    .unwrap(),
+                AxicdmaStatus::IrqError::SET.value
+                    + AxicdmaStatus::ErrDecode::SET.value
+                    + AxicdmaStatus::Idle::SET.value
+            );
+            assert!(dummy_external_sram.borrow().data().iter().all(|&b| b == 0));
+        }
+    }
 }
@@ -0,0 +1,205 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    bus_stats.rs
+
+Abstract:
+
+    Bus wrapper that counts reads and writes per peripheral.
+
+--*/
+
+use caliptra_emu_bus::{Bus, BusError, Event};
+use caliptra_emu_types::{RvAddr, RvData, RvSize};
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+/// Peripherals tracked by [`AccessCountingBus`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusPeripheral {
+    I3c,
+    Mci,
+    Mailbox,
+    Flash,
+    Otp,
+    Uart,
+    /// Any address not covered by a registered region, including memory.
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccessCount {
+    pub reads: u64,
+    pub writes: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BusAccessStats {
+    pub i3c: AccessCount,
+    pub mci: AccessCount,
+    pub mailbox: AccessCount,
+    pub flash: AccessCount,
+    pub otp: AccessCount,
+    pub uart: AccessCount,
+    pub other: AccessCount,
+}
+
+impl BusAccessStats {
+    pub fn get(&self, periph: BusPeripheral) -> &AccessCount {
+        match periph {
+            BusPeripheral::I3c => &self.i3c,
+            BusPeripheral::Mci => &self.mci,
+            BusPeripheral::Mailbox => &self.mailbox,
+            BusPeripheral::Flash => &self.flash,
+            BusPeripheral::Otp => &self.otp,
+            BusPeripheral::Uart => &self.uart,
+            BusPeripheral::Other => &self.other,
+        }
+    }
+
+    fn get_mut(&mut self, periph: BusPeripheral) -> &mut AccessCount {
+        match periph {
+            BusPeripheral::I3c => &mut self.i3c,
+            BusPeripheral::Mci => &mut self.mci,
+            BusPeripheral::Mailbox => &mut self.mailbox,
+            BusPeripheral::Flash => &mut self.flash,
+            BusPeripheral::Otp => &mut self.otp,
+            BusPeripheral::Uart => &mut self.uart,
+            BusPeripheral::Other => &mut self.other,
+        }
+    }
+}
+
+/// Wraps a bus and tallies accesses per peripheral. Counting is off until
+/// [`AccessCountingBus::set_enabled`] is called, so that the only cost in
+/// normal runs is a branch per access.
+pub struct AccessCountingBus<TBus: Bus> {
+    pub bus: TBus,
+    regions: Vec<(Range<RvAddr>, BusPeripheral)>,
+    stats: Option<BusAccessStats>,
+}
+
+impl<TBus: Bus> AccessCountingBus<TBus> {
+    pub fn new(bus: TBus, regions: Vec<(Range<RvAddr>, BusPeripheral)>) -> Self {
+        Self {
+            bus,
+            regions,
+            stats: None,
+        }
+    }
+
+    /// Start or stop counting. Enabling resets the counters.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.stats = enabled.then(BusAccessStats::default);
+    }
+
+    /// The counters since counting was enabled, or `None` if it is disabled.
+    pub fn stats(&self) -> Option<&BusAccessStats> {
+        self.stats.as_ref()
+    }
+
+    fn count(&mut self, addr: RvAddr) -> Option<&mut AccessCount> {
+        let stats = self.stats.as_mut()?;
+        let periph = self
+            .regions
+            .iter()
+            .find(|(range, _)| range.contains(&addr))
+            .map_or(BusPeripheral::Other, |(_, periph)| *periph);
+        Some(stats.get_mut(periph))
+    }
+}
+
+impl<TBus: Bus> Bus for AccessCountingBus<TBus> {
+    fn read(&mut self, size: RvSize, addr: RvAddr) -> Result<RvData, BusError> {
+        if let Some(count) = self.count(addr) {
+            count.reads += 1;
+        }
+        self.bus.read(size, addr)
+    }
+
+    fn write(&mut self, size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
+        if let Some(count) = self.count(addr) {
+            count.writes += 1;
+        }
+        self.bus.write(size, addr, val)
+    }
+
+    fn poll(&mut self) {
+        self.bus.poll();
+    }
+
+    fn warm_reset(&mut self) {
+        self.bus.warm_reset();
+    }
+
+    fn update_reset(&mut self) {
+        self.bus.update_reset();
+    }
+
+    fn incoming_event(&mut self, event: Rc<Event>) {
+        self.bus.incoming_event(event);
+    }
+
+    fn register_outgoing_events(&mut self, sender: mpsc::Sender<Event>) {
+        self.bus.register_outgoing_events(sender);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use caliptra_emu_bus::Ram;
+
+    #[test]
+    fn test_access_counts() {
+        let mut bus = AccessCountingBus::new(
+            Ram::new(vec![0; 0x100]),
+            vec![
+                (0x10..0x20, BusPeripheral::Uart),
+                (0x20..0x40, BusPeripheral::Mci),
+            ],
+        );
+
+        // disabled by default
+        bus.read(RvSize::Word, 0x10).unwrap();
+        assert_eq!(bus.stats(), None);
+
+        bus.set_enabled(true);
+        bus.read(RvSize::Word, 0x10).unwrap();
+        bus.write(RvSize::Word, 0x1c, 1).unwrap();
+        bus.read(RvSize::Word, 0x20).unwrap();
+        bus.read(RvSize::Word, 0x3c).unwrap();
+        bus.write(RvSize::Word, 0x80, 1).unwrap();
+
+        let stats = bus.stats().unwrap();
+        assert_eq!(
+            *stats.get(BusPeripheral::Uart),
+            AccessCount {
+                reads: 1,
+                writes: 1
+            }
+        );
+        assert_eq!(
+            *stats.get(BusPeripheral::Mci),
+            AccessCount {
+                reads: 2,
+                writes: 0
+            }
+        );
+        assert_eq!(
+            *stats.get(BusPeripheral::Other),
+            AccessCount {
+                reads: 0,
+                writes: 1
+            }
+        );
+        assert_eq!(*stats.get(BusPeripheral::I3c), AccessCount::default());
+
+        bus.set_enabled(false);
+        assert_eq!(bus.stats(), None);
+    }
+}
@@ -0,0 +1,114 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    ecc_inject.rs
+
+Abstract:
+
+    Bus wrapper that injects ICCM/DCCM ECC errors on MCU reads.
+
+--*/
+
+use caliptra_emu_bus::{Bus, BusError, Event};
+use caliptra_emu_types::{RvAddr, RvData, RvSize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+/// Wraps a bus and fails or corrects reads of words with an injected ECC
+/// error. Corrected errors are counted in a counter shared with the MCI (see
+/// [`crate::Mci::sram_ecc_cor_count`]), which reports them to firmware.
+pub struct EccInjectingBus<TBus: Bus> {
+    pub bus: TBus,
+    /// Pending ECC errors by word address; the value is true for a double-bit error.
+    errors: HashMap<RvAddr, bool>,
+    corrected_count: Rc<RefCell<u32>>,
+}
+
+impl<TBus: Bus> EccInjectingBus<TBus> {
+    pub fn new(bus: TBus, corrected_count: Rc<RefCell<u32>>) -> Self {
+        Self {
+            bus,
+            errors: HashMap::new(),
+            corrected_count,
+        }
+    }
+
+    /// Make the next read of the word containing `addr` see an ECC error. A
+    /// double-bit error fails the read with a load access fault; a single-bit
+    /// error is corrected and increments the shared corrected-error count.
+    /// The injection is cleared once it fires.
+    pub fn inject_ecc_error(&mut self, addr: RvAddr, double_bit: bool) {
+        self.errors.insert(addr & !3, double_bit);
+    }
+}
+
+impl<TBus: Bus> Bus for EccInjectingBus<TBus> {
+    fn read(&mut self, size: RvSize, addr: RvAddr) -> Result<RvData, BusError> {
+        if !self.errors.is_empty() {
+            match self.errors.remove(&(addr & !3)) {
+                Some(true) => return Err(BusError::LoadAccessFault),
+                Some(false) => {
+                    let mut count = self.corrected_count.borrow_mut();
+                    *count = count.wrapping_add(1);
+                }
+                None => {}
+            }
+        }
+        self.bus.read(size, addr)
+    }
+
+    fn write(&mut self, size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
+        self.bus.write(size, addr, val)
+    }
+
+    fn poll(&mut self) {
+        self.bus.poll();
+    }
+
+    fn warm_reset(&mut self) {
+        self.bus.warm_reset();
+    }
+
+    fn update_reset(&mut self) {
+        self.bus.update_reset();
+    }
+
+    fn incoming_event(&mut self, event: Rc<Event>) {
+        self.bus.incoming_event(event);
+    }
+
+    fn register_outgoing_events(&mut self, sender: mpsc::Sender<Event>) {
+        self.bus.register_outgoing_events(sender);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use caliptra_emu_bus::Ram;
+
+    #[test]
+    fn test_ecc_injection() {
+        let corrected = Rc::new(RefCell::new(0));
+        let mut bus = EccInjectingBus::new(Ram::new(vec![0xa5; 0x100]), corrected.clone());
+
+        bus.inject_ecc_error(0x10, true);
+        bus.inject_ecc_error(0x22, false);
+
+        // Double-bit errors fault on any access to the word, once
+        assert_eq!(bus.read(RvSize::Byte, 0x13), Err(BusError::LoadAccessFault));
+        assert_eq!(bus.read(RvSize::Word, 0x10), Ok(0xa5a5_a5a5));
+        assert_eq!(*corrected.borrow(), 0);
+
+        // Single-bit errors are corrected and counted
+        assert_eq!(bus.read(RvSize::Word, 0x20), Ok(0xa5a5_a5a5));
+        assert_eq!(*corrected.borrow(), 1);
+        assert_eq!(bus.read(RvSize::Word, 0x20), Ok(0xa5a5_a5a5));
+        assert_eq!(*corrected.borrow(), 1);
+    }
+}
@@ -14,10 +14,57 @@ Abstract:
 
 use caliptra_emu_bus::{Bus, BusError};
 use caliptra_emu_types::{RvAddr, RvData, RvSize};
+use std::cell::Cell;
 use std::process::exit;
+use std::rc::Rc;
+
+/// Process exit status the emulator keeps for its own use (sysexits' `EX_TEMPFAIL`); see
+/// `mcu_testing_common::i3c_socket_server::I3C_PORT_IN_USE_EXIT_CODE`.
+pub const RESERVED_EXIT_STATUS: i32 = 75;
+
+/// The process status for a firmware exit `code`. Only the low byte reaches the parent, and
+/// a firmware failure that would look like [`RESERVED_EXIT_STATUS`] is reported as 1.
+fn process_exit_status(code: u32) -> i32 {
+    match code as i32 {
+        status if status & 0xff == RESERVED_EXIT_STATUS => 1,
+        status => status,
+    }
+}
+
+struct ExitState {
+    code: Cell<Option<u32>>,
+    exit_process: Cell<bool>,
+}
+
+/// Handle to the exit request of an [`EmuCtrl`], kept by the owner of the bus.
+#[derive(Clone)]
+pub struct EmuCtrlExit(Rc<ExitState>);
+
+impl EmuCtrlExit {
+    /// Exit code the firmware wrote, if it has requested an exit. 0 means the test passed.
+    pub fn code(&self) -> Option<u32> {
+        self.0.code.get()
+    }
+
+    /// Whether an exit request terminates the emulator process (the default) or is only
+    /// recorded in [`Self::code`].
+    pub fn set_exit_process(&self, exit_process: bool) {
+        self.0.exit_process.set(exit_process);
+    }
+
+    /// Request an exit with `code`, exactly as if the firmware had written it.
+    pub fn request_exit(&self, code: u32) {
+        self.0.code.set(Some(code));
+        if self.0.exit_process.get() {
+            exit(process_exit_status(code));
+        }
+    }
+}
 
 /// Emulation Control
-pub struct EmuCtrl {}
+pub struct EmuCtrl {
+    exit: EmuCtrlExit,
+}
 
 impl EmuCtrl {
     // Exit emulator address
@@ -29,7 +76,17 @@ impl EmuCtrl {
     ///
     /// * `name` - Name of the device
     pub fn new() -> Self {
-        Self {}
+        Self {
+            exit: EmuCtrlExit(Rc::new(ExitState {
+                code: Cell::new(None),
+                exit_process: Cell::new(true),
+            })),
+        }
+    }
+
+    /// Handle to observe the firmware's exit request.
+    pub fn exit_handle(&self) -> EmuCtrlExit {
+        self.exit.clone()
     }
     /// Memory map size.
     pub fn mmap_size(&self) -> RvAddr {
@@ -75,11 +132,36 @@ impl Bus for EmuCtrl {
     ///   or `RvExceptionCause::StoreAddrMisaligned`
     fn write(&mut self, _size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
         match addr {
-            EmuCtrl::ADDR_EXIT => {
-                exit(val as i32);
-            }
+            EmuCtrl::ADDR_EXIT => self.exit.request_exit(val),
             _ => Err(BusError::StoreAccessFault)?,
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_recorded() {
+        let mut ctrl = EmuCtrl::new();
+        let exit = ctrl.exit_handle();
+        exit.set_exit_process(false);
+        assert_eq!(exit.code(), None);
+
+        ctrl.write(RvSize::Word, EmuCtrl::ADDR_EXIT, 0).unwrap();
+        assert_eq!(exit.code(), Some(0));
+        ctrl.write(RvSize::Word, EmuCtrl::ADDR_EXIT, 1).unwrap();
+        assert_eq!(exit.code(), Some(1));
+    }
+
+    #[test]
+    fn test_process_exit_status() {
+        assert_eq!(process_exit_status(0), 0);
+        assert_eq!(process_exit_status(2), 2);
+        // The firmware can't produce the status the emulator reserves.
+        assert_eq!(process_exit_status(RESERVED_EXIT_STATUS as u32), 1);
+        assert_eq!(process_exit_status(0x100 | RESERVED_EXIT_STATUS as u32), 1);
+    }
+}
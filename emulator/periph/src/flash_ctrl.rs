@@ -88,6 +88,7 @@ pub struct DummyFlashCtrl {
     file: Option<File>,
     buffer: Vec<u8>,
     operation_start: Option<ActionHandle>,
+    op_latency: u64,
     error_irq: Irq,
     event_irq: Irq,
 }
@@ -100,7 +101,7 @@ impl DummyFlashCtrl {
     /// This is a dummy value, the actual value should be set based on the flash storage size.
     pub const MAX_PAGES: u32 = 64 * 1024 * 1024 / Self::PAGE_SIZE as u32;
 
-    /// I/O processing delay in ticks
+    /// Default I/O processing delay in ticks
     pub const IO_START_DELAY: u64 = 200;
 
     fn initialize_flash_storage(
@@ -178,11 +179,22 @@ impl DummyFlashCtrl {
             file,
             buffer: vec![0; Self::PAGE_SIZE],
             operation_start: None,
+            op_latency: Self::IO_START_DELAY,
             error_irq,
             event_irq,
         })
     }
 
+    /// Set the number of cycles between setting FL_CONTROL.START and the read, program or
+    /// erase completing, at which point OP_STATUS is updated and the interrupt is raised.
+    pub fn set_op_latency(&mut self, cycles: u64) {
+        assert!(
+            cycles > 0,
+            "flash operation latency must be at least 1 cycle"
+        );
+        self.op_latency = cycles;
+    }
+
     fn raise_interrupt(&mut self, interrupt_type: FlashCtrlIntType) {
         match interrupt_type {
             FlashCtrlIntType::Error => {
@@ -562,7 +574,7 @@ impl PrimaryFlashPeripheral for DummyFlashCtrl {
             self.ctrl_regwen.reg.modify(CtrlRegwen::En::CLEAR);
 
             // Schedule the timer to start the operation after the delay
-            self.operation_start = Some(self.timer.schedule_poll_in(Self::IO_START_DELAY));
+            self.operation_start = Some(self.timer.schedule_poll_in(self.op_latency));
         }
     }
 
@@ -733,7 +745,7 @@ impl SecondaryFlashPeripheral for DummyFlashCtrl {
             self.ctrl_regwen.reg.modify(CtrlRegwen::En::CLEAR);
 
             // Schedule the timer to start the operation after the delay
-            self.operation_start = Some(self.timer.schedule_poll_in(Self::IO_START_DELAY));
+            self.operation_start = Some(self.timer.schedule_poll_in(self.op_latency));
         }
     }
 
@@ -1593,4 +1605,85 @@ mod test {
     fn test_secondary_flash_erase_page_error() {
         test_erase_page_error(FlashType::ImagePartitionB);
     }
+
+    #[test]
+    fn test_flash_op_latency() {
+        const LATENCY: u64 = 1000;
+        let test_file = NamedTempFile::new().unwrap().path().to_path_buf();
+        let dummy_clock = Clock::new();
+        let pic = Pic::new();
+
+        let mut flash_controller = Box::new(
+            DummyFlashCtrl::new(
+                &dummy_clock,
+                None,
+                Some(test_file),
+                pic.register_irq(19),
+                pic.register_irq(20),
+                None,
+            )
+            .unwrap(),
+        );
+        flash_controller.set_op_latency(LATENCY);
+        let mut bus = AutoRootBus::new(
+            vec![],
+            None,
+            None,
+            Some(flash_controller),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        bus.write(RvSize::Word, PRIMARY_FLASH_CTRL_ADDR + PAGE_NUM_OFFSET, 10)
+            .unwrap();
+        bus.write(
+            RvSize::Word,
+            PRIMARY_FLASH_CTRL_ADDR + PAGE_SIZE_OFFSET,
+            DummyFlashCtrl::PAGE_SIZE as u32,
+        )
+        .unwrap();
+        bus.write(
+            RvSize::Word,
+            PRIMARY_FLASH_CTRL_ADDR + CONTROL_OFFSET,
+            (FlControl::Start::SET + FlControl::Op.val(FlashOperation::ErasePage as u32)).value,
+        )
+        .unwrap();
+
+        // Still pending one cycle before the latency elapses
+        for _ in 0..LATENCY - 1 {
+            dummy_clock.increment_and_process_timer_actions(1, &mut bus);
+        }
+        bus.poll();
+        assert_eq!(
+            bus.read(RvSize::Word, PRIMARY_FLASH_CTRL_ADDR + OP_STATUS_OFFSET)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            bus.read(RvSize::Word, PRIMARY_FLASH_CTRL_ADDR + INT_STATE_OFFSET)
+                .unwrap(),
+            0
+        );
+
+        dummy_clock.increment_and_process_timer_actions(1, &mut bus);
+        bus.poll();
+        assert_eq!(
+            bus.read(RvSize::Word, PRIMARY_FLASH_CTRL_ADDR + OP_STATUS_OFFSET)
+                .unwrap(),
+            OpStatus::Done::SET.value
+        );
+        assert_eq!(
+            bus.read(RvSize::Word, PRIMARY_FLASH_CTRL_ADDR + INT_STATE_OFFSET)
+                .unwrap(),
+            FlInterruptState::Event::SET.value
+        );
+    }
 }
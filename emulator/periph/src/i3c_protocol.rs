@@ -16,6 +16,7 @@ use mcu_testing_common::i3c::{
     DynamicI3cAddress, I3cBusCommand, I3cBusResponse, I3cError, I3cTcriCommandXfer,
     I3cTcriResponseXfer,
 };
+use mcu_testing_common::i3c_socket_server::I3cSocket;
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
@@ -31,6 +32,7 @@ pub struct I3cController {
     running: Arc<AtomicBool>,
     // used for testing
     incoming_counter: Arc<AtomicUsize>,
+    fixed_dynamic_address: Option<DynamicI3cAddress>,
 }
 
 impl Drop for I3cController {
@@ -47,9 +49,22 @@ impl I3cController {
             tx: Some(tx),
             running: Arc::new(AtomicBool::new(false)),
             incoming_counter: Arc::new(AtomicUsize::new(0)),
+            fixed_dynamic_address: None,
         }
     }
 
+    /// Starts `socket` and creates a controller serving it. If the socket has a fixed
+    /// dynamic address, the first attached target gets it instead of the first free one.
+    pub fn with_socket(
+        running: &'static AtomicBool,
+        socket: &I3cSocket,
+    ) -> std::io::Result<I3cController> {
+        let (rx, tx) = socket.start(running)?;
+        let mut controller = I3cController::new(rx, tx);
+        controller.fixed_dynamic_address = socket.fixed_dynamic_address();
+        Ok(controller)
+    }
+
     /// Stops the thread that processes incoming commands and sends responses.
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::Relaxed);
@@ -123,6 +138,8 @@ impl I3cController {
                 .dynamic_address
                 .ok_or(I3cError::DeviceAttachedWithoutAddress)?;
             highest_address.next().ok_or(I3cError::NoMoreAddresses)?
+        } else if let Some(fixed_address) = self.fixed_dynamic_address {
+            fixed_address
         } else {
             DynamicI3cAddress::new(8)?
         };
@@ -273,6 +290,7 @@ pub struct I3cTargetDevice {
 #[cfg(test)]
 mod test {
     use super::*;
+    use mcu_config::McuStraps;
     use mcu_testing_common::i3c::{I3cTcriCommand, ImmediateDataTransferCommand};
     use std::sync::mpsc::channel;
     use zerocopy::FromBytes;
@@ -300,4 +318,28 @@ mod test {
         controller.run_once();
         assert_eq!(1, controller.incoming_counter.load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn fixed_dynamic_address_test() {
+        // The socket thread exits right away, as nothing is running.
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+        let straps = McuStraps::default();
+        let socket = I3cSocket::new(0)
+            .with_fixed_dynamic_address(0x20, &straps)
+            .unwrap();
+        let mut controller = I3cController::with_socket(&RUNNING, &socket).unwrap();
+
+        let first = I3cTarget::default();
+        let second = I3cTarget::default();
+        controller.attach_target(first.clone()).unwrap();
+        controller.attach_target(second.clone()).unwrap();
+        assert_eq!(
+            Some(DynamicI3cAddress::new(0x20).unwrap()),
+            first.get_address()
+        );
+        assert_eq!(
+            Some(DynamicI3cAddress::new(0x21).unwrap()),
+            second.get_address()
+        );
+    }
 }
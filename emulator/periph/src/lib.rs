@@ -15,8 +15,10 @@ Abstract:
 #![feature(cell_update)]
 
 mod axicdma;
+mod bus_stats;
 mod caliptra_to_ext_bus;
 mod doe_mbox;
+mod ecc_inject;
 mod emu_ctrl;
 mod flash_ctrl;
 mod i3c;
@@ -29,11 +31,14 @@ mod otp_digest;
 mod reset_reason;
 mod root_bus;
 mod uart;
+mod watch;
 
 pub use axicdma::AxiCDMA;
+pub use bus_stats::{AccessCount, AccessCountingBus, BusAccessStats, BusPeripheral};
 pub use caliptra_to_ext_bus::CaliptraToExtBus;
 pub use doe_mbox::{DoeMboxPeriph, DummyDoeMbox};
-pub use emu_ctrl::EmuCtrl;
+pub use ecc_inject::EccInjectingBus;
+pub use emu_ctrl::{EmuCtrl, EmuCtrlExit, RESERVED_EXIT_STATUS};
 pub use flash_ctrl::DummyFlashCtrl;
 pub use i3c::I3c;
 pub use i3c_protocol::*;
@@ -45,3 +50,4 @@ pub use otp_digest::{otp_digest, otp_scramble, otp_unscramble};
 pub use reset_reason::ResetReasonEmulator;
 pub use root_bus::{McuRootBus, McuRootBusArgs, McuRootBusOffsets};
 pub use uart::Uart;
+pub use watch::{WatchHit, WatchingBus};
@@ -7,8 +7,8 @@ use caliptra_emu_cpu::Irq;
 use caliptra_emu_types::RvData;
 use emulator_registers_generated::mci::MciPeripheral;
 use registers_generated::mci::bits::{
-    Error0IntrT, Notif0IntrEnT, Notif0IntrT, ResetReason, ResetRequest, WdtStatus, WdtTimer1Ctrl,
-    WdtTimer1En, WdtTimer2Ctrl, WdtTimer2En,
+    Error0IntrT, MboxxAxiUserLock, Notif0IntrEnT, Notif0IntrT, ResetReason, ResetRequest,
+    WdtStatus, WdtTimer1Ctrl, WdtTimer1En, WdtTimer2Ctrl, WdtTimer2En,
 };
 use std::{cell::RefCell, rc::Rc};
 use tock_registers::interfaces::{ReadWriteable, Readable};
@@ -36,6 +36,13 @@ pub struct Mci {
     mtimecmp: u64,
     op_mtimecmp_due_action: Option<ActionHandle>,
     mcu_mailbox1: Option<McuMailbox0Internal>,
+
+    // driven by the SoC; read-only from the MCU
+    generic_input_wires: Rc<RefCell<[u32; 2]>>,
+
+    // corrected MCU SRAM ECC errors, counted by the bus; see sram_ecc_cor_count()
+    sram_ecc_cor_count: Rc<RefCell<u32>>,
+    sram_ecc_cor_seen: u32,
 }
 
 impl Mci {
@@ -75,9 +82,23 @@ impl Mci {
             mtimecmp: default_mtimecmp,
             op_mtimecmp_due_action: None,
             mcu_mailbox1,
+            generic_input_wires: Rc::new(RefCell::new([0; 2])),
+            sram_ecc_cor_count: Rc::new(RefCell::new(0)),
+            sram_ecc_cor_seen: 0,
         }
     }
 
+    /// Shared handle to the MCI generic input wires, for the SoC side to drive.
+    pub fn generic_input_wires(&self) -> Rc<RefCell<[u32; 2]>> {
+        self.generic_input_wires.clone()
+    }
+
+    /// Shared handle to the corrected MCU SRAM ECC error counter, for the bus to
+    /// increment. Each increment sets the notif0 SRAM ECC correctable status.
+    pub fn sram_ecc_cor_count(&self) -> Rc<RefCell<u32>> {
+        self.sram_ecc_cor_count.clone()
+    }
+
     fn arm_mtime_interrupt(&mut self) {
         // clean up previous pending timers
 
@@ -102,6 +123,51 @@ impl Mci {
                 .schedule_action_in(delay, TimerAction::MachineTimerInterrupt),
         );
     }
+
+    /// Sets the notif0 status bit for a mailbox event if it is enabled and
+    /// raises the MCI IRQ.
+    fn raise_mcu_mailbox_irq(
+        &mut self,
+        event: crate::mcu_mbox0::IrqEventToMcu,
+        cmd_avail_en: u32,
+        cmd_avail_sts: u32,
+        target_done_en: u32,
+        target_done_sts: u32,
+    ) {
+        let (en, sts) = match event {
+            crate::mcu_mbox0::IrqEventToMcu::Mbox0CmdAvailable => (cmd_avail_en, cmd_avail_sts),
+            crate::mcu_mbox0::IrqEventToMcu::Mbox0TargetDone => (target_done_en, target_done_sts),
+        };
+        self.raise_notif0_irq(en, sts);
+    }
+
+    /// Sets the notif0 status bit `sts` if `en` is enabled and raises the MCI IRQ.
+    fn raise_notif0_irq(&mut self, en: u32, sts: u32) {
+        let mut notif_reg = self
+            .ext_mci_regs
+            .regs
+            .borrow()
+            .intr_block_rf_notif0_internal_intr_r;
+
+        let notif_en = self
+            .ext_mci_regs
+            .regs
+            .borrow()
+            .intr_block_rf_notif0_intr_en_r;
+
+        // Set the corresponding bit for the event if enabled
+        if notif_en & en != 0 {
+            notif_reg |= sts;
+        }
+        self.ext_mci_regs
+            .regs
+            .borrow_mut()
+            .intr_block_rf_notif0_internal_intr_r = notif_reg;
+        // Raise IRQ level if any bit is set
+        if notif_reg != 0 {
+            self.irq.borrow_mut().set_level(true);
+        }
+    }
 }
 
 impl MciPeripheral for Mci {
@@ -428,6 +494,124 @@ impl MciPeripheral for Mci {
             .intr_block_rf_notif0_intr_en_r = val.reg.get();
     }
 
+    fn read_mci_reg_mbox0_valid_axi_user(&mut self, index: usize) -> caliptra_emu_types::RvData {
+        self.mcu_mailbox0
+            .as_mut()
+            .expect("mcu_mbox0 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .read_valid_axi_user(index)
+    }
+
+    fn write_mci_reg_mbox0_valid_axi_user(
+        &mut self,
+        val: caliptra_emu_types::RvData,
+        index: usize,
+    ) {
+        self.mcu_mailbox0
+            .as_mut()
+            .expect("mcu_mbox0 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .write_valid_axi_user(index, val)
+    }
+
+    fn read_mci_reg_mbox0_axi_user_lock(
+        &mut self,
+        index: usize,
+    ) -> caliptra_emu_bus::ReadWriteRegister<
+        u32,
+        registers_generated::mci::bits::MboxxAxiUserLock::Register,
+    > {
+        let locked = self
+            .mcu_mailbox0
+            .as_mut()
+            .expect("mcu_mbox0 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .read_axi_user_lock(index);
+        caliptra_emu_bus::ReadWriteRegister::new(locked as u32)
+    }
+
+    fn write_mci_reg_mbox0_axi_user_lock(
+        &mut self,
+        val: caliptra_emu_bus::ReadWriteRegister<
+            u32,
+            registers_generated::mci::bits::MboxxAxiUserLock::Register,
+        >,
+        index: usize,
+    ) {
+        self.mcu_mailbox0
+            .as_mut()
+            .expect("mcu_mbox0 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .write_axi_user_lock(index, val.reg.is_set(MboxxAxiUserLock::Lock))
+    }
+
+    fn read_mci_reg_mbox1_valid_axi_user(&mut self, index: usize) -> caliptra_emu_types::RvData {
+        self.mcu_mailbox1
+            .as_mut()
+            .expect("mcu_mbox1 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .read_valid_axi_user(index)
+    }
+
+    fn write_mci_reg_mbox1_valid_axi_user(
+        &mut self,
+        val: caliptra_emu_types::RvData,
+        index: usize,
+    ) {
+        self.mcu_mailbox1
+            .as_mut()
+            .expect("mcu_mbox1 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .write_valid_axi_user(index, val)
+    }
+
+    fn read_mci_reg_mbox1_axi_user_lock(
+        &mut self,
+        index: usize,
+    ) -> caliptra_emu_bus::ReadWriteRegister<
+        u32,
+        registers_generated::mci::bits::MboxxAxiUserLock::Register,
+    > {
+        let locked = self
+            .mcu_mailbox1
+            .as_mut()
+            .expect("mcu_mbox1 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .read_axi_user_lock(index);
+        caliptra_emu_bus::ReadWriteRegister::new(locked as u32)
+    }
+
+    fn write_mci_reg_mbox1_axi_user_lock(
+        &mut self,
+        val: caliptra_emu_bus::ReadWriteRegister<
+            u32,
+            registers_generated::mci::bits::MboxxAxiUserLock::Register,
+        >,
+        index: usize,
+    ) {
+        self.mcu_mailbox1
+            .as_mut()
+            .expect("mcu_mbox1 is not initialized")
+            .regs
+            .lock()
+            .unwrap()
+            .write_axi_user_lock(index, val.reg.is_set(MboxxAxiUserLock::Lock))
+    }
+
     fn read_mcu_mbox0_csr_mbox_sram(&mut self, index: usize) -> caliptra_emu_types::RvData {
         self.mcu_mailbox0
             .as_mut()
@@ -912,6 +1096,19 @@ impl MciPeripheral for Mci {
             .read_mcu_mbox0_csr_mbox_hw_status()
     }
 
+    fn read_mci_reg_generic_input_wires(&mut self, index: usize) -> RvData {
+        self.generic_input_wires.borrow()[index]
+    }
+
+    fn read_mci_reg_intr_block_rf_notif_mcu_sram_ecc_cor_intr_count_r(&mut self) -> RvData {
+        *self.sram_ecc_cor_count.borrow()
+    }
+
+    fn write_mci_reg_intr_block_rf_notif_mcu_sram_ecc_cor_intr_count_r(&mut self, val: RvData) {
+        *self.sram_ecc_cor_count.borrow_mut() = val;
+        self.sram_ecc_cor_seen = val;
+    }
+
     fn read_mci_reg_hw_rev_id(
         &mut self,
     ) -> caliptra_emu_bus::ReadWriteRegister<u32, registers_generated::mci::bits::HwRevId::Register>
@@ -1021,41 +1218,34 @@ impl MciPeripheral for Mci {
             self.irq.borrow_mut().set_level(false);
         }
 
-        // Check if there are any mcu_mbox0 IRQ events to process.
+        // Check if there are any mcu_mbox0/mcu_mbox1 IRQ events to process.
         if let Some(event) = self.mcu_mailbox0.as_mut().and_then(|mb| mb.get_notif_irq()) {
-            let mut notif_reg = self
-                .ext_mci_regs
-                .regs
-                .borrow()
-                .intr_block_rf_notif0_internal_intr_r;
-
-            let notif_en = self
-                .ext_mci_regs
-                .regs
-                .borrow()
-                .intr_block_rf_notif0_intr_en_r;
-
-            // Set the corresponding bit for the event if enabled
-            match event {
-                crate::mcu_mbox0::IrqEventToMcu::Mbox0CmdAvailable => {
-                    if notif_en & Notif0IntrEnT::NotifMbox0CmdAvailEn::SET.value != 0 {
-                        notif_reg |= Notif0IntrT::NotifMbox0CmdAvailSts::SET.value;
-                    }
-                }
-                crate::mcu_mbox0::IrqEventToMcu::Mbox0TargetDone => {
-                    if notif_en & Notif0IntrEnT::NotifMbox0TargetDoneEn::SET.value != 0 {
-                        notif_reg |= Notif0IntrT::NotifMbox0TargetDoneSts::SET.value;
-                    }
-                }
-            }
-            self.ext_mci_regs
-                .regs
-                .borrow_mut()
-                .intr_block_rf_notif0_internal_intr_r = notif_reg;
-            // Raise IRQ level if any bit is set
-            if notif_reg != 0 {
-                self.irq.borrow_mut().set_level(true);
-            }
+            self.raise_mcu_mailbox_irq(
+                event,
+                Notif0IntrEnT::NotifMbox0CmdAvailEn::SET.value,
+                Notif0IntrT::NotifMbox0CmdAvailSts::SET.value,
+                Notif0IntrEnT::NotifMbox0TargetDoneEn::SET.value,
+                Notif0IntrT::NotifMbox0TargetDoneSts::SET.value,
+            );
+        }
+        if let Some(event) = self.mcu_mailbox1.as_mut().and_then(|mb| mb.get_notif_irq()) {
+            self.raise_mcu_mailbox_irq(
+                event,
+                Notif0IntrEnT::NotifMbox1CmdAvailEn::SET.value,
+                Notif0IntrT::NotifMbox1CmdAvailSts::SET.value,
+                Notif0IntrEnT::NotifMbox1TargetDoneEn::SET.value,
+                Notif0IntrT::NotifMbox1TargetDoneSts::SET.value,
+            );
+        }
+
+        // Report MCU SRAM ECC errors corrected by the bus since the last poll.
+        let sram_ecc_cor_count = *self.sram_ecc_cor_count.borrow();
+        if sram_ecc_cor_count != self.sram_ecc_cor_seen {
+            self.sram_ecc_cor_seen = sram_ecc_cor_count;
+            self.raise_notif0_irq(
+                Notif0IntrEnT::NotifMcuSramEccCorEn::SET.value,
+                Notif0IntrT::NotifMcuSramEccCorSts::SET.value,
+            );
         }
     }
 }
@@ -1075,6 +1265,8 @@ mod tests {
     pub const CPTRA_WDT_STATUS_START: u32 = 0xd0;
     pub const NOTIF0_INTR_EN_OFFSET: u32 = 0x100c;
     pub const NOTIF0_INTERNAL_INTR_R_OFFSET: u32 = 0x1024;
+    pub const GENERIC_INPUT_WIRES_OFFSET: u32 = 0x400;
+    pub const NOTIF_MCU_SRAM_ECC_COR_INTR_COUNT_OFFSET: u32 = 0x1200;
 
     fn next_action(clock: &Clock) -> Option<TimerAction> {
         let mut actions = clock.increment(4);
@@ -1085,6 +1277,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generic_input_wires() {
+        let clock = Clock::new();
+        let ext_mci_regs = caliptra_emu_periph::mci::Mci::new(vec![]);
+        let pic = caliptra_emu_cpu::Pic::new();
+        let irq = pic.register_irq(1);
+        let mci_reg: Mci = Mci::new(&clock, ext_mci_regs, Rc::new(RefCell::new(irq)), None, None);
+        let wires = mci_reg.generic_input_wires();
+        let mut mci_bus = MciBus {
+            periph: Box::new(mci_reg),
+        };
+
+        *wires.borrow_mut() = [0x1234_5678, 0x9abc_def0];
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, GENERIC_INPUT_WIRES_OFFSET)
+                .unwrap(),
+            0x1234_5678
+        );
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, GENERIC_INPUT_WIRES_OFFSET + 4)
+                .unwrap(),
+            0x9abc_def0
+        );
+    }
+
+    #[test]
+    fn test_sram_ecc_cor_notif() {
+        let clock = Clock::new();
+        let ext_mci_regs = caliptra_emu_periph::mci::Mci::new(vec![]);
+        let pic = caliptra_emu_cpu::Pic::new();
+        let irq = pic.register_irq(1);
+        let mci_reg: Mci = Mci::new(&clock, ext_mci_regs, Rc::new(RefCell::new(irq)), None, None);
+        let count = mci_reg.sram_ecc_cor_count();
+        let mut mci_bus = MciBus {
+            periph: Box::new(mci_reg),
+        };
+        let sts = Notif0IntrT::NotifMcuSramEccCorSts::SET.value;
+
+        // Disabled: counted but no status
+        *count.borrow_mut() += 1;
+        mci_bus.poll();
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, NOTIF_MCU_SRAM_ECC_COR_INTR_COUNT_OFFSET)
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET)
+                .unwrap()
+                & sts,
+            0
+        );
+
+        mci_bus
+            .write(
+                RvSize::Word,
+                NOTIF0_INTR_EN_OFFSET,
+                Notif0IntrEnT::NotifMcuSramEccCorEn::SET.value,
+            )
+            .unwrap();
+        mci_bus.poll();
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET)
+                .unwrap()
+                & sts,
+            0
+        );
+        *count.borrow_mut() += 1;
+        mci_bus.poll();
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET)
+                .unwrap()
+                & sts,
+            sts
+        );
+
+        // Firmware clears the status and the counter
+        mci_bus
+            .write(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET, sts)
+            .unwrap();
+        mci_bus
+            .write(RvSize::Word, NOTIF_MCU_SRAM_ECC_COR_INTR_COUNT_OFFSET, 0)
+            .unwrap();
+        mci_bus.poll();
+        assert_eq!(*count.borrow(), 0);
+        assert_eq!(
+            mci_bus
+                .read(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET)
+                .unwrap()
+                & sts,
+            0
+        );
+    }
+
     #[test]
     fn test_wdt() {
         let clock = Clock::new();
@@ -1209,6 +1501,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mailbox1_interrupt_handling() {
+        let clock = Clock::new();
+        let ext_mci_regs = caliptra_emu_periph::mci::Mci::new(vec![]);
+        let pic = caliptra_emu_cpu::Pic::new();
+        let irq = pic.register_irq(1);
+        let mci_reg = Mci::new(
+            &clock,
+            ext_mci_regs.clone(),
+            Rc::new(RefCell::new(irq)),
+            Some(McuMailbox0Internal::new(&clock)),
+            Some(McuMailbox0Internal::new(&clock)),
+        );
+        let mut mcu_mailbox1 = mci_reg.mcu_mailbox1.clone().unwrap();
+        let mut mci_bus = MciBus {
+            periph: Box::new(mci_reg),
+        };
+        check_mcu_mailbox0_interrupt(
+            &clock,
+            &mut mci_bus,
+            &mut mcu_mailbox1,
+            IrqEventToMcu::Mbox0CmdAvailable,
+            Notif0IntrEnT::NotifMbox1CmdAvailEn::SET.value,
+            Notif0IntrT::NotifMbox1CmdAvailSts::SET.value,
+        );
+        // mbox1 events must not set the mbox0 status bit
+        let notif_status = mci_bus
+            .read(RvSize::Word, NOTIF0_INTERNAL_INTR_R_OFFSET)
+            .unwrap();
+        assert_eq!(
+            notif_status & Notif0IntrT::NotifMbox0CmdAvailSts::SET.value,
+            0
+        );
+    }
+
     #[test]
     fn test_mtimecmp_write_high_then_low() {
         let clock = Clock::new();
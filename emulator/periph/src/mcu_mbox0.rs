@@ -8,6 +8,9 @@ use registers_generated::mci::bits::MboxExecute;
 use std::sync::{Arc, Mutex};
 use tock_registers::interfaces::{Readable, Writeable};
 
+/// Number of MBOXx_VALID_AXI_USER / MBOXx_AXI_USER_LOCK register pairs in MCI.
+const MCI_MAILBOX_AXI_USER_COUNT: usize = 5;
+
 #[derive(Clone)]
 pub struct MciMailboxRam {
     pub ram: Arc<Mutex<Ram>>,
@@ -43,9 +46,12 @@ impl McuMailbox0Internal {
         }
     }
 
+    /// Every SoC view of the mailbox shares the AXI user held in the mailbox regs, so
+    /// creating a view, or calling [`McuMailbox0External::set_axi_user`] on any of them,
+    /// changes it for all of them.
     pub fn as_external(&self, soc_agent: MciMailboxRequester) -> McuMailbox0External {
+        self.regs.lock().unwrap().soc_agent = soc_agent;
         McuMailbox0External {
-            soc_agent,
             regs: self.regs.clone(),
         }
     }
@@ -72,10 +78,33 @@ impl McuMailbox0Internal {
 // External interface for MCU Mailbox 0, used by SoC agent.
 #[derive(Clone)]
 pub struct McuMailbox0External {
-    pub soc_agent: MciMailboxRequester,
     pub regs: Arc<Mutex<MciMailboxImpl>>,
 }
 
+impl McuMailbox0External {
+    /// Set the AXI user the SoC presents to the mailbox, for every SoC view of it.
+    pub fn set_axi_user(&self, axi_user: u32) {
+        self.regs.lock().unwrap().soc_agent = MciMailboxRequester::SocAgent(axi_user);
+    }
+
+    /// Read MBOX_LOCK as the SoC agent. Reading 0 means the lock was acquired, or that
+    /// the access was dropped because the AXI user is not allowed.
+    pub fn read_mbox_lock(&self) -> u32 {
+        let mut regs = self.regs.lock().unwrap();
+        let soc_agent = regs.soc_agent;
+        regs.read_mbox_lock_as(soc_agent).reg.get()
+    }
+
+    /// Try to take the mailbox lock as the SoC agent, returning whether it now holds it.
+    pub fn acquire_lock(&self) -> bool {
+        let mut regs = self.regs.lock().unwrap();
+        let soc_agent = regs.soc_agent;
+        regs.read_mbox_lock_as(soc_agent).reg.get() == 0
+            && regs.is_locked()
+            && regs.user.reg.get() == u32::from(soc_agent)
+    }
+}
+
 // MCU Mailbox 0 implementation.
 pub struct MciMailboxImpl {
     /// Mailbox SRAM
@@ -114,6 +143,15 @@ pub struct MciMailboxImpl {
     /// Current requester (MCU or SoC agent)
     pub requester: MciMailboxRequester,
 
+    /// AXI user presented by the SoC's views of the mailbox
+    soc_agent: MciMailboxRequester,
+
+    /// MBOXx_VALID_AXI_USER registers, programmed through MCI
+    valid_axi_users: [u32; MCI_MAILBOX_AXI_USER_COUNT],
+
+    /// MBOXx_AXI_USER_LOCK registers; a locked entry can no longer be changed
+    axi_user_locks: [bool; MCI_MAILBOX_AXI_USER_COUNT],
+
     /// Maximum DLEN seen in the current lock session (for zeroization)
     max_dlen_in_lock_session: usize,
 
@@ -184,6 +222,9 @@ impl MciMailboxImpl {
             cmd_status: ReadWriteRegister::new(Self::CMD_STATUS_VAL),
             hw_status: ReadOnlyRegister::new(Self::HW_STATUS_VAL),
             requester: MciMailboxRequester::Mcu,
+            soc_agent: MciMailboxRequester::SocAgent(0),
+            valid_axi_users: [0; MCI_MAILBOX_AXI_USER_COUNT],
+            axi_user_locks: [false; MCI_MAILBOX_AXI_USER_COUNT],
             irq: false,
             last_irq_event: None,
             timer: Timer::new(clock),
@@ -207,6 +248,41 @@ impl MciMailboxImpl {
         self.requester = requester;
     }
 
+    pub fn read_valid_axi_user(&self, index: usize) -> u32 {
+        self.valid_axi_users[index]
+    }
+
+    pub fn write_valid_axi_user(&mut self, index: usize, val: u32) {
+        if !self.axi_user_locks[index] {
+            self.valid_axi_users[index] = val;
+        }
+    }
+
+    pub fn read_axi_user_lock(&self, index: usize) -> bool {
+        self.axi_user_locks[index]
+    }
+
+    /// The lock is sticky: once set it is only cleared by a cold reset.
+    pub fn write_axi_user_lock(&mut self, index: usize, locked: bool) {
+        self.axi_user_locks[index] |= locked;
+    }
+
+    /// The MCU always has access. SoC agents must match a locked MBOXx_VALID_AXI_USER
+    /// entry once any entry has been locked; before that the mailbox is open to all agents.
+    fn is_requester_allowed(&self, requester: MciMailboxRequester) -> bool {
+        match requester {
+            MciMailboxRequester::Mcu => true,
+            MciMailboxRequester::SocAgent(user) => {
+                !self.axi_user_locks.contains(&true)
+                    || self
+                        .valid_axi_users
+                        .iter()
+                        .zip(self.axi_user_locks.iter())
+                        .any(|(&valid, &locked)| locked && valid == user)
+            }
+        }
+    }
+
     pub fn is_locked(&self) -> bool {
         self.lock.reg.get() != 0
     }
@@ -282,10 +358,22 @@ impl MciMailboxImpl {
         &mut self,
     ) -> caliptra_emu_bus::ReadWriteRegister<u32, registers_generated::mbox::bits::MboxLock::Register>
     {
-        // If the lock is not held, we can grant it to the current requester
+        self.read_mbox_lock_as(self.requester)
+    }
+
+    fn read_mbox_lock_as(
+        &mut self,
+        requester: MciMailboxRequester,
+    ) -> caliptra_emu_bus::ReadWriteRegister<u32, registers_generated::mbox::bits::MboxLock::Register>
+    {
+        // Accesses from an AXI user that is not allowed are dropped and read as 0
+        if !self.is_requester_allowed(requester) {
+            return caliptra_emu_bus::ReadWriteRegister::new(0);
+        }
+        // If the lock is not held, we can grant it to the requester
         if self.lock.reg.get() == 0 {
-            // Grant lock to current requester
-            self.user.reg.set(self.requester.into());
+            // Grant lock to the requester
+            self.user.reg.set(requester.into());
             // Lock the mailbox
             self.lock.reg.set(1);
             // Reset max_dlen_in_lock_session for new session
@@ -491,6 +579,9 @@ mod tests {
 
     const SOC_AGENT_ID: u32 = 0x1;
 
+    const MBOX0_VALID_AXI_USER_OFFSET: u32 = 0x180;
+    const MBOX0_AXI_USER_LOCK_OFFSET: u32 = 0x1a0;
+
     fn test_helper_setup_autobus(clock: &Clock, mcu_mailbox0: &McuMailbox0Internal) -> AutoRootBus {
         let pic = Pic::new();
         let ext_mci_regs = caliptra_emu_periph::mci::Mci::new(vec![]);
@@ -706,6 +797,91 @@ mod tests {
         assert_eq!(hw_status_val, 0, "HW status should be 0");
     }
 
+    #[test]
+    fn test_soc_axi_user() {
+        let dummy_clock = Clock::new();
+        let mcu_mailbox0 = McuMailbox0Internal::new(&dummy_clock);
+        let soc = mcu_mailbox0.as_external(MciMailboxRequester::SocAgent(SOC_AGENT_ID));
+
+        soc.set_axi_user(0x42);
+        assert_eq!(soc.read_mbox_lock(), 0);
+        assert_eq!(soc.read_mbox_lock(), 1);
+
+        // The lock is owned by the SoC agent, and the MCU side is still the MCU
+        let mut regs = mcu_mailbox0.regs.lock().unwrap();
+        assert_eq!(regs.read_mcu_mbox0_csr_mbox_user(), 0x42);
+        assert_eq!(regs.requester, MciMailboxRequester::Mcu);
+        assert_eq!(regs.read_mcu_mbox0_csr_mbox_lock().reg.get(), 1);
+        assert_eq!(regs.read_mcu_mbox0_csr_mbox_user(), 0x42);
+    }
+
+    #[test]
+    fn test_soc_axi_user_shared_by_views() {
+        let dummy_clock = Clock::new();
+        let mcu_mailbox0 = McuMailbox0Internal::new(&dummy_clock);
+        let soc = mcu_mailbox0.as_external(MciMailboxRequester::SocAgent(SOC_AGENT_ID));
+        let other = soc.clone();
+
+        // The AXI user set through one view is the one every other view presents
+        soc.set_axi_user(0x42);
+        assert!(other.acquire_lock());
+        assert!(!soc.acquire_lock());
+        assert_eq!(
+            mcu_mailbox0
+                .regs
+                .lock()
+                .unwrap()
+                .read_mcu_mbox0_csr_mbox_user(),
+            0x42
+        );
+    }
+
+    #[test]
+    fn test_axi_user_lock_check() {
+        let dummy_clock = Clock::new();
+        let mcu_mailbox0 = McuMailbox0Internal::new(&dummy_clock);
+        let mut bus = test_helper_setup_autobus(&dummy_clock, &mcu_mailbox0);
+        let soc = mcu_mailbox0.as_external(MciMailboxRequester::SocAgent(SOC_AGENT_ID));
+
+        // MCU allows SOC_AGENT_ID and locks the entry
+        bus.write(
+            RvSize::Word,
+            MCI_BASE_ADDR + MBOX0_VALID_AXI_USER_OFFSET,
+            SOC_AGENT_ID,
+        )
+        .unwrap();
+        bus.write(RvSize::Word, MCI_BASE_ADDR + MBOX0_AXI_USER_LOCK_OFFSET, 1)
+            .unwrap();
+        // A locked entry can't be changed
+        bus.write(
+            RvSize::Word,
+            MCI_BASE_ADDR + MBOX0_VALID_AXI_USER_OFFSET,
+            SOC_AGENT_ID + 1,
+        )
+        .unwrap();
+        assert_eq!(
+            bus.read(RvSize::Word, MCI_BASE_ADDR + MBOX0_VALID_AXI_USER_OFFSET)
+                .unwrap(),
+            SOC_AGENT_ID
+        );
+
+        // Any other AXI user never gets the lock
+        soc.set_axi_user(SOC_AGENT_ID + 1);
+        for _ in 0..2 {
+            assert_eq!(soc.read_mbox_lock(), 0);
+        }
+        assert!(!mcu_mailbox0.regs.lock().unwrap().is_locked());
+
+        soc.set_axi_user(SOC_AGENT_ID);
+        assert_eq!(soc.read_mbox_lock(), 0);
+        assert_eq!(soc.read_mbox_lock(), 1);
+        assert_eq!(
+            bus.read(RvSize::Word, MCI_BASE_ADDR + MBOX_USER_OFFSET)
+                .unwrap(),
+            SOC_AGENT_ID
+        );
+    }
+
     #[test]
     fn test_soc_send_mcu_receive() {
         let dummy_clock = Clock::new();
@@ -19,10 +19,12 @@ use caliptra_image_types::FwVerificationPqcKeyType;
 use registers_generated::fuses::{self};
 use registers_generated::otp_ctrl::bits::{DirectAccessCmd, OtpStatus};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::fs::File;
 use std::io::Seek;
 use std::path::PathBuf;
+use std::rc::Rc;
 #[allow(unused_imports)] // Rust compiler doesn't like these
 use tock_registers::interfaces::{Readable, Writeable};
 
@@ -126,7 +128,8 @@ pub struct Otp {
     direct_access_cmd: ReadWriteRegister<u32, DirectAccessCmd::Register>,
     status: ReadWriteRegister<u32, OtpStatus::Register>,
     timer: Timer,
-    partitions: Vec<u8>,
+    /// Shared so that the raw memory can be inspected while the device is on the bus.
+    partitions: Rc<RefCell<Vec<u8>>>,
     digests: [u32; PARTITIONS.len() * 2],
     /// Partitions to calculate digests for on reset.
     calculate_digests_on_reset: HashSet<usize>,
@@ -178,13 +181,13 @@ impl Otp {
             status: 0b100_0000_0000_0000_0000_0000u32.into(), // DAI idle state
             calculate_digests_on_reset: HashSet::new(),
             timer: Timer::new(clock),
-            partitions: vec![0u8; TOTAL_SIZE],
+            partitions: Rc::new(RefCell::new(partitions)),
             digests: [0; PARTITIONS.len() * 2],
         };
         otp.read_from_file()?;
         if let Some(mut vendor_pk_hash) = args.vendor_pk_hash {
             swap_endianness(&mut vendor_pk_hash);
-            otp.partitions[fuses::VENDOR_HASHES_MANUF_PARTITION_BYTE_OFFSET
+            otp.partitions.borrow_mut()[fuses::VENDOR_HASHES_MANUF_PARTITION_BYTE_OFFSET
                 ..fuses::VENDOR_HASHES_MANUF_PARTITION_BYTE_OFFSET + 48]
                 .copy_from_slice(&vendor_pk_hash);
         }
@@ -193,12 +196,12 @@ impl Otp {
             FwVerificationPqcKeyType::MLDSA => 0,
             FwVerificationPqcKeyType::LMS => 1,
         };
-        otp.partitions[fuses::VENDOR_HASHES_MANUF_PARTITION_BYTE_OFFSET + 48] = val;
-        otp.partitions[fuses::SVN_PARTITION_BYTE_OFFSET + 36] =
+        otp.partitions.borrow_mut()[fuses::VENDOR_HASHES_MANUF_PARTITION_BYTE_OFFSET + 48] = val;
+        otp.partitions.borrow_mut()[fuses::SVN_PARTITION_BYTE_OFFSET + 36] =
             args.soc_manifest_max_svn.unwrap_or(0);
         if let Some(soc_manifest_svn) = args.soc_manifest_svn {
             let svn_bitmap = Self::svn_to_bitmap(soc_manifest_svn as u32);
-            otp.partitions
+            otp.partitions.borrow_mut()
                 [fuses::SVN_PARTITION_BYTE_OFFSET + 20..fuses::SVN_PARTITION_BYTE_OFFSET + 36]
                 .copy_from_slice(&svn_bitmap);
         }
@@ -207,7 +210,7 @@ impl Otp {
             let dst_start = fuses::VENDOR_HASHES_PROD_PARTITION_BYTE_OFFSET;
             let max_len = fuses::VENDOR_HASHES_PROD_PARTITION_BYTE_SIZE;
             let copy_len = vendor_hashes_prod_partition.len().min(max_len);
-            otp.partitions[dst_start..dst_start + copy_len]
+            otp.partitions.borrow_mut()[dst_start..dst_start + copy_len]
                 .copy_from_slice(&vendor_hashes_prod_partition[..copy_len]);
         }
 
@@ -216,6 +219,12 @@ impl Otp {
         Ok(otp)
     }
 
+    /// A handle to the raw OTP memory, which remains valid after the device is
+    /// moved onto a bus. The layout matches `OtpArgs::raw_memory`.
+    pub fn memory(&self) -> Rc<RefCell<Vec<u8>>> {
+        self.partitions.clone()
+    }
+
     /// Memory map size.
     pub fn mmap_size(&self) -> RvAddr {
         4096
@@ -235,22 +244,25 @@ impl Otp {
             return;
         }
         let (addr, size) = PARTITIONS[partition];
-        let digest =
-            otp_digest::otp_digest(&self.partitions[addr..addr + size], DIGEST_IV, DIGEST_CONST);
+        let digest = otp_digest::otp_digest(
+            &self.partitions.borrow()[addr..addr + size],
+            DIGEST_IV,
+            DIGEST_CONST,
+        );
         self.digests[partition * 2] = (digest & 0xffff_ffff) as u32;
         self.digests[partition * 2 + 1] = (digest >> 32) as u32;
     }
 
     fn get_state(&self) -> OtpState {
         OtpState {
-            partitions: self.partitions.clone(),
+            partitions: self.partitions.borrow().clone(),
             calculate_digests_on_reset: self.calculate_digests_on_reset.clone(),
             digests: self.digests.to_vec(),
         }
     }
 
     fn load_state(&mut self, state: &OtpState) {
-        self.partitions = state.partitions.clone();
+        *self.partitions.borrow_mut() = state.partitions.clone();
         self.calculate_digests_on_reset = state.calculate_digests_on_reset.clone();
         self.digests.copy_from_slice(&state.digests);
     }
@@ -356,8 +368,9 @@ impl emulator_registers_generated::otp::OtpPeripheral for Otp {
             let addr = (self.direct_access_address & 0xffff_fffc) as usize;
             if addr + 4 <= TOTAL_SIZE {
                 // refuse to write twice
-                if self.partitions[addr..addr + 4].iter().all(|x| *x == 0) {
-                    self.partitions[addr..addr + 4]
+                let mut partitions = self.partitions.borrow_mut();
+                if partitions[addr..addr + 4].iter().all(|x| *x == 0) {
+                    partitions[addr..addr + 4]
                         .copy_from_slice(&self.direct_access_buffer.to_le_bytes());
                 }
             }
@@ -371,7 +384,7 @@ impl emulator_registers_generated::otp::OtpPeripheral for Otp {
             let addr = (self.direct_access_address & 0xffff_fffc) as usize;
             if addr + 4 <= TOTAL_SIZE {
                 let mut buf = [0; 4];
-                buf.copy_from_slice(&self.partitions[addr..addr + 4]);
+                buf.copy_from_slice(&self.partitions.borrow()[addr..addr + 4]);
                 self.direct_access_buffer = u32::from_le_bytes(buf);
             }
             // reset direct access
@@ -455,6 +468,38 @@ mod test {
         otp.write_vendor_test_partition_read_lock(0u32.into());
     }
 
+    #[test]
+    fn test_raw_memory() {
+        let clock = Clock::new();
+        let mut raw_memory = vec![0u8; fuses::LIFE_CYCLE_BYTE_OFFSET + 4];
+        raw_memory[fuses::LIFE_CYCLE_BYTE_OFFSET..].copy_from_slice(&[1, 2, 3, 4]);
+        let mut otp = Otp::new(
+            &clock,
+            OtpArgs {
+                raw_memory: Some(raw_memory),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let memory = otp.memory();
+        assert_eq!(memory.borrow().len(), TOTAL_SIZE);
+        assert_eq!(
+            &memory.borrow()[fuses::LIFE_CYCLE_BYTE_OFFSET..fuses::LIFE_CYCLE_BYTE_OFFSET + 4],
+            &[1, 2, 3, 4]
+        );
+
+        // writes through the direct access interface are visible in the handle
+        let addr = fuses::VENDOR_TEST_PARTITION_BYTE_OFFSET;
+        otp.write_dai_wdata_rf_direct_access_wdata_0(0x1234_5678);
+        otp.write_direct_access_address((addr as u32).into());
+        otp.write_direct_access_cmd(2u32.into());
+        otp.poll();
+        assert_eq!(
+            &memory.borrow()[addr..addr + 4],
+            &0x1234_5678u32.to_le_bytes()
+        );
+    }
+
     #[test]
     fn test_write_and_read() {
         let clock = Clock::new();
@@ -80,6 +80,8 @@ pub struct McuRootBusArgs {
     pub rom: Vec<u8>,
     pub log_dir: PathBuf,
     pub uart_output: Option<Rc<RefCell<Vec<u8>>>>,
+    /// Keep only this many of the most recent bytes in `uart_output` (unbounded if `None`).
+    pub uart_output_max_bytes: Option<usize>,
     pub uart_rx: Option<Arc<Mutex<Option<u8>>>>,
     pub offsets: McuRootBusOffsets,
 }
@@ -124,12 +126,14 @@ impl McuRootBus {
         let mci_irq = pic.register_irq(McuRootBus::MCI_IRQ);
         let mcu_mailbox0 = McuMailbox0Internal::new(&clock.clone());
         let mcu_mailbox1 = McuMailbox0Internal::new(&clock.clone());
+        let mut uart = Uart::new(args.uart_output, args.uart_rx, uart_irq, &clock.clone());
+        uart.set_output_max_bytes(args.uart_output_max_bytes);
 
         Ok(Self {
             rom,
             ram: Rc::new(RefCell::new(ram)),
             rom_sram: Rc::new(RefCell::new(rom_sram)),
-            uart: Uart::new(args.uart_output, args.uart_rx, uart_irq, &clock.clone()),
+            uart,
             ctrl: EmuCtrl::new(),
             pic_regs: pic.mmio_regs(clock.clone()),
             event_sender: None,
@@ -24,6 +24,7 @@ pub struct Uart {
     data_bits: u8,
     stop_bits: u8,
     output: Option<Rc<RefCell<Vec<u8>>>>,
+    output_max_bytes: Option<usize>,
     input: Option<Arc<Mutex<Option<u8>>>>,
     bytes_read: Cell<u64>,
     byte_last_irq_triggered: Cell<u64>,
@@ -59,6 +60,7 @@ impl Uart {
             data_bits: 8,
             stop_bits: 1,
             output,
+            output_max_bytes: None,
             input,
             irq,
             bytes_read: Cell::new(0),
@@ -67,6 +69,12 @@ impl Uart {
             char_buffer: Cell::new(PartialUtf8::new()),
         }
     }
+
+    /// Keep only the most recent `max_bytes` bytes of captured output. `None` (the default)
+    /// keeps everything.
+    pub fn set_output_max_bytes(&mut self, max_bytes: Option<usize>) {
+        self.output_max_bytes = max_bytes;
+    }
 }
 
 impl Bus for Uart {
@@ -141,6 +149,10 @@ impl Bus for Uart {
                 Some(output) => {
                     let mut out = output.borrow_mut();
                     out.push(value as u8);
+                    if let Some(max_bytes) = self.output_max_bytes {
+                        let excess = out.len().saturating_sub(max_bytes);
+                        out.drain(..excess);
+                    }
                 }
                 None => {
                     match value as u8 {
@@ -228,6 +240,34 @@ impl PartialUtf8 {
 #[cfg(test)]
 mod test {
     use super::*;
+    use caliptra_emu_cpu::Pic;
+
+    #[test]
+    fn test_output_max_bytes() {
+        let clock = Clock::new();
+        let pic = Pic::new();
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let mut uart = Uart::new(Some(output.clone()), None, pic.register_irq(16), &clock);
+        uart.set_output_max_bytes(Some(4));
+
+        for &ch in b"abc" {
+            uart.write(RvSize::Byte, Uart::ADDR_TX_DATA, ch as RvData)
+                .unwrap();
+        }
+        assert_eq!(output.borrow().as_slice(), b"abc");
+
+        for &ch in b"defg" {
+            uart.write(RvSize::Byte, Uart::ADDR_TX_DATA, ch as RvData)
+                .unwrap();
+        }
+        assert_eq!(output.borrow().as_slice(), b"defg");
+
+        // A reader draining the buffer leaves room for new output
+        output.borrow_mut().clear();
+        uart.write(RvSize::Byte, Uart::ADDR_TX_DATA, b'h' as RvData)
+            .unwrap();
+        assert_eq!(output.borrow().as_slice(), b"h");
+    }
     #[test]
     fn test_utf8_buffer() {
         let mut p = PartialUtf8::new();
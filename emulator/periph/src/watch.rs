@@ -0,0 +1,135 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    watch.rs
+
+Abstract:
+
+    Bus wrapper that records writes to watched address ranges.
+
+--*/
+
+use caliptra_emu_bus::{Bus, BusError, Event};
+use caliptra_emu_types::{RvAddr, RvData, RvSize};
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+/// A committed write that touched a watched range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchHit {
+    pub addr: RvAddr,
+    pub size: RvSize,
+    pub val: RvData,
+}
+
+/// Wraps a bus and records successful writes that overlap any watched range.
+/// With no ranges set the only cost is a branch per write.
+pub struct WatchingBus<TBus: Bus> {
+    pub bus: TBus,
+    ranges: Vec<Range<RvAddr>>,
+    hits: Vec<WatchHit>,
+}
+
+impl<TBus: Bus> WatchingBus<TBus> {
+    pub fn new(bus: TBus) -> Self {
+        Self {
+            bus,
+            ranges: vec![],
+            hits: vec![],
+        }
+    }
+
+    /// Record successful writes that overlap any of `ranges`, replacing the previous ranges.
+    pub fn set_watch_ranges(&mut self, ranges: Vec<Range<RvAddr>>) {
+        self.ranges = ranges;
+        if self.ranges.is_empty() {
+            self.hits.clear();
+        }
+    }
+
+    /// Writes to watched ranges since the last call, oldest first.
+    pub fn take_watch_hits(&mut self) -> Vec<WatchHit> {
+        std::mem::take(&mut self.hits)
+    }
+}
+
+impl<TBus: Bus> Bus for WatchingBus<TBus> {
+    fn read(&mut self, size: RvSize, addr: RvAddr) -> Result<RvData, BusError> {
+        self.bus.read(size, addr)
+    }
+
+    fn write(&mut self, size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
+        self.bus.write(size, addr, val)?;
+        if !self.ranges.is_empty() {
+            let end = addr.saturating_add(size as RvAddr);
+            if self
+                .ranges
+                .iter()
+                .any(|range| addr < range.end && range.start < end)
+            {
+                self.hits.push(WatchHit { addr, size, val });
+            }
+        }
+        Ok(())
+    }
+
+    fn poll(&mut self) {
+        self.bus.poll();
+    }
+
+    fn warm_reset(&mut self) {
+        self.bus.warm_reset();
+    }
+
+    fn update_reset(&mut self) {
+        self.bus.update_reset();
+    }
+
+    fn incoming_event(&mut self, event: Rc<Event>) {
+        self.bus.incoming_event(event);
+    }
+
+    fn register_outgoing_events(&mut self, sender: mpsc::Sender<Event>) {
+        self.bus.register_outgoing_events(sender);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use caliptra_emu_bus::Ram;
+
+    #[test]
+    fn test_watch_hits() {
+        let mut bus = WatchingBus::new(Ram::new(vec![0; 0x100]));
+        bus.set_watch_ranges(vec![0x21..0x23]);
+
+        bus.write(RvSize::Word, 0x10, 1).unwrap();
+        bus.write(RvSize::Word, 0x20, 0x1234_5678).unwrap();
+        bus.write(RvSize::Byte, 0x23, 2).unwrap();
+        bus.write(RvSize::HalfWord, 0x22, 0xabcd).unwrap();
+        // Failed writes are not reported
+        assert!(bus.write(RvSize::Word, 0x200, 3).is_err());
+
+        assert_eq!(
+            bus.take_watch_hits(),
+            vec![
+                WatchHit {
+                    addr: 0x20,
+                    size: RvSize::Word,
+                    val: 0x1234_5678
+                },
+                WatchHit {
+                    addr: 0x22,
+                    size: RvSize::HalfWord,
+                    val: 0xabcd
+                },
+            ]
+        );
+        assert_eq!(bus.take_watch_hits(), vec![]);
+    }
+}
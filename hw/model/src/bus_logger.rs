@@ -4,12 +4,76 @@ use std::{
     cell::RefCell,
     fs::File,
     io::{BufWriter, Write},
+    ops::Range,
     path::Path,
     rc::Rc,
+    sync::mpsc,
 };
 
-use caliptra_emu_bus::{Bus, BusError};
+use caliptra_emu_bus::{Bus, BusError, Clock};
 use caliptra_emu_types::{RvAddr, RvData, RvSize};
+use mcu_config::McuMemoryMap;
+
+// Blocks within the MCI address space, relative to the MCI offset
+const MCI_MBOX0_OFFSET: RvAddr = 0x40_0000;
+const MCI_MBOX1_OFFSET: RvAddr = 0x80_0000;
+const MCI_SRAM_OFFSET: RvAddr = 0xc0_0000;
+const MCI_SRAM_END: RvAddr = 0xe0_0000;
+
+/// Address ranges of the peripherals reported in [`BusEvent::peripheral`],
+/// taken from `memory_map`. Earlier entries take precedence.
+fn peripherals(memory_map: &McuMemoryMap) -> Vec<(&'static str, Range<RvAddr>)> {
+    let region = |offset: u32, size: u32| offset..offset.saturating_add(size);
+    let mci = memory_map.mci_offset;
+    vec![
+        ("i3c", region(memory_map.i3c_offset, memory_map.i3c_size)),
+        ("mbox0", mci + MCI_MBOX0_OFFSET..mci + MCI_MBOX1_OFFSET),
+        ("mbox1", mci + MCI_MBOX1_OFFSET..mci + MCI_SRAM_OFFSET),
+        ("mcu_sram", mci + MCI_SRAM_OFFSET..mci + MCI_SRAM_END),
+        ("mci", region(mci, memory_map.mci_size)),
+        (
+            "caliptra_mbox",
+            region(memory_map.mbox_offset, memory_map.mbox_size),
+        ),
+        (
+            "soc_ifc",
+            region(memory_map.soc_offset, memory_map.soc_size),
+        ),
+        ("otp", region(memory_map.otp_offset, memory_map.otp_size)),
+        ("lc", region(memory_map.lc_offset, memory_map.lc_size)),
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusDirection {
+    Read,
+    Write,
+}
+
+/// A successful peripheral access, see `ModelEmulated::subscribe_bus_events()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusEvent {
+    /// Cycle count at the time of the access.
+    pub cycle: u64,
+    /// Who made the access: "UC" for the MCU, "SoC" for the model itself.
+    pub source: &'static str,
+    pub peripheral: &'static str,
+    pub addr: RvAddr,
+    pub size: RvSize,
+    pub direction: BusDirection,
+    /// Value read or written.
+    pub value: RvData,
+}
+
+pub struct BusEventSink {
+    sender: mpsc::Sender<BusEvent>,
+    clock: Rc<Clock>,
+}
+impl BusEventSink {
+    pub fn new(sender: mpsc::Sender<BusEvent>, clock: Rc<Clock>) -> Self {
+        Self { sender, clock }
+    }
+}
 
 #[derive(Clone)]
 pub struct LogFile(Rc<RefCell<BufWriter<File>>>);
@@ -49,18 +113,59 @@ impl Bus for NullBus {
 pub struct BusLogger<TBus: Bus> {
     pub bus: TBus,
     pub log: Option<LogFile>,
+    pub events: Option<BusEventSink>,
+    peripherals: Vec<(&'static str, Range<RvAddr>)>,
 }
 impl<TBus: Bus> BusLogger<TBus> {
-    pub fn new(bus: TBus) -> Self {
-        Self { bus, log: None }
+    pub fn new(bus: TBus, memory_map: &McuMemoryMap) -> Self {
+        Self {
+            bus,
+            log: None,
+            events: None,
+            peripherals: peripherals(memory_map),
+        }
+    }
+    fn peripheral_name(&self, addr: RvAddr) -> Option<&'static str> {
+        self.peripherals
+            .iter()
+            .find(|(_, range)| range.contains(&addr))
+            .map(|(name, _)| *name)
+    }
+    fn send_event(
+        &mut self,
+        source: &'static str,
+        size: RvSize,
+        addr: RvAddr,
+        direction: BusDirection,
+        value: RvData,
+    ) {
+        let (Some(sink), Some(peripheral)) = (&self.events, self.peripheral_name(addr)) else {
+            return;
+        };
+        let event = BusEvent {
+            cycle: sink.clock.now(),
+            source,
+            peripheral,
+            addr,
+            size,
+            direction,
+            value,
+        };
+        if sink.sender.send(event).is_err() {
+            // The receiver is gone, stop generating events
+            self.events = None;
+        }
     }
     pub fn log_read(
         &mut self,
-        bus_name: &str,
+        bus_name: &'static str,
         size: RvSize,
         addr: RvAddr,
         result: Result<RvData, caliptra_emu_bus::BusError>,
     ) {
+        if let Ok(val) = result {
+            self.send_event(bus_name, size, addr, BusDirection::Read, val);
+        }
         if let Some(log) = &mut self.log {
             let size = usize::from(size);
             match result {
@@ -75,12 +180,15 @@ impl<TBus: Bus> BusLogger<TBus> {
     }
     pub fn log_write(
         &mut self,
-        bus_name: &str,
+        bus_name: &'static str,
         size: RvSize,
         addr: RvAddr,
         val: RvData,
         result: Result<(), caliptra_emu_bus::BusError>,
     ) {
+        if result.is_ok() {
+            self.send_event(bus_name, size, addr, BusDirection::Write, val);
+        }
         if addr < 0x1000_0000 {
             // Don't care about memory
             return;
@@ -141,3 +249,31 @@ impl<TBus: Bus> Bus for BusLogger<TBus> {
         self.bus.register_outgoing_events(sender);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peripheral_names_follow_memory_map() {
+        let logger = BusLogger::new(NullBus(), &McuMemoryMap::default());
+        assert_eq!(logger.peripheral_name(0x2000_4000), Some("i3c"));
+        assert_eq!(logger.peripheral_name(0x2100_0000), Some("mci"));
+        assert_eq!(logger.peripheral_name(0x2140_0010), Some("mbox0"));
+        assert_eq!(logger.peripheral_name(0x21c0_0000), Some("mcu_sram"));
+        assert_eq!(logger.peripheral_name(0x7000_0400), Some("lc"));
+        assert_eq!(logger.peripheral_name(0x4000_0000), None);
+
+        let memory_map = McuMemoryMap {
+            mci_offset: 0x1000_0000,
+            otp_offset: 0x9000_0000,
+            ..Default::default()
+        };
+        let logger = BusLogger::new(NullBus(), &memory_map);
+        assert_eq!(logger.peripheral_name(0x1000_0000), Some("mci"));
+        assert_eq!(logger.peripheral_name(0x1080_0000), Some("mbox1"));
+        assert_eq!(logger.peripheral_name(0x9000_0000), Some("otp"));
+        assert_eq!(logger.peripheral_name(0x2100_0000), None);
+        assert_eq!(logger.peripheral_name(0x7000_0000), None);
+    }
+}
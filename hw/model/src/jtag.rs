@@ -8,7 +8,7 @@ use caliptra_api::mailbox::CommandId;
 use caliptra_hw_model::jtag::CaliptraCoreReg;
 use caliptra_hw_model::openocd::openocd_jtag_tap::OpenOcdJtagTap;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use zerocopy::FromBytes;
 
 /// Wait for Caliptra Core mailbox response over JTAG TAP.
@@ -68,3 +68,84 @@ pub fn jtag_send_caliptra_mailbox_cmd(
 
     Ok(())
 }
+
+/// A TAP discovered on the JTAG scan chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JtagChainTap {
+    /// IDCODE captured in DR after TAP reset, or 0 if the TAP only has BYPASS.
+    pub idcode: u32,
+    /// Instruction register length in bits.
+    pub ir_len: u32,
+}
+
+/// Parse the table printed by OpenOCD's `scan_chain` command.
+///
+/// TAPs are returned in the order OpenOCD lists them (scan chain position).
+pub fn parse_scan_chain(output: &str) -> Result<Vec<JtagChainTap>> {
+    let mut taps = vec![];
+    for line in output.lines() {
+        // Rows look like:
+        //  0 riscv.cpu              Y     0x00000001 0x00000000     5 0x01  0x03
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.first().and_then(|f| f.parse::<usize>().ok()) != Some(taps.len()) {
+            continue;
+        }
+        if fields.len() < 6 {
+            bail!("Truncated scan_chain row: {:?}", line);
+        }
+        let idcode = u32::from_str_radix(fields[3].trim_start_matches("0x"), 16)
+            .with_context(|| format!("Invalid IDCODE in scan_chain row: {:?}", line))?;
+        if idcode != 0 && idcode & 1 == 0 {
+            bail!("IDCODE {:#010x} does not have bit 0 set", idcode);
+        }
+        let ir_len = fields[5]
+            .parse::<u32>()
+            .with_context(|| format!("Invalid IR length in scan_chain row: {:?}", line))?;
+        taps.push(JtagChainTap { idcode, ir_len });
+    }
+    Ok(taps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scan_chain() {
+        let output = "> scan_chain
+   TapName             Enabled  IdCode     Expected   IrLen IrCap IrMask
+-- ------------------- -------- ---------- ---------- ----- ----- ------
+ 0 lc_ctrl.tap            Y     0x10001cdf 0x10001cdf     5 0x01  0x03
+ 1 riscv.tap              Y     0x00000001 0x00000000     5 0x01  0x03
+ 2 bypass.tap             Y     0x00000000 0x00000000     8 0x01  0x03
+> ";
+        assert_eq!(
+            parse_scan_chain(output).unwrap(),
+            vec![
+                JtagChainTap {
+                    idcode: 0x10001cdf,
+                    ir_len: 5,
+                },
+                JtagChainTap {
+                    idcode: 0x00000001,
+                    ir_len: 5,
+                },
+                JtagChainTap {
+                    idcode: 0,
+                    ir_len: 8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_scan_chain_invalid_idcode() {
+        let output = " 0 riscv.tap              Y     0x00000002 0x00000000     5 0x01  0x03";
+        assert!(parse_scan_chain(output).is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_chain_empty() {
+        assert_eq!(parse_scan_chain("> scan_chain\n").unwrap(), vec![]);
+    }
+}
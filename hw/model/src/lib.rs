@@ -3,6 +3,7 @@
 use anyhow::{bail, Result};
 pub use api::mailbox::mbox_write_fifo;
 pub use api_types::{DbgManufServiceRegReq, DeviceLifecycle, Fuses, U4};
+pub use bus_logger::{BusDirection, BusEvent};
 use caliptra_api::{self as api, SocManager};
 use caliptra_api_types as api_types;
 use caliptra_emu_bus::Event;
@@ -64,6 +65,12 @@ pub type DefaultHwModel = ModelFpgaRealtime;
 
 pub const DEFAULT_APB_PAUSER: u32 = 0x01;
 
+/// Default number of cycles to wait for a mailbox command to complete (100ms @400MHz).
+pub const DEFAULT_MAILBOX_TIMEOUT_CYCLES: u64 = 40_000_000;
+
+/// Number of steps between progress callbacks in `mailbox_execute_with_progress()`.
+pub const MAILBOX_PROGRESS_INTERVAL: u64 = 10_000;
+
 // This is a random number, but should be kept in sync with what is the default value in the FPGA ROM.
 const DEFAULT_LIFECYCLE_RAW_TOKEN: LifecycleToken =
     LifecycleToken(0x05edb8c608fcc830de181732cfd65e57u128.to_le_bytes());
@@ -134,7 +141,7 @@ pub struct InitParams<'a> {
     /// This will override any otp_memory contents.
     pub vendor_pk_hash: Option<[u8; 48]>,
     /// PQC key type for vendor public key.
-    /// This will override any otp_memory contents.
+    /// This will override any otp_memory contents and `BootParams::fuses.fuse_pqc_key_type`.
     pub vendor_pqc_type: Option<FwVerificationPqcKeyType>,
 
     pub log_writer: Box<dyn std::io::Write>,
@@ -176,6 +183,10 @@ pub struct InitParams<'a> {
     // registers in response to requests via CPTRA_TRNG_STATUS
     pub etrng_responses: Box<dyn Iterator<Item = EtrngResponse> + Send>,
 
+    // Seed `itrng_nibbles` and `etrng_responses` were created from, if any. Models that
+    // build their own Caliptra TRNG sources seed them with it.
+    pub trng_seed: Option<u64>,
+
     // If true (and the HwModel supports it), initialize the SRAM with random
     // data. This will likely result in a ECC double-bit error if the CPU
     // attempts to read uninitialized memory.
@@ -193,9 +204,20 @@ pub struct InitParams<'a> {
     pub enable_mcu_uart_log: bool,
 
     pub i3c_port: Option<u16>,
+
+    // Dynamic address to assign to the MCU I3C target instead of the first free one.
+    // Requires `i3c_port`.
+    pub i3c_dynamic_address: Option<u8>,
 }
 
 impl InitParams<'_> {
+    /// Seed the itrng and etrng sources so that two runs with the same seed
+    /// and inputs produce identical traces. This overrides `CPTRA_TRNG_SEED`.
+    pub fn set_trng_seed(&mut self, seed: u64) {
+        (self.itrng_nibbles, self.etrng_responses) = trng_sources(Some(seed));
+        self.trng_seed = Some(seed);
+    }
+
     pub fn summary(&self) -> InitParamsSummary {
         InitParamsSummary {
             rom_sha384: sha2::Sha384::digest(self.mcu_rom).into(),
@@ -204,22 +226,32 @@ impl InitParams<'_> {
     }
 }
 
+type TrngSources = (
+    Box<dyn Iterator<Item = u8> + Send>,
+    Box<dyn Iterator<Item = EtrngResponse> + Send>,
+);
+
+/// Returns the itrng nibble and etrng response iterators, seeded
+/// deterministically if `seed` is set and from entropy otherwise.
+fn trng_sources(seed: Option<u64>) -> TrngSources {
+    match seed {
+        Some(seed) => (
+            Box::new(RandomNibbles(StdRng::seed_from_u64(seed))),
+            Box::new(RandomEtrngResponses(StdRng::seed_from_u64(seed))),
+        ),
+        None => (
+            Box::new(RandomNibbles(StdRng::from_entropy())),
+            Box::new(RandomEtrngResponses::new_from_stdrng()),
+        ),
+    }
+}
+
 impl Default for InitParams<'_> {
     fn default() -> Self {
         let seed = std::env::var("CPTRA_TRNG_SEED")
             .ok()
             .and_then(|s| u64::from_str(&s).ok());
-        let itrng_nibbles: Box<dyn Iterator<Item = u8> + Send> = if let Some(seed) = seed {
-            Box::new(RandomNibbles(StdRng::seed_from_u64(seed)))
-        } else {
-            Box::new(RandomNibbles(StdRng::from_entropy()))
-        };
-        let etrng_responses: Box<dyn Iterator<Item = EtrngResponse> + Send> =
-            if let Some(seed) = seed {
-                Box::new(RandomEtrngResponses(StdRng::seed_from_u64(seed)))
-            } else {
-                Box::new(RandomEtrngResponses::new_from_stdrng())
-            };
+        let (itrng_nibbles, etrng_responses) = trng_sources(seed);
         Self {
             caliptra_rom: Default::default(),
             caliptra_firmware: Default::default(),
@@ -245,6 +277,7 @@ impl Default for InitParams<'_> {
             cptra_obf_key: DEFAULT_CPTRA_OBF_KEY,
             itrng_nibbles,
             etrng_responses,
+            trng_seed: seed,
             random_sram_puf: true,
             trace_path: None,
             stack_info: None,
@@ -254,6 +287,7 @@ impl Default for InitParams<'_> {
             vendor_pk_hash: None,
             vendor_pqc_type: None,
             i3c_port: None,
+            i3c_dynamic_address: None,
         }
     }
 }
@@ -278,6 +312,46 @@ fn trace_path_or_env(trace_path: Option<PathBuf>) -> Option<PathBuf> {
     std::env::var("CPTRA_TRACE_PATH").ok().map(PathBuf::from)
 }
 
+/// A mailbox command sent by the model and the response it received.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MailboxTranscriptEntry {
+    pub cmd: u32,
+    pub request: Vec<u8>,
+    /// The response data, or the error message if the command failed.
+    pub response: Result<Option<Vec<u8>>, String>,
+}
+
+/// A record of the mailbox commands executed through a model, see
+/// [`McuHwModel::enable_mailbox_transcript`].
+#[derive(Clone, Debug, Default)]
+pub struct MailboxTranscript {
+    pub entries: Vec<MailboxTranscriptEntry>,
+    pending: Option<(u32, Vec<u8>)>,
+}
+
+impl MailboxTranscript {
+    fn start(&mut self, cmd: u32, request: &[u8]) {
+        self.pending = Some((cmd, request.to_vec()));
+    }
+
+    fn finish(&mut self, response: Result<&Option<Vec<u8>>, &anyhow::Error>) {
+        // A response without a matching request is still recorded, with an unknown command.
+        let (cmd, request) = self.pending.take().unwrap_or((u32::MAX, vec![]));
+        self.entries.push(MailboxTranscriptEntry {
+            cmd,
+            request,
+            response: response.cloned().map_err(|e| e.to_string()),
+        });
+    }
+}
+
+fn check_output_prefix(output: &str, expected_output: &str) -> Result<()> {
+    if &output[..expected_output.len()] != expected_output {
+        bail!("expected output {:?}, was {:?}", expected_output, output);
+    }
+    Ok(())
+}
+
 // Represents a emulator or simulation of the caliptra core hardware, to be called
 // from tests. Typically, test cases should use [`crate::new()`] to create a model
 // based on the cargo features (and any model-specific environment variables).
@@ -291,11 +365,17 @@ pub trait McuHwModel {
     /// Create a model, and boot it to the point where CPU execution can
     /// occur. This includes programming the fuses, initializing the
     /// boot_fsm state machine, and (optionally) uploading firmware.
-    fn new(init_params: InitParams, boot_params: BootParams) -> Result<Self>
+    ///
+    /// If `init_params.vendor_pqc_type` is set, it overrides
+    /// `boot_params.fuses.fuse_pqc_key_type` so both describe the same key type.
+    fn new(init_params: InitParams, mut boot_params: BootParams) -> Result<Self>
     where
         Self: Sized,
     {
         let init_params_summary = init_params.summary();
+        if let Some(pqc_type) = init_params.vendor_pqc_type {
+            boot_params.fuses.fuse_pqc_key_type = pqc_type as u32;
+        }
 
         let mut hw: Self = McuHwModel::new_unbooted(init_params)?;
         println!("Using hardware-model {}", hw.type_name());
@@ -313,6 +393,16 @@ pub trait McuHwModel {
 
     fn save_otp_memory(&self, path: &Path) -> Result<()>;
 
+    /// Read the current contents of OTP memory, without ECC bits.
+    ///
+    /// The byte layout matches the file written by `save_otp_memory()` after
+    /// decoding with [`read_otp_vmem_data`].
+    fn read_otp_memory(&self) -> Result<Vec<u8>> {
+        let file = tempfile::NamedTempFile::new()?;
+        self.save_otp_memory(file.path())?;
+        read_otp_vmem_data(&std::fs::read(file.path())?)
+    }
+
     /// The type name of this model
     fn type_name(&self) -> &'static str;
 
@@ -331,6 +421,24 @@ pub trait McuHwModel {
         }
     }
 
+    /// Execute until the result of `predicate` becomes true, or fail if it is
+    /// still false after `max_cycles` cycles.
+    fn step_until_timeout(
+        &mut self,
+        max_cycles: u64,
+        mut predicate: impl FnMut(&mut Self) -> bool,
+    ) -> Result<()> {
+        let start = self.cycle_count();
+        while !predicate(self) {
+            let elapsed = self.cycle_count() - start;
+            if elapsed > max_cycles {
+                bail!("Timed out after {elapsed} cycles");
+            }
+            self.step();
+        }
+        Ok(())
+    }
+
     /// Returns true if the microcontroller has signalled that it is ready for
     /// firmware to be written to the mailbox. For RTL implementations, this
     /// should come via a caliptra_top wire rather than an APB register.
@@ -392,14 +500,16 @@ pub trait McuHwModel {
     /// Execute until the output buffer starts with `expected_output`
     fn step_until_output(&mut self, expected_output: &str) -> Result<()> {
         self.step_until(|m| m.output().peek().len() >= expected_output.len());
-        if &self.output().peek()[..expected_output.len()] != expected_output {
-            bail!(
-                "expected output {:?}, was {:?}",
-                expected_output,
-                self.output().peek()
-            );
-        }
-        Ok(())
+        check_output_prefix(self.output().peek(), expected_output)
+    }
+
+    /// Execute until the output buffer starts with `expected_output`, or fail
+    /// if not enough output was written after `max_cycles` cycles.
+    fn step_until_output_timeout(&mut self, expected_output: &str, max_cycles: u64) -> Result<()> {
+        self.step_until_timeout(max_cycles, |m| {
+            m.output().peek().len() >= expected_output.len()
+        })?;
+        check_output_prefix(self.output().peek(), expected_output)
     }
 
     /// Execute until the output buffer starts with `expected_output`, and remove it
@@ -453,6 +563,29 @@ pub trait McuHwModel {
         McuBootMilestones::from((self.mci_flow_status() >> 16) as u16)
     }
 
+    /// Execute until the MCI flow status milestones include all the bits in
+    /// `milestone`, or fail if they don't after `max_cycles` cycles.
+    fn step_until_milestone(
+        &mut self,
+        milestone: McuBootMilestones,
+        max_cycles: u64,
+    ) -> Result<()> {
+        let bits = u16::from(milestone);
+        self.step_until_timeout(max_cycles, |hw| {
+            hw.mci_boot_milestones()
+                .contains(McuBootMilestones::from(bits))
+        })
+        .map_err(|e| {
+            let wanted: Vec<_> = McuBootMilestones::from(bits).set_milestones().collect();
+            let reached: Vec<_> = self.mci_boot_milestones().set_milestones().collect();
+            e.context(format!(
+                "waiting for boot milestone {} (reached: {})",
+                wanted.join(", "),
+                reached.join(", ")
+            ))
+        })
+    }
+
     /// Executes `cmd` with request data `buf`. Returns `Ok(Some(_))` if
     /// the uC responded with data, `Ok(None)` if the uC indicated success
     /// without data, Err(ModelError::MailboxCmdFailed) if the microcontroller
@@ -463,59 +596,65 @@ pub trait McuHwModel {
         self.finish_mailbox_execute()
     }
 
+    /// Like `mailbox_execute()`, but while waiting for the response calls `on_step`
+    /// with the current `cycle_count()` every `MAILBOX_PROGRESS_INTERVAL` steps.
+    fn mailbox_execute_with_progress(
+        &mut self,
+        cmd: u32,
+        buf: &[u8],
+        mut on_step: impl FnMut(u64),
+    ) -> Result<Option<Vec<u8>>>
+    where
+        Self: Sized,
+    {
+        self.start_mailbox_execute(cmd, buf)?;
+        finish_mailbox_execute(self, &mut on_step)
+    }
+
     /// Send a command to the mailbox but don't wait for the response
     fn start_mailbox_execute(&mut self, cmd: u32, buf: &[u8]) -> Result<()> {
-        // Read a 0 to get the lock
-        while !(self.mcu_manager().mbox0().mbox_lock().read().lock()) {
-            self.step();
+        if let Some(transcript) = self.mailbox_transcript().and_then(Option::as_mut) {
+            transcript.start(cmd, buf);
         }
-
-        // Mailbox lock value should read 1 now
-        // If not, the reads are likely being blocked by the AXI_USER check or some other issue
-        if !(self.mcu_manager().mbox0().mbox_lock().read().lock()) {
-            bail!("Mailbox lock is not set");
+        let result = send_mailbox_command(self, cmd, buf);
+        if let (Err(e), Some(transcript)) =
+            (&result, self.mailbox_transcript().and_then(Option::as_mut))
+        {
+            transcript.finish(Err(e));
         }
+        result
+    }
 
-        println!(
-            "<<< Executing mbox cmd 0x{cmd:08x} ({} bytes) from SoC",
-            buf.len()
-        );
-
-        self.mcu_manager().with_mbox0(|mbox| {
-            mbox.mbox_cmd().write(|_| cmd);
-            mbox.mbox_dlen().write(|_| buf.len() as u32);
-
-            // Write the data to the mailbox SRAM. NOTE: all access to SRAM must be in words.
-            let len_words = buf.len() / size_of::<u32>();
-            let word_bytes = &buf[..len_words * size_of::<u32>()];
-            for (i, word) in word_bytes.chunks_exact(4).enumerate() {
-                let word = u32::from_le_bytes(word.try_into().unwrap());
-                mbox.mbox_sram().at(i).write(|_| word);
-            }
-
-            let remaining = &buf[word_bytes.len()..];
-            if !remaining.is_empty() {
-                let mut word_bytes = [0u8; 4];
-                word_bytes[..remaining.len()].copy_from_slice(remaining);
-                let word = u32::from_le_bytes(word_bytes);
-                mbox.mbox_sram().at(len_words).write(|_| word);
-            }
+    /// Storage for the mailbox transcript, holding `None` when recording is disabled.
+    /// Models that return `None` here (the default) don't support transcripts.
+    fn mailbox_transcript(&mut self) -> Option<&mut Option<MailboxTranscript>> {
+        None
+    }
 
-            // Ask the microcontroller to execute this command
-            mbox.mbox_execute().write(|w| w.execute(true));
-        });
+    /// Start recording every mailbox command and response executed through this model.
+    /// Does nothing if the model doesn't support transcripts.
+    fn enable_mailbox_transcript(&mut self) {
+        if let Some(transcript) = self.mailbox_transcript() {
+            transcript.get_or_insert_with(Default::default);
+        }
+    }
 
-        // The hardware does not send the interrupt because it thinks MCU controls the mailbox. We
-        // need to manually trigger it.
-        self.mcu_manager().with_mci(|mci| {
-            mci.intr_block_rf()
-                .notif0_intr_trig_r()
-                .write(|w| w.notif_mbox0_cmd_avail_trig(true));
-        });
+    /// Return the transcript recorded so far (if enabled), and start a new one.
+    fn take_mailbox_transcript(&mut self) -> Option<MailboxTranscript> {
+        self.mailbox_transcript()
+            .and_then(Option::as_mut)
+            .map(std::mem::take)
+    }
 
-        Ok(())
+    /// The number of cycles `finish_mailbox_execute()` waits for a command to complete.
+    fn mailbox_timeout_cycles(&self) -> u64 {
+        DEFAULT_MAILBOX_TIMEOUT_CYCLES
     }
 
+    /// Set the number of cycles `finish_mailbox_execute()` waits for a command to complete.
+    /// Models without storage for the timeout ignore this and keep the default.
+    fn set_mailbox_timeout(&mut self, _cycles: u64) {}
+
     fn cmd_status(&mut self) -> MboxStatusE {
         self.mcu_manager()
             .with_mbox0(|mbox| mbox.mbox_cmd_status().read().status())
@@ -523,69 +662,148 @@ pub trait McuHwModel {
 
     /// Wait for the response to a previous call to `start_mailbox_execute()`.
     fn finish_mailbox_execute(&mut self) -> Result<Option<Vec<u8>>> {
-        // Wait for the microcontroller to finish executing
-        let mut timeout_cycles = 40000000; // 100ms @400MHz
-        while self.cmd_status().cmd_busy() {
-            self.step();
-            timeout_cycles -= 1;
-            if timeout_cycles == 0 {
-                bail!("Mailbox command timed out");
-            }
+        finish_mailbox_execute(self, &mut |_| {})
+    }
+
+    fn warm_reset(&mut self);
+}
+
+/// Send a command to the mailbox but don't wait for the response.
+fn send_mailbox_command<M: McuHwModel + ?Sized>(model: &mut M, cmd: u32, buf: &[u8]) -> Result<()> {
+    // Read a 0 to get the lock
+    while !(model.mcu_manager().mbox0().mbox_lock().read().lock()) {
+        model.step();
+    }
+
+    // Mailbox lock value should read 1 now
+    // If not, the reads are likely being blocked by the AXI_USER check or some other issue
+    if !(model.mcu_manager().mbox0().mbox_lock().read().lock()) {
+        bail!("Mailbox lock is not set");
+    }
+
+    println!(
+        "<<< Executing mbox cmd 0x{cmd:08x} ({} bytes) from SoC",
+        buf.len()
+    );
+
+    model.mcu_manager().with_mbox0(|mbox| {
+        mbox.mbox_cmd().write(|_| cmd);
+        mbox.mbox_dlen().write(|_| buf.len() as u32);
+
+        // Write the data to the mailbox SRAM. NOTE: all access to SRAM must be in words.
+        let len_words = buf.len() / size_of::<u32>();
+        let word_bytes = &buf[..len_words * size_of::<u32>()];
+        for (i, word) in word_bytes.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes(word.try_into().unwrap());
+            mbox.mbox_sram().at(i).write(|_| word);
         }
 
-        let status = self.cmd_status();
-
-        if status.cmd_failure() {
-            println!(">>> mbox cmd response: failed");
-            self.mcu_manager().with_mbox0(|mbox| {
-                mbox.mbox_execute().write(|w| w.execute(false));
-            });
-            return self.mcu_manager().with_mci(|mci| {
-                let fatal = mci.fw_error_fatal().read();
-                if fatal != 0 {
-                    bail!("Fatal firmware error {fatal:08x}")
-                }
-                let non_fatal = mci.fw_error_non_fatal().read();
-                if non_fatal != 0 {
-                    bail!("Non-fatal firmware error {non_fatal:08x}")
-                }
-                bail!("Unknown firmware error")
-            });
+        let remaining = &buf[word_bytes.len()..];
+        if !remaining.is_empty() {
+            let mut word_bytes = [0u8; 4];
+            word_bytes[..remaining.len()].copy_from_slice(remaining);
+            let word = u32::from_le_bytes(word_bytes);
+            mbox.mbox_sram().at(len_words).write(|_| word);
         }
 
-        self.mcu_manager().with_mbox0(|mbox| {
-            if status.cmd_complete() {
-                println!(">>> mbox cmd response: success");
-                mbox.mbox_execute().write(|w| w.execute(false));
-                return Ok(None);
-            }
-            if !status.data_ready() {
-                bail!("Unknown mailbox status {:x}", u32::from(status));
-            }
+        // Ask the microcontroller to execute this command
+        mbox.mbox_execute().write(|w| w.execute(true));
+    });
 
-            let dlen = mbox.mbox_dlen().read() as usize;
-            let mut output = Vec::with_capacity(dlen);
-            println!(">>> mbox cmd response data ({dlen} bytes)");
+    // The hardware does not send the interrupt because it thinks MCU controls the mailbox. We
+    // need to manually trigger it.
+    model.mcu_manager().with_mci(|mci| {
+        mci.intr_block_rf()
+            .notif0_intr_trig_r()
+            .write(|w| w.notif_mbox0_cmd_avail_trig(true));
+    });
 
-            // Read the output from the mailbox SRAM. NOTE: all access to SRAM must be in words.
-            let len_words = dlen / size_of::<u32>();
-            for i in 0..len_words {
-                let word = mbox.mbox_sram().at(i).read();
-                output.extend_from_slice(&word.to_le_bytes());
-            }
+    Ok(())
+}
 
-            let remaining = dlen % size_of::<u32>();
-            if remaining > 0 {
-                let word = mbox.mbox_sram().at(len_words).read();
-                output.extend_from_slice(&word.to_le_bytes()[..remaining]);
-            }
+/// Wait for the response to a previous call to `start_mailbox_execute()` and record it
+/// in the transcript.
+fn finish_mailbox_execute<M: McuHwModel + ?Sized>(
+    model: &mut M,
+    on_step: &mut dyn FnMut(u64),
+) -> Result<Option<Vec<u8>>> {
+    let result = wait_mailbox_response(model, on_step);
+    if let Some(transcript) = model.mailbox_transcript().and_then(Option::as_mut) {
+        transcript.finish(result.as_ref());
+    }
+    result
+}
+
+/// Wait for the response to a previous call to `start_mailbox_execute()`, calling
+/// `on_step` with the cycle count every `MAILBOX_PROGRESS_INTERVAL` steps.
+fn wait_mailbox_response<M: McuHwModel + ?Sized>(
+    model: &mut M,
+    on_step: &mut dyn FnMut(u64),
+) -> Result<Option<Vec<u8>>> {
+    // Wait for the microcontroller to finish executing
+    let timeout_cycles = model.mailbox_timeout_cycles();
+    let mut elapsed_cycles = 0;
+    while model.cmd_status().cmd_busy() {
+        model.step();
+        elapsed_cycles += 1;
+        if elapsed_cycles % MAILBOX_PROGRESS_INTERVAL == 0 {
+            on_step(model.cycle_count());
+        }
+        if elapsed_cycles >= timeout_cycles {
+            bail!("Mailbox command timed out after {elapsed_cycles} cycles");
+        }
+    }
+
+    let status = model.cmd_status();
 
+    if status.cmd_failure() {
+        println!(">>> mbox cmd response: failed");
+        model.mcu_manager().with_mbox0(|mbox| {
             mbox.mbox_execute().write(|w| w.execute(false));
-            Ok(Some(output))
-        })
+        });
+        return model.mcu_manager().with_mci(|mci| {
+            let fatal = mci.fw_error_fatal().read();
+            if fatal != 0 {
+                bail!("Fatal firmware error {fatal:08x}")
+            }
+            let non_fatal = mci.fw_error_non_fatal().read();
+            if non_fatal != 0 {
+                bail!("Non-fatal firmware error {non_fatal:08x}")
+            }
+            bail!("Unknown firmware error")
+        });
     }
 
-    fn warm_reset(&mut self);
+    model.mcu_manager().with_mbox0(|mbox| {
+        if status.cmd_complete() {
+            println!(">>> mbox cmd response: success");
+            mbox.mbox_execute().write(|w| w.execute(false));
+            return Ok(None);
+        }
+        if !status.data_ready() {
+            bail!("Unknown mailbox status {:x}", u32::from(status));
+        }
+
+        let dlen = mbox.mbox_dlen().read() as usize;
+        let mut output = Vec::with_capacity(dlen);
+        println!(">>> mbox cmd response data ({dlen} bytes)");
+
+        // Read the output from the mailbox SRAM. NOTE: all access to SRAM must be in words.
+        let len_words = dlen / size_of::<u32>();
+        for i in 0..len_words {
+            let word = mbox.mbox_sram().at(i).read();
+            output.extend_from_slice(&word.to_le_bytes());
+        }
+
+        let remaining = dlen % size_of::<u32>();
+        if remaining > 0 {
+            let word = mbox.mbox_sram().at(len_words).read();
+            output.extend_from_slice(&word.to_le_bytes()[..remaining]);
+        }
+
+        mbox.mbox_execute().write(|w| w.execute(false));
+        Ok(Some(output))
+    })
 }
 
 #[ignore]
@@ -670,6 +888,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trng_seed() {
+        let seeded = |seed| {
+            let mut params = InitParams::default();
+            params.set_trng_seed(seed);
+            params
+        };
+        let mut first = seeded(0x1234);
+        let mut second = seeded(0x1234);
+        assert_eq!(first.trng_seed, Some(0x1234));
+        assert_eq!(
+            first.itrng_nibbles.by_ref().take(64).collect::<Vec<_>>(),
+            second.itrng_nibbles.by_ref().take(64).collect::<Vec<_>>()
+        );
+        assert_ne!(
+            seeded(0x1235).itrng_nibbles.take(64).collect::<Vec<_>>(),
+            first.itrng_nibbles.take(64).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_mailbox_transcript() {
+        let mut transcript = MailboxTranscript::default();
+        transcript.start(0x1234, &[1, 2, 3]);
+        transcript.finish(Ok(&Some(vec![4, 5])));
+        transcript.start(0x5678, &[]);
+        transcript.finish(Err(&anyhow::anyhow!("Mailbox lock is not set")));
+        assert_eq!(
+            transcript.entries,
+            vec![
+                MailboxTranscriptEntry {
+                    cmd: 0x1234,
+                    request: vec![1, 2, 3],
+                    response: Ok(Some(vec![4, 5])),
+                },
+                MailboxTranscriptEntry {
+                    cmd: 0x5678,
+                    request: vec![],
+                    response: Err("Mailbox lock is not set".into()),
+                },
+            ]
+        );
+    }
+
     #[test]
     pub fn test_mailbox_execute() -> Result<()> {
         let mcu_rom = if let Ok(binaries) = mcu_builder::FirmwareBinaries::from_env() {
@@ -713,8 +975,12 @@ mod tests {
         );
 
         // Send command that returns 7 bytes of output
+        let mut last_cycle = 0;
         assert_eq!(
-            model.mailbox_execute(0x1000_1000, &[])?,
+            model.mailbox_execute_with_progress(0x1000_1000, &[], |cycle| {
+                assert!(cycle > last_cycle);
+                last_cycle = cycle;
+            })?,
             Some(vec![0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd])
         );
 
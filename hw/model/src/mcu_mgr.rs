@@ -1,6 +1,6 @@
 // Licensed under the Apache-2.0 license
 
-use ureg::MmioMut;
+use ureg::{Mmio, MmioMut};
 
 pub trait McuManager {
     const I3C_ADDR: u32;
@@ -100,6 +100,29 @@ pub trait McuManager {
         }
     }
 
+    /// Read-modify-write the 32-bit MCI register at byte `offset` from the MCI base, replacing
+    /// the bits selected by `mask` with the corresponding bits of `value`. No other access to the
+    /// model happens in between, so the update is atomic from the test's perspective. Returns the
+    /// previous register value.
+    fn modify_mci_reg(&mut self, offset: u32, mask: u32, value: u32) -> u32 {
+        self.modify_mci_reg_with(offset, |old: u32| (old & !mask) | (value & mask))
+    }
+
+    /// Typed variant of [`Self::modify_mci_reg`]: the current register value is converted to `R`
+    /// (e.g. a generated `ReadVal` type) and the closure returns the value to write back.
+    fn modify_mci_reg_with<R, W, F>(&mut self, offset: u32, f: F) -> R
+    where
+        R: From<u32> + Copy,
+        W: Into<u32>,
+        F: FnOnce(R) -> W,
+    {
+        let addr = (Self::MCI_ADDR + offset) as *mut u32;
+        let mmio = self.mmio_mut();
+        let old = R::from(unsafe { mmio.read_volatile(addr as *const u32) });
+        unsafe { mmio.write_volatile(addr, f(old).into()) };
+        old
+    }
+
     fn with_regs<T, F>(&mut self, f: F) -> T
     where
         F: FnOnce(&mut Self) -> T,
@@ -163,3 +186,67 @@ pub trait McuManager {
         f(self.lc_ctrl())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use caliptra_emu_bus::{Bus, BusError, BusMmio, Ram};
+    use caliptra_emu_types::{RvAddr, RvData, RvSize};
+
+    struct RamBus<'a>(&'a mut Ram);
+
+    impl Bus for RamBus<'_> {
+        fn read(&mut self, size: RvSize, addr: RvAddr) -> Result<RvData, BusError> {
+            self.0.read(size, addr)
+        }
+        fn write(&mut self, size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
+            self.0.write(size, addr, val)
+        }
+    }
+
+    struct RamMcuManager(Ram);
+
+    impl McuManager for RamMcuManager {
+        type TMmio<'a>
+            = BusMmio<RamBus<'a>>
+        where
+            Self: 'a;
+
+        fn mmio_mut(&mut self) -> Self::TMmio<'_> {
+            BusMmio::new(RamBus(&mut self.0))
+        }
+
+        const I3C_ADDR: u32 = 0;
+        const MCI_ADDR: u32 = 0x1000;
+        const TRACE_BUFFER_ADDR: u32 = 0;
+        const MBOX_0_ADDR: u32 = 0;
+        const MBOX_1_ADDR: u32 = 0;
+        const MCU_SRAM_ADDR: u32 = 0;
+        const OTP_CTRL_ADDR: u32 = 0;
+        const LC_CTRL_ADDR: u32 = 0;
+    }
+
+    #[test]
+    fn test_modify_mci_reg_preserves_other_bits() {
+        const OFFSET: u32 = 0x34;
+        let mut mgr = RamMcuManager(Ram::new(vec![0; 0x2000]));
+        let addr = RamMcuManager::MCI_ADDR + OFFSET;
+        mgr.0.write(RvSize::Word, addr, 0xa5a5_0f0f).unwrap();
+
+        // Set a bit that is currently clear.
+        assert_eq!(mgr.modify_mci_reg(OFFSET, 1 << 4, 1 << 4), 0xa5a5_0f0f);
+        assert_eq!(mgr.0.read(RvSize::Word, addr).unwrap(), 0xa5a5_0f1f);
+
+        // Clear it again.
+        assert_eq!(mgr.modify_mci_reg(OFFSET, 1 << 4, 0), 0xa5a5_0f1f);
+        assert_eq!(mgr.0.read(RvSize::Word, addr).unwrap(), 0xa5a5_0f0f);
+
+        // Bits of `value` outside `mask` are ignored.
+        mgr.modify_mci_reg(OFFSET, 1 << 31, 0x0000_ffff);
+        assert_eq!(mgr.0.read(RvSize::Word, addr).unwrap(), 0x25a5_0f0f);
+
+        // Closure variant toggles a single bit.
+        mgr.modify_mci_reg_with(OFFSET, |old: u32| old ^ 1);
+        assert_eq!(mgr.0.read(RvSize::Word, addr).unwrap(), 0x25a5_0f0e);
+    }
+}
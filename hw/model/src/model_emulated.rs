@@ -1,14 +1,18 @@
 // Licensed under the Apache-2.0 license
 
+use crate::bus_logger::BusEventSink;
 use crate::bus_logger::BusLogger;
 use crate::bus_logger::LogFile;
 use crate::otp_provision::lc_generate_memory;
 use crate::otp_provision::otp_generate_lifecycle_tokens_mem;
 use crate::trace_path_or_env;
+use crate::BusEvent;
 use crate::InitParams;
+use crate::MailboxTranscript;
 use crate::McuHwModel;
 use crate::McuManager;
 use crate::DEFAULT_LIFECYCLE_RAW_TOKENS;
+use crate::DEFAULT_MAILBOX_TIMEOUT_CYCLES;
 use anyhow::Result;
 use caliptra_api::SocManager;
 use caliptra_emu_bus::Bus;
@@ -43,9 +47,10 @@ use emulator_periph::{I3c, I3cController, Mci, McuRootBus, McuRootBusArgs, Otp,
 use emulator_registers_generated::axicdma::AxicdmaPeripheral;
 use emulator_registers_generated::root_bus::AutoRootBus;
 use mcu_config::McuMemoryMap;
+use mcu_config_emulator::EMULATOR_MCU_STRAPS;
 use mcu_rom_common::LifecycleControllerState;
 use mcu_rom_common::McuBootMilestones;
-use mcu_testing_common::i3c_socket_server::start_i3c_socket;
+use mcu_testing_common::i3c_socket_server::I3cSocket;
 use mcu_testing_common::{MCU_RUNNING, MCU_RUNTIME_STARTED};
 use registers_generated::fuses;
 use semver::Version;
@@ -88,6 +93,9 @@ pub struct ModelEmulated {
     i3c_controller: I3cController,
     i3c_address: Option<u8>,
     i3c_controller_join_handle: Option<JoinHandle<()>>,
+    mailbox_timeout_cycles: u64,
+    mailbox_transcript: Option<MailboxTranscript>,
+    otp_memory: Rc<RefCell<Vec<u8>>>,
 }
 
 fn hash_slice(slice: &[u8]) -> u64 {
@@ -208,8 +216,14 @@ impl McuHwModel for ModelEmulated {
         let mcu_root_bus = McuRootBus::new(bus_args).unwrap();
 
         let mut i3c_controller = if let Some(i3c_port) = params.i3c_port {
-            let (rx, tx) = start_i3c_socket(&MCU_RUNNING, i3c_port);
-            I3cController::new(rx, tx)
+            let mut socket = I3cSocket::new(i3c_port);
+            if let Some(addr) = params.i3c_dynamic_address {
+                socket = socket
+                    .with_fixed_dynamic_address(addr, &EMULATOR_MCU_STRAPS)
+                    .map_err(|e| anyhow::anyhow!("Invalid I3C dynamic address {addr:#x}: {e:?}"))?;
+            }
+            I3cController::with_socket(&MCU_RUNNING, &socket)
+                .expect("Failed to bind TCP socket for port")
         } else {
             I3cController::default()
         };
@@ -260,6 +274,7 @@ impl McuHwModel for ModelEmulated {
                 ..Default::default()
             },
         )?;
+        let otp_memory = otp.memory();
 
         let create_flash_controller =
             |default_path: &str,
@@ -326,6 +341,8 @@ impl McuHwModel for ModelEmulated {
             device_lifecycle,
             req_idevid_csr,
             use_mcu_recovery_interface,
+            trng_seed: params.trng_seed,
+            ready_for_fw: None,
         })
         .expect("Failed to start Caliptra CPU");
 
@@ -363,11 +380,11 @@ impl McuHwModel for ModelEmulated {
 
         let args = CpuArgs {
             org: CpuOrgArgs {
-                reset_vector: McuMemoryMap::default().rom_offset,
+                reset_vector: memory_map.rom_offset,
                 ..Default::default()
             },
         };
-        let mut cpu = Cpu::new(BusLogger::new(auto_root_bus), clock, pic, args);
+        let mut cpu = Cpu::new(BusLogger::new(auto_root_bus, &memory_map), clock, pic, args);
 
         if let Some(stack_info) = params.stack_info {
             cpu.with_stack_info(stack_info);
@@ -404,6 +421,9 @@ impl McuHwModel for ModelEmulated {
             i3c_controller,
             i3c_address: Some(i3c_dynamic_address.into()),
             i3c_controller_join_handle: None,
+            mailbox_timeout_cycles: DEFAULT_MAILBOX_TIMEOUT_CYCLES,
+            mailbox_transcript: None,
+            otp_memory,
         };
         // Turn tracing on if the trace path was set
         m.tracing_hint(true);
@@ -486,6 +506,18 @@ impl McuHwModel for ModelEmulated {
         self.iccm_image_tag = Some(hash_slice(iccm_image));
     }
 
+    fn mailbox_transcript(&mut self) -> Option<&mut Option<MailboxTranscript>> {
+        Some(&mut self.mailbox_transcript)
+    }
+
+    fn mailbox_timeout_cycles(&self) -> u64 {
+        self.mailbox_timeout_cycles
+    }
+
+    fn set_mailbox_timeout(&mut self, cycles: u64) {
+        self.mailbox_timeout_cycles = cycles;
+    }
+
     fn tracing_hint(&mut self, enable: bool) {
         if enable == self.caliptra_trace_fn.is_some() {
             // No change
@@ -526,8 +558,13 @@ impl McuHwModel for ModelEmulated {
         self.cpu.clock.now()
     }
 
-    fn save_otp_memory(&self, _path: &Path) -> Result<()> {
-        unimplemented!()
+    fn save_otp_memory(&self, path: &Path) -> Result<()> {
+        let s = crate::vmem::write_otp_vmem_data(&self.otp_memory.borrow())?;
+        Ok(std::fs::write(path, s.as_bytes())?)
+    }
+
+    fn read_otp_memory(&self) -> Result<Vec<u8>> {
+        Ok(self.otp_memory.borrow().clone())
     }
 
     fn mcu_manager(&mut self) -> impl McuManager {
@@ -562,6 +599,15 @@ impl ModelEmulated {
     fn caliptra_axi_bus(&mut self) -> EmulatedAxiBus<'_> {
         EmulatedAxiBus { model: self }
     }
+
+    /// Returns a channel that receives a [`BusEvent`] for every peripheral access made by
+    /// the MCU or through the model's SoC interface, in the order they happen.
+    /// Only one subscriber is supported; subscribing again replaces the previous channel.
+    pub fn subscribe_bus_events(&mut self) -> mpsc::Receiver<BusEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.cpu.bus.events = Some(BusEventSink::new(sender, self.cpu.clock.clone()));
+        receiver
+    }
 }
 
 pub struct EmulatedAxiBus<'a> {
@@ -572,7 +618,8 @@ impl Bus for EmulatedAxiBus<'_> {
     fn read(&mut self, size: RvSize, addr: RvAddr) -> Result<RvData, BusError> {
         let bus: &mut dyn Bus = match addr {
             0x3002_0000..=0x3003_ffff => &mut self.model.soc_to_caliptra_bus,
-            _ => &mut self.model.cpu.bus,
+            // bypass the logger, the access is logged below as coming from the SoC
+            _ => &mut self.model.cpu.bus.bus,
         };
         let result = bus.read(size, addr);
         self.model.cpu.bus.log_read("SoC", size, addr, result);
@@ -581,7 +628,7 @@ impl Bus for EmulatedAxiBus<'_> {
     fn write(&mut self, size: RvSize, addr: RvAddr, val: RvData) -> Result<(), BusError> {
         let bus: &mut dyn Bus = match addr {
             0x3002_0000..=0x3003_ffff => &mut self.model.soc_to_caliptra_bus,
-            _ => &mut self.model.cpu.bus,
+            _ => &mut self.model.cpu.bus.bus,
         };
         let result = bus.write(size, addr, val);
         self.model.cpu.bus.log_write("SoC", size, addr, val, result);
@@ -641,8 +688,7 @@ mod test {
     use super::*;
     use crate::{InitParams, McuHwModel, ModelEmulated};
 
-    #[test]
-    fn test_new_unbooted() {
+    fn new_unbooted_model() -> ModelEmulated {
         let mcu_rom = mcu_builder::rom_build(None, "").expect("Could not build MCU ROM");
         let mcu_runtime = &mcu_builder::runtime_build_with_apps_cached(
             &[],
@@ -689,7 +735,7 @@ mod test {
         let caliptra_rom = std::fs::read(caliptra_rom).unwrap();
         let caliptra_fw = std::fs::read(caliptra_fw).unwrap();
 
-        let mut model = ModelEmulated::new_unbooted(InitParams {
+        ModelEmulated::new_unbooted(InitParams {
             mcu_rom: &mcu_rom,
             mcu_firmware: &mcu_runtime,
             soc_manifest: &soc_manifest,
@@ -698,7 +744,12 @@ mod test {
             vendor_pk_hash: Some(vendor_pk_hash),
             ..Default::default()
         })
-        .unwrap();
+        .unwrap()
+    }
+
+    #[test]
+    fn test_new_unbooted() {
+        let mut model = new_unbooted_model();
         model.cpu_enabled.set(true);
         for _ in 0..100_000 {
             model.step();
@@ -713,4 +764,41 @@ mod test {
             .mci_boot_milestones()
             .contains(McuBootMilestones::CPTRA_FUSES_WRITTEN));
     }
+
+    #[test]
+    fn test_mailbox_bus_events() {
+        use crate::BusDirection;
+
+        let mut model = new_unbooted_model();
+        let events = model.subscribe_bus_events();
+
+        let cmd = 0x4d43_5054;
+        let data = [0x11u8; 10];
+        model.start_mailbox_execute(cmd, &data).unwrap();
+
+        // Only the MCU could have accessed the bus otherwise, and it is not running
+        let events: Vec<_> = events.try_iter().collect();
+        assert!(events.iter().all(|e| e.source == "SoC"));
+        assert!(events.windows(2).all(|w| w[0].cycle <= w[1].cycle));
+
+        // The lock is acquired first
+        assert_eq!("mbox0", events[0].peripheral);
+        assert_eq!(BusDirection::Read, events[0].direction);
+
+        // Then the command and its data are written to the mailbox...
+        let writes: Vec<_> = events
+            .iter()
+            .filter(|e| e.direction == BusDirection::Write)
+            .collect();
+        let (trigger, mbox_writes) = writes.split_last().unwrap();
+        assert!(mbox_writes.iter().all(|e| e.peripheral == "mbox0"));
+        assert_eq!(cmd, mbox_writes[0].value);
+        assert_eq!(data.len() as u32, mbox_writes[1].value);
+        // command, length, 3 data words and execute
+        assert_eq!(6, mbox_writes.len());
+
+        // ...before the MCI interrupt is triggered
+        assert_eq!("mci", trigger.peripheral);
+        assert_eq!(events.last().unwrap(), *trigger);
+    }
 }
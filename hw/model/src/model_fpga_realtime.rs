@@ -2,7 +2,10 @@
 
 #![allow(clippy::mut_from_ref)]
 
-use crate::{InitParams, McuHwModel, McuManager};
+use crate::jtag::{parse_scan_chain, JtagChainTap};
+use crate::{
+    InitParams, MailboxTranscript, McuHwModel, McuManager, DEFAULT_MAILBOX_TIMEOUT_CYCLES,
+};
 use anyhow::{bail, Result};
 use caliptra_api::SocManager;
 use caliptra_api_types::Fuses;
@@ -20,7 +23,7 @@ use mcu_testing_common::i3c::{
     I3cBusCommand, I3cBusResponse, I3cTcriCommand, I3cTcriResponseXfer, ResponseDescriptor,
 };
 use mcu_testing_common::{update_ticks, MCU_RUNNING, MCU_RUNTIME_STARTED};
-use std::io::Write;
+use std::io::{ErrorKind, Read, Write};
 use std::marker::PhantomData;
 use std::net::{SocketAddr, TcpStream};
 use std::path::Path;
@@ -57,6 +60,8 @@ pub struct ModelFpgaRealtime {
     i3c_handle: Option<JoinHandle<()>>,
     i3c_tx: Option<mpsc::Sender<I3cBusResponse>>,
     i3c_next_private_read_len: Option<u16>,
+    mailbox_timeout_cycles: u64,
+    mailbox_transcript: Option<MailboxTranscript>,
 }
 
 impl ModelFpgaRealtime {
@@ -128,6 +133,33 @@ impl ModelFpgaRealtime {
         Ok(())
     }
 
+    /// Re-examine the JTAG scan chain through the OpenOCD telnet socket and return the
+    /// IDCODE and IR length of every TAP.
+    pub fn scan_jtag_chain(&mut self) -> Result<Vec<JtagChainTap>> {
+        let Some(socket) = self.openocd.as_mut() else {
+            bail!("openocd socket is not open");
+        };
+
+        // arp_init resets the TAPs and shifts out their IDCODEs and IR capture values.
+        socket.write_all("jtag arp_init\nscan_chain\n".as_bytes())?;
+
+        // The telnet interface has no end-of-response marker, so read until it goes quiet.
+        socket.set_read_timeout(Some(Duration::from_millis(500)))?;
+        let mut output = vec![];
+        let mut buf = [0u8; 1024];
+        loop {
+            match socket.read(&mut buf) {
+                Ok(0) => break,
+                Ok(len) => output.extend_from_slice(&buf[..len]),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+                Err(e) => Err(e)?,
+            }
+        }
+        socket.set_read_timeout(None)?;
+
+        parse_scan_chain(&String::from_utf8_lossy(&output))
+    }
+
     fn caliptra_axi_bus(&mut self) -> FpgaRealtimeBus<'_> {
         FpgaRealtimeBus {
             caliptra_mmio: self.base.caliptra_mmio,
@@ -340,6 +372,8 @@ impl McuHwModel for ModelFpgaRealtime {
             i3c_handle,
             i3c_tx,
             i3c_next_private_read_len: None,
+            mailbox_timeout_cycles: DEFAULT_MAILBOX_TIMEOUT_CYCLES,
+            mailbox_transcript: None,
         };
 
         Ok(m)
@@ -401,6 +435,18 @@ impl McuHwModel for ModelFpgaRealtime {
         true
     }
 
+    fn mailbox_transcript(&mut self) -> Option<&mut Option<MailboxTranscript>> {
+        Some(&mut self.mailbox_transcript)
+    }
+
+    fn mailbox_timeout_cycles(&self) -> u64 {
+        self.mailbox_timeout_cycles
+    }
+
+    fn set_mailbox_timeout(&mut self, cycles: u64) {
+        self.mailbox_timeout_cycles = cycles;
+    }
+
     fn tracing_hint(&mut self, _enable: bool) {
         // Do nothing; we don't support tracing yet
     }
@@ -45,6 +45,9 @@ pub const EMULATOR_MEMORY_MAP: McuMemoryMap = McuMemoryMap {
     lc_offset: 0x7000_0400,
     lc_size: 0x8c,
     lc_properties: MemoryRegionType::MMIO,
+
+    mrac_override_valid: false,
+    mrac_override: 0,
 };
 
 pub const EMULATOR_MCU_STRAPS: McuStraps = McuStraps::default();
@@ -2,6 +2,7 @@
 
 #![cfg_attr(target_arch = "riscv32", no_std)]
 
+use mcu_rom_common::{svn_is_allowed, ImageVerifyError};
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 #[derive(Default, FromBytes, IntoBytes, KnownLayout, Immutable)]
@@ -10,3 +11,61 @@ pub struct McuImageHeader {
     pub reserved1: u16,
     pub reserved2: u32,
 }
+
+impl McuImageHeader {
+    /// Parses `header` and checks it against the SVN burned into `svn_fuses`.
+    pub fn verify<'a>(
+        header: &'a [u8],
+        svn_fuses: &[u8],
+    ) -> Result<&'a McuImageHeader, ImageVerifyError> {
+        let (header, _) =
+            McuImageHeader::ref_from_prefix(header).map_err(|_| ImageVerifyError::InvalidLength)?;
+        if !svn_is_allowed(header.svn, svn_fuses) {
+            return Err(ImageVerifyError::SvnTooLow);
+        }
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcu_rom_common::fuse_svn_mask;
+
+    #[test]
+    fn test_verify() {
+        let fuses = fuse_svn_mask(5).to_le_bytes();
+        let header = McuImageHeader {
+            svn: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            McuImageHeader::verify(header.as_bytes(), &fuses).map(|h| h.svn),
+            Ok(5)
+        );
+    }
+
+    #[test]
+    fn test_verify_invalid_length() {
+        let fuses = fuse_svn_mask(0).to_le_bytes();
+        let header = McuImageHeader::default();
+        let bytes = header.as_bytes();
+        assert_eq!(
+            McuImageHeader::verify(&bytes[..bytes.len() - 1], &fuses).err(),
+            Some(ImageVerifyError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_verify_svn_too_low() {
+        let fuses = fuse_svn_mask(5).to_le_bytes();
+        let header = McuImageHeader {
+            svn: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            McuImageHeader::verify(header.as_bytes(), &fuses).err(),
+            Some(ImageVerifyError::SvnTooLow)
+        );
+    }
+}
@@ -1,48 +1,36 @@
 // Licensed under the Apache-2.0 license
 
-use mcu_rom_common::ImageVerifier;
-
-use registers_generated::fuses::Fuses;
-
 #[cfg(any(feature = "test-mcu-svn-gt-fuse", feature = "test-mcu-svn-lt-fuse"))]
 use mcu_image_header::McuImageHeader;
 #[cfg(any(feature = "test-mcu-svn-gt-fuse", feature = "test-mcu-svn-lt-fuse"))]
-use zerocopy::FromBytes;
+use mcu_rom_common::fuse_svn;
+use mcu_rom_common::{ImageVerifier, ImageVerifyError};
+
+use registers_generated::fuses::Fuses;
+
 pub struct McuImageVerifier;
 
 impl ImageVerifier for McuImageVerifier {
-    fn verify_header(&self, _header: &[u8], _fuses: &Fuses) -> bool {
+    fn verify_header(&self, _header: &[u8], _fuses: &Fuses) -> Result<(), ImageVerifyError> {
         #[cfg(any(feature = "test-mcu-svn-gt-fuse", feature = "test-mcu-svn-lt-fuse"))]
         {
-            let Ok((header, _)) = McuImageHeader::ref_from_prefix(_header) else {
-                romtime::println!("[mcu-rom] Invalid MCU image header");
-                return false;
-            };
-
-            let mut fuse_vendor_svn: u16 = 0;
             // Use the first 128 bits of vendor test partition as SVN
-            for byte in _fuses.vendor_hashes_prod_partition[..16].iter() {
-                // Count contiguous 1's in the byte
-                let mut count = 0;
-                for bit in 0..8 {
-                    if byte & (1 << bit) != 0 {
-                        count += 1;
-                    } else {
-                        break;
-                    }
+            let svn_fuses = &_fuses.vendor_hashes_prod_partition[..16];
+            match McuImageHeader::verify(_header, svn_fuses) {
+                Ok(_) => {}
+                Err(ImageVerifyError::SvnTooLow) => {
+                    romtime::println!(
+                        "[mcu-rom] Image SVN is less than fuse vendor test SVN {}",
+                        fuse_svn(svn_fuses)
+                    );
+                    return Err(ImageVerifyError::SvnTooLow);
+                }
+                Err(err) => {
+                    romtime::println!("[mcu-rom] Invalid MCU image header");
+                    return Err(err);
                 }
-                fuse_vendor_svn += count;
-            }
-
-            if header.svn < fuse_vendor_svn {
-                romtime::println!(
-                    "[mcu-rom] Image SVN {} is less than fuse vendor test SVN {}",
-                    header.svn,
-                    fuse_vendor_svn
-                );
-                return false;
             }
         }
-        true
+        Ok(())
     }
 }
@@ -3,6 +3,7 @@
 //! This provides the dma syscall driver
 
 use kernel::grant::{AllowRoCount, AllowRwCount, Grant, UpcallCount};
+use kernel::processbuffer::ReadableProcessBuffer;
 use kernel::syscall::{CommandReturn, SyscallDriver};
 use kernel::utilities::cells::OptionalCell;
 use kernel::{ErrorCode, ProcessId};
@@ -15,16 +16,75 @@ pub const BUF_LEN: usize = 512;
 
 pub const BLOCK_SIZE: usize = 1; // Currently supported block size is 1 byte (byte transfer)
 
+/// Maximum number of descriptors in a scatter-gather list.
+pub const MAX_SG_DESCRIPTORS: usize = 16;
+
+/// Size of an encoded scatter-gather descriptor: 64-bit source address, 64-bit destination
+/// address and 32-bit byte count, all little-endian.
+pub const SG_DESCRIPTOR_SIZE: usize = 20;
+
 /// Subscription IDs for asynchronous notifications.
 mod dma_subscribe {
     pub const XFER_DONE: u32 = 0;
 }
 
+/// First argument of the `XFER_DONE` upcall. The second argument carries the `DMAStatus` on
+/// success or the `DMAError` on failure.
+mod xfer_result {
+    pub const SUCCESS: usize = 0;
+    pub const FAILURE: usize = 1;
+}
+
 mod dma_cmd {
     pub const SET_BYTE_XFER_COUNT: u32 = 0;
     pub const SET_SRC_ADDR: u32 = 1;
     pub const SET_DEST_ADDR: u32 = 2;
     pub const XFER_AXI_TO_AXI: u32 = 3;
+    pub const XFER_SG_AXI_TO_AXI: u32 = 5;
+}
+
+/// Read-only buffer IDs.
+mod ro_allow {
+    /// Encoded scatter-gather descriptor list.
+    pub const SG_LIST: usize = 0;
+    /// The number of allow buffers the kernel stores for this grant.
+    pub const COUNT: u8 = 1;
+}
+
+/// A single AXI to AXI transfer in a scatter-gather list.
+#[derive(Clone, Copy, Default)]
+pub struct SgDescriptor {
+    pub source_address: u64,
+    pub dest_address: u64,
+    pub length: usize,
+}
+
+impl SgDescriptor {
+    fn decode(bytes: &[u8]) -> Self {
+        let mut src = [0u8; 8];
+        let mut dest = [0u8; 8];
+        let mut len = [0u8; 4];
+        src.copy_from_slice(&bytes[0..8]);
+        dest.copy_from_slice(&bytes[8..16]);
+        len.copy_from_slice(&bytes[16..20]);
+        SgDescriptor {
+            source_address: u64::from_le_bytes(src),
+            dest_address: u64::from_le_bytes(dest),
+            length: u32::from_le_bytes(len) as usize,
+        }
+    }
+
+    /// A descriptor must move at least one byte and neither region may wrap the AXI address
+    /// space. Whether the regions fit in the memory behind them is checked by the DMA engine,
+    /// which fails the transfer with an error if not.
+    fn is_valid(&self) -> bool {
+        self.length != 0
+            && self
+                .source_address
+                .checked_add(self.length as u64)
+                .is_some()
+            && self.dest_address.checked_add(self.length as u64).is_some()
+    }
 }
 
 #[derive(Default)]
@@ -32,20 +92,23 @@ pub struct App {
     pub source_address: Option<u64>,
     pub dest_address: Option<u64>,
     pub length: usize,
+    sg_list: [SgDescriptor; MAX_SG_DESCRIPTORS],
+    sg_count: usize,
+    sg_next: usize,
 }
 
 pub struct Dma<'a> {
     // The underlying dma storage driver.
     driver: &'a dyn dma_driver::hil::DMA,
     // Per-app state.
-    apps: Grant<App, UpcallCount<1>, AllowRoCount<0>, AllowRwCount<0>>,
+    apps: Grant<App, UpcallCount<1>, AllowRoCount<{ ro_allow::COUNT }>, AllowRwCount<0>>,
     current_app: OptionalCell<ProcessId>,
 }
 
 impl<'a> Dma<'a> {
     pub fn new(
         driver: &'a dyn dma_driver::hil::DMA,
-        grant: Grant<App, UpcallCount<1>, AllowRoCount<0>, AllowRwCount<0>>,
+        grant: Grant<App, UpcallCount<1>, AllowRoCount<{ ro_allow::COUNT }>, AllowRwCount<0>>,
     ) -> Dma<'a> {
         Dma {
             driver,
@@ -123,15 +186,105 @@ impl<'a> Dma<'a> {
                 .unwrap_or_else(|err| Err(err.into()))
         })
     }
+
+    /// Starts a scatter-gather transfer of `count` descriptors from the allowed SG list. The
+    /// whole list is validated before the first descriptor is issued, and the app receives a
+    /// single upcall once every descriptor has completed or one of them fails.
+    fn start_sg_transfer(
+        &self,
+        count: usize,
+        processid: Option<ProcessId>,
+    ) -> Result<(), ErrorCode> {
+        if count == 0 || count > MAX_SG_DESCRIPTORS {
+            return Err(ErrorCode::INVAL);
+        }
+        let processid = processid.ok_or(ErrorCode::FAIL)?;
+        if self.current_app.is_some() {
+            return Err(ErrorCode::BUSY);
+        }
+        self.current_app.set(processid);
+
+        let result = self
+            .apps
+            .enter(processid, |app, kernel_data| {
+                kernel_data
+                    .get_readonly_processbuffer(ro_allow::SG_LIST)
+                    .and_then(|list| {
+                        list.enter(|list| {
+                            if list.len() < count * SG_DESCRIPTOR_SIZE {
+                                return Err(ErrorCode::SIZE);
+                            }
+                            let mut raw = [0u8; SG_DESCRIPTOR_SIZE];
+                            for (i, entry) in app.sg_list.iter_mut().take(count).enumerate() {
+                                let offset = i * SG_DESCRIPTOR_SIZE;
+                                list[offset..offset + SG_DESCRIPTOR_SIZE].copy_to_slice(&mut raw);
+                                *entry = SgDescriptor::decode(&raw);
+                                if !entry.is_valid() {
+                                    return Err(ErrorCode::INVAL);
+                                }
+                            }
+                            Ok(())
+                        })
+                    })
+                    .unwrap_or(Err(ErrorCode::RESERVE))?;
+
+                app.sg_count = count;
+                app.sg_next = 0;
+                self.start_next_descriptor(app)
+            })
+            .unwrap_or_else(|err| Err(err.into()));
+        if result.is_err() {
+            self.current_app.clear();
+        }
+        result.map(|_| ())
+    }
+
+    /// Issues the next pending scatter-gather descriptor, if any. Returns `false` when the list
+    /// is exhausted.
+    fn start_next_descriptor(&self, app: &mut App) -> Result<bool, ErrorCode> {
+        if app.sg_next >= app.sg_count {
+            app.sg_count = 0;
+            return Ok(false);
+        }
+        let descriptor = app.sg_list[app.sg_next];
+        app.sg_next += 1;
+        self.driver.configure_transfer(
+            descriptor.length,
+            BLOCK_SIZE,
+            Some(descriptor.source_address),
+            Some(descriptor.dest_address),
+        )?;
+        self.driver.start_transfer(
+            dma_driver::hil::DmaRoute::AxiToAxi,
+            dma_driver::hil::DmaRoute::AxiToAxi,
+            false,
+        )?;
+        Ok(true)
+    }
 }
 
 impl dma_driver::hil::DMAClient for Dma<'_> {
     fn transfer_complete(&self, status: dma_driver::hil::DMAStatus) {
         if let Some(processid) = self.current_app.take() {
-            let _ = self.apps.enter(processid, move |_, kernel_data| {
+            let _ = self.apps.enter(processid, move |app, kernel_data| {
+                // Keep going if a scatter-gather list still has descriptors left.
+                let (result, detail) = match self.start_next_descriptor(app) {
+                    Ok(true) => {
+                        self.current_app.set(processid);
+                        return;
+                    }
+                    Ok(false) => (xfer_result::SUCCESS, status as usize),
+                    Err(_) => {
+                        app.sg_count = 0;
+                        (
+                            xfer_result::FAILURE,
+                            dma_driver::hil::DMAError::CommandError as usize,
+                        )
+                    }
+                };
                 // Signal the app.
                 kernel_data
-                    .schedule_upcall(dma_subscribe::XFER_DONE as usize, (status as usize, 0, 0))
+                    .schedule_upcall(dma_subscribe::XFER_DONE as usize, (result, detail, 0))
                     .ok();
             });
         };
@@ -139,10 +292,15 @@ impl dma_driver::hil::DMAClient for Dma<'_> {
 
     fn transfer_error(&self, error: dma_driver::hil::DMAError) {
         if let Some(processid) = self.current_app.take() {
-            let _ = self.apps.enter(processid, move |_, kernel_data| {
+            let _ = self.apps.enter(processid, move |app, kernel_data| {
+                // Abandon any remaining scatter-gather descriptors.
+                app.sg_count = 0;
                 // Signal the app.
                 kernel_data
-                    .schedule_upcall(dma_subscribe::XFER_DONE as usize, (error as usize, 0, 0))
+                    .schedule_upcall(
+                        dma_subscribe::XFER_DONE as usize,
+                        (xfer_result::FAILURE, error as usize, 0),
+                    )
                     .ok();
             });
         };
@@ -184,6 +342,10 @@ impl SyscallDriver for Dma<'_> {
                     Err(e) => CommandReturn::failure(e),
                 }
             }
+            dma_cmd::XFER_SG_AXI_TO_AXI => match self.start_sg_transfer(r2, Some(processid)) {
+                Ok(()) => CommandReturn::success(),
+                Err(e) => CommandReturn::failure(e),
+            },
 
             _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
         }
@@ -394,13 +394,16 @@ impl<'a, F: Flash + 'static> Log<'a, F> {
 
         // Get flash page to write to and log page being overwritten. Subtract page_size since
         // padding pointer points to start of the page following the one we want to flush after the
-        // padding operation.
+        // padding operation. No log page is overwritten until the log has wrapped around once,
+        // which a sync in the middle of the first pass through the volume can hit.
         let page_number = self.page_number(pad_ptr - self.page_size);
-        let overwritten_page = (pad_ptr - self.volume.len() - self.page_size) / self.page_size;
+        let overwritten_page = (pad_ptr - self.page_size)
+            .checked_sub(self.volume.len())
+            .map(|entry_id| entry_id / self.page_size);
 
         // Advance read and oldest entry IDs, if within flash page being overwritten.
         let read_entry_id = self.read_entry_id.get();
-        if read_entry_id / self.page_size == overwritten_page {
+        if Some(read_entry_id / self.page_size) == overwritten_page {
             // Move read entry ID to start of next page.
             self.read_entry_id.set(
                 read_entry_id + self.page_size + PAGE_HEADER_SIZE - read_entry_id % self.page_size,
@@ -408,7 +411,7 @@ impl<'a, F: Flash + 'static> Log<'a, F> {
         }
 
         let oldest_entry_id = self.oldest_entry_id.get();
-        if oldest_entry_id / self.page_size == overwritten_page {
+        if Some(oldest_entry_id / self.page_size) == overwritten_page {
             self.oldest_entry_id.set(oldest_entry_id + self.page_size);
         }
 
@@ -2,7 +2,7 @@
 
 #![cfg_attr(target_arch = "riscv32", no_std)]
 
-use doe_transport::hil::{DoeTransport, DoeTransportRxClient, DoeTransportTxClient};
+use doe_transport::hil::{DoeError, DoeTransport, DoeTransportRxClient, DoeTransportTxClient};
 
 use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
 use core::cell::Cell;
@@ -47,6 +47,8 @@ pub struct EmulatedDoeTransport<'a, A: Alarm<'a>> {
 
     state: Cell<DoeMboxState>,
     timer_mode: Cell<TimerMode>,
+    send_done_ticks: Cell<u32>,
+    receive_retry_ticks: Cell<u32>,
     alarm: VirtualMuxAlarm<'a, A>,
 }
 
@@ -78,6 +80,8 @@ impl<'a, A: Alarm<'a>> EmulatedDoeTransport<'a, A> {
             pending_reset: Cell::new(false),
             state: Cell::new(DoeMboxState::Idle),
             timer_mode: Cell::new(TimerMode::NoTimer),
+            send_done_ticks: Cell::new(Self::DEFER_SEND_DONE_TICKS),
+            receive_retry_ticks: Cell::new(Self::RECEIVE_RETRY_TICKS),
             alarm: VirtualMuxAlarm::new(alarm),
         }
     }
@@ -89,18 +93,24 @@ impl<'a, A: Alarm<'a>> EmulatedDoeTransport<'a, A> {
         self.state.set(DoeMboxState::RxWait);
     }
 
+    /// Override the number of alarm ticks to defer `send_done` and to wait
+    /// before retrying a receive. Takes effect the next time the alarm is set.
+    pub fn set_timing(&self, send_done_ticks: u32, retry_ticks: u32) {
+        self.send_done_ticks.set(send_done_ticks);
+        self.receive_retry_ticks.set(retry_ticks);
+    }
+
     fn schedule_send_done(&self) {
         self.timer_mode.set(TimerMode::SendDoneDefer);
         let now = self.alarm.now();
-        self.alarm
-            .set_alarm(now, (Self::DEFER_SEND_DONE_TICKS).into());
+        self.alarm.set_alarm(now, self.send_done_ticks.get().into());
     }
 
     fn schedule_receive_retry(&self) {
         self.timer_mode.set(TimerMode::ReceiveRetry);
         let now = self.alarm.now();
         self.alarm
-            .set_alarm(now, (Self::RECEIVE_RETRY_TICKS).into());
+            .set_alarm(now, self.receive_retry_ticks.get().into());
     }
 
     fn reset_state(&self) {
@@ -156,6 +166,8 @@ impl<'a, A: Alarm<'a>> EmulatedDoeTransport<'a, A> {
             self.registers
                 .doe_mbox_status
                 .write(DoeMboxStatus::Error::SET);
+            self.rx_client
+                .map(|client| client.receive_error(DoeError::Oversized));
             return;
         }
 
@@ -179,6 +191,8 @@ impl<'a, A: Alarm<'a>> EmulatedDoeTransport<'a, A> {
                 self.registers
                     .doe_mbox_status
                     .write(DoeMboxStatus::Error::SET);
+                self.rx_client
+                    .map(|client| client.receive_error(DoeError::NoBuffer));
                 return;
             }
         };
@@ -7,19 +7,54 @@ use core::cell::Cell;
 use kernel::hil::time::{Alarm, AlarmClient, Time};
 use kernel::utilities::cells::{OptionalCell, TakeCell};
 use kernel::utilities::registers::interfaces::{ReadWriteable, Readable, Writeable};
+use kernel::utilities::registers::{Field, ReadOnly, ReadWrite};
 use kernel::{debug, ErrorCode};
 use mcu_mbox_comm::hil::{Mailbox, MailboxClient, MailboxStatus};
 use registers_generated::mci;
-use registers_generated::mci::bits::{MboxCmdStatus, Notif0IntrEnT, Notif0IntrT};
+use registers_generated::mci::bits::{
+    MboxCmdStatus, MboxExecute, MboxLock, Notif0IntrEnT, Notif0IntrT,
+};
 use romtime::StaticRef;
 
 pub const MCU_MBOX0_SRAM_OFFSET: u32 = 0x40_0000;
 pub const MCU_MBOX1_SRAM_OFFSET: u32 = 0x80_0000;
 
+/// Selects which of the MCI mailboxes a [`McuMailbox`] instance drives.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum McuMboxIndex {
+    Mbox0,
+    Mbox1,
+}
+
+impl McuMboxIndex {
+    /// Offset of the mailbox SRAM from the MCI base address.
+    pub fn sram_offset(self) -> u32 {
+        match self {
+            McuMboxIndex::Mbox0 => MCU_MBOX0_SRAM_OFFSET,
+            McuMboxIndex::Mbox1 => MCU_MBOX1_SRAM_OFFSET,
+        }
+    }
+
+    fn cmd_avail_sts(self) -> Field<u32, Notif0IntrT::Register> {
+        match self {
+            McuMboxIndex::Mbox0 => Notif0IntrT::NotifMbox0CmdAvailSts,
+            McuMboxIndex::Mbox1 => Notif0IntrT::NotifMbox1CmdAvailSts,
+        }
+    }
+
+    fn cmd_avail_en(self) -> Field<u32, Notif0IntrEnT::Register> {
+        match self {
+            McuMboxIndex::Mbox0 => Notif0IntrEnT::NotifMbox0CmdAvailEn,
+            McuMboxIndex::Mbox1 => Notif0IntrEnT::NotifMbox1CmdAvailEn,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum McuMboxState {
     Idle,
     RxWait,            // Driver waiting for data to be received from SoC.
+    RequestPending,    // Request handed to client. Waiting for send_response.
     TxInProgress,      // Transmit is in progress. Need to wait for send_done.
     RespFinishPending, // Waiting for client to call finish_response.
 }
@@ -32,6 +67,7 @@ enum TimerMode {
 
 pub struct McuMailbox<'a, A: Alarm<'a>> {
     pub registers: StaticRef<mci::regs::Mci>,
+    mbox: McuMboxIndex,
     data_buf: TakeCell<'static, [u32]>,
     data_buf_len: usize,
     state: Cell<McuMboxState>,
@@ -40,22 +76,30 @@ pub struct McuMailbox<'a, A: Alarm<'a>> {
     client: OptionalCell<&'a dyn MailboxClient>,
 }
 
-fn mcu_mbox0_sram_static_ref(base: u32, len: usize) -> &'static mut [u32] {
-    unsafe { core::slice::from_raw_parts_mut(base as *mut u32, len) }
+fn mcu_mbox_sram_static_ref(sram: &[ReadWrite<u32>]) -> &'static mut [u32] {
+    // SAFETY: The mailbox SRAM is part of the statically mapped MCI register
+    // block and is only accessed through this buffer by the driver.
+    unsafe { core::slice::from_raw_parts_mut(sram.as_ptr() as *mut u32, sram.len()) }
 }
 
 impl<'a, A: Alarm<'a>> McuMailbox<'a, A> {
     const DEFER_SEND_DONE_TICKS: u32 = 1000;
 
+    /// Creates a driver for mailbox `mbox` of the MCI at `registers`.
     pub fn new(
         registers: StaticRef<mci::regs::Mci>,
-        sram_base: u32,
+        mbox: McuMboxIndex,
         alarm: &'a MuxAlarm<'a, A>,
     ) -> Self {
-        let dw_len = registers.mcu_mbox0_csr_mbox_sram.len();
+        let sram = match mbox {
+            McuMboxIndex::Mbox0 => &registers.mcu_mbox0_csr_mbox_sram[..],
+            McuMboxIndex::Mbox1 => &registers.mcu_mbox1_csr_mbox_sram[..],
+        };
+        let dw_len = sram.len();
         McuMailbox {
             registers,
-            data_buf: TakeCell::new(mcu_mbox0_sram_static_ref(sram_base, dw_len)),
+            mbox,
+            data_buf: TakeCell::new(mcu_mbox_sram_static_ref(sram)),
             data_buf_len: dw_len,
             state: Cell::new(McuMboxState::Idle),
             timer_mode: Cell::new(TimerMode::NoTimer),
@@ -71,12 +115,58 @@ impl<'a, A: Alarm<'a>> McuMailbox<'a, A> {
         self.state.set(McuMboxState::RxWait);
     }
 
+    pub fn mbox_index(&self) -> McuMboxIndex {
+        self.mbox
+    }
+
+    fn sram(&self) -> &[ReadWrite<u32>] {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_sram,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_sram,
+        }
+    }
+
+    fn lock(&self) -> &ReadOnly<u32, MboxLock::Register> {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_lock,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_lock,
+        }
+    }
+
+    fn cmd(&self) -> &ReadWrite<u32> {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_cmd,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_cmd,
+        }
+    }
+
+    fn dlen(&self) -> &ReadWrite<u32> {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_dlen,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_dlen,
+        }
+    }
+
+    fn execute(&self) -> &ReadWrite<u32, MboxExecute::Register> {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_execute,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_execute,
+        }
+    }
+
+    fn cmd_status(&self) -> &ReadWrite<u32, MboxCmdStatus::Register> {
+        match self.mbox {
+            McuMboxIndex::Mbox0 => &self.registers.mcu_mbox0_csr_mbox_cmd_status,
+            McuMboxIndex::Mbox1 => &self.registers.mcu_mbox1_csr_mbox_cmd_status,
+        }
+    }
+
     fn reset_before_use(&self) {
-        let mbox_sram_size = (self.registers.mcu_mbox0_csr_mbox_sram.len() * 4) as u32;
+        let mbox_sram_size = (self.sram().len() * 4) as u32;
         // MCU acquires the lock to allow SRAM clearing.
-        self.registers.mcu_mbox0_csr_mbox_lock.get();
-        self.registers.mcu_mbox0_csr_mbox_dlen.set(mbox_sram_size);
-        self.registers.mcu_mbox0_csr_mbox_execute.set(0);
+        self.lock().get();
+        self.dlen().set(mbox_sram_size);
+        self.execute().set(0);
     }
 
     pub fn handle_interrupt(&self) {
@@ -87,10 +177,11 @@ impl<'a, A: Alarm<'a>> McuMailbox<'a, A> {
 
         self.disable_interrupts();
 
-        if intr_status.is_set(Notif0IntrT::NotifMbox0CmdAvailSts) {
+        let cmd_avail = self.mbox.cmd_avail_sts();
+        if intr_status.is_set(cmd_avail) {
             self.registers
                 .intr_block_rf_notif0_internal_intr_r
-                .modify(Notif0IntrT::NotifMbox0CmdAvailSts::SET);
+                .modify(cmd_avail.val(1));
 
             self.handle_incoming_request();
         }
@@ -108,19 +199,18 @@ impl<'a, A: Alarm<'a>> McuMailbox<'a, A> {
         if self.state.get() != McuMboxState::RxWait {
             return;
         }
-        let command = self.registers.mcu_mbox0_csr_mbox_cmd.get();
-        let dlen = self.registers.mcu_mbox0_csr_mbox_dlen.get() as usize;
+        let command = self.cmd().get();
+        let dlen = self.dlen().get() as usize;
         let dw_len = dlen.div_ceil(4);
         if dw_len > self.data_buf_len {
             debug!("MCU_MBOX_DRIVER: Incoming request length exceeds buffer size");
-            self.registers
-                .mcu_mbox0_csr_mbox_cmd_status
-                .write(MboxCmdStatus::Status::CmdFailure);
+            self.cmd_status().write(MboxCmdStatus::Status::CmdFailure);
             return;
         }
 
         if let Some(client) = self.client.get() {
             if let Some(buf) = self.data_buf.take() {
+                self.state.set(McuMboxState::RequestPending);
                 // It is expected that the client will call restore_rx_buffer().
                 client.request_received(command, buf, dlen);
             } else {
@@ -134,13 +224,13 @@ impl<'a, A: Alarm<'a>> McuMailbox<'a, A> {
     fn enable_interrupts(&self) {
         self.registers
             .intr_block_rf_notif0_intr_en_r
-            .modify(Notif0IntrEnT::NotifMbox0CmdAvailEn::SET);
+            .modify(self.mbox.cmd_avail_en().val(1));
     }
 
     fn disable_interrupts(&self) {
         self.registers
             .intr_block_rf_notif0_intr_en_r
-            .modify(Notif0IntrEnT::NotifMbox0CmdAvailEn::CLEAR);
+            .modify(self.mbox.cmd_avail_en().val(0));
     }
 }
 
@@ -176,11 +266,17 @@ impl<'a, A: Alarm<'a>> Mailbox<'a> for McuMailbox<'a, A> {
         response_data: impl Iterator<Item = u32>,
         dlen: usize,
     ) -> Result<(), ErrorCode> {
-        let dw_len = dlen.div_ceil(4);
-        if dw_len > self.data_buf_len {
-            return Err(ErrorCode::INVAL);
+        if self.state.get() != McuMboxState::RequestPending {
+            debug!("MCU_MBOX_DRIVER: Can't send response without a pending request");
+            return Err(ErrorCode::FAIL);
         }
 
+        // The response must fit in the mailbox SRAM the requester reads it from.
+        if dlen > self.max_mbox_sram_dw_size() * 4 {
+            return Err(ErrorCode::SIZE);
+        }
+        let dw_len = dlen.div_ceil(4);
+
         self.state.set(McuMboxState::TxInProgress);
 
         if let Some(buf) = self.data_buf.take() {
@@ -198,7 +294,7 @@ impl<'a, A: Alarm<'a>> Mailbox<'a> for McuMailbox<'a, A> {
             self.data_buf.replace(buf);
 
             // Set mbox data length register (in bytes).
-            self.registers.mcu_mbox0_csr_mbox_dlen.set(dlen as u32);
+            self.dlen().set(dlen as u32);
 
             self.schedule_send_done();
             Ok(())
@@ -209,26 +305,27 @@ impl<'a, A: Alarm<'a>> Mailbox<'a> for McuMailbox<'a, A> {
     }
 
     fn set_mbox_cmd_status(&self, status: MailboxStatus) -> Result<(), ErrorCode> {
-        if self.state.get() != McuMboxState::RespFinishPending {
+        // A request the client can't handle is failed without sending a response.
+        let rejecting_request = self.state.get() == McuMboxState::RequestPending
+            && matches!(status, MailboxStatus::Failure);
+        if self.state.get() != McuMboxState::RespFinishPending && !rejecting_request {
             debug!("MCU_MBOX_DRIVER: Can't set mbox cmd status in current state");
             return Err(ErrorCode::FAIL);
         }
 
-        self.registers
-            .mcu_mbox0_csr_mbox_cmd_status
-            .write(match status {
-                MailboxStatus::Complete => MboxCmdStatus::Status::CmdComplete,
-                MailboxStatus::Failure => MboxCmdStatus::Status::CmdFailure,
-                MailboxStatus::DataReady => MboxCmdStatus::Status::DataReady,
-                MailboxStatus::Busy => MboxCmdStatus::Status::CmdBusy,
-            });
+        self.cmd_status().write(match status {
+            MailboxStatus::Complete => MboxCmdStatus::Status::CmdComplete,
+            MailboxStatus::Failure => MboxCmdStatus::Status::CmdFailure,
+            MailboxStatus::DataReady => MboxCmdStatus::Status::DataReady,
+            MailboxStatus::Busy => MboxCmdStatus::Status::CmdBusy,
+        });
 
         self.state.set(McuMboxState::RxWait);
         Ok(())
     }
 
     fn max_mbox_sram_dw_size(&self) -> usize {
-        self.registers.mcu_mbox0_csr_mbox_sram.len()
+        self.sram().len()
     }
 
     // Restores the data buffer after it has been taken. This method is intended to be called by client.
@@ -248,3 +345,184 @@ impl<'a, A: Alarm<'a>> Mailbox<'a> for McuMailbox<'a, A> {
         self.client.set(client);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::hil::time::{Freq1KHz, Ticks32};
+
+    struct FakeAlarm;
+
+    impl Time for FakeAlarm {
+        type Frequency = Freq1KHz;
+        type Ticks = Ticks32;
+
+        fn now(&self) -> Ticks32 {
+            0.into()
+        }
+    }
+
+    impl<'a> Alarm<'a> for FakeAlarm {
+        fn set_alarm_client(&self, _client: &'a dyn AlarmClient) {}
+
+        fn set_alarm(&self, _reference: Ticks32, _dt: Ticks32) {}
+
+        fn get_alarm(&self) -> Ticks32 {
+            0.into()
+        }
+
+        fn disarm(&self) -> Result<(), ErrorCode> {
+            Ok(())
+        }
+
+        fn is_armed(&self) -> bool {
+            false
+        }
+
+        fn minimum_dt(&self) -> Ticks32 {
+            1.into()
+        }
+    }
+
+    struct FakeClient {
+        request: Cell<Option<(u32, usize)>>,
+        rx_buf: TakeCell<'static, [u32]>,
+    }
+
+    impl MailboxClient for FakeClient {
+        fn request_received(&self, command: u32, rx_buf: &'static mut [u32], dlen: usize) {
+            self.request.set(Some((command, dlen)));
+            self.rx_buf.replace(rx_buf);
+        }
+
+        fn response_received(
+            &self,
+            _status: MailboxStatus,
+            _rx_buf: &'static mut [u32],
+            _dlen: usize,
+        ) {
+        }
+
+        fn send_done(&self, _result: Result<(), ErrorCode>) {}
+    }
+
+    /// Zeroed memory standing in for the MCI register block.
+    fn fake_registers() -> StaticRef<mci::regs::Mci> {
+        let words = core::mem::size_of::<mci::regs::Mci>().div_ceil(4);
+        let mem: &'static mut [u32] = Box::leak(vec![0u32; words].into_boxed_slice());
+        unsafe { StaticRef::new(mem.as_ptr() as *const mci::regs::Mci) }
+    }
+
+    fn new_mailbox(
+        registers: StaticRef<mci::regs::Mci>,
+        mbox: McuMboxIndex,
+    ) -> (&'static McuMailbox<'static, FakeAlarm>, &'static FakeClient) {
+        let alarm: &'static FakeAlarm = Box::leak(Box::new(FakeAlarm));
+        let mux: &'static MuxAlarm<'static, FakeAlarm> = Box::leak(Box::new(MuxAlarm::new(alarm)));
+        let driver: &'static McuMailbox<'static, FakeAlarm> =
+            Box::leak(Box::new(McuMailbox::new(registers, mbox, mux)));
+        let client: &'static FakeClient = Box::leak(Box::new(FakeClient {
+            request: Cell::new(None),
+            rx_buf: TakeCell::empty(),
+        }));
+        driver.set_client(client);
+        driver.init();
+        driver.enable();
+        (driver, client)
+    }
+
+    #[test]
+    fn test_rejected_request_does_not_block_next_request() {
+        let regs = fake_registers();
+        let (mbox0, client) = new_mailbox(regs, McuMboxIndex::Mbox0);
+
+        regs.mcu_mbox0_csr_mbox_cmd.set(0x11);
+        regs.mcu_mbox0_csr_mbox_dlen.set(8);
+        regs.intr_block_rf_notif0_internal_intr_r
+            .write(Notif0IntrT::NotifMbox0CmdAvailSts::SET);
+        mbox0.handle_interrupt();
+        assert_eq!(client.request.take(), Some((0x11, 8)));
+
+        // The client drops the bad request and fails it instead of responding.
+        mbox0.restore_rx_buffer(client.rx_buf.take().unwrap());
+        assert_eq!(mbox0.set_mbox_cmd_status(MailboxStatus::Failure), Ok(()));
+        assert!(regs
+            .mcu_mbox0_csr_mbox_cmd_status
+            .matches_all(MboxCmdStatus::Status::CmdFailure));
+
+        // The next request is delivered.
+        regs.mcu_mbox0_csr_mbox_cmd.set(0x22);
+        regs.mcu_mbox0_csr_mbox_dlen.set(4);
+        regs.intr_block_rf_notif0_internal_intr_r
+            .write(Notif0IntrT::NotifMbox0CmdAvailSts::SET);
+        mbox0.handle_interrupt();
+        assert_eq!(client.request.take(), Some((0x22, 4)));
+        mbox0.restore_rx_buffer(client.rx_buf.take().unwrap());
+        // Only a failure status may end a pending request.
+        assert_eq!(
+            mbox0.set_mbox_cmd_status(MailboxStatus::Complete),
+            Err(ErrorCode::FAIL)
+        );
+        assert_eq!(mbox0.send_response(core::iter::once(0xaa), 4), Ok(()));
+    }
+
+    #[test]
+    fn test_mbox1_request_does_not_disturb_mbox0() {
+        let regs = fake_registers();
+        let (mbox0, client0) = new_mailbox(regs, McuMboxIndex::Mbox0);
+        let (mbox1, client1) = new_mailbox(regs, McuMboxIndex::Mbox1);
+        let mbox0_dlen = regs.mcu_mbox0_csr_mbox_dlen.get();
+
+        // SoC places a request in mbox1 and raises its command available interrupt.
+        regs.mcu_mbox1_csr_mbox_cmd.set(0x55);
+        for i in 0..4 {
+            regs.mcu_mbox1_csr_mbox_sram[i].set(0x1000 + i as u32);
+        }
+        regs.mcu_mbox1_csr_mbox_dlen.set(14);
+        regs.intr_block_rf_notif0_internal_intr_r
+            .write(Notif0IntrT::NotifMbox1CmdAvailSts::SET);
+
+        mbox0.handle_interrupt();
+        mbox1.handle_interrupt();
+
+        assert_eq!(client0.request.get(), None);
+        assert_eq!(client1.request.get(), Some((0x55, 14)));
+        let rx_buf = client1.rx_buf.take().unwrap();
+        assert_eq!(&rx_buf[..4], &[0x1000, 0x1001, 0x1002, 0x1003]);
+        assert_eq!(rx_buf.len(), mbox1.max_mbox_sram_dw_size());
+        mbox1.restore_rx_buffer(rx_buf);
+
+        // mbox0 registers and interrupt enable are untouched.
+        assert_eq!(regs.mcu_mbox0_csr_mbox_cmd.get(), 0);
+        assert_eq!(regs.mcu_mbox0_csr_mbox_dlen.get(), mbox0_dlen);
+        assert!(regs
+            .intr_block_rf_notif0_intr_en_r
+            .is_set(Notif0IntrEnT::NotifMbox0CmdAvailEn));
+        assert_eq!(mbox0.mbox_index(), McuMboxIndex::Mbox0);
+    }
+
+    #[test]
+    fn test_send_response_oversized() {
+        let regs = fake_registers();
+        let (mbox0, client0) = new_mailbox(regs, McuMboxIndex::Mbox0);
+
+        regs.mcu_mbox0_csr_mbox_cmd.set(0x77);
+        regs.mcu_mbox0_csr_mbox_dlen.set(4);
+        regs.intr_block_rf_notif0_internal_intr_r
+            .write(Notif0IntrT::NotifMbox0CmdAvailSts::SET);
+        mbox0.handle_interrupt();
+        assert_eq!(client0.request.get(), Some((0x77, 4)));
+        mbox0.restore_rx_buffer(client0.rx_buf.take().unwrap());
+
+        let max_dlen = mbox0.max_mbox_sram_dw_size() * 4;
+        assert_eq!(
+            mbox0.send_response(core::iter::repeat(0), max_dlen + 1),
+            Err(ErrorCode::SIZE)
+        );
+        // The rejected response must not touch the length register.
+        assert_eq!(regs.mcu_mbox0_csr_mbox_dlen.get(), 4);
+
+        assert_eq!(mbox0.send_response(core::iter::repeat(0), max_dlen), Ok(()));
+        assert_eq!(regs.mcu_mbox0_csr_mbox_dlen.get() as usize, max_dlen);
+    }
+}
@@ -104,7 +104,7 @@ pub unsafe fn run(
     Some(0)
 }
 
-static TEST_OPS: [TestOp; 25] = [
+static TEST_OPS: [TestOp; 30] = [
     // Read back any existing entries.
     TestOp::BadRead,
     TestOp::Read,
@@ -137,6 +137,13 @@ static TEST_OPS: [TestOp; 25] = [
     // Try bad write, nothing should change.
     TestOp::BadWrite,
     TestOp::Read,
+    // Sync in the middle of a page, then keep writing until the padded page is overwritten by
+    // wraparound. Read offset should skip the clobbered entries.
+    TestOp::Write,
+    TestOp::Sync,
+    TestOp::Write,
+    TestOp::Write,
+    TestOp::Read,
     // Sync log before finishing test so that all changes persist for next test iteration.
     TestOp::Sync,
     TestOp::Erase,
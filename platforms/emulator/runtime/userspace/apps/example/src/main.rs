@@ -28,7 +28,7 @@ mod test_get_device_state;
 #[cfg(feature = "test-caliptra-crypto")]
 mod test_caliptra_crypto;
 
-#[cfg(feature = "test-dma")]
+#[cfg(any(feature = "test-dma", feature = "test-dma-sg"))]
 mod test_dma;
 
 #[cfg(feature = "test-doe-user-loopback")]
@@ -237,6 +237,11 @@ pub(crate) async fn async_main<S: Syscalls>() {
         test_dma::test_dma_xfer_local_to_external().await;
         System::exit(0);
     }
+    #[cfg(feature = "test-dma-sg")]
+    {
+        test_dma::test_dma_sg().await;
+        System::exit(0);
+    }
 
     #[cfg(feature = "test-log-flash-usermode")]
     {
@@ -7,7 +7,7 @@ use libapi_caliptra::crypto::aes_gcm::AesGcm;
 use libapi_caliptra::crypto::asym::{
     ecdh::Ecdh, ecdsa::Ecdsa, ECC_P384_PARAM_X_SIZE, ECC_P384_PARAM_Y_SIZE, ECC_P384_SIGNATURE_SIZE,
 };
-use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, ShaStream, SHA384_HASH_SIZE};
 use libapi_caliptra::crypto::hmac::{HkdfSalt, Hmac};
 use libapi_caliptra::crypto::import::Import;
 use libapi_caliptra::crypto::rng::Rng;
@@ -39,6 +39,8 @@ pub async fn test_caliptra_sha() {
 
     test_sha(data1, HashAlgoType::SHA384, &expected_sha_384).await;
     test_sha(data1, HashAlgoType::SHA512, &expected_sha_512).await;
+    test_sha_stream(data1, HashAlgoType::SHA384, &expected_sha_384).await;
+    test_sha_stream(data1, HashAlgoType::SHA512, &expected_sha_512).await;
 
     println!("SHA test completed successfully");
 }
@@ -77,6 +79,48 @@ async fn test_sha(data: &[u8], algo: HashAlgoType, expected_hash: &[u8]) {
     println!("SHA test for {:?} passed", algo);
 }
 
+async fn test_sha_stream(data: &[u8], algo: HashAlgoType, expected_hash: &[u8]) {
+    println!("Testing SHA stream for {:?}", algo);
+
+    let hash_size = algo.hash_size();
+    let mut one_shot = [0u8; 64];
+    HashContext::hash_all(algo, data, &mut one_shot)
+        .await
+        .unwrap_or_else(|e| {
+            println!("Failed to hash data with error: {:?}", e);
+            test_exit(1);
+        });
+
+    let (first, second) = data.split_at(data.len() / 2);
+    let mut stream = ShaStream::init(algo).await.unwrap_or_else(|e| {
+        println!("Failed to initialize SHA stream with error: {:?}", e);
+        test_exit(1);
+    });
+    for chunk in [first, second] {
+        stream.update(chunk).await.unwrap_or_else(|e| {
+            println!("Failed to update SHA stream with error: {:?}", e);
+            test_exit(1);
+        });
+    }
+    let mut streamed = [0u8; 64];
+    stream.finalize(&mut streamed).await.unwrap_or_else(|e| {
+        println!("Failed to finalize SHA stream with error: {:?}", e);
+        test_exit(1);
+    });
+
+    if streamed[..hash_size] != one_shot[..hash_size] || streamed[..hash_size] != expected_hash[..]
+    {
+        println!(
+            "SHA stream mismatch: expected {:x?}, got {:x?}",
+            expected_hash,
+            &streamed[..hash_size]
+        );
+        test_exit(1);
+    }
+
+    println!("SHA stream test for {:?} passed", algo);
+}
+
 pub async fn test_caliptra_rng() {
     println!("Starting Caliptra mailbox RNG test");
     // test_add_random_stir().await;
@@ -1,7 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use core::fmt::Write;
-use libsyscall_caliptra::dma::{DMASource, DMATransaction, DMA as DMASyscall};
+use libsyscall_caliptra::dma::{DMASgDescriptor, DMASource, DMATransaction, DMA as DMASyscall};
 use libsyscall_caliptra::system::System;
 use libsyscall_caliptra::DefaultSyscalls;
 use libtock_console::Console;
@@ -9,6 +9,8 @@ use romtime::println;
 
 const MCU_SRAM_HI_OFFSET: u64 = 0x0000_0000;
 const TEST_EXTERNAL_SRAM_DEST_ADDRESS: u32 = 0xB00C_0000;
+/// An AXI address that no emulated peripheral decodes.
+const TEST_UNMAPPED_AXI_ADDRESS: u64 = 0x1_0000_0000;
 
 fn local_ram_to_axi_address(addr: u32) -> u64 {
     // Convert a local address to an AXI address
@@ -99,3 +101,89 @@ pub(crate) async fn test_dma_xfer_local_to_external() {
         System::exit(1);
     }
 }
+
+#[allow(unused)]
+pub(crate) async fn test_dma_sg() {
+    writeln!(Console::<DefaultSyscalls>::writer(), "Starting test_dma_sg");
+
+    let dma_syscall: DMASyscall = DMASyscall::new();
+
+    // Three non-contiguous source regions with distinct patterns, gathered into separate slices
+    // of a single destination buffer with gaps left untouched in between.
+    let source_0 = [0x11u8; 8];
+    let source_1 = [0x22u8; 16];
+    let source_2 = [0x33u8; 4];
+    let mut dest_buffer = [0u8; 40];
+    let dest_base = &dest_buffer as *const _ as u32;
+
+    let descriptors = [
+        DMASgDescriptor {
+            src_addr: local_ram_to_axi_address(&source_0 as *const _ as u32),
+            dest_addr: local_ram_to_axi_address(dest_base),
+            byte_count: source_0.len() as u32,
+        },
+        DMASgDescriptor {
+            src_addr: local_ram_to_axi_address(&source_1 as *const _ as u32),
+            dest_addr: local_ram_to_axi_address(dest_base + 12),
+            byte_count: source_1.len() as u32,
+        },
+        DMASgDescriptor {
+            src_addr: local_ram_to_axi_address(&source_2 as *const _ as u32),
+            dest_addr: local_ram_to_axi_address(dest_base + 32),
+            byte_count: source_2.len() as u32,
+        },
+    ];
+
+    dma_syscall.xfer_sg(&descriptors).await.unwrap();
+
+    let dest = unsafe { core::ptr::read_volatile(&dest_buffer) };
+    let passed = dest[0..8] == source_0
+        && dest[8..12] == [0u8; 4]
+        && dest[12..28] == source_1
+        && dest[28..32] == [0u8; 4]
+        && dest[32..36] == source_2
+        && dest[36..40] == [0u8; 4];
+
+    // An empty list must be rejected without touching the hardware.
+    let empty_rejected = dma_syscall.xfer_sg(&[]).await.is_err();
+
+    // A descriptor that fails on the bus fails the whole list and stops the chain.
+    let mut chain_dest = [0u8; 12];
+    let chain_base = &chain_dest as *const _ as u32;
+    let failing = [
+        DMASgDescriptor {
+            src_addr: local_ram_to_axi_address(&source_0 as *const _ as u32),
+            dest_addr: local_ram_to_axi_address(chain_base),
+            byte_count: 4,
+        },
+        DMASgDescriptor {
+            src_addr: TEST_UNMAPPED_AXI_ADDRESS,
+            dest_addr: local_ram_to_axi_address(chain_base + 4),
+            byte_count: 4,
+        },
+        DMASgDescriptor {
+            src_addr: local_ram_to_axi_address(&source_2 as *const _ as u32),
+            dest_addr: local_ram_to_axi_address(chain_base + 8),
+            byte_count: 4,
+        },
+    ];
+    let failure_reported = dma_syscall.xfer_sg(&failing).await.is_err();
+    let chain = unsafe { core::ptr::read_volatile(&chain_dest) };
+    let chain_stopped = chain[0..4] == source_0[..4] && chain[4..12] == [0u8; 8];
+
+    // The controller is usable again after the failure.
+    let recovered = dma_syscall.xfer_sg(&failing[..1]).await.is_ok();
+
+    if passed && empty_rejected && failure_reported && chain_stopped && recovered {
+        writeln!(
+            Console::<DefaultSyscalls>::writer(),
+            "Test test_dma_sg passed"
+        );
+    } else {
+        writeln!(
+            Console::<DefaultSyscalls>::writer(),
+            "Test test_dma_sg failed"
+        );
+        System::exit(1);
+    }
+}
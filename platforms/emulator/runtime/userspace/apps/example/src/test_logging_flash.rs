@@ -23,6 +23,7 @@ pub async fn test_logging_flash_simple() {
         log.append_entry(&entry).await.is_ok(),
         "Failed to append entry"
     );
+    assert!(log.sync().await.is_ok(), "Sync failed");
 
     let mut buffer = [0u8; 256];
     let read_result = log.read_entry(&mut buffer).await;
@@ -72,6 +73,7 @@ pub async fn test_logging_flash_various_entries() {
             i
         );
     }
+    assert!(log.sync().await.is_ok(), "Sync failed");
 
     let mut buffer = [0u8; 128];
     let expected_refs: [&[u8]; 4] = [
@@ -4,9 +4,10 @@ extern crate alloc;
 
 use alloc::boxed::Box;
 use async_trait::async_trait;
+use core::str::FromStr;
 use external_cmds_common::{
     CommandError, DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid,
-    UnifiedCommandHandler, MAX_FW_VERSION_LEN, MAX_UID_LEN,
+    UnifiedCommandHandler,
 };
 use mcu_mbox_common::config;
 
@@ -32,13 +33,7 @@ impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
             _ => return Err(CommandError::InvalidParams),
         };
 
-        let bytes = s.as_bytes();
-        if bytes.len() > MAX_FW_VERSION_LEN {
-            return Err(CommandError::RespLengthTooLarge);
-        }
-        let len = bytes.len().min(version.ver_str.len());
-        version.ver_str[..len].copy_from_slice(&bytes[..len]);
-        version.len = len;
+        *version = FirmwareVersion::from_str(s)?;
         Ok(())
     }
 
@@ -54,17 +49,7 @@ impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
     async fn get_device_info(&self, index: u32, info: &mut DeviceInfo) -> Result<(), CommandError> {
         match index {
             0 => {
-                let test_uid = &config::TEST_UID;
-                if test_uid.len() > MAX_UID_LEN {
-                    return Err(CommandError::RespLengthTooLarge);
-                }
-                let mut unique_chip_id = [0u8; MAX_UID_LEN];
-                unique_chip_id[..test_uid.len()].copy_from_slice(&test_uid[..]);
-                let uid = Uid {
-                    len: test_uid.len(),
-                    unique_chip_id,
-                };
-                *info = DeviceInfo::Uid(uid);
+                *info = DeviceInfo::Uid(Uid::from_slice(&config::TEST_UID)?);
                 Ok(())
             }
             _ => Err(CommandError::InvalidParams),
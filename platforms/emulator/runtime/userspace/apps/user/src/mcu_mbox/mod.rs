@@ -30,11 +30,16 @@ async fn start_mcu_mbox_service() -> Result<(), ErrorCode> {
     #[cfg(feature = "test-mcu-mbox-cmds")]
     {
         let handler = cmd_handler_mock::NonCryptoCmdHandlerMock::default();
+        let non_crypto_handler = mcu_mbox_lib::cmd_interface::NonCryptoCmdHandler::new(&handler);
+        let mut dispatcher = mcu_mbox_lib::dispatcher::MailboxDispatcher::new();
+        non_crypto_handler
+            .register(&mut dispatcher)
+            .map_err(|_| ErrorCode::Fail)?;
         let mut transport = mcu_mbox_lib::transport::McuMboxTransport::new(
             libsyscall_caliptra::mcu_mbox::MCU_MBOX0_DRIVER_NUM,
         );
         let mut mcu_mbox_service = mcu_mbox_lib::daemon::McuMboxService::init(
-            &handler,
+            dispatcher,
             &mut transport,
             crate::EXECUTOR.get().spawner(),
         );
@@ -44,13 +44,14 @@ pub const FPGA_MEMORY_MAP: McuMemoryMap = McuMemoryMap {
     lc_offset: 0xa404_0000,
     lc_size: 0x8c,
     lc_properties: MemoryRegionType::MMIO,
-};
 
-pub const FPGA_MCU_STRAPS: McuStraps = McuStraps {
-    i3c_static_addr: 0x3a,
-    axi_user: 0x1,
-    cptra_wdt_cfg0: 200_000_000,
-    cptra_wdt_cfg1: 200_000_000,
-    mcu_wdt_cfg0: 800_000_000, // the FPGA is slower to boot
-    mcu_wdt_cfg1: 1,
+    mrac_override_valid: false,
+    mrac_override: 0,
 };
+
+pub const FPGA_MCU_STRAPS: McuStraps = McuStraps::builder()
+    .with_axi_user(0x1)
+    .with_cptra_wdt_cfg0(200_000_000)
+    .with_cptra_wdt_cfg1(200_000_000)
+    .with_mcu_wdt_cfg0(800_000_000) // the FPGA is slower to boot
+    .build();
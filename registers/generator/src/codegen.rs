@@ -207,6 +207,7 @@ pub fn generate_code(
     is_root_module: bool,
     register_types_to_crates: &mut HashMap<String, String>,
     addr_only: bool,
+    debug_impls: bool,
 ) -> String {
     let address_tokens = generate_address_tokens(block.block());
     if addr_only {
@@ -228,6 +229,22 @@ pub fn generate_code(
     );
     bit_tokens = indent(&bit_tokens, 4);
 
+    let debug_tokens = if debug_impls {
+        let mut debug_types: Vec<_> = block
+            .register_types()
+            .values()
+            .chain(block.block().declared_register_types.iter())
+            .filter(|rt| rt.name.as_ref().is_some_and(|n| defined_bits.contains(n)))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        debug_types.sort_by_key(|rt| rt.name.clone().unwrap());
+        indent(&generate_debug_impls(&debug_types), 4)
+    } else {
+        String::new()
+    };
+
     let reg_tokens = if block.block().name.trim().is_empty() || no_registers(block.block()) {
         assert!(block.block().registers.is_empty());
         String::new()
@@ -256,6 +273,15 @@ pub fn generate_code(
         );
     }
 
+    if !debug_tokens.trim().is_empty() {
+        tokens += &format!(
+            "pub mod debug {{
+    //! Wrappers around raw register values that print each field when formatted with `Debug`.
+{debug_tokens}
+}}\n"
+        );
+    }
+
     if !reg_tokens.trim().is_empty() {
         tokens += &format!(
             "pub mod regs {{
@@ -503,3 +529,84 @@ fn generate_bitfields(
 
     tokens
 }
+
+/// Generates, for each register type, a wrapper around the raw register value whose
+/// `Debug` implementation prints every field. Enum-encoded fields are decoded to their
+/// variant; other fields are printed in hex.
+fn generate_debug_impls(register_types: &[Rc<RegisterType>]) -> String {
+    let mut tokens = String::new();
+    for rt in register_types {
+        let name = camel_case(rt.name.as_ref().unwrap());
+        let width = rt.width.rust_primitive_name();
+        let mut field_tokens = String::new();
+        for field in rt.fields.iter() {
+            let field_name = snake_case(&field.name);
+            let field_ident = camel_case(&field.name);
+            let value = if field.enum_type.is_some() {
+                format!("&reg.read_as_enum::<super::bits::{name}::{field_ident}::Value>(super::bits::{name}::{field_ident})")
+            } else {
+                format!("&format_args!(\"{{:#x}}\", reg.read(super::bits::{name}::{field_ident}))")
+            };
+            field_tokens += &format!("            .field(\"{field_name}\", {value})\n");
+        }
+        tokens += &format!(
+            "#[derive(Clone, Copy)]
+pub struct {name}(pub {width});
+impl core::fmt::Debug for {name} {{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
+        let reg = tock_registers::LocalRegisterCopy::<{width}, super::bits::{name}::Register>::new(self.0);
+        f.debug_struct(\"{name}\")
+{field_tokens}            .finish()
+    }}
+}}
+"
+        );
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod debug_impl_tests {
+    use super::*;
+    use crate::schema::{Enum, EnumVariant, RegisterField};
+
+    #[test]
+    fn test_generate_debug_impls() {
+        let rt = Rc::new(RegisterType {
+            name: Some("CTRL".into()),
+            width: RegisterWidth::_32,
+            fields: vec![
+                RegisterField {
+                    name: "EN".into(),
+                    position: 0,
+                    width: 1,
+                    ..Default::default()
+                },
+                RegisterField {
+                    name: "MODE".into(),
+                    position: 4,
+                    width: 2,
+                    enum_type: Some(Rc::new(Enum {
+                        name: Some("mode_t".into()),
+                        variants: vec![
+                            EnumVariant {
+                                name: "IDLE".into(),
+                                value: 0,
+                            },
+                            EnumVariant {
+                                name: "BUSY".into(),
+                                value: 1,
+                            },
+                        ],
+                        bit_width: 2,
+                    })),
+                    ..Default::default()
+                },
+            ],
+        });
+        assert_eq!(
+            generate_debug_impls(&[rt]),
+            include_str!("../testdata/debug_impls.golden")
+        );
+    }
+}
@@ -3,8 +3,11 @@
 //! Abstract Syntax Tree (AST) for SystemRDL parser.
 
 use crate::parser::{root, tokens};
-use crate::{token_iter::TokenIter, Bits, FileSource, Token, TokenKind, Tokens};
-use std::path::Path;
+use crate::token::new_tokens;
+use crate::{token_iter::TokenIter, Bits, FileSource, Token, TokenKind};
+use std::cell::Cell;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use winnow::Parser;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -172,8 +175,33 @@ pub enum Description {
 }
 
 impl Root {
+    /// All enums defined in the file, at the top level or inside any component.
+    pub fn enum_defs(&self) -> Vec<&EnumDef> {
+        fn visit<'a>(body: &'a ComponentBody, defs: &mut Vec<&'a EnumDef>) {
+            for elem in body.elements.iter() {
+                match elem {
+                    ComponentBodyElem::EnumDef(def) => defs.push(def),
+                    ComponentBodyElem::ComponentDef(c) => visit(c.def.body(), defs),
+                    _ => {}
+                }
+            }
+        }
+        let mut defs = vec![];
+        for desc in self.descriptions.iter() {
+            match desc {
+                Description::EnumDef(def) => defs.push(def),
+                Description::ComponentDef(c) => visit(c.def.body(), &mut defs),
+                _ => {}
+            }
+        }
+        defs
+    }
+
     pub fn from_file(file_source: &dyn FileSource, name: &Path) -> Result<Self, anyhow::Error> {
         let mut tokens = vec![];
+        // (path, contents) of each file the tokens came from, and the (file, offset) of each token
+        let mut files: Vec<(PathBuf, &str)> = vec![];
+        let mut positions = vec![];
         let mut iter = TokenIter::from_path(file_source, name)?;
         loop {
             let t = iter.next();
@@ -181,31 +209,101 @@ impl Root {
                 break;
             }
             let span = iter.last_span();
+            if files
+                .last()
+                .is_none_or(|(path, _)| path != iter.current_file_path())
+            {
+                files.push((
+                    iter.current_file_path().to_path_buf(),
+                    iter.current_file_contents(),
+                ));
+            }
+            positions.push((files.len() - 1, span.start));
             // TODO: this span could refer to the previous file if the fifo was not empty; we should return the correct string in that case
             tokens.push(Token {
                 kind: t,
                 raw: &(iter.current_file_contents()[span.start..span.end]),
             });
         }
-        let tokens = Tokens::new(&tokens);
-        root.parse(tokens).map_err(|e| {
-            let t = &e.input()[0];
-            anyhow::format_err!("Error parsing input at: `{}` token: {:?}", t.raw, t.kind)
+        parse_tokens(&tokens, |index| {
+            let (file, offset) = match positions.get(index) {
+                Some(&position) => position,
+                // end of input
+                None => files
+                    .len()
+                    .checked_sub(1)
+                    .map_or((0, 0), |file| (file, files[file].1.len())),
+            };
+            match files.get(file) {
+                Some((path, contents)) => Location::new(Some(path.clone()), contents, offset),
+                None => Location::new(Some(name.to_path_buf()), "", 0),
+            }
         })
     }
 }
 
+/// A 1-based line and column in RDL source.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn new(file: Option<PathBuf>, contents: &str, offset: usize) -> Self {
+        let before = &contents[..offset];
+        Self {
+            file,
+            line: before.matches('\n').count() + 1,
+            column: before.chars().rev().take_while(|&c| c != '\n').count() + 1,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
+}
+
+/// Parses `tokens`, reporting an error at the furthest token the parser reached.
+/// `locate` maps a token index (or `tokens.len()` for the end of input) to its location.
+fn parse_tokens(
+    tokens: &[Token<'_>],
+    locate: impl Fn(usize) -> Location,
+) -> Result<Root, anyhow::Error> {
+    let furthest = Cell::new(usize::MAX);
+    root.parse(new_tokens(tokens, &furthest)).map_err(|e| {
+        let index = tokens.len().saturating_sub(furthest.get()).max(e.offset());
+        match tokens.get(index) {
+            Some(t) => anyhow::format_err!(
+                "{}: unexpected token `{}` ({:?})",
+                locate(index),
+                t.raw,
+                t.kind
+            ),
+            None => anyhow::format_err!("{}: unexpected end of input", locate(index)),
+        }
+    })
+}
+
 impl std::str::FromStr for Root {
     type Err = anyhow::Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim_end();
         let tokens = tokens
-            .parse(input.trim_end())
+            .parse(input)
             .map_err(|e| anyhow::format_err!("{e}"))?;
-        let tokens = Tokens::new(&tokens);
-        root.parse(tokens).map_err(|e| {
-            let t = &e.input()[0];
-            anyhow::format_err!("Error parsing input at: `{}` token: {:?}", t.raw, t.kind)
+        parse_tokens(&tokens, |index| {
+            let offset = tokens.get(index).map_or(input.len(), |t| {
+                t.raw.as_ptr() as usize - input.as_ptr() as usize
+            });
+            Location::new(None, input, offset)
         })
     }
 }
@@ -252,6 +350,61 @@ pub struct EnumDef {
     pub body: Vec<EnumEntry>,
 }
 
+/// A field encoding with its value resolved.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnumVariant {
+    pub name: String,
+    pub value: u64,
+    pub desc: Option<String>,
+}
+
+impl EnumDef {
+    /// Resolves the value of each entry. An entry without a value is one more than
+    /// the previous entry (the first entry defaults to 0).
+    pub fn variants(&self) -> Result<Vec<EnumVariant>, anyhow::Error> {
+        let mut next = 0;
+        self.body
+            .iter()
+            .map(|entry| {
+                let value = match &entry.expr {
+                    Some(expr) => expr.literal_value().ok_or_else(|| {
+                        anyhow::format_err!(
+                            "Unsupported value for enum entry {}::{}",
+                            self.id,
+                            entry.id
+                        )
+                    })?,
+                    None => next,
+                };
+                next = value + 1;
+                Ok(EnumVariant {
+                    name: entry.id.clone(),
+                    value,
+                    desc: entry.desc().map(str::to_string),
+                })
+            })
+            .collect()
+    }
+}
+
+impl EnumEntry {
+    /// The `desc` property of the entry, without the surrounding quotes.
+    pub fn desc(&self) -> Option<&str> {
+        self.property_assignments.iter().find_map(|p| match p {
+            ExplicitPropertyAssignment::Assignment(
+                IdentityOrPropKeyword::Id(id),
+                Some(PropAssignmentRhs::ConstantExpr(ConstantExpr::ConstantPrimary(
+                    ConstantPrimary::Base(ConstantPrimaryBase::PrimaryLiteral(
+                        PrimaryLiteral::StringLiteral(s),
+                    )),
+                    None,
+                ))),
+            ) if id == "desc" => Some(s.trim_matches('"')),
+            _ => None,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct StructDef {
     pub id: String,
@@ -384,6 +537,31 @@ pub struct ComponentBody {
     pub elements: Vec<ComponentBodyElem>,
 }
 
+impl ComponentBody {
+    /// The enums defined directly in this body.
+    pub fn enum_defs(&self) -> impl Iterator<Item = &EnumDef> {
+        self.elements.iter().filter_map(|e| match e {
+            ComponentBodyElem::EnumDef(def) => Some(def),
+            _ => None,
+        })
+    }
+
+    /// The name of the enum assigned with `encode = ...`, if any.
+    pub fn encode(&self) -> Option<&str> {
+        self.elements.iter().find_map(|e| match e {
+            ComponentBodyElem::PropertyAssignment(
+                PropertyAssignment::ExplicitOrDefaultPropAssignment(
+                    ExplicitOrDefaultPropAssignment::ExplicitPropAssignment(
+                        None,
+                        ExplicitPropertyAssignment::EncodeAssignment(id),
+                    ),
+                ),
+            ) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParamDef {
     Params(Vec<ParamDefElem>),
@@ -395,6 +573,15 @@ pub enum ComponentDef {
     Anon(ComponentType, ComponentBody),
 }
 
+impl ComponentDef {
+    pub fn body(&self) -> &ComponentBody {
+        match self {
+            ComponentDef::Named(_, _, _, body) => body,
+            ComponentDef::Anon(_, body) => body,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ComponentInstType {
     Internal,
@@ -547,6 +734,23 @@ pub enum ConstantExpr {
     ),
 }
 
+impl ConstantExpr {
+    /// The value of a bare number or bit-vector literal.
+    pub fn literal_value(&self) -> Option<u64> {
+        match self {
+            ConstantExpr::ConstantPrimary(
+                ConstantPrimary::Base(ConstantPrimaryBase::PrimaryLiteral(literal)),
+                None,
+            ) => match literal {
+                PrimaryLiteral::Number(n) => Some(*n),
+                PrimaryLiteral::Bits(b) => Some(b.val()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ConstantExprContinue {
     BinaryOp(
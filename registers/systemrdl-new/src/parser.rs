@@ -5,19 +5,28 @@
 use crate::ast::*;
 use crate::lexer::Lexer;
 use crate::token::TokenKind;
-use crate::token::{Token, Tokens};
+use crate::token::{record_failure, Token, Tokens};
 use winnow::combinator::{alt, fail, opt, preceded, repeat, separated, terminated};
 use winnow::error::ParserError;
 use winnow::stream::Stream;
 use winnow::{Parser, Result};
 
+/// Rewinds to `start`, the token a parser was matching, and fails there. Recording the
+/// failure lets parse errors point at the furthest token that could not be matched.
+fn fail_at<'i, O>(i: &mut Tokens<'i>, start: &<Tokens<'i> as Stream>::Checkpoint) -> Result<O> {
+    i.reset(start);
+    record_failure(i);
+    fail.parse_next(i)
+}
+
 fn identifier(i: &mut Tokens) -> Result<String> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::Identifier(id),
             ..
         }) => Ok(id.to_string()),
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     }
 }
 
@@ -26,6 +35,7 @@ fn identifier(i: &mut Tokens) -> Result<String> {
 //   | signal
 // component_primary_type ::= addrmap | regfile | reg | field | mem
 fn component_type(i: &mut Tokens<'_>) -> Result<ComponentType> {
+    let start = i.checkpoint();
     let ct = match i.next_token() {
         Some(Token {
             kind: TokenKind::Reg,
@@ -51,13 +61,14 @@ fn component_type(i: &mut Tokens<'_>) -> Result<ComponentType> {
             kind: TokenKind::AddrMap,
             ..
         }) => ComponentType::AddrMap,
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     };
     Ok(ct)
 }
 
 // component_primary_type ::= addrmap | regfile | reg | field | mem
 fn component_primary_type(i: &mut Tokens<'_>) -> Result<ComponentType> {
+    let start = i.checkpoint();
     let ct = match i.next_token() {
         Some(Token {
             kind: TokenKind::Reg,
@@ -79,7 +90,7 @@ fn component_primary_type(i: &mut Tokens<'_>) -> Result<ComponentType> {
             kind: TokenKind::AddrMap,
             ..
         }) => ComponentType::AddrMap,
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     };
     Ok(ct)
 }
@@ -267,12 +278,13 @@ fn prop_assignment_lhs(i: &mut Tokens<'_>) -> Result<IdentityOrPropKeyword> {
 
 // precedencetype_literal ::= hw | sw
 fn precedence_type(i: &mut Tokens<'_>) -> Result<PrecedenceType> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::PrecedenceTypeLiteral(x),
             ..
         }) => Ok(*x),
-        _ => fail.parse_next(i),
+        _ => fail_at(i, &start),
     }
 }
 
@@ -559,6 +571,7 @@ fn explicit_component_inst(i: &mut Tokens<'_>) -> Result<ExplicitComponentInst>
 
 // prop_mod ::= posedge | negedge | bothedge | level | nonsticky
 fn prop_mod(i: &mut Tokens<'_>) -> Result<PropMod> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::PosEdge,
@@ -580,7 +593,7 @@ fn prop_mod(i: &mut Tokens<'_>) -> Result<PropMod> {
             kind: TokenKind::NonSticky,
             ..
         }) => Ok(PropMod::NonSticky),
-        _ => fail.parse_next(i),
+        _ => fail_at(i, &start),
     }
 }
 
@@ -785,6 +798,7 @@ fn component_anon_def(i: &mut Tokens<'_>) -> Result<ComponentDef> {
 
 // component_inst_type ::= external | internal
 fn component_inst_type(i: &mut Tokens<'_>) -> Result<ComponentInstType> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::Internal,
@@ -794,7 +808,7 @@ fn component_inst_type(i: &mut Tokens<'_>) -> Result<ComponentInstType> {
             kind: TokenKind::External,
             ..
         }) => Ok(ComponentInstType::External),
-        _ => fail.parse_next(i),
+        _ => fail_at(i, &start),
     }
 }
 
@@ -844,6 +858,7 @@ fn component_insts(i: &mut Tokens<'_>) -> Result<ComponentInsts> {
 //   | enumerator_literal
 //   | this
 fn primary_literal(i: &mut Tokens<'_>) -> Result<PrimaryLiteral> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::Number(n),
@@ -886,6 +901,7 @@ fn primary_literal(i: &mut Tokens<'_>) -> Result<PrimaryLiteral> {
             ..
         }) => Ok(PrimaryLiteral::This),
         _ => {
+            i.reset(&start);
             let (a, _, _, b) =
                 (identifier, TokenKind::Colon, TokenKind::Colon, identifier).parse_next(i)?;
             Ok(PrimaryLiteral::EnumeratorLiteral(a, b))
@@ -933,6 +949,7 @@ fn instance_ref(i: &mut Tokens<'_>) -> Result<InstanceRef> {
 }
 
 fn id_or_prop_keyword(i: &mut Tokens<'_>) -> Result<IdentityOrPropKeyword> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::Identifier(id),
@@ -962,7 +979,7 @@ fn id_or_prop_keyword(i: &mut Tokens<'_>) -> Result<IdentityOrPropKeyword> {
             kind: TokenKind::OnWriteTypeLiteral(OnWriteType::WoSet),
             ..
         }) => Ok(IdentityOrPropKeyword::PropKeyword(PropKeyword::WoSet)),
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     }
 }
 
@@ -1092,6 +1109,7 @@ fn constant_primary(i: &mut Tokens<'_>) -> Result<ConstantPrimary> {
 // unary_operator :
 //     ! | + | - | ~ | & | ~& | | | ~| | ^ | ~^ | ^~
 fn unary_operator(i: &mut Tokens<'_>) -> Result<UnaryOp> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::And,
@@ -1110,7 +1128,7 @@ fn unary_operator(i: &mut Tokens<'_>) -> Result<UnaryOp> {
         // Some(Token::Nor) => Ok(UnaryOp::Nor),
         // Some(Token::Xor) => Ok(UnaryOp::Xor),
         // Some(Token::Xnor) => Ok(UnaryOp::Xnor),
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     }
 }
 
@@ -1118,6 +1136,7 @@ fn unary_operator(i: &mut Tokens<'_>) -> Result<UnaryOp> {
 //     && | || | < | > | <= | >= | == | != | >> | <<
 //   | & | | | ^ | ~^| ^~ | * | / | % | + | - | **
 fn binary_operator(i: &mut Tokens<'_>) -> Result<BinaryOp> {
+    let start = i.checkpoint();
     match i.next_token() {
         Some(Token {
             kind: TokenKind::AndAnd,
@@ -1157,7 +1176,7 @@ fn binary_operator(i: &mut Tokens<'_>) -> Result<BinaryOp> {
             kind: TokenKind::NotEquals,
             ..
         }) => Ok(BinaryOp::NotEquals),
-        _ => fail.parse_next(i)?,
+        _ => fail_at(i, &start)?,
     }
 }
 
@@ -1709,13 +1728,10 @@ mod test {
             .map_err(|e| anyhow::format_err!("{e}"))
             .unwrap();
         println!("tokens: {:?}", tokens);
-        let tokens = Tokens::new(&tokens);
+        let furthest = std::cell::Cell::new(usize::MAX);
         let result = constant_expr
-            .parse(tokens)
-            .map_err(|e| {
-                let t = &e.input()[0];
-                anyhow::format_err!("Error parsing input at: `{}` token: {:?}", t.raw, t.kind)
-            })
+            .parse(crate::token::new_tokens(&tokens, &furthest))
+            .map_err(|e| anyhow::format_err!("Error parsing input at token {}", e.offset()))
             .unwrap();
 
         println!("{:?}", result);
@@ -1781,4 +1797,76 @@ mod test {
         let result = parse(input).unwrap();
         println!("{:?}", result);
     }
+
+    #[test]
+    fn test_enum_field_encoding() {
+        let input = r#"
+            addrmap {
+                reg {
+                    enum mode_t {
+                        ALERT;
+                        TIRED = 2'd1;
+                        SLEEPING = 2'd2 {
+                            desc = "Power consumption is minimal";
+                        };
+                        OFF;
+                    };
+                    field {encode=mode_t;} MODE[1:0];
+                } MODE @0x1000;
+            } my_addrmap;"#;
+        let root = parse(input).unwrap();
+
+        let enums = root.enum_defs();
+        assert_eq!(enums.len(), 1);
+        assert_eq!(enums[0].id, "mode_t");
+        let variants: Vec<_> = enums[0]
+            .variants()
+            .unwrap()
+            .into_iter()
+            .map(|v| (v.name, v.value, v.desc))
+            .collect();
+        assert_eq!(
+            variants,
+            vec![
+                ("ALERT".to_string(), 0, None),
+                ("TIRED".to_string(), 1, None),
+                (
+                    "SLEEPING".to_string(),
+                    2,
+                    Some("Power consumption is minimal".to_string())
+                ),
+                ("OFF".to_string(), 3, None),
+            ]
+        );
+
+        let Description::ComponentDef(addrmap) = &root.descriptions[0] else {
+            panic!("expected addrmap");
+        };
+        let ComponentBodyElem::ComponentDef(reg) = &addrmap.def.body().elements[0] else {
+            panic!("expected reg");
+        };
+        let ComponentBodyElem::ComponentDef(field) = &reg.def.body().elements[1] else {
+            panic!("expected field");
+        };
+        assert_eq!(field.def.body().encode(), Some("mode_t"));
+    }
+
+    #[test]
+    fn test_error_location() {
+        let input = "addrmap {\n    reg { field {} f; } a;\n    reg { field {} } b;\n} mcu;";
+        let err = parse(input).unwrap_err().to_string();
+        assert!(err.starts_with("3:20: unexpected token `}`"), "{err}");
+
+        let source = crate::file_source::MemFileSource::from_entries(&[(
+            "bad.rdl".into(),
+            "addrmap {\n  reg { field {} f; } a @ ;\n} mcu;".into(),
+        )]);
+        let err = Root::from_file(&source, std::path::Path::new("bad.rdl"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.starts_with("bad.rdl:2:27: unexpected token `;`"),
+            "{err}"
+        );
+    }
 }
@@ -2,7 +2,9 @@
 
 use crate::ast::{AccessType, AddressingType, OnReadType, OnWriteType, PrecedenceType};
 use crate::Bits;
+use std::cell::Cell;
 use std::fmt::Display;
+use winnow::stream::{Stateful, Stream};
 use winnow::Parser;
 use winnow::Result;
 use winnow::{error::ContextError, stream::TokenSlice, token::literal};
@@ -30,7 +32,22 @@ impl<'s> PartialEq<TokenKind<'s>> for Token<'s> {
     }
 }
 
-pub type Tokens<'i> = TokenSlice<'i, Token<'i>>;
+/// Token stream consumed by the parser. The state tracks the fewest tokens remaining
+/// at any failed match, which is the furthest point the parser reached and where
+/// parse errors are reported.
+pub type Tokens<'i> = Stateful<TokenSlice<'i, Token<'i>>, &'i Cell<usize>>;
+
+pub(crate) fn new_tokens<'i>(tokens: &'i [Token<'i>], furthest: &'i Cell<usize>) -> Tokens<'i> {
+    Stateful {
+        input: TokenSlice::new(tokens),
+        state: furthest,
+    }
+}
+
+/// Records a failed match at the current position of `input`.
+pub(crate) fn record_failure(input: &Tokens<'_>) {
+    input.state.set(input.state.get().min(input.eof_offset()));
+}
 
 impl winnow::stream::ContainsToken<&'_ Token<'_>> for TokenKind<'_> {
     #[inline(always)]
@@ -62,7 +79,11 @@ impl<const LEN: usize> winnow::stream::ContainsToken<&'_ Token<'_>> for [TokenKi
 
 impl<'i> Parser<Tokens<'i>, &'i Token<'i>, ContextError> for TokenKind<'i> {
     fn parse_next(&mut self, input: &mut Tokens<'i>) -> Result<&'i Token<'i>> {
-        literal(self.clone()).parse_next(input).map(|t| &t[0])
+        let result = literal(self.clone()).parse_next(input).map(|t| &t[0]);
+        if result.is_err() {
+            record_failure(input);
+        }
+        result
     }
 }
 
@@ -166,7 +166,6 @@ impl<'a> TokenIter<'a> {
         self.current_file_contents
     }
 
-    #[allow(unused)]
     pub fn current_file_path(&self) -> &Path {
         &self.current_file_path
     }
@@ -112,8 +112,32 @@ impl From<u16> for McuBootMilestones {
     }
 }
 
+impl McuBootMilestones {
+    /// Names of the milestones that are set, in bit order (e.g. "ROM_STARTED").
+    pub fn set_milestones(&self) -> impl Iterator<Item = &'static str> {
+        self.iter_names().map(|(name, _)| name)
+    }
+}
+
 impl From<McuBootMilestones> for u16 {
     fn from(value: McuBootMilestones) -> u16 {
         value.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_milestones() {
+        let milestones = McuBootMilestones::from(
+            (McuBootMilestones::CPTRA_FUSES_WRITTEN | McuBootMilestones::COLD_BOOT_FLOW_COMPLETE)
+                .bits(),
+        );
+        assert!(milestones
+            .set_milestones()
+            .eq(["CPTRA_FUSES_WRITTEN", "COLD_BOOT_FLOW_COMPLETE"]));
+        assert_eq!(McuBootMilestones::from(0).set_milestones().count(), 0);
+    }
+}
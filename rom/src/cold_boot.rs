@@ -348,9 +348,12 @@ impl BootFlow for ColdBoot {
             };
 
             romtime::println!("[mcu-rom] Verifying firmware header");
-            if !image_verifier.verify_header(header, fuses) {
-                romtime::println!("Firmware header verification failed; halting");
-                fatal_error(McuError::ROM_COLD_BOOT_HEADER_VERIFY_ERROR);
+            if let Err(err) = image_verifier.verify_header(header, fuses) {
+                romtime::println!(
+                    "Firmware header verification failed: {}; halting",
+                    HexWord(err.into())
+                );
+                fatal_error(err.into());
             }
         }
 
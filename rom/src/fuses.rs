@@ -738,3 +738,62 @@ impl Otp {
         Ok(())
     }
 }
+
+/// Number of SVN bits available in a 128-bit fuse field.
+pub const FUSE_SVN_MAX: u16 = 128;
+
+/// Returns the thermometer-encoded fuse value for `svn`, i.e. the lowest
+/// `svn` bits set. SVNs above [`FUSE_SVN_MAX`] saturate to all ones.
+pub fn fuse_svn_mask(svn: u16) -> u128 {
+    match svn {
+        0 => 0,
+        n if n >= FUSE_SVN_MAX => u128::MAX,
+        n => (1u128 << n) - 1,
+    }
+}
+
+/// Decodes the thermometer-encoded SVN from the first 16 bytes (little-endian)
+/// of `fuse_partition`: the number of contiguous ones starting at bit 0.
+pub fn fuse_svn(fuse_partition: &[u8]) -> u16 {
+    let mut bytes = [0u8; 16];
+    let len = fuse_partition.len().min(bytes.len());
+    bytes[..len].copy_from_slice(&fuse_partition[..len]);
+    u128::from_le_bytes(bytes).trailing_ones() as u16
+}
+
+/// Anti-rollback policy: an image is allowed if its SVN is at least the SVN
+/// burned into `fuse_partition`.
+pub fn svn_is_allowed(image_svn: u16, fuse_partition: &[u8]) -> bool {
+    image_svn >= fuse_svn(fuse_partition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuse_svn_mask() {
+        assert_eq!(fuse_svn_mask(0), 0);
+        assert_eq!(fuse_svn_mask(30), 0x3fff_ffff);
+        assert_eq!(fuse_svn_mask(128), u128::MAX);
+        assert_eq!(fuse_svn_mask(200), u128::MAX);
+    }
+
+    #[test]
+    fn test_svn_is_allowed() {
+        let zero = fuse_svn_mask(0).to_le_bytes();
+        assert_eq!(fuse_svn(&zero), 0);
+        assert!(svn_is_allowed(0, &zero));
+
+        let mid = fuse_svn_mask(40).to_le_bytes();
+        assert_eq!(fuse_svn(&mid), 40);
+        assert!(svn_is_allowed(40, &mid));
+        assert!(svn_is_allowed(100, &mid));
+        assert!(!svn_is_allowed(25, &mid));
+
+        let full = fuse_svn_mask(128).to_le_bytes();
+        assert_eq!(fuse_svn(&full), 128);
+        assert!(svn_is_allowed(128, &full));
+        assert!(!svn_is_allowed(127, &full));
+    }
+}
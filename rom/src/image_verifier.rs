@@ -1,7 +1,38 @@
 // Licensed under the Apache-2.0 license
 
+use mcu_error::McuError;
 use registers_generated::fuses::Fuses;
 
+/// Reason an image header was rejected by an [`ImageVerifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ImageVerifyError {
+    /// The header has the wrong magic number.
+    BadMagic = 1,
+    /// A checksum or digest in the header does not match the image.
+    ChecksumMismatch = 2,
+    /// The image SVN is below the minimum SVN in the fuses.
+    SvnTooLow = 3,
+    /// The image signature does not verify.
+    SignatureInvalid = 4,
+    /// The header is too short to be parsed.
+    InvalidLength = 5,
+}
+
+impl From<ImageVerifyError> for u32 {
+    fn from(err: ImageVerifyError) -> u32 {
+        err as u32
+    }
+}
+
+/// All verification failures map to the same fatal-error code; the specific
+/// [`ImageVerifyError`] is only logged.
+impl From<ImageVerifyError> for McuError {
+    fn from(_: ImageVerifyError) -> McuError {
+        McuError::ROM_COLD_BOOT_HEADER_VERIFY_ERROR
+    }
+}
+
 /// Verifies the authenticity and integrity of the provided image header
 /// against the device's fuse state.
 ///
@@ -13,8 +44,31 @@ use registers_generated::fuses::Fuses;
 ///   fuses:  Immutable view of device/programmed fuse values
 ///
 /// Returns:
-///   true if every required check passes.
-///   false on any structural, policy, or cryptographic failure.
+///   Ok(()) if every required check passes.
+///   The reason for the first structural, policy, or cryptographic failure otherwise.
 pub trait ImageVerifier {
-    fn verify_header(&self, header: &[u8], fuses: &Fuses) -> bool;
+    fn verify_header(&self, header: &[u8], fuses: &Fuses) -> Result<(), ImageVerifyError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let errors = [
+            (ImageVerifyError::BadMagic, 1),
+            (ImageVerifyError::ChecksumMismatch, 2),
+            (ImageVerifyError::SvnTooLow, 3),
+            (ImageVerifyError::SignatureInvalid, 4),
+            (ImageVerifyError::InvalidLength, 5),
+        ];
+        for (err, code) in errors {
+            assert_eq!(u32::from(err), code);
+            assert_eq!(
+                McuError::from(err),
+                McuError::ROM_COLD_BOOT_HEADER_VERIFY_ERROR
+            );
+        }
+    }
 }
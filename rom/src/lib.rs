@@ -21,7 +21,7 @@ pub use flash::*;
 mod fuses;
 pub use fuses::*;
 pub mod image_verifier;
-pub use image_verifier::ImageVerifier;
+pub use image_verifier::{ImageVerifier, ImageVerifyError};
 mod rom;
 pub use rom::*;
 mod rom_env;
@@ -45,23 +45,47 @@ macro_rules! println {
     };
 }
 
+fn write_hex_byte(f: &mut core::fmt::Formatter<'_>, x: u8, alpha: u8) -> core::fmt::Result {
+    for c in [x >> 4, x & 0xf] {
+        if c < 10 {
+            f.write_char((c + b'0') as char)?;
+        } else {
+            f.write_char((c - 10 + alpha) as char)?;
+        }
+    }
+    Ok(())
+}
+
 pub struct HexBytes<'a>(pub &'a [u8]);
 impl Display for HexBytes<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        // Rust can't prove the indexes are correct in a format macro.
         for &x in self.0.iter() {
-            let c = x >> 4;
-            if c < 10 {
-                f.write_char((c + b'0') as char)?;
-            } else {
-                f.write_char((c - 10 + b'A') as char)?;
-            }
-            let c = x & 0xf;
-            if c < 10 {
-                f.write_char((c + b'0') as char)?;
-            } else {
-                f.write_char((c - 10 + b'A') as char)?;
+            write_hex_byte(f, x, b'A')?;
+        }
+        Ok(())
+    }
+}
+
+/// Like [`HexBytes`], but with lowercase digits.
+pub struct HexBytesLower<'a>(pub &'a [u8]);
+impl Display for HexBytesLower<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for &x in self.0.iter() {
+            write_hex_byte(f, x, b'a')?;
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase hex with the given separator between bytes, e.g. `de:ad:be:ef`.
+pub struct HexBytesSep<'a>(pub &'a [u8], pub char);
+impl Display for HexBytesSep<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, &x) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(self.1)?;
             }
+            write_hex_byte(f, x, b'a')?;
         }
         Ok(())
     }
@@ -119,3 +143,101 @@ pub fn crc8(crc: u8, data: u8) -> u8 {
     let b = unsafe { core::arch::riscv32::clmul(a >> 8, polynomial) };
     (a ^ b) as u8
 }
+
+#[cfg(not(target_arch = "riscv32"))]
+pub fn crc16_ccitt(crc: u16, data: u8) -> u16 {
+    // CRC-16 with last 16 bits of polynomial x^16 + x^12 + x^5 + 1.
+    let polynomial = 0x1021;
+    let mut crc = crc;
+    crc ^= (data as u16) << 8;
+    for _ in 0..8 {
+        if crc & 0x8000 != 0 {
+            crc = (crc << 1) ^ polynomial;
+        } else {
+            crc <<= 1;
+        }
+    }
+    crc
+}
+
+#[cfg(target_arch = "riscv32")]
+pub fn crc16_ccitt(crc: u16, data: u8) -> u16 {
+    // CRC-16 with last 16 bits of polynomial x^16 + x^12 + x^5 + 1.
+    let polynomial = 0x1021;
+    let top = ((crc >> 8) as u8 ^ data) as usize;
+    let a = unsafe { core::arch::riscv32::clmul(top, polynomial) };
+    let b = unsafe { core::arch::riscv32::clmul(a >> 16, polynomial) };
+    (crc << 8) ^ (a ^ b) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Software carry-less multiply, used to check the riscv32 reduction on the host.
+    fn clmul(a: usize, b: usize) -> usize {
+        (0..usize::BITS)
+            .filter(|i| (b >> i) & 1 != 0)
+            .fold(0, |acc, i| acc ^ (a << i))
+    }
+
+    fn crc16_ccitt_clmul(crc: u16, data: u8) -> u16 {
+        let polynomial = 0x1021;
+        let top = ((crc >> 8) as u8 ^ data) as usize;
+        let a = clmul(top, polynomial);
+        let b = clmul(a >> 16, polynomial);
+        (crc << 8) ^ (a ^ b) as u16
+    }
+
+    fn crc8_clmul(crc: u8, data: u8) -> u8 {
+        let polynomial = 0x07;
+        let crc = (crc ^ data) as usize;
+        let a = clmul(crc, polynomial);
+        let b = clmul(a >> 8, polynomial);
+        (a ^ b) as u8
+    }
+
+    #[test]
+    fn test_hex_bytes() {
+        assert_eq!(format!("{}", HexBytes(&[])), "");
+        assert_eq!(format!("{}", HexBytesLower(&[])), "");
+        assert_eq!(format!("{}", HexBytesSep(&[], ':')), "");
+
+        assert_eq!(format!("{}", HexBytes(&[0xab])), "AB");
+        assert_eq!(format!("{}", HexBytesLower(&[0xab])), "ab");
+        assert_eq!(format!("{}", HexBytesSep(&[0xab], ':')), "ab");
+
+        let bytes = [0x01, 0x2f, 0xde, 0xad, 0xbe, 0xef];
+        assert_eq!(format!("{}", HexBytes(&bytes)), "012FDEADBEEF");
+        assert_eq!(format!("{}", HexBytesLower(&bytes)), "012fdeadbeef");
+        assert_eq!(format!("{}", HexBytesSep(&bytes, ':')), "01:2f:de:ad:be:ef");
+        assert_eq!(format!("{}", HexWord(0x1234abcd)), "1234ABCD");
+    }
+
+    #[test]
+    fn test_crc8() {
+        // CRC-8/SMBUS check value
+        assert_eq!(b"123456789".iter().fold(0, |crc, &b| crc8(crc, b)), 0xf4);
+        for crc in 0..=u8::MAX {
+            for data in 0..=u8::MAX {
+                assert_eq!(crc8(crc, data), crc8_clmul(crc, data));
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc16_ccitt() {
+        // CRC-16/CCITT-FALSE and CRC-16/XMODEM check values
+        let check = b"123456789";
+        assert_eq!(
+            check.iter().fold(0xffff, |crc, &b| crc16_ccitt(crc, b)),
+            0x29b1
+        );
+        assert_eq!(check.iter().fold(0, |crc, &b| crc16_ccitt(crc, b)), 0x31c3);
+        for crc in [0, 1, 0x00ff, 0x1234, 0x8000, 0xa5a5, 0xffff] {
+            for data in 0..=u8::MAX {
+                assert_eq!(crc16_ccitt(crc, data), crc16_ccitt_clmul(crc, data));
+            }
+        }
+    }
+}
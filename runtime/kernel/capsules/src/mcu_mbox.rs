@@ -134,6 +134,13 @@ impl<'a, T: hil::Mailbox<'a>> McuMboxDriver<'a, T> {
         Ok(())
     }
 
+    /// Fails a request no app will respond to, so the driver can receive the next one.
+    fn reject_request(&self) {
+        if let Err(err) = self.driver.set_mbox_cmd_status(hil::MailboxStatus::Failure) {
+            println!("MCU_MBOX_CAPSULE: Failed to reject request: {:?}", err);
+        }
+    }
+
     fn buffer_message(&self, app: &mut App, command: u32, rx_buf: &[u32], dlen: usize) -> bool {
         let dw_len = dlen.div_ceil(4);
         if dw_len > app.buffered_msg.data.len() {
@@ -240,14 +247,17 @@ impl<'a, T: hil::Mailbox<'a>> hil::MailboxClient for McuMboxDriver<'a, T> {
                 dw_len
             );
             self.driver.restore_rx_buffer(rx_buf);
+            self.reject_request();
             return;
         }
 
+        // Set once an app has received or buffered the request and will respond to it.
+        let mut delivered = false;
         self.apps.each(|_, app, kernel_data| {
             if app.waiting_rx.get() {
                 app.waiting_rx.set(false);
             } else {
-                let _ = self.buffer_message(app, command, rx_buf, dlen);
+                delivered |= self.buffer_message(app, command, rx_buf, dlen);
                 return;
             }
 
@@ -281,6 +291,7 @@ impl<'a, T: hil::Mailbox<'a>> hil::MailboxClient for McuMboxDriver<'a, T> {
 
             match process_result  {
                 Ok(Ok(len)) => {
+                    delivered = true;
                     kernel_data
                         .schedule_upcall(upcall::REQUEST_RECEIVED, (command as usize, len, 0))
                         .ok();
@@ -295,6 +306,9 @@ impl<'a, T: hil::Mailbox<'a>> hil::MailboxClient for McuMboxDriver<'a, T> {
         });
         // Restore driver rx buffer
         self.driver.restore_rx_buffer(rx_buf);
+        if !delivered {
+            self.reject_request();
+        }
     }
 
     fn response_received(
@@ -11,6 +11,15 @@ pub trait DoeTransportTxClient<'a> {
     fn send_done(&self, result: Result<(), ErrorCode>);
 }
 
+/// Reasons a DOE data object could not be received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DoeError {
+    /// The data object is larger than the maximum supported size.
+    Oversized,
+    /// No receive buffer was available to hold the data object.
+    NoBuffer,
+}
+
 pub trait DoeTransportRxClient {
     /// Called to receive a DOE data object.
     ///
@@ -18,6 +27,12 @@ pub trait DoeTransportRxClient {
     /// * `rx_buf` - buffer containing the received DOE data object
     /// * `len_dw` - The length of the data received in dwords
     fn receive(&self, rx_buf: &'static mut [u32], len_dw: usize);
+
+    /// Called when an incoming DOE data object was dropped and the error bit was set.
+    ///
+    /// # Arguments
+    /// * `reason` - Why the data object could not be received
+    fn receive_error(&self, _reason: DoeError) {}
 }
 
 pub trait DoeTransport<'a> {
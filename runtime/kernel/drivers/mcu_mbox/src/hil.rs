@@ -50,6 +50,9 @@ pub trait Mailbox<'a> {
 
     /// Sets the command status of the MCU mailbox (Receiver mode).
     ///
+    /// Setting `MailboxStatus::Failure` while a request is pending rejects that request
+    /// without a response, so the driver is ready to receive the next one.
+    ///
     /// # Arguments
     /// * `status` - The status to set for the mailbox.
     ///
@@ -67,7 +67,7 @@ impl<'a> VeeRDefaultPeripherals<'a> {
             mci: mci_driver,
             mcu_mbox0: mcu_mbox_driver::McuMailbox::new(
                 mci,
-                memory_map.mci_offset + mcu_mbox_driver::MCU_MBOX0_SRAM_OFFSET,
+                mcu_mbox_driver::McuMboxIndex::Mbox0,
                 alarm,
             ),
             additional_interrupt_handler,
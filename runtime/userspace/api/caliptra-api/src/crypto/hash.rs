@@ -202,3 +202,107 @@ impl HashContext {
         Ok(())
     }
 }
+
+/// Streaming SHA-384/SHA-512 over the Caliptra mailbox.
+///
+/// Data passed to `update` may be of any length; it is split across as many
+/// `CM_SHA_UPDATE` requests as needed.
+pub struct ShaStream {
+    ctx: HashContext,
+}
+
+impl ShaStream {
+    /// Starts a new stream using `hash_algo`.
+    pub async fn init(hash_algo: HashAlgoType) -> CaliptraApiResult<Self> {
+        let mut ctx = HashContext::new();
+        ctx.init(hash_algo, None).await?;
+        Ok(ShaStream { ctx })
+    }
+
+    /// Returns the hash algorithm of the stream.
+    pub fn hash_algo(&self) -> Option<HashAlgoType> {
+        self.ctx.hash_algo()
+    }
+
+    /// Appends `data` to the stream.
+    pub async fn update(&mut self, data: &[u8]) -> CaliptraApiResult<()> {
+        self.ctx.update(data).await
+    }
+
+    /// Completes the stream and writes the digest to `hash`.
+    ///
+    /// `hash` must hold at least `hash_algo().hash_size()` bytes.
+    pub async fn finalize(mut self, hash: &mut [u8]) -> CaliptraApiResult<()> {
+        self.ctx.finalize(hash).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use libtock_unittest::fake;
+    use libtock_unittest::fake::FakeMailboxDriver;
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::vec::Vec;
+    use zerocopy::FromZeros;
+
+    fn context_resp(fill: u8) -> CmShaInitResp {
+        let mut resp = CmShaInitResp::new_zeroed();
+        resp.context = [fill; CMB_SHA_CONTEXT_SIZE];
+        resp
+    }
+
+    #[test]
+    fn test_sha512_stream() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeMailboxDriver::new());
+        kernel.add_driver(&driver);
+
+        let mut final_resp = CmShaFinalResp::new_zeroed();
+        final_resp.hash[..64].copy_from_slice(&[0x5a; 64]);
+        driver.add_ready_response(CmShaInitReq::ID.0, context_resp(1).as_bytes());
+        driver.add_ready_response(CmShaUpdateReq::ID.0, context_resp(2).as_bytes());
+        driver.add_ready_response(CmShaUpdateReq::ID.0, context_resp(3).as_bytes());
+        driver.add_ready_response(CmShaFinalReq::ID.0, final_resp.as_bytes());
+
+        let mut stream =
+            fake::wait_for_future_ready(Box::pin(ShaStream::init(HashAlgoType::SHA512))).unwrap();
+        assert_eq!(stream.hash_algo(), Some(HashAlgoType::SHA512));
+        let init_req = ShaInitReq::read_from_bytes(&driver.get_last_ro_input().unwrap()).unwrap();
+        assert_eq!(init_req.hash_algorithm, CmHashAlgorithm::Sha512 as u32);
+        assert_eq!(init_req.input_size, 0);
+
+        // One byte more than a single request carries, so two updates are sent.
+        let data: Vec<u8> = (0..=MAX_CRYPTO_MBOX_DATA_SIZE).map(|i| i as u8).collect();
+        fake::wait_for_future_ready(Box::pin(stream.update(&data))).unwrap();
+        let update_req =
+            ShaUpdateReq::read_from_bytes(&driver.get_last_ro_input().unwrap()).unwrap();
+        assert_eq!(update_req.context, [2; CMB_SHA_CONTEXT_SIZE]);
+        assert_eq!(update_req.input_size, 1);
+        assert_eq!(update_req.input[0], data[MAX_CRYPTO_MBOX_DATA_SIZE]);
+
+        let mut hash = [0u8; 64];
+        fake::wait_for_future_ready(Box::pin(stream.finalize(&mut hash))).unwrap();
+        assert_eq!(driver.get_last_command(), Some(CmShaFinalReq::ID.0));
+        assert_eq!(hash, [0x5a; 64]);
+    }
+
+    #[test]
+    fn test_stream_finalize_buffer_too_small() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeMailboxDriver::new());
+        kernel.add_driver(&driver);
+        driver.add_ready_response(CmShaInitReq::ID.0, context_resp(1).as_bytes());
+
+        let stream =
+            fake::wait_for_future_ready(Box::pin(ShaStream::init(HashAlgoType::SHA512))).unwrap();
+        let mut hash = [0u8; SHA384_HASH_SIZE];
+        assert_eq!(
+            fake::wait_for_future_ready(Box::pin(stream.finalize(&mut hash))),
+            Err(CaliptraApiError::InvalidArgument("Hash buffer too small"))
+        );
+    }
+}
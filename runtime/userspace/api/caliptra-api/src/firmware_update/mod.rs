@@ -18,7 +18,7 @@ use caliptra_auth_man_types::{
 };
 use embassy_executor::Spawner;
 use flash_image::{
-    FlashHeader, ImageHeader, CALIPTRA_FMC_RT_IDENTIFIER, MCU_RT_IDENTIFIER,
+    ChecksumAlgo, FlashHeader, ImageHeader, CALIPTRA_FMC_RT_IDENTIFIER, MCU_RT_IDENTIFIER,
     SOC_MANIFEST_IDENTIFIER,
 };
 use libsyscall_caliptra::dma::AXIAddr;
@@ -116,6 +116,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         &self,
         num_images: usize,
         image_headers_offset: usize,
+        checksum_algo: ChecksumAlgo,
         image_id: u32,
     ) -> Result<(usize, usize), ErrorCode> {
         let mut current_header_offset = image_headers_offset;
@@ -126,7 +127,10 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
                 .await?;
             let (image_header, _) =
                 ImageHeader::read_from_prefix(&image_header).map_err(|_| ErrorCode::Fail)?;
-            image_header.verify().then_some(()).ok_or(ErrorCode::Fail)?;
+            image_header
+                .verify(checksum_algo)
+                .then_some(())
+                .ok_or(ErrorCode::Fail)?;
 
             if image_header.identifier == image_id {
                 return Ok((image_header.offset as usize, image_header.size as usize));
@@ -141,6 +145,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         &self,
         num_images: usize,
         image_headers_offset: usize,
+        checksum_algo: ChecksumAlgo,
         index: usize,
     ) -> Result<ImageHeader, ErrorCode> {
         if index >= num_images {
@@ -151,7 +156,10 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         self.staging_memory.read(offset, &mut image_header).await?;
         let (image_header, _) =
             ImageHeader::read_from_prefix(&image_header).map_err(|_| ErrorCode::Fail)?;
-        image_header.verify().then_some(()).ok_or(ErrorCode::Fail)?;
+        image_header
+            .verify(checksum_algo)
+            .then_some(())
+            .ok_or(ErrorCode::Fail)?;
 
         Ok(image_header)
     }
@@ -166,6 +174,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         let (flash_header, _) =
             FlashHeader::read_from_prefix(&flash_header).map_err(|_| ErrorCode::Fail)?;
         flash_header.verify().then_some(()).ok_or(ErrorCode::Fail)?;
+        let checksum_algo = flash_header.checksum_algo().ok_or(ErrorCode::Fail)?;
 
         // Verify the new Auth Manifest
         writeln!(
@@ -177,6 +186,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
             .get_image_toc(
                 flash_header.image_count as usize,
                 flash_header.image_headers_offset as usize,
+                checksum_algo,
                 SOC_MANIFEST_IDENTIFIER,
             )
             .await
@@ -188,6 +198,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
                 .get_image_toc_by_index(
                     flash_header.image_count as usize,
                     flash_header.image_headers_offset as usize,
+                    checksum_algo,
                     i,
                 )
                 .await?;
@@ -268,6 +279,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
     }
 
     async fn update_caliptra(&mut self, flash_header: &FlashHeader) -> Result<(), ErrorCode> {
+        let checksum_algo = flash_header.checksum_algo().ok_or(ErrorCode::Fail)?;
         writeln!(
             Console::<DefaultSyscalls>::writer(),
             "[FW Upd] Updating Caliptra"
@@ -277,6 +289,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
             .get_image_toc(
                 flash_header.image_count as usize,
                 flash_header.image_headers_offset as usize,
+                checksum_algo,
                 CALIPTRA_FMC_RT_IDENTIFIER,
             )
             .await
@@ -463,6 +476,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
     }
 
     async fn update_mcu(&mut self, flash_header: &FlashHeader) -> Result<(), ErrorCode> {
+        let checksum_algo = flash_header.checksum_algo().ok_or(ErrorCode::Fail)?;
         writeln!(
             Console::<DefaultSyscalls>::writer(),
             "[FW Upd] Updating MCU"
@@ -472,6 +486,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
             .get_image_toc(
                 flash_header.image_count as usize,
                 flash_header.image_headers_offset as usize,
+                checksum_algo,
                 MCU_RT_IDENTIFIER,
             )
             .await
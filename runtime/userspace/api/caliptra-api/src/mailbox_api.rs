@@ -26,6 +26,7 @@
 //! - `CertificateChainResp`: Represents a response containing a chunk of a certificate chain. Equivalent to `GetCertificateChainResp`.
 //! - `RandomStirReq`: Represents a request to stir the random number generator. Equivalent to `CmRandomStirReq`.
 //! - `RandomGenerateResp`: Represents a response for generating random numbers. Equivalent to `CmRandomGenerateResp`.
+//! - `FwInfo`: Typed view of the fields of interest in a `FW_INFO` response. Parsed from `FwInfoResp`.
 //!
 //! # Enums
 //! - `DpeResponse`: Enum representing various DPE command responses:
@@ -38,8 +39,8 @@ use crate::error::CaliptraApiError;
 use crate::error::CaliptraApiResult;
 use caliptra_api::mailbox::CmRandomGenerateResp;
 use caliptra_api::mailbox::{
-    CmRandomStirReq, InvokeDpeResp, MailboxReqHeader, MailboxRespHeader, MailboxRespHeaderVarSize,
-    CMB_SHA_CONTEXT_SIZE, MAX_CMB_DATA_SIZE,
+    CmRandomStirReq, CommandId, FwInfoResp, InvokeDpeResp, MailboxReqHeader, MailboxRespHeader,
+    MailboxRespHeaderVarSize, CMB_SHA_CONTEXT_SIZE, MAX_CMB_DATA_SIZE,
 };
 use core::mem::size_of;
 use dpe::context::ContextHandle;
@@ -193,3 +194,124 @@ pub(crate) async fn execute_mailbox_cmd(
         Err(e) => Err(CaliptraApiError::Mailbox(e))?,
     }
 }
+
+/// Firmware information reported by Caliptra in response to `FW_INFO`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FwInfo {
+    /// PAUSER value granted PL0 privileges. All other users are PL1.
+    pub pl0_pauser: u32,
+    /// Current firmware (FMC + runtime) SVN.
+    pub fw_svn: u32,
+    /// Minimum firmware SVN observed since cold boot.
+    pub min_fw_svn: u32,
+    /// Firmware SVN at cold boot.
+    pub cold_boot_fw_svn: u32,
+    pub attestation_disabled: bool,
+    pub rom_revision: [u8; 20],
+    pub fmc_revision: [u8; 20],
+    pub runtime_revision: [u8; 20],
+    pub rom_sha256_digest: [u32; 8],
+    pub fmc_sha384_digest: [u32; 12],
+    pub runtime_sha384_digest: [u32; 12],
+    pub owner_pub_key_hash: [u32; 12],
+}
+
+impl FwInfo {
+    /// Parses a raw `FW_INFO` response.
+    ///
+    /// Returns `CaliptraApiError::InvalidResponse` if `rsp_bytes` is shorter than `FwInfoResp`.
+    pub fn from_response(rsp_bytes: &[u8]) -> CaliptraApiResult<Self> {
+        let (resp, _) = FwInfoResp::read_from_prefix(rsp_bytes)
+            .map_err(|_| CaliptraApiError::InvalidResponse)?;
+
+        Ok(FwInfo {
+            pl0_pauser: resp.pl0_pauser,
+            fw_svn: resp.fw_svn,
+            min_fw_svn: resp.min_fw_svn,
+            cold_boot_fw_svn: resp.cold_boot_fw_svn,
+            attestation_disabled: resp.attestation_disabled != 0,
+            rom_revision: resp.rom_revision,
+            fmc_revision: resp.fmc_revision,
+            runtime_revision: resp.runtime_revision,
+            rom_sha256_digest: resp.rom_sha256_digest,
+            fmc_sha384_digest: resp.fmc_sha384_digest,
+            runtime_sha384_digest: resp.runtime_sha384_digest,
+            owner_pub_key_hash: resp.owner_pub_key_hash,
+        })
+    }
+}
+
+/// Issues `FW_INFO` to Caliptra and returns the parsed response.
+pub async fn get_fw_info(mailbox: &Mailbox) -> CaliptraApiResult<FwInfo> {
+    let mut req = MailboxReqHeader::default();
+    let mut rsp_bytes = [0u8; size_of::<FwInfoResp>()];
+
+    let size = execute_mailbox_cmd(
+        mailbox,
+        u32::from(CommandId::FW_INFO),
+        req.as_mut_bytes(),
+        &mut rsp_bytes,
+    )
+    .await?;
+    if size != size_of::<FwInfoResp>() {
+        return Err(CaliptraApiError::InvalidResponse);
+    }
+
+    FwInfo::from_response(&rsp_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zerocopy::FromZeros;
+
+    fn canned_fw_info_resp() -> FwInfoResp {
+        let mut resp = FwInfoResp::new_zeroed();
+        resp.pl0_pauser = 0x1234_5678;
+        resp.fw_svn = 5;
+        resp.min_fw_svn = 3;
+        resp.cold_boot_fw_svn = 4;
+        resp.attestation_disabled = 1;
+        resp.rom_revision = [0xa1; 20];
+        resp.fmc_revision = [0xa2; 20];
+        resp.runtime_revision = [0xa3; 20];
+        resp.rom_sha256_digest = [0x1111_1111; 8];
+        resp.fmc_sha384_digest = [0x2222_2222; 12];
+        resp.runtime_sha384_digest = [0x3333_3333; 12];
+        resp.owner_pub_key_hash = [0x4444_4444; 12];
+        resp
+    }
+
+    #[test]
+    fn test_fw_info_from_response() {
+        let resp = canned_fw_info_resp();
+        let info = FwInfo::from_response(resp.as_bytes()).unwrap();
+
+        assert_eq!(info.pl0_pauser, 0x1234_5678);
+        assert_eq!(info.fw_svn, 5);
+        assert_eq!(info.min_fw_svn, 3);
+        assert_eq!(info.cold_boot_fw_svn, 4);
+        assert!(info.attestation_disabled);
+        assert_eq!(info.rom_revision, [0xa1; 20]);
+        assert_eq!(info.fmc_revision, [0xa2; 20]);
+        assert_eq!(info.runtime_revision, [0xa3; 20]);
+        assert_eq!(info.rom_sha256_digest, [0x1111_1111; 8]);
+        assert_eq!(info.fmc_sha384_digest, [0x2222_2222; 12]);
+        assert_eq!(info.runtime_sha384_digest, [0x3333_3333; 12]);
+        assert_eq!(info.owner_pub_key_hash, [0x4444_4444; 12]);
+    }
+
+    #[test]
+    fn test_fw_info_short_buffer() {
+        let resp = canned_fw_info_resp();
+        let bytes = resp.as_bytes();
+        assert_eq!(
+            FwInfo::from_response(&bytes[..bytes.len() - 1]),
+            Err(CaliptraApiError::InvalidResponse)
+        );
+        assert_eq!(
+            FwInfo::from_response(&[]),
+            Err(CaliptraApiError::InvalidResponse)
+        );
+    }
+}
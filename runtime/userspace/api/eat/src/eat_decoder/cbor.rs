@@ -0,0 +1,177 @@
+// Licensed under the Apache-2.0 license
+
+// CBOR decoding functionality
+use crate::eat_encoder::EatError;
+
+// Maximum nesting depth accepted when skipping over items
+const MAX_SKIP_DEPTH: usize = 16;
+
+// CBOR decoder over a borrowed buffer
+pub struct CborDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborDecoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    // Returns the bytes between `start` and the current position
+    pub fn slice_from(&self, start: usize) -> Result<&'a [u8], EatError> {
+        self.data.get(start..self.pos).ok_or(EatError::InvalidData)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, EatError> {
+        let byte = *self.data.get(self.pos).ok_or(EatError::InvalidData)?;
+        self.pos = self.pos.saturating_add(1);
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<&'a [u8], EatError> {
+        let len = usize::try_from(len).map_err(|_| EatError::InvalidData)?;
+        let end_pos = self.pos.checked_add(len).ok_or(EatError::InvalidData)?;
+        let bytes = self
+            .data
+            .get(self.pos..end_pos)
+            .ok_or(EatError::InvalidData)?;
+        self.pos = end_pos;
+        Ok(bytes)
+    }
+
+    pub fn peek_major_type(&self) -> Result<u8, EatError> {
+        let byte = self.data.get(self.pos).ok_or(EatError::InvalidData)?;
+        Ok(byte >> 5)
+    }
+
+    // Decode major type + argument according to CBOR rules. Indefinite lengths
+    // are not produced by the encoder and are rejected.
+    fn decode_type_value(&mut self) -> Result<(u8, u64), EatError> {
+        let initial = self.read_byte()?;
+        let major_type = initial >> 5;
+        let value = match initial & 0x1f {
+            info @ 0..=23 => info as u64,
+            24 => self.read_byte()? as u64,
+            25 => u16::from_be_bytes(
+                self.read_bytes(2)?
+                    .try_into()
+                    .map_err(|_| EatError::InvalidData)?,
+            ) as u64,
+            26 => u32::from_be_bytes(
+                self.read_bytes(4)?
+                    .try_into()
+                    .map_err(|_| EatError::InvalidData)?,
+            ) as u64,
+            27 => u64::from_be_bytes(
+                self.read_bytes(8)?
+                    .try_into()
+                    .map_err(|_| EatError::InvalidData)?,
+            ),
+            _ => return Err(EatError::InvalidData),
+        };
+        Ok((major_type, value))
+    }
+
+    fn expect_type(&mut self, major_type: u8) -> Result<u64, EatError> {
+        match self.decode_type_value()? {
+            (major, value) if major == major_type => Ok(value),
+            _ => Err(EatError::InvalidData),
+        }
+    }
+
+    // Major type 0: Unsigned integer
+    pub fn decode_uint(&mut self) -> Result<u64, EatError> {
+        self.expect_type(0)
+    }
+
+    // Major type 0 or 1: Integer
+    pub fn decode_int(&mut self) -> Result<i64, EatError> {
+        match self.decode_type_value()? {
+            (0, value) => i64::try_from(value).map_err(|_| EatError::InvalidData),
+            (1, value) => {
+                // -1 - n
+                let value = i64::try_from(value).map_err(|_| EatError::InvalidData)?;
+                Ok(-1 - value)
+            }
+            _ => Err(EatError::InvalidData),
+        }
+    }
+
+    // Major type 2: Byte string
+    pub fn decode_bytes(&mut self) -> Result<&'a [u8], EatError> {
+        let len = self.expect_type(2)?;
+        self.read_bytes(len)
+    }
+
+    // Major type 3: Text string
+    pub fn decode_text(&mut self) -> Result<&'a str, EatError> {
+        let len = self.expect_type(3)?;
+        let bytes = self.read_bytes(len)?;
+        core::str::from_utf8(bytes).map_err(|_| EatError::InvalidUtf8)
+    }
+
+    // Major type 4: Array
+    pub fn decode_array_header(&mut self) -> Result<u64, EatError> {
+        self.expect_type(4)
+    }
+
+    // Major type 5: Map
+    pub fn decode_map_header(&mut self) -> Result<u64, EatError> {
+        self.expect_type(5)
+    }
+
+    // Major type 6: Tag
+    pub fn decode_tag(&mut self) -> Result<u64, EatError> {
+        self.expect_type(6)
+    }
+
+    // Consume a tag if one is present, returning its value
+    pub fn decode_optional_tag(&mut self) -> Result<Option<u64>, EatError> {
+        if self.peek_major_type()? == 6 {
+            Ok(Some(self.decode_tag()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Skip over one complete data item, including any nested items
+    pub fn skip_item(&mut self) -> Result<(), EatError> {
+        self.skip_item_nested(0)
+    }
+
+    fn skip_item_nested(&mut self, depth: usize) -> Result<(), EatError> {
+        if depth > MAX_SKIP_DEPTH {
+            return Err(EatError::InvalidData);
+        }
+        let (major_type, value) = self.decode_type_value()?;
+        match major_type {
+            // Integers and simple values carry everything in the argument
+            0 | 1 | 7 => {}
+            2 | 3 => {
+                self.read_bytes(value)?;
+            }
+            4 => {
+                for _ in 0..value {
+                    self.skip_item_nested(depth.saturating_add(1))?;
+                }
+            }
+            5 => {
+                for _ in 0..value {
+                    self.skip_item_nested(depth.saturating_add(1))?;
+                    self.skip_item_nested(depth.saturating_add(1))?;
+                }
+            }
+            6 => self.skip_item_nested(depth.saturating_add(1))?,
+            _ => return Err(EatError::InvalidData),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+// Licensed under the Apache-2.0 license
+
+// EAT (Entity Attestation Token) claims decoding
+//
+// Decodes the claims map produced by `CborEncoder::encode_ocp_eat_claims`.
+// Without an allocator the nested measurement, DLOA and RIM locator
+// structures cannot be rebuilt as borrowed `OcpEatClaims` slices, so they are
+// returned as views into the input that can be iterated or re-parsed.
+
+mod cbor;
+
+pub use cbor::CborDecoder;
+
+use crate::eat_encoder::{
+    CLAIM_KEY_BOOTCOUNT, CLAIM_KEY_BOOTSEED, CLAIM_KEY_CTI, CLAIM_KEY_DBGSTAT, CLAIM_KEY_DLOAS,
+    CLAIM_KEY_EAT_PROFILE, CLAIM_KEY_HWMODEL, CLAIM_KEY_ISSUER, CLAIM_KEY_MEASUREMENTS,
+    CLAIM_KEY_NONCE, CLAIM_KEY_OEMID, CLAIM_KEY_RIM_LOCATORS, CLAIM_KEY_UEID, CLAIM_KEY_UPTIME,
+    DebugStatus, EatError, PrivateClaim,
+};
+
+// CBOR tag for OIDs, used by the eat_profile claim
+const CBOR_TAG_OID: u64 = 111;
+
+// Private claim keys are below this value
+const PRIVATE_CLAIM_KEY_LIMIT: i64 = -65536;
+
+// A measurement entry: [content_type, bstr .cbor concise-evidence]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedMeasurement<'a> {
+    pub content_type: u16,
+    pub concise_evidence: &'a [u8], // CBOR encoded concise evidence
+}
+
+// View over the encoded measurements array
+#[derive(Debug, Clone, Copy)]
+pub struct DecodedMeasurements<'a> {
+    count: u64,
+    items: &'a [u8],
+}
+
+impl<'a> DecodedMeasurements<'a> {
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn iter(&self) -> MeasurementIter<'a> {
+        MeasurementIter {
+            decoder: CborDecoder::new(self.items),
+            remaining: self.count,
+        }
+    }
+}
+
+pub struct MeasurementIter<'a> {
+    decoder: CborDecoder<'a>,
+    remaining: u64,
+}
+
+impl<'a> Iterator for MeasurementIter<'a> {
+    type Item = DecodedMeasurement<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // Entries were validated by decode_claims
+        decode_measurement(&mut self.decoder).ok()
+    }
+}
+
+// Iterator over the private claims (keys < -65536) of a claims map
+pub struct PrivateClaimIter<'a> {
+    decoder: CborDecoder<'a>,
+    remaining: u64,
+}
+
+impl<'a> Iterator for PrivateClaimIter<'a> {
+    type Item = PrivateClaim<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            let key = self.decoder.decode_int().ok()?;
+            if key < PRIVATE_CLAIM_KEY_LIMIT {
+                let value = self.decoder.decode_bytes().ok()?;
+                return Some(PrivateClaim {
+                    key: i32::try_from(key).ok()?,
+                    value,
+                });
+            }
+            self.decoder.skip_item().ok()?;
+        }
+        None
+    }
+}
+
+// Decoded OCP Profile EAT claims borrowing from the encoded token
+#[derive(Debug, Clone, Copy)]
+pub struct DecodedEatClaims<'a> {
+    // Mandatory claims
+    pub issuer: &'a str,
+    pub cti: &'a [u8],
+    pub nonce: &'a [u8],
+    pub dbgstat: DebugStatus,
+    pub eat_profile: &'a str,
+    pub measurements: DecodedMeasurements<'a>,
+
+    // Optional claims
+    pub ueid: Option<&'a [u8]>,
+    pub oemid: Option<&'a [u8]>,
+    pub hwmodel: Option<&'a [u8]>,
+    pub uptime: Option<u64>,
+    pub bootcount: Option<u64>,
+    pub bootseed: Option<&'a [u8]>,
+    pub dloas: Option<&'a [u8]>,        // CBOR encoded DLOA array
+    pub rim_locators: Option<&'a [u8]>, // CBOR encoded RIM locator array
+
+    // The complete claims map, used to iterate private claims
+    claims_map: &'a [u8],
+}
+
+impl<'a> DecodedEatClaims<'a> {
+    pub fn private_claims(&self) -> PrivateClaimIter<'a> {
+        let mut decoder = CborDecoder::new(self.claims_map);
+        let remaining = decoder.decode_map_header().unwrap_or(0);
+        PrivateClaimIter { decoder, remaining }
+    }
+}
+
+fn decode_measurement<'a>(
+    decoder: &mut CborDecoder<'a>,
+) -> Result<DecodedMeasurement<'a>, EatError> {
+    if decoder.decode_array_header()? != 2 {
+        return Err(EatError::InvalidData);
+    }
+    let content_type = u16::try_from(decoder.decode_uint()?).map_err(|_| EatError::InvalidData)?;
+    let concise_evidence = decoder.decode_bytes()?;
+    Ok(DecodedMeasurement {
+        content_type,
+        concise_evidence,
+    })
+}
+
+fn decode_debug_status(decoder: &mut CborDecoder) -> Result<DebugStatus, EatError> {
+    match decoder.decode_uint()? {
+        1 => Ok(DebugStatus::Disabled),
+        _ => Err(EatError::InvalidData),
+    }
+}
+
+// Skip over an array and return its encoding
+fn raw_array<'a>(decoder: &mut CborDecoder<'a>) -> Result<&'a [u8], EatError> {
+    let start = decoder.position();
+    if decoder.peek_major_type()? != 4 {
+        return Err(EatError::InvalidData);
+    }
+    decoder.skip_item()?;
+    decoder.slice_from(start)
+}
+
+/// Decode an OCP EAT claims map, as produced by
+/// `CborEncoder::encode_ocp_eat_claims`. Unknown claims are skipped.
+pub fn decode_claims(data: &[u8]) -> Result<DecodedEatClaims<'_>, EatError> {
+    let mut decoder = CborDecoder::new(data);
+    let entries = decoder.decode_map_header()?;
+
+    let mut issuer = None;
+    let mut cti = None;
+    let mut nonce = None;
+    let mut dbgstat = None;
+    let mut eat_profile = None;
+    let mut measurements = None;
+    let mut ueid = None;
+    let mut oemid = None;
+    let mut hwmodel = None;
+    let mut uptime = None;
+    let mut bootcount = None;
+    let mut bootseed = None;
+    let mut dloas = None;
+    let mut rim_locators = None;
+
+    for _ in 0..entries {
+        let key = decoder.decode_int()?;
+        let Ok(key) = i32::try_from(key) else {
+            decoder.skip_item()?;
+            continue;
+        };
+        match key {
+            CLAIM_KEY_ISSUER => issuer = Some(decoder.decode_text()?),
+            CLAIM_KEY_CTI => cti = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_NONCE => nonce = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_DBGSTAT => dbgstat = Some(decode_debug_status(&mut decoder)?),
+            CLAIM_KEY_EAT_PROFILE => {
+                if decoder
+                    .decode_optional_tag()?
+                    .is_some_and(|tag| tag != CBOR_TAG_OID)
+                {
+                    return Err(EatError::InvalidData);
+                }
+                let profile = decoder.decode_bytes()?;
+                eat_profile =
+                    Some(core::str::from_utf8(profile).map_err(|_| EatError::InvalidUtf8)?);
+            }
+            CLAIM_KEY_MEASUREMENTS => {
+                let count = decoder.decode_array_header()?;
+                let start = decoder.position();
+                for _ in 0..count {
+                    decode_measurement(&mut decoder)?;
+                }
+                measurements = Some(DecodedMeasurements {
+                    count,
+                    items: decoder.slice_from(start)?,
+                });
+            }
+            CLAIM_KEY_UEID => ueid = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_OEMID => oemid = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_HWMODEL => hwmodel = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_UPTIME => uptime = Some(decoder.decode_uint()?),
+            CLAIM_KEY_BOOTCOUNT => bootcount = Some(decoder.decode_uint()?),
+            CLAIM_KEY_BOOTSEED => bootseed = Some(decoder.decode_bytes()?),
+            CLAIM_KEY_DLOAS => dloas = Some(raw_array(&mut decoder)?),
+            CLAIM_KEY_RIM_LOCATORS => rim_locators = Some(raw_array(&mut decoder)?),
+            key if (key as i64) < PRIVATE_CLAIM_KEY_LIMIT => {
+                decoder.decode_bytes()?;
+            }
+            _ => decoder.skip_item()?,
+        }
+    }
+
+    // The claims map must be the whole input
+    if !decoder.is_empty() {
+        return Err(EatError::InvalidData);
+    }
+
+    Ok(DecodedEatClaims {
+        issuer: issuer.ok_or(EatError::MissingMandatoryClaim)?,
+        cti: cti.ok_or(EatError::MissingMandatoryClaim)?,
+        nonce: nonce.ok_or(EatError::MissingMandatoryClaim)?,
+        dbgstat: dbgstat.ok_or(EatError::MissingMandatoryClaim)?,
+        eat_profile: eat_profile.ok_or(EatError::MissingMandatoryClaim)?,
+        measurements: measurements.ok_or(EatError::MissingMandatoryClaim)?,
+        ueid,
+        oemid,
+        hwmodel,
+        uptime,
+        bootcount,
+        bootseed,
+        dloas,
+        rim_locators,
+        claims_map: data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eat_encoder::{
+        CborEncoder, ClassMap, ConciseEvidence, ConciseEvidenceMap, DigestAlgorithm, DigestEntry,
+        EnvironmentMap, EvTriplesMap, EvidenceTripleRecord, MeasurementFormat, MeasurementMap,
+        MeasurementValue, OcpEatClaims,
+    };
+
+    #[test]
+    fn test_decode_claims_round_trip() {
+        let digest = [0xab; 64];
+        let digests = [DigestEntry::with_algorithm(
+            DigestAlgorithm::Sha512,
+            &digest,
+        )];
+        let measurement_maps = [MeasurementMap {
+            key: 0,
+            mval: MeasurementValue {
+                version: Some("1.0.0"),
+                svn: Some(3),
+                digests: Some(&digests),
+                integrity_registers: None,
+                raw_value: None,
+                raw_value_mask: None,
+            },
+        }];
+        let evidence_triples = [EvidenceTripleRecord {
+            environment: EnvironmentMap {
+                class: ClassMap {
+                    class_id: "test-class",
+                    vendor: Some("vendor"),
+                    model: None,
+                },
+            },
+            measurements: &measurement_maps,
+        }];
+        let evidence = ConciseEvidence::Map(ConciseEvidenceMap {
+            ev_triples: EvTriplesMap {
+                evidence_triples: Some(&evidence_triples),
+                identity_triples: None,
+                dependency_triples: None,
+                membership_triples: None,
+                coswid_triples: None,
+                attest_key_triples: None,
+            },
+            evidence_id: None,
+            profile: None,
+        });
+        let measurements = [MeasurementFormat::new(&evidence)];
+
+        let cti = [1u8; 16];
+        let nonce = [2u8; 32];
+        let ueid = [3u8; 17];
+        let private_value = [4u8; 5];
+        let private_claims = [PrivateClaim {
+            key: -70000,
+            value: &private_value,
+        }];
+        let mut claims = OcpEatClaims::new(
+            "issuer",
+            &cti,
+            &nonce,
+            DebugStatus::Disabled,
+            "2.16.840.1.113741",
+            &measurements,
+        );
+        claims.ueid = Some(&ueid);
+        claims.bootcount = Some(42);
+        claims.uptime = Some(1234);
+        claims.private_claims = &private_claims;
+
+        let mut buffer = [0u8; 1024];
+        let mut encoder = CborEncoder::new(&mut buffer);
+        encoder.encode_ocp_eat_claims(&claims).unwrap();
+        let len = encoder.len();
+
+        let decoded = decode_claims(&buffer[..len]).unwrap();
+        assert_eq!(decoded.issuer, claims.issuer);
+        assert_eq!(decoded.cti, claims.cti);
+        assert_eq!(decoded.nonce, claims.nonce);
+        assert!(matches!(decoded.dbgstat, DebugStatus::Disabled));
+        assert_eq!(decoded.eat_profile, claims.eat_profile);
+        assert_eq!(decoded.ueid, claims.ueid);
+        assert_eq!(decoded.bootcount, Some(42));
+        assert_eq!(decoded.uptime, Some(1234));
+        assert_eq!(decoded.oemid, None);
+        assert_eq!(decoded.dloas, None);
+
+        // Measurements round trip to the same concise evidence encoding
+        let mut evidence_buffer = [0u8; 512];
+        let mut evidence_encoder = CborEncoder::new(&mut evidence_buffer);
+        evidence_encoder.encode_concise_evidence(&evidence).unwrap();
+        let evidence_len = evidence_encoder.len();
+        assert_eq!(decoded.measurements.len(), 1);
+        let measurement = decoded.measurements.iter().next().unwrap();
+        assert_eq!(measurement.content_type, measurements[0].content_type);
+        assert_eq!(
+            measurement.concise_evidence,
+            &evidence_buffer[..evidence_len]
+        );
+
+        let mut private = decoded.private_claims();
+        let claim = private.next().unwrap();
+        assert_eq!(claim.key, -70000);
+        assert_eq!(claim.value, &private_value);
+        assert!(private.next().is_none());
+    }
+
+    #[test]
+    fn test_decode_claims_errors() {
+        // Truncated map
+        assert!(matches!(decode_claims(&[0xa1]), Err(EatError::InvalidData)));
+        // Empty map is missing the mandatory claims
+        assert!(matches!(
+            decode_claims(&[0xa0]),
+            Err(EatError::MissingMandatoryClaim)
+        ));
+        // Not a map
+        assert!(matches!(decode_claims(&[0x80]), Err(EatError::InvalidData)));
+    }
+}
@@ -25,10 +25,54 @@ pub const CE_COSWID_TAG_ID: i32 = 0;
 pub const CE_COSWID_EVIDENCE: i32 = 1;
 pub const CE_AUTHORIZED_BY: i32 = 2;
 
+// Digest algorithms, encoded with their IANA Named Information hash algorithm identifiers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    #[default]
+    Sha384, // Used by Caliptra
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    // Named Information Hash Algorithm Registry identifier, as used by CoRIM digests
+    pub const fn alg_id(self) -> i32 {
+        match self {
+            DigestAlgorithm::Sha256 => 1,
+            DigestAlgorithm::Sha384 => 7,
+            DigestAlgorithm::Sha512 => 8,
+        }
+    }
+
+    // Digest length in bytes
+    pub const fn digest_len(self) -> usize {
+        match self {
+            DigestAlgorithm::Sha256 => 32,
+            DigestAlgorithm::Sha384 => 48,
+            DigestAlgorithm::Sha512 => 64,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DigestEntry<'a> {
-    pub alg_id: i32,     // Algorithm identifier (e.g., SHA-256 = -16)
-    pub value: &'a [u8], // Digest value
+    pub alg: DigestAlgorithm, // Digest algorithm
+    pub value: &'a [u8],      // Digest value
+}
+
+impl<'a> DigestEntry<'a> {
+    /// Create a digest entry using the default SHA-384 algorithm
+    pub const fn new(value: &'a [u8]) -> Self {
+        Self {
+            alg: DigestAlgorithm::Sha384,
+            value,
+        }
+    }
+
+    /// Create a digest entry for the given algorithm
+    pub const fn with_algorithm(alg: DigestAlgorithm, value: &'a [u8]) -> Self {
+        Self { alg, value }
+    }
 }
 
 // Integrity register identifier choice (uint or text)
@@ -184,10 +228,13 @@ pub enum ConciseEvidence<'a> {
 
 // Extension methods for CborEncoder to handle concise evidence encoding
 impl CborEncoder<'_> {
-    // Encode digest entry
+    // Encode digest entry; the value must match the algorithm's digest length
     pub fn encode_digest_entry(&mut self, digest: &DigestEntry) -> Result<(), EatError> {
+        if digest.value.len() != digest.alg.digest_len() {
+            return Err(EatError::InvalidData);
+        }
         self.encode_array_header(2)?; // [alg_id, value]
-        self.encode_int(digest.alg_id as i64)?;
+        self.encode_int(digest.alg.alg_id() as i64)?;
         self.encode_bytes(digest.value)?;
         Ok(())
     }
@@ -675,3 +722,58 @@ impl CborEncoder<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_digest(digest: &DigestEntry, buffer: &mut [u8]) -> Result<usize, EatError> {
+        let mut encoder = CborEncoder::new(buffer);
+        encoder.encode_digest_entry(digest)?;
+        Ok(encoder.len())
+    }
+
+    #[test]
+    fn test_digest_entry_algorithm_ids() {
+        let value = [0x5a; 64];
+        let mut buffer = [0u8; 80];
+
+        // [alg_id, bstr .size digest_len]
+        for (alg, id, len) in [
+            (DigestAlgorithm::Sha256, 1, 32),
+            (DigestAlgorithm::Sha384, 7, 48),
+            (DigestAlgorithm::Sha512, 8, 64),
+        ] {
+            assert_eq!(alg.digest_len(), len);
+            let encoded_len = encode_digest(
+                &DigestEntry::with_algorithm(alg, &value[..len]),
+                &mut buffer,
+            )
+            .unwrap();
+            assert_eq!(&buffer[..4], &[0x82, id, 0x58, len as u8]);
+            assert_eq!(&buffer[4..encoded_len], &value[..len]);
+        }
+
+        // SHA-384 is the default
+        assert_eq!(DigestEntry::new(&value).alg, DigestAlgorithm::Sha384);
+        assert_eq!(DigestAlgorithm::default(), DigestAlgorithm::Sha384);
+    }
+
+    #[test]
+    fn test_digest_entry_length_mismatch() {
+        let value = [0x5a; 48];
+        let mut buffer = [0u8; 80];
+
+        for alg in [DigestAlgorithm::Sha256, DigestAlgorithm::Sha512] {
+            assert!(matches!(
+                encode_digest(&DigestEntry::with_algorithm(alg, &value), &mut buffer),
+                Err(EatError::InvalidData)
+            ));
+        }
+        assert!(matches!(
+            encode_digest(&DigestEntry::new(&value[..47]), &mut buffer),
+            Err(EatError::InvalidData)
+        ));
+        assert!(encode_digest(&DigestEntry::new(&value), &mut buffer).is_ok());
+    }
+}
@@ -13,6 +13,8 @@ pub enum EatError {
     InvalidClaimSize,
     EncodingError,
     InvalidUtf8,
+    InvalidNonceLength,
+    InvalidUeidLength,
 }
 
 // Constants for claim keys (as per OCP Profile spec)
@@ -33,6 +35,12 @@ pub const CLAIM_KEY_BOOTSEED: i32 = 268;
 pub const CLAIM_KEY_DLOAS: i32 = 269;
 pub const CLAIM_KEY_RIM_LOCATORS: i32 = -70001;
 
+// Claim size limits (as per EAT spec)
+pub const NONCE_MIN_LEN: usize = 8;
+pub const NONCE_MAX_LEN: usize = 64;
+pub const UEID_MIN_LEN: usize = 7;
+pub const UEID_MAX_LEN: usize = 33;
+
 #[derive(Debug, Clone, Copy)]
 #[repr(u8)]
 pub enum DebugStatus {
@@ -417,7 +425,7 @@ impl EatEncoder {
             return Err(EatError::InvalidClaimSize);
         }
 
-        if claims.nonce.len() < 8 || claims.nonce.len() > 64 {
+        if !(NONCE_MIN_LEN..=NONCE_MAX_LEN).contains(&claims.nonce.len()) {
             return Err(EatError::InvalidClaimSize);
         }
 
@@ -431,7 +439,7 @@ impl EatEncoder {
 
         // Validate optional claims size constraints
         if let Some(ueid) = claims.ueid {
-            if ueid.len() < 7 || ueid.len() > 33 {
+            if !(UEID_MIN_LEN..=UEID_MAX_LEN).contains(&ueid.len()) {
                 return Err(EatError::InvalidClaimSize);
             }
         }
@@ -557,6 +565,24 @@ impl<'a> OcpEatClaims<'a> {
             private_claims: &[],
         }
     }
+
+    /// Set the nonce claim, rejecting lengths outside 8-64 bytes
+    pub fn set_nonce(&mut self, nonce: &'a [u8]) -> Result<(), EatError> {
+        if !(NONCE_MIN_LEN..=NONCE_MAX_LEN).contains(&nonce.len()) {
+            return Err(EatError::InvalidNonceLength);
+        }
+        self.nonce = nonce;
+        Ok(())
+    }
+
+    /// Set the UEID claim, rejecting lengths outside 7-33 bytes
+    pub fn set_ueid(&mut self, ueid: &'a [u8]) -> Result<(), EatError> {
+        if !(UEID_MIN_LEN..=UEID_MAX_LEN).contains(&ueid.len()) {
+            return Err(EatError::InvalidUeidLength);
+        }
+        self.ueid = Some(ueid);
+        Ok(())
+    }
 }
 
 impl<'a> MeasurementFormat<'a> {
@@ -618,3 +644,59 @@ pub fn create_sign1_context(
 
     Ok(encoder.len())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: [u8; 8] = [0x11; 8];
+
+    fn claims() -> OcpEatClaims<'static> {
+        OcpEatClaims::new(
+            "issuer",
+            &[0x22; 8],
+            &NONCE,
+            DebugStatus::Disabled,
+            "profile",
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_set_nonce_length() {
+        let data = [0xaa; 65];
+        let mut claims = claims();
+
+        for len in [7, 65] {
+            assert!(matches!(
+                claims.set_nonce(&data[..len]),
+                Err(EatError::InvalidNonceLength)
+            ));
+            assert_eq!(claims.nonce, &NONCE);
+        }
+
+        for len in [8, 33, 34, 64] {
+            claims.set_nonce(&data[..len]).unwrap();
+            assert_eq!(claims.nonce.len(), len);
+        }
+    }
+
+    #[test]
+    fn test_set_ueid_length() {
+        let data = [0xbb; 65];
+        let mut claims = claims();
+
+        for len in [6, 34, 64, 65] {
+            assert!(matches!(
+                claims.set_ueid(&data[..len]),
+                Err(EatError::InvalidUeidLength)
+            ));
+            assert!(claims.ueid.is_none());
+        }
+
+        for len in [7, 8, 33] {
+            claims.set_ueid(&data[..len]).unwrap();
+            assert_eq!(claims.ueid.map(|ueid| ueid.len()), Some(len));
+        }
+    }
+}
@@ -35,15 +35,19 @@ pub use eat::{
     EatEncoder,
     EatError,
     MeasurementFormat,
+    NONCE_MAX_LEN,
+    NONCE_MIN_LEN,
     OcpEatClaims,
     PrivateClaim,
     ProtectedHeader,
+    UEID_MAX_LEN,
+    UEID_MIN_LEN,
     cose_headers,
     create_sign1_context,
 };
 
 // Re-export only used items from the concise_evidence module
 pub use concise_evidence::{
-    ClassMap, ConciseEvidence, ConciseEvidenceMap, DigestEntry, EnvironmentMap, EvTriplesMap,
-    EvidenceTripleRecord, MeasurementMap, MeasurementValue, TaggedConciseEvidence,
+    ClassMap, ConciseEvidence, ConciseEvidenceMap, DigestAlgorithm, DigestEntry, EnvironmentMap,
+    EvTriplesMap, EvidenceTripleRecord, MeasurementMap, MeasurementValue, TaggedConciseEvidence,
 };
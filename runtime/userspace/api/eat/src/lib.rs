@@ -4,7 +4,8 @@
 //! OCP EAT (Entity Attestation Token) encoder library
 //!
 //! This library provides a no_std compatible implementation for encoding
-//! OCP Profile Entity Attestation Tokens using CBOR and COSE Sign1.
+//! OCP Profile Entity Attestation Tokens using CBOR and COSE Sign1, and for
+//! decoding the claims back with [`decode_claims`].
 //!
 //! # Features
 //!
@@ -58,6 +59,7 @@
 //! let measurement_format = MeasurementFormat::new(&evidence);
 //! ```
 
+pub mod eat_decoder;
 pub mod eat_encoder;
 
 // Re-export main types for easier usage
@@ -73,6 +75,7 @@ pub use eat_encoder::{
 
     // Enums and constants
     DebugStatus,
+    DigestAlgorithm,
     DigestEntry,
     DloaType,
     // Encoder
@@ -96,6 +99,10 @@ pub use eat_encoder::{
     cose_headers,
 };
 
+pub use eat_decoder::{
+    CborDecoder, DecodedEatClaims, DecodedMeasurement, DecodedMeasurements, decode_claims,
+};
+
 // Re-export claim key constants
 pub use eat_encoder::{
     CLAIM_KEY_BOOTCOUNT, CLAIM_KEY_BOOTSEED, CLAIM_KEY_CTI, CLAIM_KEY_DBGSTAT, CLAIM_KEY_DLOAS,
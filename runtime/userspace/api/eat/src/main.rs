@@ -40,6 +40,8 @@ impl fmt::Display for EatError {
             EatError::InvalidClaimSize => write!(f, "Invalid claim size"),
             EatError::EncodingError => write!(f, "Encoding error"),
             EatError::InvalidUtf8 => write!(f, "Invalid UTF-8"),
+            EatError::InvalidNonceLength => write!(f, "Invalid nonce length"),
+            EatError::InvalidUeidLength => write!(f, "Invalid UEID length"),
         }
     }
 }
@@ -241,17 +243,22 @@ pub fn create_example_eat(
 ) -> Result<usize, EatError> {
     let cti = [0x01; 16];
     let nonce = [0x02; 16];
+    // RAND-type UEID: type byte followed by 16 random bytes
+    let mut ueid = [0x03; 17];
+    ueid[0] = 0x01;
     let measurement = MeasurementFormat::new(concise_evidence);
     let measurements_array = [measurement];
 
-    let claims = OcpEatClaims::new(
+    let mut claims = OcpEatClaims::new(
         issuer,
         &cti,
-        &nonce,
+        &[],
         DebugStatus::Disabled,
         "1.2.3.4.5",
         &measurements_array,
     );
+    claims.set_nonce(&nonce)?;
+    claims.set_ueid(&ueid)?;
 
     // Use P-384 (ES384) for our P-384 key
     let protected_header = ProtectedHeader::new_es384();
@@ -333,10 +340,7 @@ fn create_mock_concise_evidence_structured() -> eat_encoder::ConciseEvidence<'st
         mval: MeasurementValue {
             version: Some("1.2.3"),
             svn: Some(1),
-            digests: Some(&[DigestEntry {
-                alg_id: 7, // SHA-384
-                value: DIGEST_DATA,
-            }]),
+            digests: Some(&[DigestEntry::new(DIGEST_DATA)]), // SHA-384
             integrity_registers: None,
             raw_value: None,
             raw_value_mask: None,
@@ -534,6 +538,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 EatError::InvalidUtf8 => {
                     println!("  Error: Invalid UTF-8 string");
                 }
+                EatError::InvalidNonceLength => {
+                    println!("  Error: Nonce must be 8-64 bytes");
+                }
+                EatError::InvalidUeidLength => {
+                    println!("  Error: UEID must be 7-33 bytes");
+                }
             }
             return Err(format!("EAT encoding failed: {:?}", error).into());
         }
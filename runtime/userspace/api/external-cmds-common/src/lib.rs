@@ -7,6 +7,7 @@ extern crate alloc;
 
 use alloc::boxed::Box;
 use async_trait::async_trait;
+use core::str::FromStr;
 use zerocopy::{Immutable, IntoBytes};
 
 pub const MAX_FW_VERSION_LEN: usize = 32;
@@ -28,6 +29,25 @@ pub struct FirmwareVersion {
     pub ver_str: [u8; MAX_FW_VERSION_LEN],
 }
 
+impl FromStr for FirmwareVersion {
+    type Err = CommandError;
+
+    /// Creates a firmware version from a string, failing with `CommandError::RespLengthTooLarge`
+    /// if it does not fit in `MAX_FW_VERSION_LEN` bytes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        if bytes.len() > MAX_FW_VERSION_LEN {
+            return Err(CommandError::RespLengthTooLarge);
+        }
+        let mut ver_str = [0u8; MAX_FW_VERSION_LEN];
+        ver_str[..bytes.len()].copy_from_slice(bytes);
+        Ok(Self {
+            len: bytes.len(),
+            ver_str,
+        })
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct DeviceId {
@@ -44,6 +64,22 @@ pub struct Uid {
     pub unique_chip_id: [u8; MAX_UID_LEN],
 }
 
+impl Uid {
+    /// Creates a UID from a byte slice, failing with `CommandError::RespLengthTooLarge` if it
+    /// does not fit in `MAX_UID_LEN` bytes.
+    pub fn from_slice(uid: &[u8]) -> Result<Self, CommandError> {
+        if uid.len() > MAX_UID_LEN {
+            return Err(CommandError::RespLengthTooLarge);
+        }
+        let mut unique_chip_id = [0u8; MAX_UID_LEN];
+        unique_chip_id[..uid.len()].copy_from_slice(uid);
+        Ok(Self {
+            len: uid.len(),
+            unique_chip_id,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DeviceInfo {
     Uid(Uid),
@@ -60,6 +96,16 @@ pub struct DeviceCapabilities {
     pub reserved: [u8; 4],     // Bytes [28:31]
 }
 
+/// Device information gathered in a single request for device discovery.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DeviceInfoBundle {
+    pub device_id: DeviceId,
+    pub capabilities: DeviceCapabilities,
+    pub uid: Uid,
+    /// Firmware version at index 0.
+    pub firmware_version: FirmwareVersion,
+}
+
 /// Asynchronous trait for handling commands common to both external MCU mailbox and MCTP VDM protocols.
 ///
 /// Each function represents a protocol-agnostic command handler. Implementors should provide
@@ -110,4 +156,127 @@ pub trait UnifiedCommandHandler {
         &self,
         capabilities: &mut DeviceCapabilities,
     ) -> Result<(), CommandError>;
+
+    /// Retrieves the device ID, capabilities, UID and firmware version at index 0 at once.
+    ///
+    /// This reduces the number of protocol round-trips needed for device discovery. The default
+    /// implementation queries each field through the individual getters.
+    ///
+    /// # Returns
+    /// * `Result<DeviceInfoBundle, CommandError>` - The aggregated device information on success,
+    ///   or the first error returned by an individual getter.
+    async fn get_all_device_info(&self) -> Result<DeviceInfoBundle, CommandError> {
+        let mut bundle = DeviceInfoBundle::default();
+        self.get_device_id(&mut bundle.device_id).await?;
+        self.get_device_capabilities(&mut bundle.capabilities)
+            .await?;
+
+        let mut info = DeviceInfo::Uid(Uid::default());
+        self.get_device_info(0, &mut info).await?;
+        let DeviceInfo::Uid(uid) = info;
+        bundle.uid = uid;
+
+        self.get_firmware_version(0, &mut bundle.firmware_version)
+            .await?;
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    struct MockHandler;
+
+    #[async_trait]
+    impl UnifiedCommandHandler for MockHandler {
+        async fn get_firmware_version(
+            &self,
+            index: u32,
+            version: &mut FirmwareVersion,
+        ) -> Result<(), CommandError> {
+            if index != 0 {
+                return Err(CommandError::InvalidParams);
+            }
+            *version = FirmwareVersion::from_str("1.2.3")?;
+            Ok(())
+        }
+
+        async fn get_device_id(&self, device_id: &mut DeviceId) -> Result<(), CommandError> {
+            *device_id = DeviceId {
+                vendor_id: 0x1414,
+                device_id: 0x0010,
+                subsystem_vendor_id: 0x0001,
+                subsystem_id: 0x0002,
+            };
+            Ok(())
+        }
+
+        async fn get_device_info(
+            &self,
+            index: u32,
+            info: &mut DeviceInfo,
+        ) -> Result<(), CommandError> {
+            if index != 0 {
+                return Err(CommandError::InvalidParams);
+            }
+            *info = DeviceInfo::Uid(Uid::from_slice(&[0xaa, 0xbb, 0xcc, 0xdd])?);
+            Ok(())
+        }
+
+        async fn get_device_capabilities(
+            &self,
+            capabilities: &mut DeviceCapabilities,
+        ) -> Result<(), CommandError> {
+            capabilities.mcu_rt = [1, 2, 3, 4, 5, 6, 7, 8];
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_get_all_device_info() {
+        let handler = MockHandler;
+        let bundle = block_on(handler.get_all_device_info()).unwrap();
+
+        assert_eq!(bundle.device_id.vendor_id, 0x1414);
+        assert_eq!(bundle.device_id.device_id, 0x0010);
+        assert_eq!(bundle.device_id.subsystem_vendor_id, 0x0001);
+        assert_eq!(bundle.device_id.subsystem_id, 0x0002);
+        assert_eq!(bundle.capabilities.mcu_rt, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            bundle.uid.unique_chip_id[..bundle.uid.len],
+            [0xaa, 0xbb, 0xcc, 0xdd]
+        );
+        assert_eq!(
+            &bundle.firmware_version.ver_str[..bundle.firmware_version.len],
+            b"1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_firmware_version_from_str() {
+        let s = core::str::from_utf8(&[b'a'; MAX_FW_VERSION_LEN]).unwrap();
+        let version = FirmwareVersion::from_str(s).unwrap();
+        assert_eq!(version.len, MAX_FW_VERSION_LEN);
+        assert_eq!(&version.ver_str[..], s.as_bytes());
+
+        let s = core::str::from_utf8(&[b'a'; MAX_FW_VERSION_LEN + 1]).unwrap();
+        assert!(matches!(
+            FirmwareVersion::from_str(s),
+            Err(CommandError::RespLengthTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_uid_from_slice() {
+        let uid = Uid::from_slice(&[0x5a; MAX_UID_LEN]).unwrap();
+        assert_eq!(uid.len, MAX_UID_LEN);
+        assert_eq!(uid.unique_chip_id, [0x5a; MAX_UID_LEN]);
+
+        assert!(matches!(
+            Uid::from_slice(&[0x5a; MAX_UID_LEN + 1]),
+            Err(CommandError::RespLengthTooLarge)
+        ));
+    }
 }
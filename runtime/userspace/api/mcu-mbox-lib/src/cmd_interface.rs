@@ -1,6 +1,8 @@
 // Licensed under the Apache-2.0 license
 
+use crate::dispatcher::{DispatcherError, MailboxCmdHandler, MailboxDispatcher};
 use crate::transport::McuMboxTransport;
+use async_trait::async_trait;
 use core::sync::atomic::{AtomicBool, Ordering};
 use external_cmds_common::{
     DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, UnifiedCommandHandler, MAX_UID_LEN,
@@ -24,18 +26,17 @@ pub enum MsgHandlerError {
 /// Command interface for handling MCU mailbox commands.
 pub struct CmdInterface<'a> {
     transport: &'a mut McuMboxTransport,
-    non_crypto_cmds_handler: &'a dyn UnifiedCommandHandler,
+    dispatcher: MailboxDispatcher<'a>,
     busy: AtomicBool,
 }
 
 impl<'a> CmdInterface<'a> {
-    pub fn new(
-        transport: &'a mut McuMboxTransport,
-        non_crypto_cmds_handler: &'a dyn UnifiedCommandHandler,
-    ) -> Self {
+    /// Requests are routed through `dispatcher`; commands without a registered handler
+    /// complete with `MbxCmdStatus::Failure`.
+    pub fn new(transport: &'a mut McuMboxTransport, dispatcher: MailboxDispatcher<'a>) -> Self {
         Self {
             transport,
-            non_crypto_cmds_handler,
+            dispatcher,
             busy: AtomicBool::new(false),
         }
     }
@@ -52,7 +53,15 @@ impl<'a> CmdInterface<'a> {
             .map_err(|_| MsgHandlerError::Transport)?;
 
         // Process the request and prepare the response.
-        let (resp_len, status) = self.process_request(msg_buf, cmd_id, req_len).await?;
+        let (resp_len, status) = match self.process_request(msg_buf, cmd_id, req_len).await {
+            Err(MsgHandlerError::UnsupportedCommand) => {
+                self.transport
+                    .finalize_response(MbxCmdStatus::Failure)
+                    .map_err(|_| MsgHandlerError::Transport)?;
+                return Err(MsgHandlerError::UnsupportedCommand);
+            }
+            result => result?,
+        };
 
         // Send the response back via the transport.
         self.transport
@@ -80,17 +89,63 @@ impl<'a> CmdInterface<'a> {
 
         self.busy.store(true, Ordering::SeqCst);
 
-        let result = match CommandId::from(cmd) {
+        let result = self
+            .dispatcher
+            .dispatch(CommandId::from(cmd), msg_buf, req_len)
+            .await;
+
+        self.busy.store(false, Ordering::SeqCst);
+        result
+    }
+}
+
+/// Handles the non-crypto commands backed by a [`UnifiedCommandHandler`].
+pub struct NonCryptoCmdHandler<'a> {
+    non_crypto_cmds_handler: &'a dyn UnifiedCommandHandler,
+}
+
+#[async_trait(?Send)]
+impl MailboxCmdHandler for NonCryptoCmdHandler<'_> {
+    async fn handle(
+        &self,
+        cmd: CommandId,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<(usize, MbxCmdStatus), MsgHandlerError> {
+        match cmd {
             CommandId::MC_FIRMWARE_VERSION => self.handle_fw_version(msg_buf, req_len).await,
             CommandId::MC_DEVICE_CAPABILITIES => self.handle_device_caps(msg_buf, req_len).await,
             CommandId::MC_DEVICE_ID => self.handle_device_id(msg_buf, req_len).await,
             CommandId::MC_DEVICE_INFO => self.handle_device_info(msg_buf, req_len).await,
-            // TODO: Add more command handlers
             _ => Err(MsgHandlerError::UnsupportedCommand),
-        };
+        }
+    }
+}
 
-        self.busy.store(false, Ordering::SeqCst);
-        result
+impl<'a> NonCryptoCmdHandler<'a> {
+    /// Commands served by this handler.
+    pub const COMMANDS: [CommandId; 4] = [
+        CommandId::MC_FIRMWARE_VERSION,
+        CommandId::MC_DEVICE_CAPABILITIES,
+        CommandId::MC_DEVICE_ID,
+        CommandId::MC_DEVICE_INFO,
+    ];
+
+    pub fn new(non_crypto_cmds_handler: &'a dyn UnifiedCommandHandler) -> Self {
+        Self {
+            non_crypto_cmds_handler,
+        }
+    }
+
+    /// Registers this handler for all of [`NonCryptoCmdHandler::COMMANDS`].
+    pub fn register(
+        &'a self,
+        dispatcher: &mut MailboxDispatcher<'a>,
+    ) -> Result<(), DispatcherError> {
+        for cmd in Self::COMMANDS {
+            dispatcher.register(cmd, self)?;
+        }
+        Ok(())
     }
 
     async fn handle_fw_version(
@@ -1,10 +1,10 @@
 // Licensed under the Apache-2.0 license
 
 use crate::cmd_interface::CmdInterface;
+use crate::dispatcher::MailboxDispatcher;
 use crate::transport::McuMboxTransport;
 use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_executor::Spawner;
-use external_cmds_common::UnifiedCommandHandler;
 
 const MAX_MCU_MBOX_MSG_SIZE: usize = 2048; // Adjust as needed
 
@@ -29,12 +29,13 @@ pub struct McuMboxService<'a> {
 }
 
 impl<'a> McuMboxService<'a> {
+    /// Requests are served by the handlers registered with `dispatcher`.
     pub fn init(
-        non_crypto_cmd_handler: &'a dyn UnifiedCommandHandler,
+        dispatcher: MailboxDispatcher<'a>,
         transport: &'a mut McuMboxTransport,
         spawner: Spawner,
     ) -> Self {
-        let cmd_interface = CmdInterface::new(transport, non_crypto_cmd_handler);
+        let cmd_interface = CmdInterface::new(transport, dispatcher);
         Self {
             spawner,
             cmd_interface,
@@ -0,0 +1,169 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cmd_interface::MsgHandlerError;
+use async_trait::async_trait;
+use libsyscall_caliptra::mcu_mbox::MbxCmdStatus;
+use mcu_mbox_common::messages::CommandId;
+
+/// Maximum number of command IDs that can be registered with a [`MailboxDispatcher`].
+pub const MAX_MBOX_CMD_HANDLERS: usize = 32;
+
+/// Handler for one or more MCU mailbox commands.
+#[async_trait(?Send)]
+pub trait MailboxCmdHandler {
+    /// Handles the request for `cmd` held in `msg_buf[..req_len]`.
+    ///
+    /// The response (including its header and checksum) is written to the start of `msg_buf`.
+    /// Returns the response length and the status to finalize the command with.
+    async fn handle(
+        &self,
+        cmd: CommandId,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<(usize, MbxCmdStatus), MsgHandlerError>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DispatcherError {
+    /// `MAX_MBOX_CMD_HANDLERS` command IDs are already registered.
+    TableFull,
+    /// A handler is already registered for this command ID.
+    AlreadyRegistered,
+}
+
+/// Routes MCU mailbox requests to the handler registered for their command ID.
+pub struct MailboxDispatcher<'a> {
+    handlers: [Option<(CommandId, &'a dyn MailboxCmdHandler)>; MAX_MBOX_CMD_HANDLERS],
+}
+
+impl Default for MailboxDispatcher<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MailboxDispatcher<'a> {
+    pub fn new() -> Self {
+        Self {
+            handlers: [None; MAX_MBOX_CMD_HANDLERS],
+        }
+    }
+
+    /// Registers `handler` for requests with command ID `cmd`.
+    pub fn register(
+        &mut self,
+        cmd: CommandId,
+        handler: &'a dyn MailboxCmdHandler,
+    ) -> Result<(), DispatcherError> {
+        if self.handler(cmd).is_some() {
+            return Err(DispatcherError::AlreadyRegistered);
+        }
+        let slot = self
+            .handlers
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(DispatcherError::TableFull)?;
+        *slot = Some((cmd, handler));
+        Ok(())
+    }
+
+    /// Returns the handler registered for `cmd`, if any.
+    pub fn handler(&self, cmd: CommandId) -> Option<&'a dyn MailboxCmdHandler> {
+        self.handlers
+            .iter()
+            .flatten()
+            .find(|(id, _)| *id == cmd)
+            .map(|(_, handler)| *handler)
+    }
+
+    /// Runs the handler registered for `cmd`, or fails with
+    /// `MsgHandlerError::UnsupportedCommand` if there is none.
+    pub async fn dispatch(
+        &self,
+        cmd: CommandId,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<(usize, MbxCmdStatus), MsgHandlerError> {
+        let handler = self
+            .handler(cmd)
+            .ok_or(MsgHandlerError::UnsupportedCommand)?;
+        handler.handle(cmd, msg_buf, req_len).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    const CMD_A: CommandId = CommandId(0x4141_4141);
+    const CMD_B: CommandId = CommandId(0x4242_4242);
+
+    // Echoes the request length and a per-handler tag byte
+    struct TagHandler(u8);
+
+    #[async_trait(?Send)]
+    impl MailboxCmdHandler for TagHandler {
+        async fn handle(
+            &self,
+            _cmd: CommandId,
+            msg_buf: &mut [u8],
+            req_len: usize,
+        ) -> Result<(usize, MbxCmdStatus), MsgHandlerError> {
+            msg_buf[0] = self.0;
+            Ok((req_len, MbxCmdStatus::Complete))
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let handler_a = TagHandler(0xaa);
+        let handler_b = TagHandler(0xbb);
+        let mut dispatcher = MailboxDispatcher::new();
+        dispatcher.register(CMD_A, &handler_a).unwrap();
+        dispatcher.register(CMD_B, &handler_b).unwrap();
+        assert_eq!(
+            dispatcher.register(CMD_A, &handler_b),
+            Err(DispatcherError::AlreadyRegistered)
+        );
+
+        let mut msg_buf = [0u8; 16];
+        block_on(async {
+            let (len, status) = dispatcher
+                .dispatch(CMD_A, &mut msg_buf, 4)
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!((len, status, msg_buf[0]), (4, MbxCmdStatus::Complete, 0xaa));
+
+            let (len, status) = dispatcher
+                .dispatch(CMD_B, &mut msg_buf, 8)
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!((len, status, msg_buf[0]), (8, MbxCmdStatus::Complete, 0xbb));
+
+            msg_buf.fill(0);
+            assert!(matches!(
+                dispatcher
+                    .dispatch(CommandId(0x4343_4343), &mut msg_buf, 4)
+                    .await,
+                Err(MsgHandlerError::UnsupportedCommand)
+            ));
+            assert_eq!(msg_buf[0], 0);
+        });
+    }
+
+    #[test]
+    fn test_register_table_full() {
+        let handler = TagHandler(0);
+        let mut dispatcher = MailboxDispatcher::new();
+        for i in 0..MAX_MBOX_CMD_HANDLERS as u32 {
+            dispatcher.register(CommandId(i), &handler).unwrap();
+        }
+        assert_eq!(
+            dispatcher.register(CommandId(MAX_MBOX_CMD_HANDLERS as u32), &handler),
+            Err(DispatcherError::TableFull)
+        );
+    }
+}
@@ -5,4 +5,5 @@
 
 pub mod cmd_interface;
 pub mod daemon;
+pub mod dispatcher;
 pub mod transport;
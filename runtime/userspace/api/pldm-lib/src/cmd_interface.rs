@@ -107,6 +107,10 @@ impl<'a> CmdInterface<'a> {
         Ok(())
     }
 
+    pub async fn abort_update(&self) -> Result<(), MsgHandlerError> {
+        self.fd_ctx.abort_update().await
+    }
+
     pub async fn should_start_initiator_mode(&self) -> bool {
         self.fd_ctx.should_start_initiator_mode().await
     }
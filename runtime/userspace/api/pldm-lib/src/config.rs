@@ -7,6 +7,7 @@ use pldm_common::protocol::firmware_update::{FwUpdateCmd, PldmFdTime};
 
 pub const PLDM_PROTOCOL_CAP_COUNT: usize = 2;
 pub const FD_MAX_XFER_SIZE: usize = 512; // Arbitrary limit and change as needed.
+pub const FD_MAX_COMPONENT_COUNT: usize = 8; // Maximum number of components tracked in one update.
 pub const DEFAULT_FD_T1_TIMEOUT: PldmFdTime = 120000; // FD_T1 update mode idle timeout, range is [60s, 120s].
 pub const DEFAULT_FD_T2_RETRY_TIME: PldmFdTime = 5000; // FD_T2 retry request for firmware data, range is [1s, 5s].
 pub const INSTANCE_ID_COUNT: u8 = 32;
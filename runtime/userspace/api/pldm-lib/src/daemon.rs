@@ -5,7 +5,7 @@ use crate::config;
 use crate::firmware_device::fd_context::FirmwareDeviceContext;
 use crate::firmware_device::fd_ops::FdOps;
 use crate::timer::AsyncAlarm;
-use crate::transport::MctpTransport;
+use crate::transport::{MctpTransport, PldmTransportConfig};
 use core::fmt::Write;
 use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_executor::Spawner;
@@ -22,6 +22,7 @@ pub const MAX_MCTP_PLDM_MSG_SIZE: usize = 1024;
 pub enum PldmServiceError {
     StartError,
     StopError,
+    AbortError,
 }
 
 /// Represents a PLDM (Platform Level Data Model) service.
@@ -41,6 +42,7 @@ pub enum PldmServiceError {
 pub struct PldmService<'a> {
     spawner: Spawner,
     cmd_interface: CmdInterface<'a>,
+    transport_config: PldmTransportConfig,
     running: &'static AtomicBool,
     initiator_signal: &'static Signal<CriticalSectionRawMutex, ()>,
 }
@@ -49,13 +51,24 @@ pub struct PldmService<'a> {
 // It will be extended and refactored to support additional PLDM commands in both responder and requester modes.
 impl<'a> PldmService<'a> {
     pub fn init(fdops: &'a dyn FdOps, spawner: Spawner) -> Self {
+        Self::init_with_transport_config(fdops, spawner, PldmTransportConfig::default())
+    }
+
+    /// Like [`PldmService::init`], with the transfer and reassembly buffer sizes chosen by the
+    /// platform.
+    pub fn init_with_transport_config(
+        fdops: &'a dyn FdOps,
+        spawner: Spawner,
+        transport_config: PldmTransportConfig,
+    ) -> Self {
         let cmd_interface = CmdInterface::new(
             config::PLDM_PROTOCOL_CAPABILITIES.get(),
-            FirmwareDeviceContext::new(fdops),
+            FirmwareDeviceContext::new(fdops, transport_config.max_transfer_size),
         );
         Self {
             spawner,
             cmd_interface,
+            transport_config,
             running: {
                 static RUNNING: AtomicBool = AtomicBool::new(false);
                 &RUNNING
@@ -82,6 +95,7 @@ impl<'a> PldmService<'a> {
                 cmd_interface,
                 self.running,
                 self.initiator_signal,
+                self.transport_config,
             ))
             .unwrap();
 
@@ -90,11 +104,23 @@ impl<'a> PldmService<'a> {
                 cmd_interface,
                 self.running,
                 self.initiator_signal,
+                self.transport_config,
             ))
             .unwrap();
         Ok(())
     }
 
+    /// Aborts a firmware update in progress and returns the firmware device to the idle state.
+    ///
+    /// The initiator task stops issuing requests once the device leaves the update states, and
+    /// a subsequent `GetStatus` from the UA reports `IDLE`.
+    pub async fn abort_update(&self) -> Result<(), PldmServiceError> {
+        self.cmd_interface
+            .abort_update()
+            .await
+            .map_err(|_| PldmServiceError::AbortError)
+    }
+
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::Relaxed);
     }
@@ -105,8 +131,9 @@ pub async fn pldm_initiator_task(
     cmd_interface: &'static CmdInterface<'static>,
     running: &'static AtomicBool,
     initiator_signal: &'static Signal<CriticalSectionRawMutex, ()>,
+    transport_config: PldmTransportConfig,
 ) {
-    pldm_initiator(cmd_interface, running, initiator_signal).await;
+    pldm_initiator(cmd_interface, running, initiator_signal, transport_config).await;
 }
 
 #[embassy_executor::task]
@@ -114,14 +141,16 @@ pub async fn pldm_responder_task(
     cmd_interface: &'static CmdInterface<'static>,
     running: &'static AtomicBool,
     initiator_signal: &'static Signal<CriticalSectionRawMutex, ()>,
+    transport_config: PldmTransportConfig,
 ) {
-    pldm_responder(cmd_interface, running, initiator_signal).await;
+    pldm_responder(cmd_interface, running, initiator_signal, transport_config).await;
 }
 
 pub async fn pldm_initiator(
     cmd_interface: &'static CmdInterface<'static>,
     running: &'static AtomicBool,
     initiator_signal: &'static Signal<CriticalSectionRawMutex, ()>,
+    transport_config: PldmTransportConfig,
 ) {
     let mut console_writer = Console::<DefaultSyscalls>::writer();
     loop {
@@ -133,7 +162,7 @@ pub async fn pldm_initiator(
         }
 
         let mut msg_buffer = [0; MAX_MCTP_PLDM_MSG_SIZE];
-        let mut transport = MctpTransport::new(driver_num::MCTP_PLDM);
+        let mut transport = MctpTransport::new(driver_num::MCTP_PLDM, transport_config);
         while running.load(Ordering::SeqCst) {
             if cmd_interface.should_stop_initiator_mode().await {
                 break;
@@ -165,8 +194,9 @@ pub async fn pldm_responder(
     cmd_interface: &'static CmdInterface<'static>,
     running: &'static AtomicBool,
     initiator_signal: &'static Signal<CriticalSectionRawMutex, ()>,
+    transport_config: PldmTransportConfig,
 ) {
-    let mut transport = MctpTransport::new(driver_num::MCTP_PLDM);
+    let mut transport = MctpTransport::new(driver_num::MCTP_PLDM, transport_config);
 
     let mut msg_buffer = [0; MAX_MCTP_PLDM_MSG_SIZE];
     let mut console_writer = Console::<DefaultSyscalls>::writer();
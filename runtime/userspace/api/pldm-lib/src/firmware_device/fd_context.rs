@@ -2,7 +2,7 @@
 
 use crate::cmd_interface::generate_failure_response;
 use crate::error::MsgHandlerError;
-use crate::firmware_device::fd_internal::{FdInternal, FdReqState};
+use crate::firmware_device::fd_internal::{ComponentUpdateState, FdInternal, FdReqState};
 use crate::firmware_device::fd_ops::{ComponentOperation, FdOps};
 use pldm_common::codec::PldmCodec;
 use pldm_common::message::firmware_update::activate_fw::{
@@ -60,14 +60,16 @@ use crate::firmware_device::fd_internal::{
 pub struct FirmwareDeviceContext<'a> {
     ops: &'a dyn FdOps,
     internal: FdInternal,
+    // Largest RequestFirmwareData chunk the transport can carry
+    max_transfer_size: usize,
 }
 
 impl<'a> FirmwareDeviceContext<'a> {
-    #[allow(clippy::new_without_default)]
-    pub fn new(ops: &'a dyn FdOps) -> Self {
+    pub fn new(ops: &'a dyn FdOps, max_transfer_size: usize) -> Self {
         Self {
             ops,
             internal: FdInternal::default(),
+            max_transfer_size,
         }
     }
 
@@ -159,6 +161,14 @@ impl<'a> FirmwareDeviceContext<'a> {
             .await
             .map_err(MsgHandlerError::FdOps)?;
 
+        // Every RequestFirmwareData must fit the transport
+        if fd_transfer_size > self.max_transfer_size {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::InvalidTransferLength as u8,
+            );
+        }
+
         // Set transfer size to the internal state
         self.internal.set_xfer_size(fd_transfer_size).await;
 
@@ -173,6 +183,9 @@ impl<'a> FirmwareDeviceContext<'a> {
 
         match resp.encode(payload) {
             Ok(bytes) => {
+                // Start learning a new component table
+                self.internal.reset_component_table().await;
+
                 // Move FD state to 'LearnComponents'
                 self.internal
                     .set_fd_state(FirmwareDeviceState::LearnComponents)
@@ -230,7 +243,7 @@ impl<'a> FirmwareDeviceContext<'a> {
             .await
             .map_err(MsgHandlerError::FdOps)?;
 
-        let comp_resp_code = self
+        let mut comp_resp_code = self
             .ops
             .handle_component(
                 &pass_comp,
@@ -240,6 +253,14 @@ impl<'a> FirmwareDeviceContext<'a> {
             .await
             .map_err(MsgHandlerError::FdOps)?;
 
+        // Track the component so it can be updated later. If the component table is full,
+        // the component cannot be updated.
+        if comp_resp_code == ComponentResponseCode::CompCanBeUpdated
+            && !self.internal.add_component(&pass_comp).await
+        {
+            comp_resp_code = ComponentResponseCode::CompNotSupported;
+        }
+
         // Construct response
         let resp = PassComponentTableResponse::new(
             req.fixed.hdr.instance_id(),
@@ -314,15 +335,19 @@ impl<'a> FirmwareDeviceContext<'a> {
             .await
             .map_err(MsgHandlerError::FdOps)?;
 
-        let comp_resp_code = self
-            .ops
-            .handle_component(
-                &update_comp,
-                &firmware_params,
-                ComponentOperation::UpdateComponent, /* This indicates this is an update request */
-            )
-            .await
-            .map_err(MsgHandlerError::FdOps)?;
+        // Only components learned through PassComponentTable can be updated
+        let comp_resp_code = if self.internal.is_component_passed(&update_comp).await {
+            self.ops
+                .handle_component(
+                    &update_comp,
+                    &firmware_params,
+                    ComponentOperation::UpdateComponent, /* This indicates this is an update request */
+                )
+                .await
+                .map_err(MsgHandlerError::FdOps)?
+        } else {
+            ComponentResponseCode::CompNotSupported
+        };
 
         // Construct response
         let resp = UpdateComponentResponse::new(
@@ -342,6 +367,8 @@ impl<'a> FirmwareDeviceContext<'a> {
         match resp.encode(payload) {
             Ok(bytes) => {
                 if comp_resp_code == ComponentResponseCode::CompCanBeUpdated {
+                    // Select the component, resetting its transfer state.
+                    self.internal.select_component(&update_comp).await;
                     self.internal
                         .set_initiator_mode(InitiatorModeState::Download(DownloadState::default()))
                         .await;
@@ -469,6 +496,14 @@ impl<'a> FirmwareDeviceContext<'a> {
         match resp.encode(payload) {
             Ok(bytes) => {
                 if should_cancel {
+                    // The component can be selected again by a later UpdateComponent
+                    self.internal
+                        .set_component_state(ComponentUpdateState::Passed)
+                        .await;
+                    // Drop any outstanding request for the cancelled component
+                    self.internal
+                        .set_fd_req(FdReqState::Unused, false, None, None, None, None)
+                        .await;
                     // Set FD state to 'ReadyTransfer'
                     self.internal
                         .set_fd_state(FirmwareDeviceState::ReadyXfer)
@@ -550,6 +585,34 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 
+    /// Aborts the update in progress, if any, and moves the FD back to the idle state.
+    ///
+    /// This allows firmware to recover from a stuck update without waiting for the UA to
+    /// cancel it. The component being transferred is cancelled through
+    /// `FdOps::cancel_update_component` so that the device can release any buffers it holds.
+    pub async fn abort_update(&self) -> Result<(), MsgHandlerError> {
+        if !self.internal.is_update_mode().await {
+            return Ok(());
+        }
+
+        if matches!(
+            self.internal.get_fd_state().await,
+            FirmwareDeviceState::Download
+                | FirmwareDeviceState::Verify
+                | FirmwareDeviceState::Apply
+        ) {
+            self.ops
+                .cancel_update_component(&self.internal.get_component().await)
+                .await
+                .map_err(MsgHandlerError::FdOps)?;
+        }
+
+        self.internal
+            .set_fd_idle(GetStatusReasonCode::CancelUpdate)
+            .await;
+        Ok(())
+    }
+
     pub async fn get_status_rsp(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         let req = GetStatusRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
 
@@ -769,6 +832,9 @@ impl<'a> FirmwareDeviceContext<'a> {
             .map_err(MsgHandlerError::FdOps)?;
 
         if res == TransferResult::TransferSuccess {
+            self.internal
+                .record_component_download(offset, length)
+                .await;
             if self.ops.is_download_complete(fw_component).await {
                 // Mark as complete, next progress() call will send the TransferComplete request
                 self.internal
@@ -878,7 +944,10 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
 
         if fd_req.result == Some(ApplyResult::ApplySuccess as u8) {
-            // Switch to Xfer
+            self.internal
+                .set_component_state(ComponentUpdateState::Applied)
+                .await;
+            // Switch to Xfer so that the next component can be updated
             self.internal
                 .set_fd_req(FdReqState::Unused, false, None, None, None, None)
                 .await;
@@ -1097,3 +1166,457 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::firmware_device::fd_ops::FdOpsError;
+    use async_trait::async_trait;
+    use core::cell::{Cell, RefCell};
+    use futures::executor::block_on;
+    use pldm_common::message::firmware_update::apply_complete::ApplyCompleteResponse;
+    use pldm_common::message::firmware_update::get_status::GetStatusResponse;
+    use pldm_common::message::firmware_update::request_fw_data::RequestFirmwareDataResponse;
+    use pldm_common::message::firmware_update::transfer_complete::TransferCompleteResponse;
+    use pldm_common::message::firmware_update::verify_complete::VerifyCompleteResponse;
+    use pldm_common::protocol::base::PldmFailureResponse;
+    use pldm_common::protocol::firmware_update::{ComponentClassification, PldmFdTime};
+
+    const PAYLOAD_BUFFER_LEN: usize = 256;
+    const XFER_SIZE: usize = PLDM_FWUP_BASELINE_TRANSFER_SIZE;
+    // (component identifier, image size)
+    const COMPONENTS: [(u16, usize); 2] = [(0x0010, 100), (0x0020, 40)];
+
+    struct TestFdOps {
+        downloaded: RefCell<[usize; COMPONENTS.len()]>,
+        cancelled: Cell<bool>,
+        xfer_size: usize,
+    }
+
+    impl TestFdOps {
+        fn new() -> Self {
+            Self {
+                downloaded: RefCell::new([0; COMPONENTS.len()]),
+                cancelled: Cell::new(false),
+                xfer_size: XFER_SIZE,
+            }
+        }
+
+        fn index(component: &FirmwareComponent) -> usize {
+            COMPONENTS
+                .iter()
+                .position(|(id, _)| *id == component.comp_identifier)
+                .unwrap()
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl FdOps for TestFdOps {
+        async fn get_device_identifiers(
+            &self,
+            _device_identifiers: &mut [Descriptor],
+        ) -> Result<usize, FdOpsError> {
+            Ok(0)
+        }
+
+        async fn get_firmware_parms(
+            &self,
+            _firmware_params: &mut FirmwareParameters,
+        ) -> Result<(), FdOpsError> {
+            Ok(())
+        }
+
+        async fn get_xfer_size(&self, ua_transfer_size: usize) -> Result<usize, FdOpsError> {
+            Ok(ua_transfer_size.min(self.xfer_size))
+        }
+
+        async fn handle_component(
+            &self,
+            component: &FirmwareComponent,
+            _fw_params: &FirmwareParameters,
+            _op: ComponentOperation,
+        ) -> Result<ComponentResponseCode, FdOpsError> {
+            if COMPONENTS
+                .iter()
+                .any(|(id, _)| *id == component.comp_identifier)
+            {
+                Ok(ComponentResponseCode::CompCanBeUpdated)
+            } else {
+                Ok(ComponentResponseCode::CompNotSupported)
+            }
+        }
+
+        async fn query_download_offset_and_length(
+            &self,
+            component: &FirmwareComponent,
+        ) -> Result<(usize, usize), FdOpsError> {
+            let idx = Self::index(component);
+            let offset = self.downloaded.borrow()[idx];
+            Ok((offset, (COMPONENTS[idx].1 - offset).min(XFER_SIZE)))
+        }
+
+        async fn download_fw_data(
+            &self,
+            offset: usize,
+            data: &[u8],
+            component: &FirmwareComponent,
+        ) -> Result<TransferResult, FdOpsError> {
+            let idx = Self::index(component);
+            let mut downloaded = self.downloaded.borrow_mut();
+            assert_eq!(offset, downloaded[idx]);
+            downloaded[idx] += data.len();
+            Ok(TransferResult::TransferSuccess)
+        }
+
+        async fn is_download_complete(&self, component: &FirmwareComponent) -> bool {
+            let idx = Self::index(component);
+            self.downloaded.borrow()[idx] >= COMPONENTS[idx].1
+        }
+
+        async fn query_download_progress(
+            &self,
+            _component: &FirmwareComponent,
+            _progress_percent: &mut ProgressPercent,
+        ) -> Result<(), FdOpsError> {
+            Ok(())
+        }
+
+        async fn verify(
+            &self,
+            _component: &FirmwareComponent,
+            progress_percent: &mut ProgressPercent,
+        ) -> Result<VerifyResult, FdOpsError> {
+            *progress_percent = ProgressPercent::new(100).unwrap();
+            Ok(VerifyResult::VerifySuccess)
+        }
+
+        async fn apply(
+            &self,
+            _component: &FirmwareComponent,
+            progress_percent: &mut ProgressPercent,
+        ) -> Result<ApplyResult, FdOpsError> {
+            *progress_percent = ProgressPercent::new(100).unwrap();
+            Ok(ApplyResult::ApplySuccess)
+        }
+
+        async fn activate(
+            &self,
+            _self_contained_activation: u8,
+            _estimated_time: &mut u16,
+        ) -> Result<u8, FdOpsError> {
+            Ok(PldmBaseCompletionCode::Success as u8)
+        }
+
+        async fn cancel_update_component(
+            &self,
+            component: &FirmwareComponent,
+        ) -> Result<(), FdOpsError> {
+            // Discard the partially downloaded image
+            self.downloaded.borrow_mut()[Self::index(component)] = 0;
+            self.cancelled.set(true);
+            Ok(())
+        }
+
+        async fn now(&self) -> PldmFdTime {
+            0
+        }
+    }
+
+    fn component(comp_identifier: u16, comp_image_size: Option<u32>) -> FirmwareComponent {
+        FirmwareComponent::new(
+            ComponentClassification::Firmware as u16,
+            comp_identifier,
+            0,
+            1,
+            PldmFirmwareString::new("ASCII", "1.0.0").unwrap(),
+            comp_image_size,
+            None,
+        )
+    }
+
+    fn instance_id(payload: &[u8]) -> u8 {
+        PldmMsgHeader::<[u8; 3]>::decode(payload)
+            .unwrap()
+            .instance_id()
+    }
+
+    async fn request_update(ctx: &FirmwareDeviceContext<'_>) {
+        let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+        RequestUpdateRequest::new(
+            0,
+            PldmMsgType::Request,
+            512,
+            COMPONENTS.len() as u16,
+            1,
+            0,
+            &PldmFirmwareString::new("ASCII", "1.0.0").unwrap(),
+        )
+        .encode(&mut payload)
+        .unwrap();
+        ctx.request_update_rsp(&mut payload).await.unwrap();
+        assert_eq!(
+            ctx.internal.get_fd_state().await,
+            FirmwareDeviceState::LearnComponents
+        );
+    }
+
+    async fn pass_component(
+        ctx: &FirmwareDeviceContext<'_>,
+        transfer_flag: TransferRespFlag,
+        comp_identifier: u16,
+    ) {
+        let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+        PassComponentTableRequest::new(
+            0,
+            PldmMsgType::Request,
+            transfer_flag,
+            ComponentClassification::Firmware,
+            comp_identifier,
+            0,
+            1,
+            &PldmFirmwareString::new("ASCII", "1.0.0").unwrap(),
+        )
+        .encode(&mut payload)
+        .unwrap();
+        ctx.pass_component_rsp(&mut payload).await.unwrap();
+        let rsp = PassComponentTableResponse::decode(&payload).unwrap();
+        assert_eq!(rsp.completion_code, PldmBaseCompletionCode::Success as u8);
+        assert_eq!(rsp.comp_resp, ComponentResponse::CompCanBeUpdated as u8);
+    }
+
+    async fn update_component(ctx: &FirmwareDeviceContext<'_>, comp_identifier: u16, size: u32) {
+        let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+        UpdateComponentRequest::new(
+            0,
+            PldmMsgType::Request,
+            ComponentClassification::Firmware,
+            comp_identifier,
+            0,
+            1,
+            size,
+            UpdateOptionFlags(0),
+            &PldmFirmwareString::new("ASCII", "1.0.0").unwrap(),
+        )
+        .encode(&mut payload)
+        .unwrap();
+        ctx.update_component_rsp(&mut payload).await.unwrap();
+    }
+
+    // Drives the download, verify and apply phases of the selected component, acting as the UA
+    async fn download_verify_apply(ctx: &FirmwareDeviceContext<'_>, image_size: u32) {
+        let image = [0x5au8; PAYLOAD_BUFFER_LEN];
+        let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+
+        // Download
+        let mut expected_offset = 0;
+        loop {
+            assert_eq!(
+                ctx.internal.get_fd_state().await,
+                FirmwareDeviceState::Download
+            );
+            payload.fill(0);
+            ctx.fd_progress(&mut payload).await.unwrap();
+            let cmd = PldmMsgHeader::<[u8; 3]>::decode(&payload)
+                .unwrap()
+                .cmd_code();
+            let instance_id = instance_id(&payload);
+            if cmd == FwUpdateCmd::TransferComplete as u8 {
+                assert_eq!(expected_offset, image_size);
+                TransferCompleteResponse::new(instance_id, PldmBaseCompletionCode::Success as u8)
+                    .encode(&mut payload)
+                    .unwrap();
+                ctx.handle_response(&mut payload).await.unwrap();
+                break;
+            }
+
+            assert_eq!(cmd, FwUpdateCmd::RequestFirmwareData as u8);
+            let req = RequestFirmwareDataRequest::decode(&payload).unwrap();
+            let (offset, length) = (req.offset, req.length);
+            assert_eq!(offset, expected_offset);
+            assert!(offset + length <= image_size);
+            expected_offset += length;
+            RequestFirmwareDataResponse::new(
+                instance_id,
+                PldmBaseCompletionCode::Success as u8,
+                &image[..length as usize],
+            )
+            .encode(&mut payload)
+            .unwrap();
+            ctx.handle_response(&mut payload).await.unwrap();
+        }
+
+        // Verify
+        assert_eq!(
+            ctx.internal.get_fd_state().await,
+            FirmwareDeviceState::Verify
+        );
+        payload.fill(0);
+        ctx.fd_progress(&mut payload).await.unwrap();
+        VerifyCompleteResponse::new(instance_id(&payload), PldmBaseCompletionCode::Success as u8)
+            .encode(&mut payload)
+            .unwrap();
+        ctx.handle_response(&mut payload).await.unwrap();
+
+        // Apply
+        assert_eq!(
+            ctx.internal.get_fd_state().await,
+            FirmwareDeviceState::Apply
+        );
+        payload.fill(0);
+        ctx.fd_progress(&mut payload).await.unwrap();
+        ApplyCompleteResponse::new(instance_id(&payload), PldmBaseCompletionCode::Success as u8)
+            .encode(&mut payload)
+            .unwrap();
+        ctx.handle_response(&mut payload).await.unwrap();
+    }
+
+    #[test]
+    fn test_multi_component_update() {
+        let ops = TestFdOps::new();
+        let ctx = FirmwareDeviceContext::new(&ops, XFER_SIZE);
+
+        block_on(async {
+            request_update(&ctx).await;
+            pass_component(&ctx, TransferRespFlag::Start, COMPONENTS[0].0).await;
+            assert_eq!(
+                ctx.internal.get_fd_state().await,
+                FirmwareDeviceState::LearnComponents
+            );
+            pass_component(&ctx, TransferRespFlag::End, COMPONENTS[1].0).await;
+            assert_eq!(
+                ctx.internal.get_fd_state().await,
+                FirmwareDeviceState::ReadyXfer
+            );
+
+            // A component that was not passed cannot be updated
+            update_component(&ctx, 0x0030, 16).await;
+            assert_eq!(
+                ctx.internal.get_fd_state().await,
+                FirmwareDeviceState::ReadyXfer
+            );
+
+            for (idx, (comp_identifier, size)) in COMPONENTS.iter().enumerate() {
+                update_component(&ctx, *comp_identifier, *size as u32).await;
+                assert_eq!(
+                    ctx.internal.get_fd_state().await,
+                    FirmwareDeviceState::Download
+                );
+                assert_eq!(
+                    ctx.internal
+                        .get_component_state(&component(*comp_identifier, None))
+                        .await,
+                    Some(ComponentUpdateState::Download)
+                );
+
+                // Offsets are validated against the selected component's image
+                assert!(ctx
+                    .internal
+                    .get_fd_download_chunk(*size as u32 + 1, 1)
+                    .await
+                    .is_none());
+
+                download_verify_apply(&ctx, *size as u32).await;
+                assert_eq!(
+                    ctx.internal.get_fd_state().await,
+                    FirmwareDeviceState::ReadyXfer
+                );
+                assert_eq!(
+                    ctx.internal
+                        .get_component_state(&component(*comp_identifier, None))
+                        .await,
+                    Some(ComponentUpdateState::Applied)
+                );
+                assert_eq!(ops.downloaded.borrow()[idx], *size);
+            }
+        });
+    }
+
+    #[test]
+    fn test_cancel_update_during_download() {
+        let ops = TestFdOps::new();
+        let ctx = FirmwareDeviceContext::new(&ops, XFER_SIZE);
+
+        block_on(async {
+            request_update(&ctx).await;
+            pass_component(&ctx, TransferRespFlag::StartAndEnd, COMPONENTS[0].0).await;
+            update_component(&ctx, COMPONENTS[0].0, COMPONENTS[0].1 as u32).await;
+            assert_eq!(
+                ctx.internal.get_fd_state().await,
+                FirmwareDeviceState::Download
+            );
+
+            // The FD requests the first chunk of firmware data
+            let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+            ctx.fd_progress(&mut payload).await.unwrap();
+            let req = RequestFirmwareDataRequest::decode(&payload).unwrap();
+            let fw_data_instance_id = instance_id(&payload);
+
+            // The UA cancels the update before responding
+            payload.fill(0);
+            CancelUpdateRequest::new(1, PldmMsgType::Request)
+                .encode(&mut payload)
+                .unwrap();
+            ctx.cancel_update_rsp(&mut payload).await.unwrap();
+            assert_eq!(ctx.internal.get_fd_state().await, FirmwareDeviceState::Idle);
+            assert!(ops.cancelled.get());
+            assert!(ctx.should_stop_initiator_mode().await);
+
+            // GetStatus reports IDLE
+            payload.fill(0);
+            GetStatusRequest::new(2, PldmMsgType::Request)
+                .encode(&mut payload)
+                .unwrap();
+            ctx.get_status_rsp(&mut payload).await.unwrap();
+            let rsp = GetStatusResponse::decode(&payload).unwrap();
+            assert_eq!(rsp.current_state, FirmwareDeviceState::Idle as u8);
+            assert_eq!(rsp.previous_state, FirmwareDeviceState::Download as u8);
+            assert_eq!(rsp.aux_state, AuxState::IdleLearnComponentsReadXfer as u8);
+            assert_eq!(rsp.reason_code, GetStatusReasonCode::CancelUpdate as u8);
+
+            // A late firmware data response is rejected and nothing is written
+            let image = [0x5au8; PAYLOAD_BUFFER_LEN];
+            payload.fill(0);
+            RequestFirmwareDataResponse::new(
+                fw_data_instance_id,
+                PldmBaseCompletionCode::Success as u8,
+                &image[..req.length as usize],
+            )
+            .encode(&mut payload)
+            .unwrap();
+            assert!(ctx.handle_response(&mut payload).await.is_err());
+            assert_eq!(ops.downloaded.borrow()[0], 0);
+        });
+    }
+
+    #[test]
+    fn test_request_update_transfer_size_exceeds_transport() {
+        // The FD would request chunks larger than the transport can carry
+        let ops = TestFdOps {
+            xfer_size: 2 * XFER_SIZE,
+            ..TestFdOps::new()
+        };
+        let ctx = FirmwareDeviceContext::new(&ops, XFER_SIZE);
+
+        block_on(async {
+            let mut payload = [0u8; PAYLOAD_BUFFER_LEN];
+            RequestUpdateRequest::new(
+                0,
+                PldmMsgType::Request,
+                512,
+                COMPONENTS.len() as u16,
+                1,
+                0,
+                &PldmFirmwareString::new("ASCII", "1.0.0").unwrap(),
+            )
+            .encode(&mut payload)
+            .unwrap();
+            ctx.request_update_rsp(&mut payload).await.unwrap();
+            let rsp = PldmFailureResponse::decode(&payload).unwrap();
+            assert_eq!(
+                rsp.completion_code,
+                FwUpdateCompletionCode::InvalidTransferLength as u8
+            );
+            assert_eq!(ctx.internal.get_fd_state().await, FirmwareDeviceState::Idle);
+        });
+    }
+}
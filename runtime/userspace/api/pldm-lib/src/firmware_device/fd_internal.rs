@@ -1,5 +1,6 @@
 // Licensed under the Apache-2.0 license
 
+use crate::config::FD_MAX_COMPONENT_COUNT;
 use crate::control_context::Tid;
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
 use embassy_sync::mutex::Mutex;
@@ -28,6 +29,12 @@ pub struct FdInternalInner {
     // Set by `UpdateComponent`, available during download/verify/apply.
     update_comp: FirmwareComponent,
 
+    // Components learned during `PassComponentTable`, each with its own transfer state.
+    comp_table: [Option<ComponentEntry>; FD_MAX_COMPONENT_COUNT],
+
+    // Index into `comp_table` of the component selected by `UpdateComponent`.
+    cur_comp_idx: Option<usize>,
+
     // Flags indicating update options.
     update_flags: UpdateOptionFlags,
 
@@ -94,6 +101,13 @@ impl FdInternal {
             inner.prev_state = inner.state.clone();
             inner.state = FirmwareDeviceState::Idle;
             inner.reason = Some(reason_code);
+
+            // Leaving update mode drops any in-flight request and per-component transfer
+            // state, so a late response cannot resume a partially downloaded image.
+            inner.req = FdReq::new();
+            inner.initiator_mode_state = InitiatorModeState::Download(DownloadState::default());
+            inner.comp_table = [None; FD_MAX_COMPONENT_COUNT];
+            inner.cur_comp_idx = None;
         }
     }
 
@@ -147,6 +161,75 @@ impl FdInternal {
         inner.update_comp.clone()
     }
 
+    pub async fn reset_component_table(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.comp_table = [None; FD_MAX_COMPONENT_COUNT];
+        inner.cur_comp_idx = None;
+    }
+
+    // Records a component passed in `PassComponentTable`. Returns false if the table is full.
+    pub async fn add_component(&self, comp: &FirmwareComponent) -> bool {
+        let mut inner = self.inner.lock().await;
+        let idx = match inner.find_component(comp) {
+            Some(idx) => idx,
+            None => match inner.comp_table.iter().position(|entry| entry.is_none()) {
+                Some(idx) => idx,
+                None => return false,
+            },
+        };
+        inner.comp_table[idx] = Some(ComponentEntry::new(comp));
+        true
+    }
+
+    pub async fn is_component_passed(&self, comp: &FirmwareComponent) -> bool {
+        let inner = self.inner.lock().await;
+        inner.find_component(comp).is_some()
+    }
+
+    // Selects a previously passed component for download and resets its transfer state.
+    // Returns false if the component was not part of the component table.
+    pub async fn select_component(&self, comp: &FirmwareComponent) -> bool {
+        let mut inner = self.inner.lock().await;
+        let Some(idx) = inner.find_component(comp) else {
+            return false;
+        };
+        if let Some(entry) = inner.comp_table[idx].as_mut() {
+            entry.image_size = comp.comp_image_size.unwrap_or(0);
+            entry.bytes_downloaded = 0;
+            entry.state = ComponentUpdateState::Download;
+        }
+        inner.cur_comp_idx = Some(idx);
+        true
+    }
+
+    pub async fn set_component_state(&self, state: ComponentUpdateState) {
+        let mut inner = self.inner.lock().await;
+        if let Some(entry) = inner.current_component_mut() {
+            entry.state = state;
+        }
+    }
+
+    pub async fn get_component_state(
+        &self,
+        comp: &FirmwareComponent,
+    ) -> Option<ComponentUpdateState> {
+        let inner = self.inner.lock().await;
+        inner
+            .find_component(comp)
+            .and_then(|idx| inner.comp_table[idx].as_ref())
+            .map(|entry| entry.state)
+    }
+
+    // Records a chunk of firmware data received for the selected component.
+    pub async fn record_component_download(&self, offset: u32, length: u32) {
+        let mut inner = self.inner.lock().await;
+        if let Some(entry) = inner.current_component_mut() {
+            entry.bytes_downloaded = entry
+                .bytes_downloaded
+                .max(offset.saturating_add(length).min(entry.image_size));
+        }
+    }
+
     pub async fn set_update_flags(&self, flags: UpdateOptionFlags) {
         let mut inner = self.inner.lock().await;
         inner.update_flags = flags;
@@ -228,9 +311,14 @@ impl FdInternal {
             return None;
         }
 
-        let comp_image_size = inner.update_comp.comp_image_size.unwrap_or(0);
+        // Offsets are validated against the image of the selected component only
+        let entry = inner.current_component()?;
+        if entry.state != ComponentUpdateState::Download {
+            return None;
+        }
+        let comp_image_size = entry.image_size;
         if requested_offset > comp_image_size
-            || requested_offset + requested_length
+            || requested_offset.saturating_add(requested_length)
                 > comp_image_size + PLDM_FWUP_MAX_PADDING_SIZE as u32
         {
             return None;
@@ -341,6 +429,8 @@ impl FdInternalInner {
             prev_state: FirmwareDeviceState::Idle,
             reason: None,
             update_comp: FirmwareComponent::default(),
+            comp_table: [None; FD_MAX_COMPONENT_COUNT],
+            cur_comp_idx: None,
             update_flags: UpdateOptionFlags(0),
             max_xfer_size,
             req: FdReq::new(),
@@ -351,6 +441,68 @@ impl FdInternalInner {
             fd_t2_retry_time,
         }
     }
+
+    fn find_component(&self, comp: &FirmwareComponent) -> Option<usize> {
+        self.comp_table
+            .iter()
+            .position(|entry| entry.as_ref().is_some_and(|entry| entry.matches(comp)))
+    }
+
+    fn current_component(&self) -> Option<&ComponentEntry> {
+        self.cur_comp_idx
+            .and_then(|idx| self.comp_table.get(idx))
+            .and_then(|entry| entry.as_ref())
+    }
+
+    fn current_component_mut(&mut self) -> Option<&mut ComponentEntry> {
+        self.cur_comp_idx
+            .and_then(|idx| self.comp_table.get_mut(idx))
+            .and_then(|entry| entry.as_mut())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ComponentUpdateState {
+    // Passed in the component table, not yet selected by `UpdateComponent`.
+    #[default]
+    Passed,
+    // Selected by `UpdateComponent` and going through download/verify/apply.
+    Download,
+    // Successfully applied.
+    Applied,
+}
+
+// Per-component entry of the component table.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComponentEntry {
+    pub comp_classification: u16,
+    pub comp_identifier: u16,
+    pub comp_classification_index: u8,
+
+    // Image size from `UpdateComponent`, only valid once the component is selected.
+    pub image_size: u32,
+
+    // Number of bytes of the image received so far.
+    pub bytes_downloaded: u32,
+
+    pub state: ComponentUpdateState,
+}
+
+impl ComponentEntry {
+    fn new(comp: &FirmwareComponent) -> Self {
+        Self {
+            comp_classification: comp.comp_classification,
+            comp_identifier: comp.comp_identifier,
+            comp_classification_index: comp.comp_classification_index,
+            ..Default::default()
+        }
+    }
+
+    fn matches(&self, comp: &FirmwareComponent) -> bool {
+        self.comp_classification == comp.comp_classification
+            && self.comp_identifier == comp.comp_identifier
+            && self.comp_classification_index == comp.comp_classification_index
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -224,6 +224,9 @@ pub trait FdOps {
 
     /// Cancels the update operation for a specific firmware component.
     ///
+    /// The update may be cancelled in the middle of a download. The implementation must release
+    /// any buffers held for the component and must not mark a partially written image as valid.
+    ///
     /// # Arguments
     ///
     /// * `component` - A reference to the `FirmwareComponent` for which the update operation should be canceled.
@@ -1,6 +1,9 @@
 // Licensed under the Apache-2.0 license
 
+use crate::config::FD_MAX_XFER_SIZE;
+use crate::daemon::MAX_MCTP_PLDM_MSG_SIZE;
 use libsyscall_caliptra::mctp::{Mctp, MessageInfo};
+use pldm_common::protocol::firmware_update::PLDM_FWUP_BASELINE_TRANSFER_SIZE;
 use pldm_common::util::mctp_transport::{
     MctpCommonHeader, MCTP_COMMON_HEADER_OFFSET, MCTP_PLDM_MSG_TYPE,
 };
@@ -9,7 +12,7 @@ pub enum PldmTransportType {
     Mctp,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum TransportError {
     DriverError,
     BufferTooSmall,
@@ -20,21 +23,67 @@ pub enum TransportError {
     NoRequestInFlight,
 }
 
+/// Sizing of the PLDM transport, chosen per platform at construction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PldmTransportConfig {
+    /// Largest firmware data chunk carried by one RequestFirmwareData exchange.
+    pub max_transfer_size: usize,
+    /// Size of the buffer a complete PLDM message is reassembled into.
+    pub reassembly_capacity: usize,
+}
+
+impl PldmTransportConfig {
+    /// Out-of-range sizes are clamped so that
+    /// `PLDM_FWUP_BASELINE_TRANSFER_SIZE <= max_transfer_size <= reassembly_capacity` and the
+    /// reassembly buffer is no larger than the daemon's message buffer.
+    pub fn new(max_transfer_size: usize, reassembly_capacity: usize) -> Self {
+        let max_transfer_size =
+            max_transfer_size.clamp(PLDM_FWUP_BASELINE_TRANSFER_SIZE, MAX_MCTP_PLDM_MSG_SIZE);
+        let reassembly_capacity =
+            reassembly_capacity.clamp(max_transfer_size, MAX_MCTP_PLDM_MSG_SIZE);
+        Self {
+            max_transfer_size,
+            reassembly_capacity,
+        }
+    }
+}
+
+impl Default for PldmTransportConfig {
+    fn default() -> Self {
+        Self {
+            max_transfer_size: FD_MAX_XFER_SIZE,
+            reassembly_capacity: MAX_MCTP_PLDM_MSG_SIZE,
+        }
+    }
+}
+
 pub struct MctpTransport {
     mctp: Mctp,
+    config: PldmTransportConfig,
     cur_resp_ctx: Option<MessageInfo>,
     cur_req_ctx: Option<MessageInfo>,
 }
 
 impl MctpTransport {
-    pub fn new(drv_num: u32) -> Self {
+    pub fn new(drv_num: u32, config: PldmTransportConfig) -> Self {
         Self {
             mctp: Mctp::new(drv_num),
+            config,
             cur_resp_ctx: None,
             cur_req_ctx: None,
         }
     }
 
+    pub fn config(&self) -> &PldmTransportConfig {
+        &self.config
+    }
+
+    // Limits a receive buffer to the configured reassembly capacity
+    fn reassembly_buffer<'b>(&self, buf: &'b mut [u8]) -> Result<&'b mut [u8], TransportError> {
+        buf.get_mut(..self.config.reassembly_capacity)
+            .ok_or(TransportError::BufferTooSmall)
+    }
+
     pub async fn send_request(&mut self, dest_eid: u8, req: &[u8]) -> Result<(), TransportError> {
         let mctp_hdr = MctpCommonHeader(req[MCTP_COMMON_HEADER_OFFSET]);
         if mctp_hdr.ic() != 0 || mctp_hdr.msg_type() != MCTP_PLDM_MSG_TYPE {
@@ -53,6 +102,7 @@ impl MctpTransport {
     }
 
     pub async fn receive_response(&mut self, rsp: &mut [u8]) -> Result<(), TransportError> {
+        let rsp = self.reassembly_buffer(rsp)?;
         // Reset msg buffer
         rsp.fill(0);
         let (rsp_len, _msg_info) = if let Some(msg_info) = &self.cur_req_ctx {
@@ -79,6 +129,7 @@ impl MctpTransport {
     }
 
     pub async fn receive_request(&mut self, req: &mut [u8]) -> Result<(), TransportError> {
+        let req = self.reassembly_buffer(req)?;
         // Reset msg buffer
         req.fill(0);
         let (req_len, msg_info) = self
@@ -122,3 +173,38 @@ impl MctpTransport {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_transport_config_clamping() {
+        let config = |max_transfer_size, reassembly_capacity| PldmTransportConfig {
+            max_transfer_size,
+            reassembly_capacity,
+        };
+        assert_eq!(PldmTransportConfig::new(256, 512), config(256, 512));
+        // Reassembly buffer smaller than one transfer grows to hold it
+        assert_eq!(PldmTransportConfig::new(512, 256), config(512, 512));
+        // Below the PLDM baseline transfer size
+        assert_eq!(
+            PldmTransportConfig::new(PLDM_FWUP_BASELINE_TRANSFER_SIZE - 1, 512),
+            config(PLDM_FWUP_BASELINE_TRANSFER_SIZE, 512)
+        );
+        // Larger than the daemon's message buffer
+        assert_eq!(
+            PldmTransportConfig::new(512, MAX_MCTP_PLDM_MSG_SIZE + 1),
+            config(512, MAX_MCTP_PLDM_MSG_SIZE)
+        );
+        assert_eq!(
+            PldmTransportConfig::new(MAX_MCTP_PLDM_MSG_SIZE + 1, 0),
+            config(MAX_MCTP_PLDM_MSG_SIZE, MAX_MCTP_PLDM_MSG_SIZE)
+        );
+        let default = PldmTransportConfig::default();
+        assert_eq!(
+            PldmTransportConfig::new(default.max_transfer_size, default.reassembly_capacity),
+            default
+        );
+    }
+}
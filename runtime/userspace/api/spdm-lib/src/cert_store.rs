@@ -26,6 +26,8 @@ pub enum CertStoreError {
     BufferTooSmall,
     InvalidOffset,
     CertReadError,
+    CertWriteError,
+    Unsupported,
     CaliptraApi(CaliptraApiError),
 }
 pub type CertStoreResult<T> = Result<T, CertStoreError>;
@@ -146,6 +148,41 @@ pub trait SpdmCertStore {
     /// # Returns
     /// * `KeyUsageMask` - The KeyUsageMask associated with the certificate chain or None if not supported or not found.
     async fn key_usage_mask(&self, slot_id: u8) -> Option<KeyUsageMask>;
+
+    /// Generate a certificate signing request (CSR) for the device key.
+    /// Only called if the responder advertises CSR_CAP.
+    ///
+    /// # Arguments
+    /// * `key_pair_id` - The KeyPairID of the key to generate the CSR for (0 if not applicable).
+    /// * `requester_info` - The DER-encoded CertificationRequestInfo supplied by the requester.
+    /// * `opaque_data` - The opaque data supplied by the requester.
+    /// * `csr` - The output buffer to store the DER-encoded CSR.
+    ///
+    /// # Returns
+    /// * `usize` - The length of the CSR in bytes or error.
+    async fn get_csr<'a>(
+        &self,
+        _key_pair_id: u8,
+        _requester_info: &'a [u8],
+        _opaque_data: &'a [u8],
+        _csr: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        Err(CertStoreError::Unsupported)
+    }
+
+    /// Install a certificate chain into the given slot.
+    /// Only called if the responder advertises SET_CERT_CAP.
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID to install the certificate chain into.
+    /// * `cert_chain` - The certificate chain in SPDM certificate chain format
+    ///   (Length, Reserved, RootHash, Certificates).
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn set_cert_chain<'a>(&self, _slot_id: u8, _cert_chain: &'a [u8]) -> CertStoreResult<()> {
+        Err(CertStoreError::Unsupported)
+    }
 }
 
 pub(crate) fn validate_cert_store(cert_store: &dyn SpdmCertStore) -> SpdmResult<()> {
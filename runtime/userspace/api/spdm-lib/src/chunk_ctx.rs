@@ -59,6 +59,7 @@ pub(crate) enum LargeResponse {
 pub(crate) struct LargeResponseCtx {
     chunk_info: ChunkInfo,
     response: Option<LargeResponse>,
+    max_chunk_size: Option<usize>,
 }
 
 impl LargeResponseCtx {
@@ -117,4 +118,61 @@ impl LargeResponseCtx {
     pub fn bytes_transferred(&self) -> usize {
         self.chunk_info.bytes_transferred
     }
+
+    /// Record that a chunk has been sent, moving on to the next sequence number.
+    /// The context is reset once the whole large response has been transferred.
+    ///
+    /// # Arguments
+    /// * `chunk_size` - The size of the chunk data that was sent
+    pub fn advance(&mut self, chunk_size: usize) {
+        self.chunk_info.bytes_transferred += chunk_size;
+        self.chunk_info.chunk_seq_num = self.chunk_info.chunk_seq_num.wrapping_add(1);
+        if self.chunk_info.bytes_transferred >= self.chunk_info.large_msg_size {
+            self.reset();
+        }
+    }
+
+    /// Limit the size of each CHUNK_RESPONSE message. This is kept across resets.
+    ///
+    /// # Arguments
+    /// * `size` - The maximum size of a CHUNK_RESPONSE message in bytes
+    pub fn set_max_chunk_size(&mut self, size: usize) {
+        self.max_chunk_size = Some(size);
+    }
+
+    pub fn max_chunk_size(&self) -> Option<usize> {
+        self.max_chunk_size
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commands::chunk_get_rsp::chunk_data_size;
+
+    #[test]
+    fn test_large_response_split_by_max_chunk_size() {
+        let mut ctx = LargeResponseCtx::default();
+        ctx.set_max_chunk_size(64);
+        let handle = ctx.chunk_info.init(300, None);
+
+        // Each CHUNK_RESPONSE carries 12 bytes of header, plus the 4-byte
+        // large response size in the first chunk
+        let mut chunk_sizes = [0usize; 8];
+        let mut exchanges = 0;
+        while ctx.in_progress() {
+            assert!(ctx.valid(handle, exchanges as u16));
+            let max_chunk_data = chunk_data_size(ctx.max_chunk_size().unwrap(), exchanges as u16);
+            let (last, remaining) = ctx.last_chunk(max_chunk_data);
+            let chunk_size = if last { remaining } else { max_chunk_data };
+            chunk_sizes[exchanges] = chunk_size;
+            ctx.advance(chunk_size);
+            exchanges += 1;
+        }
+
+        assert_eq!(exchanges, 6);
+        assert_eq!(&chunk_sizes[..exchanges], &[48, 52, 52, 52, 52, 44]);
+        assert_eq!(ctx.bytes_transferred(), 0);
+        assert_eq!(ctx.max_chunk_size(), Some(64));
+    }
 }
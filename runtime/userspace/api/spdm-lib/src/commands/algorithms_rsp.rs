@@ -136,6 +136,11 @@ bitfield! {
 
 impl CommonCodec for AlgStructure {}
 
+pub(crate) fn meas_supported(local_cap_flags: &CapabilityFlags) -> bool {
+    local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithNoSignature as u8
+        || local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithSignature as u8
+}
+
 pub(crate) fn selected_measurement_specification(ctx: &SpdmContext) -> MeasurementSpecification {
     let local_cap_flags = &ctx.local_capabilities.flags;
     let local_algorithms = &ctx.local_algorithms.device_algorithms;
@@ -143,10 +148,7 @@ pub(crate) fn selected_measurement_specification(ctx: &SpdmContext) -> Measureme
     let algorithm_priority_table = &ctx.local_algorithms.algorithm_priority_table;
 
     let mut measurement_specification_sel = MeasurementSpecification::default();
-    if local_cap_flags.mel_cap() == 1
-        || (local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithNoSignature as u8
-            || local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithSignature as u8)
-    {
+    if local_cap_flags.mel_cap() == 1 || meas_supported(local_cap_flags) {
         measurement_specification_sel =
             MeasurementSpecification(local_algorithms.measurement_spec.0.prioritize(
                 &peer_algorithms.measurement_spec.0,
@@ -321,25 +323,10 @@ async fn generate_algorithms_response<'a>(
             ));
     }
 
-    // MeasurementHashAlgo
-    let mut measurement_hash_algo = MeasurementHashAlgo::default();
-    if local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithNoSignature as u8
-        || local_cap_flags.meas_cap() == MeasCapability::MeasurementsWithSignature as u8
-    {
-        measurement_hash_algo = local_algorithms.measurement_hash_algo;
-    }
-
-    // BaseAsymSel
-    let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
-        &peer_algorithms.base_asym_algo.0,
-        algorithm_priority_table.base_asym_algo,
-    ));
-
-    // BaseHashSel
-    let base_hash_sel = local_algorithms.base_hash_algo.prioritize(
-        &peer_algorithms.base_hash_algo,
-        algorithm_priority_table.base_hash_algo,
-    );
+    // MeasurementHashAlgo, BaseAsymSel and BaseHashSel
+    let negotiated_algorithms = ctx
+        .local_algorithms
+        .negotiate(peer_algorithms, meas_supported(local_cap_flags));
 
     // MelSpecificationSel
     let mel_specification_sel = MelSpecification(local_algorithms.mel_specification.0.prioritize(
@@ -353,9 +340,9 @@ async fn generate_algorithms_response<'a>(
         length: rsp_length as u16,
         measurement_specification_sel,
         other_params_selection,
-        measurement_hash_algo,
-        base_asym_sel,
-        base_hash_sel,
+        measurement_hash_algo: negotiated_algorithms.measurement_hash_algo,
+        base_asym_sel: negotiated_algorithms.base_asym_algo,
+        base_hash_sel: negotiated_algorithms.base_hash_algo,
         reserved_2: [0; 11],
         mel_specification_sel,
         ext_asym_sel_count: 0,
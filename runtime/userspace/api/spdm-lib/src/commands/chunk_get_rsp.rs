@@ -57,17 +57,22 @@ pub(crate) fn max_chunked_resp_size(ctx: &SpdmContext) -> usize {
         - size_of::<u32>()
 }
 
-// Computes the chunk size based on the context and the chunk sequence number
-// Returns the chunk size and a boolean indicating if this is the last chunk
-fn compute_chunk_size(ctx: &SpdmContext, chunk_seq_num: u16) -> (usize, bool) {
+// Computes the maximum chunk data that fits in a CHUNK_RESPONSE of `transfer_size` bytes
+pub(crate) fn chunk_data_size(transfer_size: usize, chunk_seq_num: u16) -> usize {
     let extra_field_size = if chunk_seq_num == 0 {
         size_of::<LargeResponseSize>()
     } else {
         0
     };
-    let chunk_size = ctx.min_data_transfer_size().saturating_sub(
+    transfer_size.saturating_sub(
         size_of::<SpdmMsgHdr>() + size_of::<ChunkResponseFixed>() + extra_field_size,
-    );
+    )
+}
+
+// Computes the chunk size based on the context and the chunk sequence number
+// Returns the chunk size and a boolean indicating if this is the last chunk
+fn compute_chunk_size(ctx: &SpdmContext, chunk_seq_num: u16) -> (usize, bool) {
+    let chunk_size = chunk_data_size(ctx.min_data_transfer_size(), chunk_seq_num);
 
     let (is_last_chunk, remaining_len) = ctx.large_resp_context.last_chunk(chunk_size);
 
@@ -219,6 +224,7 @@ async fn generate_chunk_response<'a>(
 
     // Encode chunk data of chunk size
     payload_len += encode_chunk_data(ctx, chunk_size, rsp).await?;
+    ctx.large_resp_context.advance(chunk_size);
 
     rsp.push_data(payload_len)
         .map_err(|e| (false, CommandError::Codec(e)))
@@ -237,7 +243,7 @@ pub(crate) async fn handle_chunk_get<'a>(
     // 3. Check if a large response is in progress
     if ctx.state.connection_info.state() < ConnectionState::AfterCapabilities
         || ctx.local_capabilities.flags.chunk_cap() == 0
-        || ctx.large_resp_context.in_progress()
+        || !ctx.large_resp_context.in_progress()
     {
         error_code = Some(ErrorCode::UnexpectedRequest);
     }
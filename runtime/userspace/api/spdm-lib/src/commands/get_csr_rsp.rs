@@ -0,0 +1,185 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::CertStoreError;
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Maximum size of the CSR returned in a single CSR response
+pub const MAX_SPDM_CSR_SIZE: usize = 1024;
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    pub struct GetCsrReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub csr_cert_model, set_csr_cert_model: 2, 0;
+    reserved, _: 3, 3;
+    pub csr_tracking_tag, set_csr_tracking_tag: 6, 4;
+    pub overwrite, set_overwrite: 7, 7;
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetCsrReq {
+    pub key_pair_id: u8,               // param1: introduced in v13
+    pub req_attr: GetCsrReqAttributes, // param2: introduced in v13
+    pub requester_info_length: u16,
+    pub opaque_data_length: u16,
+}
+
+impl CommonCodec for GetCsrReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct CsrRespCommon {
+    pub param1: u8,
+    pub param2: u8,
+    pub csr_length: u16,
+    pub reserved: u16,
+}
+
+impl CommonCodec for CsrRespCommon {}
+
+async fn process_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+    csr: &mut [u8],
+) -> CommandResult<usize> {
+    // Validate the version
+    let connection_version = ctx.state.connection_info.version_number();
+    if spdm_hdr.version().ok() != Some(connection_version) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::VersionMismatch, 0, None))?;
+    }
+
+    // GET_CSR was introduced in SPDM 1.2
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::GetCsr as u8,
+            None,
+        ))?;
+    }
+
+    let req = GetCsrReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // KeyPairID and request attributes are reserved prior to SPDM 1.3
+    let key_pair_id = if connection_version >= SpdmVersion::V13 {
+        // CSR tracking requires CERT_INSTALL_RESET_CAP, which is not supported
+        if req.req_attr.csr_tracking_tag() != 0 {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+        req.key_pair_id
+    } else {
+        0
+    };
+
+    let requester_info_len = req.requester_info_length as usize;
+    let req_data_len = requester_info_len + req.opaque_data_length as usize;
+    if req_payload.data_len() < req_data_len {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // GET_CSR and CSR are not part of any transcript
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetCsr);
+
+    let cert_store = ctx.device_certs_store;
+    let result = {
+        let req_data = req_payload
+            .data(req_data_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let (requester_info, opaque_data) = req_data.split_at(requester_info_len);
+        cert_store
+            .get_csr(key_pair_id, requester_info, opaque_data, csr)
+            .await
+    };
+
+    match result {
+        Ok(csr_len) if csr_len <= csr.len() => Ok(csr_len),
+        Ok(_) => Err((false, CommandError::BufferTooSmall)),
+        Err(CertStoreError::Unsupported) => Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::GetCsr as u8,
+            None,
+        )),
+        Err(CertStoreError::BufferTooSmall) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::ResponseTooLarge, 0, None))
+        }
+        Err(_) => Err(ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None)),
+    }
+}
+
+fn generate_csr_response(
+    ctx: &mut SpdmContext<'_>,
+    csr: &[u8],
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Chunking is not supported for CSR responses
+    let rsp_len = size_of::<SpdmMsgHdr>() + size_of::<CsrRespCommon>() + csr.len();
+    if rsp_len > ctx.min_data_transfer_size() {
+        Err(ctx.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+    }
+
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::Csr);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let csr_rsp_common = CsrRespCommon {
+        param1: 0,
+        param2: 0,
+        csr_length: csr.len() as u16,
+        reserved: 0,
+    };
+    payload_len += csr_rsp_common
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += encode_u8_slice(csr, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))?;
+    Ok(())
+}
+
+pub(crate) async fn handle_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Validate the connection state
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if the CSR capability is supported
+    if ctx.local_capabilities.flags.csr_cap() == 0 {
+        Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::GetCsr as u8,
+            None,
+        ))?;
+    }
+
+    // Process GET_CSR request
+    let mut csr = [0u8; MAX_SPDM_CSR_SIZE];
+    let csr_len = process_get_csr(ctx, spdm_hdr, req_payload, &mut csr).await?;
+
+    // Generate CSR response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_csr_response(ctx, &csr[..csr_len], req_payload)
+}
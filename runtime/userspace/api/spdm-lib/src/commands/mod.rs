@@ -9,7 +9,9 @@ pub mod digests_rsp;
 pub mod end_session_rsp;
 pub mod error_rsp;
 pub mod finish_rsp;
+pub mod get_csr_rsp;
 pub mod key_exchange_rsp;
 pub mod measurements_rsp;
+pub mod set_certificate_rsp;
 pub mod vendor_defined_rsp;
 pub mod version_rsp;
@@ -0,0 +1,173 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::{CertStoreError, MAX_CERT_SLOTS_SUPPORTED};
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    pub struct SetCertificateReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub slot_id, set_slot_id: 3, 0;
+    pub cert_model, set_cert_model: 6, 4; // introduced in v13
+    pub erase, set_erase: 7, 7; // introduced in v13
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub struct SetCertificateReq {
+    pub req_attr: SetCertificateReqAttributes, // param1
+    pub key_pair_id: u8,                       // param2: introduced in v13
+}
+
+impl CommonCodec for SetCertificateReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+pub struct SetCertificateRespCommon {
+    pub slot_id: u8, // param1
+    pub param2: u8,
+}
+
+impl CommonCodec for SetCertificateRespCommon {}
+
+async fn process_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<u8> {
+    // Validate the version
+    let connection_version = ctx.state.connection_info.version_number();
+    if spdm_hdr.version().ok() != Some(connection_version) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::VersionMismatch, 0, None))?;
+    }
+
+    // SET_CERTIFICATE was introduced in SPDM 1.2
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::SetCertificate as u8,
+            None,
+        ))?;
+    }
+
+    let req = SetCertificateReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let slot_id = req.req_attr.slot_id();
+    let slot_count = ctx
+        .device_certs_store
+        .slot_count()
+        .min(MAX_CERT_SLOTS_SUPPORTED);
+    if slot_id >= slot_count {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // With the Erase attribute the request carries no certificate chain
+    let erase = connection_version >= SpdmVersion::V13 && req.req_attr.erase() == 1;
+    let cert_chain_len = if erase { 0 } else { req_payload.data_len() };
+
+    if !erase {
+        // The certificate chain must carry the SPDM certificate chain header and root hash,
+        // and its Length field must match the remainder of the request
+        let length = u16::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+        // Rewind to the start of the certificate chain
+        req_payload
+            .push_data(size_of::<u16>())
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        if cert_chain_len <= SPDM_CERT_CHAIN_METADATA_LEN as usize
+            || length as usize != cert_chain_len
+        {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+    }
+
+    // SET_CERTIFICATE and SET_CERTIFICATE_RSP are not part of any transcript, but a new
+    // certificate chain invalidates the ongoing certificate exchange
+    ctx.reset_transcript_via_req_code(ReqRespCode::SetCertificate);
+
+    let cert_store = ctx.device_certs_store;
+    let result = {
+        let cert_chain = req_payload
+            .data(cert_chain_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        cert_store.set_cert_chain(slot_id, cert_chain).await
+    };
+
+    match result {
+        Ok(()) => Ok(slot_id),
+        Err(CertStoreError::Unsupported) => Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::SetCertificate as u8,
+            None,
+        )),
+        Err(CertStoreError::InvalidSlotId) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))
+        }
+        Err(_) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::OperationFailed, 0, None))
+        }
+    }
+}
+
+fn generate_set_certificate_response(
+    ctx: &mut SpdmContext<'_>,
+    slot_id: u8,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::SetCertificateRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let set_cert_rsp_common = SetCertificateRespCommon { slot_id, param2: 0 };
+    payload_len += set_cert_rsp_common
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))?;
+    Ok(())
+}
+
+pub(crate) async fn handle_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Validate the connection state
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Check if the SET_CERTIFICATE capability is supported
+    if ctx.local_capabilities.flags.set_certificate_cap() == 0 {
+        Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnsupportedRequest,
+            ReqRespCode::SetCertificate as u8,
+            None,
+        ))?;
+    }
+
+    // Process SET_CERTIFICATE request
+    let slot_id = process_set_certificate(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate SET_CERTIFICATE_RSP response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_set_certificate_response(ctx, slot_id, req_payload)
+}
@@ -6,15 +6,15 @@ use crate::codec::{encode_u8_slice, Codec, MessageBuf};
 use crate::commands::error_rsp::{encode_error_response, ErrorCode};
 use crate::commands::{
     algorithms_rsp, capabilities_rsp, certificate_rsp, challenge_auth_rsp, chunk_get_rsp,
-    digests_rsp, end_session_rsp, finish_rsp, key_exchange_rsp, measurements_rsp,
-    vendor_defined_rsp, version_rsp,
+    digests_rsp, end_session_rsp, finish_rsp, get_csr_rsp, key_exchange_rsp, measurements_rsp,
+    set_certificate_rsp, vendor_defined_rsp, version_rsp,
 };
 use crate::error::*;
 use crate::measurements::SpdmMeasurements;
 use crate::protocol::algorithms::*;
 use crate::protocol::common::{ReqRespCode, SpdmMsgHdr};
 use crate::protocol::version::*;
-use crate::protocol::DeviceCapabilities;
+use crate::protocol::{DeviceCapabilities, MIN_DATA_TRANSFER_SIZE_V12};
 use crate::session::SessionManager;
 use crate::state::{ConnectionState, State};
 use crate::transcript::{Transcript, TranscriptContext};
@@ -179,6 +179,10 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::EndSession => {
                 end_session_rsp::handle_end_session(self, req_msg_header, req).await?
             }
+            ReqRespCode::GetCsr => get_csr_rsp::handle_get_csr(self, req_msg_header, req).await?,
+            ReqRespCode::SetCertificate => {
+                set_certificate_rsp::handle_set_certificate(self, req_msg_header, req).await?
+            }
             ReqRespCode::VendorDefinedRequest => {
                 vendor_defined_rsp::handle_vendor_defined_request(self, req_msg_header, req).await?
             }
@@ -213,6 +217,19 @@ impl<'a> SpdmContext<'a> {
         }
     }
 
+    /// Returns the algorithms selected in the ALGORITHMS response, or None if
+    /// algorithm negotiation has not completed on this connection.
+    pub fn negotiated_algorithms(&self) -> Option<NegotiatedAlgorithms> {
+        if self.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+            return None;
+        }
+
+        Some(self.local_algorithms.negotiate(
+            self.state.connection_info.peer_algorithms(),
+            algorithms_rsp::meas_supported(&self.local_capabilities.flags),
+        ))
+    }
+
     pub(crate) fn reset(&mut self) {
         self.state.reset();
         self.session_mgr.reset();
@@ -226,14 +243,36 @@ impl<'a> SpdmContext<'a> {
         Ok(())
     }
 
-    /// Returns the minimum data transfer size based on local and peer capabilities.
+    /// Limit the size of each chunked response message, e.g. to fit the transport MTU.
+    ///
+    /// # Arguments
+    /// * `size` - The maximum size of a CHUNK_RESPONSE message in bytes. It must be at
+    ///   least the SPDM 1.2 minimum data transfer size and fit the transport's max message size.
+    pub fn set_max_chunk_size(&mut self, size: usize) -> SpdmResult<()> {
+        let max_message_size = self
+            .transport
+            .max_message_size()
+            .map_err(SpdmError::Transport)?;
+        if size < MIN_DATA_TRANSFER_SIZE_V12 as usize || size > max_message_size {
+            Err(SpdmError::InvalidParam)?;
+        }
+        self.large_resp_context.set_max_chunk_size(size);
+        Ok(())
+    }
+
+    /// Returns the minimum data transfer size based on local and peer capabilities,
+    /// further limited by the configured max chunk size.
     pub(crate) fn min_data_transfer_size(&self) -> usize {
-        self.local_capabilities.data_transfer_size.min(
+        let data_transfer_size = self.local_capabilities.data_transfer_size.min(
             self.state
                 .connection_info
                 .peer_capabilities()
                 .data_transfer_size,
-        ) as usize
+        ) as usize;
+        match self.large_resp_context.max_chunk_size() {
+            Some(max_chunk_size) => data_transfer_size.min(max_chunk_size),
+            None => data_transfer_size,
+        }
     }
 
     pub(crate) fn verify_negotiated_hash_algo(&mut self) -> SpdmResult<()> {
@@ -326,7 +365,8 @@ impl<'a> SpdmContext<'a> {
                     self.shared_transcript.reset_context(TranscriptContext::M1);
                 }
             }
-            ReqRespCode::GetDigests => {
+            // A newly installed certificate chain invalidates any cached certificate exchange
+            ReqRespCode::GetDigests | ReqRespCode::SetCertificate => {
                 self.shared_transcript.reset_context(TranscriptContext::M1);
             }
             _ => {}
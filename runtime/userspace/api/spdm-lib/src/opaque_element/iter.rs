@@ -0,0 +1,119 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::*;
+use core::mem::size_of;
+
+// standards_body_id + vendor_id_len, followed by vendor_id and a u16 opaque_element_data_len
+const OPAQUE_ELEMENT_FIXED_HDR_LEN: usize = 1 + 1 + 2;
+
+/// Zero-copy iterator over the opaque elements of an opaque data blob in the
+/// SPDM general opaque data format.
+///
+/// Yields `(id, data)` for each element, where `id` is the registry/standards body ID
+/// and `data` is the opaque element data (without vendor ID or padding). Every length
+/// is checked against the buffer; the first malformed element yields an error and
+/// ends the iteration.
+pub struct OpaqueElementIter<'a> {
+    buf: &'a [u8],
+    remaining: u8,
+}
+
+impl<'a> OpaqueElementIter<'a> {
+    /// Parses the general opaque data header at the start of `opaque_data`.
+    pub fn new(opaque_data: &'a [u8]) -> OpaqueDataResult<Self> {
+        if opaque_data.len() < size_of::<GeneralOpaqueDataHdr>() {
+            return Err(OpaqueDataError::InvalidFormat);
+        }
+        let (hdr, buf) = opaque_data.split_at(size_of::<GeneralOpaqueDataHdr>());
+        Ok(OpaqueElementIter {
+            buf,
+            remaining: hdr[0],
+        })
+    }
+
+    fn next_element(&mut self) -> OpaqueDataResult<(u8, &'a [u8])> {
+        let buf = self.buf;
+        if buf.len() < OPAQUE_ELEMENT_FIXED_HDR_LEN {
+            return Err(OpaqueDataError::InvalidFormat);
+        }
+        let id = buf[0];
+        let vendor_id_len = buf[1] as usize;
+        if vendor_id_len > MAX_SPDM_VENDOR_ID_LEN as usize {
+            return Err(OpaqueDataError::InvalidVendorIdLength);
+        }
+
+        let len_offset = 2 + vendor_id_len;
+        let data_offset = len_offset + 2;
+        if buf.len() < data_offset {
+            return Err(OpaqueDataError::InvalidFormat);
+        }
+        let data_len = u16::from_le_bytes([buf[len_offset], buf[len_offset + 1]]) as usize;
+        let data_end = data_offset + data_len;
+        // Each element is padded with zeros to a 4-byte boundary
+        let element_end = data_end.next_multiple_of(4);
+        if buf.len() < element_end {
+            return Err(OpaqueDataError::InvalidFormat);
+        }
+        if buf[data_end..element_end].iter().any(|&b| b != 0) {
+            return Err(OpaqueDataError::InvalidFormat);
+        }
+
+        self.buf = &buf[element_end..];
+        Ok((id, &buf[data_offset..data_end]))
+    }
+}
+
+impl<'a> Iterator for OpaqueElementIter<'a> {
+    type Item = OpaqueDataResult<(u8, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let element = self.next_element();
+        self.remaining = if element.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const OPAQUE_BLOB: [u8; 24] = [
+        // general opaque data header: 2 elements
+        0x02, 0x00, 0x00, 0x00,
+        // element 0: DMTF, no vendor ID, 2 data bytes, 2 padding bytes
+        0x00, 0x00, 0x02, 0x00, 0xaa, 0xbb, 0x00, 0x00,
+        // element 1: id 0x0a, 2-byte vendor ID, 5 data bytes, 3 padding bytes
+        0x0a, 0x02, 0x34, 0x12, 0x05, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00,
+    ];
+
+    #[test]
+    fn test_opaque_element_iter() {
+        let mut iter = OpaqueElementIter::new(&OPAQUE_BLOB).unwrap();
+        assert_eq!(iter.next(), Some(Ok((0x00, &[0xaa, 0xbb][..]))));
+        assert_eq!(
+            iter.next(),
+            Some(Ok((0x0a, &[0x01, 0x02, 0x03, 0x04, 0x05][..])))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_opaque_element_iter_truncated() {
+        // Drop the last data byte and the padding of element 1
+        let truncated = &OPAQUE_BLOB[..OPAQUE_BLOB.len() - 2];
+        let mut iter = OpaqueElementIter::new(truncated).unwrap();
+        assert_eq!(iter.next(), Some(Ok((0x00, &[0xaa, 0xbb][..]))));
+        assert_eq!(iter.next(), Some(Err(OpaqueDataError::InvalidFormat)));
+        assert_eq!(iter.next(), None);
+
+        assert!(OpaqueElementIter::new(&OPAQUE_BLOB[..3]).is_err());
+    }
+}
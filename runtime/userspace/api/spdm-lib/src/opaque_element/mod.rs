@@ -1,3 +1,6 @@
 // Licensed under the Apache-2.0 license
 
+pub mod iter;
 pub mod secure_message;
+
+pub use iter::OpaqueElementIter;
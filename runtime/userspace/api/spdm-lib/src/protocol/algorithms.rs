@@ -676,3 +676,115 @@ impl LocalDeviceAlgorithms<'_> {
         }
     }
 }
+
+/// Algorithms selected by the responder in the ALGORITHMS response
+#[derive(Debug, Clone, Copy)]
+pub struct NegotiatedAlgorithms {
+    pub base_hash_algo: BaseHashAlgo,
+    pub measurement_hash_algo: MeasurementHashAlgo,
+    pub base_asym_algo: BaseAsymAlgo,
+}
+
+impl NegotiatedAlgorithms {
+    /// Digest size in bytes of the selected base hash algorithm, if supported.
+    pub fn base_hash_size(&self) -> Option<usize> {
+        match self.base_hash_algo {
+            algo if algo.tpm_alg_sha_256() == 1 => Some(32),
+            algo if algo.tpm_alg_sha_384() == 1 => Some(48),
+            algo if algo.tpm_alg_sha_512() == 1 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Digest size in bytes of the selected measurement hash algorithm, if supported.
+    pub fn measurement_hash_size(&self) -> Option<usize> {
+        match self.measurement_hash_algo {
+            algo if algo.tpm_alg_sha_256() == 1 => Some(32),
+            algo if algo.tpm_alg_sha_384() == 1 => Some(48),
+            algo if algo.tpm_alg_sha_512() == 1 => Some(64),
+            _ => None,
+        }
+    }
+}
+
+impl LocalDeviceAlgorithms<'_> {
+    /// Select the algorithms to use with the peer.
+    ///
+    /// # Arguments
+    /// * `peer_algorithms` - The algorithms advertised by the peer in NEGOTIATE_ALGORITHMS.
+    /// * `meas_supported` - Whether the responder supports measurements.
+    ///
+    /// # Returns
+    /// * `NegotiatedAlgorithms` - The selected algorithms.
+    pub(crate) fn negotiate(
+        &self,
+        peer_algorithms: &DeviceAlgorithms,
+        meas_supported: bool,
+    ) -> NegotiatedAlgorithms {
+        let local_algorithms = &self.device_algorithms;
+        let algorithm_priority_table = &self.algorithm_priority_table;
+
+        // MeasurementHashAlgo is the responder's own selection
+        let measurement_hash_algo = if meas_supported {
+            local_algorithms.measurement_hash_algo
+        } else {
+            MeasurementHashAlgo::default()
+        };
+
+        NegotiatedAlgorithms {
+            base_hash_algo: local_algorithms.base_hash_algo.prioritize(
+                &peer_algorithms.base_hash_algo,
+                algorithm_priority_table.base_hash_algo,
+            ),
+            measurement_hash_algo,
+            base_asym_algo: BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
+                &peer_algorithms.base_asym_algo.0,
+                algorithm_priority_table.base_asym_algo,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_algorithms() {
+        let mut device_algorithms = DeviceAlgorithms::default();
+        device_algorithms.base_hash_algo.set_tpm_alg_sha_512(1);
+        let local = LocalDeviceAlgorithms::new(device_algorithms);
+
+        let mut peer = DeviceAlgorithms::default();
+        peer.base_hash_algo.set_tpm_alg_sha_256(1);
+        peer.base_hash_algo.set_tpm_alg_sha_512(1);
+        peer.base_asym_algo.set_tpm_alg_ecdsa_ecc_nist_p256(1);
+
+        // SHA-512 is preferred by the hash priority table
+        let negotiated = local.negotiate(&peer, true);
+        assert_eq!(
+            negotiated.base_hash_algo.0,
+            BaseHashAlgo::from(BaseHashAlgoType::TpmAlgSha512).0
+        );
+        assert_eq!(negotiated.base_hash_size(), Some(64));
+        assert_eq!(
+            negotiated.base_asym_algo.0,
+            u32::from(BaseAsymAlgoType::TpmAlgEcdsaEccNistP384)
+        );
+        assert_eq!(negotiated.measurement_hash_algo.tpm_alg_sha_384(), 1);
+        assert_eq!(negotiated.measurement_hash_size(), Some(48));
+
+        // Without SHA-512 on the peer, SHA-384 is selected
+        peer.base_hash_algo.set_tpm_alg_sha_512(0);
+        let negotiated = local.negotiate(&peer, false);
+        assert_eq!(negotiated.base_hash_size(), Some(48));
+        assert_eq!(negotiated.measurement_hash_algo.0, 0);
+        assert_eq!(negotiated.measurement_hash_size(), None);
+
+        // No common base hash algorithm
+        peer.base_hash_algo = BaseHashAlgo::from(BaseHashAlgoType::TpmAlgSha256);
+        let negotiated = local.negotiate(&peer, true);
+        assert_eq!(negotiated.base_hash_algo.0, 0);
+        assert_eq!(negotiated.base_hash_size(), None);
+    }
+}
@@ -31,6 +31,10 @@ pub(crate) enum ReqRespCode {
     FinishRsp = 0x65,
     EndSession = 0xEC,
     EndSessionAck = 0x6C,
+    GetCsr = 0xED,
+    Csr = 0x6D,
+    SetCertificate = 0xEE,
+    SetCertificateRsp = 0x6E,
     VendorDefinedRequest = 0xFE,
     VendorDefinedResponse = 0x7E,
     Error = 0x7F,
@@ -62,6 +66,10 @@ impl TryFrom<u8> for ReqRespCode {
             0x65 => Ok(ReqRespCode::FinishRsp),
             0xEC => Ok(ReqRespCode::EndSession),
             0x6C => Ok(ReqRespCode::EndSessionAck),
+            0xED => Ok(ReqRespCode::GetCsr),
+            0x6D => Ok(ReqRespCode::Csr),
+            0xEE => Ok(ReqRespCode::SetCertificate),
+            0x6E => Ok(ReqRespCode::SetCertificateRsp),
             0xFE => Ok(ReqRespCode::VendorDefinedRequest),
             0x7E => Ok(ReqRespCode::VendorDefinedResponse),
             _ => Err(SpdmError::UnsupportedRequest),
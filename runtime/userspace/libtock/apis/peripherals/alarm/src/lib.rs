@@ -57,6 +57,11 @@ impl Convert for Milliseconds {
 }
 
 impl<S: Syscalls, C: platform::subscribe::Config> Alarm<S, C> {
+    /// Driver number, for callers that subscribe to the alarm upcall themselves.
+    pub const DRIVER_NUM: u32 = DRIVER_NUM;
+    /// Subscribe number of the upcall delivered when the alarm fires.
+    pub const CALLBACK: u32 = subscribe::CALLBACK;
+
     /// Run a check against the console capsule to ensure it is present.
     #[inline(always)]
     pub fn exists() -> Result<(), ErrorCode> {
@@ -80,6 +85,18 @@ impl<S: Syscalls, C: platform::subscribe::Config> Alarm<S, C> {
         Ok(ticks.saturating_div(freq / 1000))
     }
 
+    /// Start the alarm without waiting for it, for callers that subscribe to
+    /// [`Self::CALLBACK`] themselves. Returns the tick count the alarm fires at.
+    pub fn set_relative<T: Convert>(time: T) -> Result<u32, ErrorCode> {
+        let ticks = time.to_ticks(Self::get_frequency()?);
+        S::command(DRIVER_NUM, command::SET_RELATIVE, ticks.0, 0).to_result()
+    }
+
+    /// Cancel an alarm started with [`Self::set_relative`].
+    pub fn stop() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command::STOP, 0, 0).to_result()
+    }
+
     pub fn sleep_for<T: Convert>(time: T) -> Result<(), ErrorCode> {
         let freq = Self::get_frequency()?;
         let ticks = time.to_ticks(freq);
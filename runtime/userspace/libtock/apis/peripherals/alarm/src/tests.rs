@@ -22,3 +22,13 @@ fn sleep() {
     assert_eq!(Alarm::sleep_for(Ticks(1000)), Ok(()));
     assert_eq!(Alarm::sleep_for(Milliseconds(1000)), Ok(()));
 }
+
+#[test]
+fn set_relative() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Alarm::set_relative(Milliseconds(10)), Ok(10));
+    assert_eq!(Alarm::set_relative(Ticks(5)), Ok(15));
+}
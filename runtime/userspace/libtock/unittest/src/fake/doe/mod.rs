@@ -0,0 +1,173 @@
+//! Fake implementation of the DOE driver.
+//!
+//! Transmitted data objects are looped back and delivered to the next receive.
+//! Additional received objects can be queued with `push_message`.
+
+use crate::fake::SyscallDriver;
+use crate::{DriverInfo, DriverShareRef};
+use crate::{RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+pub struct FakeDoeDriver {
+    max_message_size: Cell<u32>,
+    read_buffer: RefCell<RwAllowBuffer>,
+    write_buffer: RefCell<RoAllowBuffer>,
+    share_ref: DriverShareRef,
+    rx_queue: RefCell<VecDeque<Vec<u8>>>,
+    rx_pending: Cell<bool>,
+    sent: RefCell<Vec<Vec<u8>>>,
+}
+
+impl Default for FakeDoeDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeDoeDriver {
+    pub fn new() -> Self {
+        Self {
+            max_message_size: Cell::new(4096),
+            read_buffer: Default::default(),
+            write_buffer: Default::default(),
+            share_ref: Default::default(),
+            rx_queue: Default::default(),
+            rx_pending: Cell::new(false),
+            sent: Default::default(),
+        }
+    }
+
+    /// Set the maximum data object size reported to userspace
+    pub fn set_max_message_size(&self, size: u32) {
+        self.max_message_size.set(size);
+    }
+
+    /// Queue a data object to be received
+    pub fn push_message(&self, message: Vec<u8>) {
+        self.rx_queue.borrow_mut().push_back(message);
+        self.try_deliver();
+    }
+
+    /// Get all data objects transmitted so far
+    pub fn sent_messages(&self) -> Vec<Vec<u8>> {
+        self.sent.borrow().clone()
+    }
+
+    fn try_deliver(&self) {
+        if !self.rx_pending.get() {
+            return;
+        }
+        let Some(message) = self.rx_queue.borrow_mut().pop_front() else {
+            return;
+        };
+        let len = {
+            let mut buf = self.read_buffer.borrow_mut();
+            let len = message.len().min(buf.len());
+            buf[..len].copy_from_slice(&message[..len]);
+            len
+        };
+        self.rx_pending.set(false);
+        self.share_ref
+            .schedule_upcall(subscribe::MESSAGE_RECEIVED, (len as u32, 0, 0))
+            .expect("Failed to schedule MESSAGE_RECEIVED upcall");
+    }
+}
+
+impl SyscallDriver for FakeDoeDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(driver_num::DOE_SPDM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_num: u32, _arg0: u32, _arg1: u32) -> CommandReturn {
+        match command_num {
+            command::EXISTS => crate::command_return::success(),
+            command::RECEIVE_MESSAGE => {
+                if self.rx_pending.get() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.rx_pending.set(true);
+                self.try_deliver();
+                crate::command_return::success()
+            }
+            command::SEND_MESSAGE => {
+                let message = self.write_buffer.borrow().to_vec();
+                if message.len() > self.max_message_size.get() as usize {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                self.sent.borrow_mut().push(message.clone());
+                // Like the kernel driver, arg0 is 1 when the transmission succeeded.
+                self.share_ref
+                    .schedule_upcall(subscribe::MESSAGE_TRANSMITTED, (1, 0, 0))
+                    .expect("Failed to schedule MESSAGE_TRANSMITTED upcall");
+                // Loop the data object back to the receiver.
+                self.push_message(message);
+                crate::command_return::success()
+            }
+            command::MAX_DATA_OBJECT_SIZE => {
+                crate::command_return::success_u32(self.max_message_size.get())
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        allow_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if allow_num == allow_rw::MESSAGE_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readonly(
+        &self,
+        allow_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if allow_num == allow_ro::MESSAGE_WRITE {
+            Ok(self.write_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Constants and Command IDs
+// -----------------------------------------------------------------------------
+
+pub mod driver_num {
+    pub const DOE_SPDM: u32 = 0xA000_0010;
+}
+
+mod command {
+    pub const EXISTS: u32 = 0;
+    pub const RECEIVE_MESSAGE: u32 = 1;
+    pub const SEND_MESSAGE: u32 = 2;
+    pub const MAX_DATA_OBJECT_SIZE: u32 = 3;
+}
+
+mod subscribe {
+    pub const MESSAGE_RECEIVED: u32 = 0;
+    pub const MESSAGE_TRANSMITTED: u32 = 1;
+}
+
+mod allow_ro {
+    pub const MESSAGE_WRITE: u32 = 0;
+}
+
+mod allow_rw {
+    pub const MESSAGE_READ: u32 = 0;
+}
+
+#[cfg(test)]
+mod tests;
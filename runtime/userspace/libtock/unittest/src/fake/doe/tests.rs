@@ -0,0 +1,44 @@
+use super::{allow_ro, command, driver_num::DOE_SPDM, subscribe};
+use crate::fake::{self, FakeDoeDriver};
+use libtock_platform::{share, AllowRo, DefaultConfig, Subscribe, Syscalls, YieldNoWaitReturn};
+use std::cell::Cell;
+use std::rc::Rc;
+
+// The transmit-complete upcall reports success the way the kernel driver does.
+#[test]
+fn transmitted_upcall() {
+    let kernel = fake::Kernel::new();
+    let doe = Rc::new(FakeDoeDriver::new());
+    kernel.add_driver(&doe);
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    share::scope::<
+        (
+            AllowRo<_, DOE_SPDM, { allow_ro::MESSAGE_WRITE }>,
+            Subscribe<_, DOE_SPDM, { subscribe::MESSAGE_TRANSMITTED }>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        assert_eq!(
+            fake::Syscalls::subscribe::<
+                _,
+                _,
+                DefaultConfig,
+                DOE_SPDM,
+                { subscribe::MESSAGE_TRANSMITTED },
+            >(subscribe, &listener),
+            Ok(())
+        );
+        fake::Syscalls::allow_ro::<DefaultConfig, DOE_SPDM, { allow_ro::MESSAGE_WRITE }>(
+            allow_ro, b"abcd",
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DOE_SPDM, command::SEND_MESSAGE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((1,)));
+    });
+    assert_eq!(doe.sent_messages(), vec![b"abcd".to_vec()]);
+}
@@ -12,6 +12,7 @@ pub struct FakeFlashDriver {
     write_buffer: RefCell<RoAllowBuffer>,
     share_ref: DriverShareRef,
     flash_content: RefCell<Vec<u8>>,
+    stuck_byte: RefCell<Option<(usize, u8)>>,
 }
 
 impl Default for FakeFlashDriver {
@@ -30,6 +31,7 @@ impl FakeFlashDriver {
             write_buffer: Default::default(),
             share_ref: Default::default(),
             flash_content: Default::default(),
+            stuck_byte: Default::default(),
         }
     }
 
@@ -47,6 +49,16 @@ impl FakeFlashDriver {
     pub fn set_flash_content(&self, content: Vec<u8>) {
         self.flash_content.replace(content);
     }
+
+    /// Make the byte at `address` keep `value` across erases, simulating a bad cell.
+    pub fn set_stuck_byte(&self, address: usize, value: u8) {
+        self.stuck_byte.replace(Some((address, value)));
+    }
+
+    /// Get the flash content
+    pub fn flash_content(&self) -> Vec<u8> {
+        self.flash_content.borrow().clone()
+    }
 }
 
 impl SyscallDriver for FakeFlashDriver {
@@ -98,7 +110,20 @@ impl SyscallDriver for FakeFlashDriver {
             }
             flash_storage_cmd::ERASE => {
                 // Simulate erase completion
+                let address = arg0 as usize;
                 let len = arg1 as usize;
+                {
+                    let mut content = self.flash_content.borrow_mut();
+                    if address + len > content.len() {
+                        return crate::command_return::failure(ErrorCode::NoMem);
+                    }
+                    content[address..address + len].fill(0xFF);
+                    if let Some((stuck, value)) = *self.stuck_byte.borrow() {
+                        if (address..address + len).contains(&stuck) {
+                            content[stuck] = value;
+                        }
+                    }
+                }
                 self.share_ref
                     .schedule_upcall(subscribe::ERASE_DONE, (len as u32, 0, 0))
                     .expect("Failed to schedule ERASE_DONE upcall");
@@ -18,6 +18,7 @@ mod buttons;
 mod buzzer;
 mod console;
 mod dma;
+mod doe;
 mod flash;
 mod gpio;
 mod helper;
@@ -42,6 +43,7 @@ pub use buttons::Buttons;
 pub use buzzer::Buzzer;
 pub use console::Console;
 pub use dma::FakeDMADriver;
+pub use doe::FakeDoeDriver;
 pub use flash::FakeFlashDriver;
 pub use gpio::{Gpio, GpioMode, InterruptEdge, PullMode};
 pub use helper::wait_for_future_ready;
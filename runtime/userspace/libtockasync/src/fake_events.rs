@@ -0,0 +1,35 @@
+// Licensed under the Apache-2.0 license
+
+//! A fake driver for the host tests, whose upcalls are triggered directly by the test.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use libtock_platform::{CommandReturn, ErrorCode};
+use libtock_unittest::{fake, DriverInfo, DriverShareRef};
+
+pub(crate) const DRIVER_NUM: u32 = 0x8000_0000;
+
+/// Has two upcalls, 0 and 1, and supports no commands.
+#[derive(Default)]
+pub(crate) struct FakeEvents {
+    pub(crate) share_ref: DriverShareRef,
+}
+
+impl fake::SyscallDriver for FakeEvents {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, _: u32, _: u32, _: u32) -> CommandReturn {
+        libtock_unittest::command_return::failure(ErrorCode::NoSupport)
+    }
+}
+
+pub(crate) fn poll<F: Future>(f: Pin<&mut F>) -> Poll<F::Output> {
+    f.poll(&mut Context::from_waker(Waker::noop()))
+}
@@ -20,14 +20,34 @@ pub struct TockSubscribe {
     result: Cell<Option<(u32, u32, u32)>>,
     waker: Cell<Option<Waker>>,
     error: Option<ErrorCode>,
+    driver_num: u32,
+    subscribe_num: u32,
+    /// Whether the kernel holds a pointer to this instance as upcall data.
+    subscribed: bool,
+    /// Replaces the upcall with the null upcall; see [`null_upcall`].
+    unsubscribe_fn: fn(u32, u32),
+}
+
+/// Result of a [`TockSubscribe`] future.
+pub type SubscribeResult = Result<(u32, u32, u32), ErrorCode>;
+
+/// Which of the two futures passed to [`TockSubscribe::select`] completed first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Either<A, B> {
+    First(A),
+    Second(B),
 }
 
 impl TockSubscribe {
-    fn new() -> TockSubscribe {
+    fn new<S: Syscalls>(driver_num: u32, subscribe_num: u32) -> TockSubscribe {
         TockSubscribe {
             result: Cell::new(None),
             waker: Cell::new(None),
             error: None,
+            driver_num,
+            subscribe_num,
+            subscribed: false,
+            unsubscribe_fn: null_upcall::<S>,
         }
     }
 
@@ -42,12 +62,12 @@ impl TockSubscribe {
         buffer: &mut [u8],
     ) -> Pin<Box<TockSubscribe>> {
         // Pinning is necessary since we are passing a pointer to the TockSubscribe to the kernel.
-        let mut f = Pin::new(Box::new(TockSubscribe::new()));
+        let mut f = Pin::new(Box::new(TockSubscribe::new::<S>(driver_num, subscribe_num)));
         let upcall_fcn = (kernel_upcall::<S> as *const ()) as usize;
         let upcall_data = (&*f as *const TockSubscribe) as usize;
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, r2, _] = unsafe {
             S::syscall4::<{ syscall_class::ALLOW_RW }>([
                 driver_num.into(),
@@ -74,7 +94,7 @@ impl TockSubscribe {
         }
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, _, _] = unsafe {
             S::syscall4::<{ syscall_class::SUBSCRIBE }>([
                 driver_num.into(),
@@ -85,7 +105,7 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => f.subscribed = true,
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -103,12 +123,12 @@ impl TockSubscribe {
         buffer: &[u8],
     ) -> Pin<Box<TockSubscribe>> {
         // Pinning is necessary since we are passing a pointer to the TockSubscribe to the kernel.
-        let mut f = Pin::new(Box::new(TockSubscribe::new()));
+        let mut f = Pin::new(Box::new(TockSubscribe::new::<S>(driver_num, subscribe_num)));
         let upcall_fcn = (kernel_upcall::<S> as *const ()) as usize;
         let upcall_data = (&*f as *const TockSubscribe) as usize;
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, r2, _] = unsafe {
             S::syscall4::<{ syscall_class::ALLOW_RO }>([
                 driver_num.into(),
@@ -135,7 +155,7 @@ impl TockSubscribe {
         }
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, _, _] = unsafe {
             S::syscall4::<{ syscall_class::SUBSCRIBE }>([
                 driver_num.into(),
@@ -146,7 +166,7 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => f.subscribed = true,
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -166,13 +186,13 @@ impl TockSubscribe {
         buffer_rw: &mut [u8],
     ) -> Pin<Box<TockSubscribe>> {
         // Pinning is necessary since we are passing a pointer to the TockSubscribe to the kernel.
-        let mut f = Pin::new(Box::new(TockSubscribe::new()));
+        let mut f = Pin::new(Box::new(TockSubscribe::new::<S>(driver_num, subscribe_num)));
         let upcall_fcn = (kernel_upcall::<S> as *const ()) as usize;
         let upcall_data = (&*f as *const TockSubscribe) as usize;
 
         // Allow RO
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, r2, _] = unsafe {
             S::syscall4::<{ syscall_class::ALLOW_RO }>([
                 driver_num.into(),
@@ -200,7 +220,7 @@ impl TockSubscribe {
 
         // Allow RW
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, r2, _] = unsafe {
             S::syscall4::<{ syscall_class::ALLOW_RW }>([
                 driver_num.into(),
@@ -227,7 +247,7 @@ impl TockSubscribe {
         }
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, _, _] = unsafe {
             S::syscall4::<{ syscall_class::SUBSCRIBE }>([
                 driver_num.into(),
@@ -238,7 +258,7 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => f.subscribed = true,
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -251,12 +271,12 @@ impl TockSubscribe {
 
     pub fn subscribe<S: Syscalls>(driver_num: u32, subscribe_num: u32) -> Pin<Box<TockSubscribe>> {
         // Pinning is necessary since we are passing a pointer to the TockSubscribe to the kernel.
-        let mut f = Pin::new(Box::new(TockSubscribe::new()));
+        let mut f = Pin::new(Box::new(TockSubscribe::new::<S>(driver_num, subscribe_num)));
         let upcall_fcn = (kernel_upcall::<S> as *const ()) as usize;
         let upcall_data = (&*f as *const TockSubscribe) as usize;
 
         // Safety: we are passing in a fixed (safe) function pointer and a pointer to a pinned instance.
-        // If the instance is dropped before the upcall comes in, then we panic in the Drop impl.
+        // If the instance is dropped before the upcall comes in, the Drop impl unsubscribes.
         let [r0, r1, _, _] = unsafe {
             S::syscall4::<{ syscall_class::SUBSCRIBE }>([
                 driver_num.into(),
@@ -267,7 +287,7 @@ impl TockSubscribe {
         };
         let return_variant: ReturnVariant = r0.as_u32().into();
         match return_variant {
-            return_variant::SUCCESS_2_U32 => {}
+            return_variant::SUCCESS_2_U32 => f.subscribed = true,
             return_variant::FAILURE_2_U32 => {
                 f.set_err(r1.as_u32().try_into().unwrap_or(ErrorCode::Fail));
             }
@@ -290,6 +310,86 @@ impl TockSubscribe {
     pub fn cancel(&mut self) {
         self.set_err(ErrorCode::Fail);
     }
+
+    /// Replace the upcall with the null upcall, which also discards any queued upcall, and
+    /// cancel the future. Does nothing if the future already completed.
+    pub(crate) fn unsubscribe(&mut self) {
+        if self.result.get().is_some() {
+            return;
+        }
+        if self.subscribed {
+            (self.unsubscribe_fn)(self.driver_num, self.subscribe_num);
+            self.subscribed = false;
+        }
+        if self.error.is_none() {
+            self.cancel();
+        }
+    }
+
+    /// Wait for whichever of two subscriptions completes first. The other one is
+    /// unsubscribed, so its upcall will not be delivered.
+    ///
+    /// Use like:
+    /// `TockSubscribe::select(alarm, mailbox).await`.
+    pub async fn select(
+        mut a: Pin<Box<TockSubscribe>>,
+        mut b: Pin<Box<TockSubscribe>>,
+    ) -> Either<SubscribeResult, SubscribeResult> {
+        let result = core::future::poll_fn(|cx| {
+            if let Poll::Ready(result) = a.as_mut().poll(cx) {
+                return Poll::Ready(Either::First(result));
+            }
+            if let Poll::Ready(result) = b.as_mut().poll(cx) {
+                return Poll::Ready(Either::Second(result));
+            }
+            Poll::Pending
+        })
+        .await;
+        match result {
+            Either::First(_) => b.unsubscribe(),
+            Either::Second(_) => a.unsubscribe(),
+        }
+        result
+    }
+
+    /// Wait for both subscriptions to complete.
+    pub async fn join(
+        mut a: Pin<Box<TockSubscribe>>,
+        mut b: Pin<Box<TockSubscribe>>,
+    ) -> (SubscribeResult, SubscribeResult) {
+        let mut result_a = None;
+        let mut result_b = None;
+        core::future::poll_fn(|cx| {
+            if result_a.is_none() {
+                if let Poll::Ready(result) = a.as_mut().poll(cx) {
+                    result_a = Some(result);
+                }
+            }
+            if result_b.is_none() {
+                if let Poll::Ready(result) = b.as_mut().poll(cx) {
+                    result_b = Some(result);
+                }
+            }
+            match (result_a, result_b) {
+                (Some(a), Some(b)) => Poll::Ready((a, b)),
+                _ => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+/// Subscribe the null upcall, so the kernel no longer holds a pointer to a [`TockSubscribe`].
+fn null_upcall<S: Syscalls>(driver_num: u32, subscribe_num: u32) {
+    // Safety: the null upcall is always valid.
+    unsafe {
+        S::syscall4::<{ syscall_class::SUBSCRIBE }>([
+            driver_num.into(),
+            subscribe_num.into(),
+            0usize.into(),
+            0usize.into(),
+        ]);
+    }
 }
 
 extern "C" fn kernel_upcall<S: Syscalls>(arg0: u32, arg1: u32, arg2: u32, data: Register) {
@@ -298,8 +398,8 @@ extern "C" fn kernel_upcall<S: Syscalls>(arg0: u32, arg1: u32, arg2: u32, data:
     // Safety: we set the pointer to a pinned TockSubscribe instance in the subscribe.
     // If the subscribe call had failed, then the error would have been set this upcall
     // will never be called.
-    // If the reference to the TockSubscribe is dropped before the upcall, then we panic
-    // in the Drop instead of dereferencing into an invalid pointer.
+    // If the TockSubscribe is dropped before the upcall, the Drop impl replaces it with
+    // the null upcall, so this is never called with an invalid pointer.
     unsafe { (*upcall).result.set(Some((arg0, arg1, arg2))) };
     if let Some(waker) = unsafe { (*upcall).waker.take() } {
         waker.wake();
@@ -308,7 +408,7 @@ extern "C" fn kernel_upcall<S: Syscalls>(arg0: u32, arg1: u32, arg2: u32, data:
 }
 
 impl Future for TockSubscribe {
-    type Output = Result<(u32, u32, u32), ErrorCode>;
+    type Output = SubscribeResult;
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         if let Some(err) = self.error {
             return Poll::Ready(Err(err));
@@ -326,8 +426,88 @@ impl Future for TockSubscribe {
 
 impl Drop for TockSubscribe {
     fn drop(&mut self) {
-        if self.result.get().is_none() && self.error.is_none() {
-            panic!("The TockSubscribe future was dropped before the upcall happened.");
-        }
+        // The kernel must not deliver the upcall to a freed instance.
+        self.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_events::{poll, FakeEvents, DRIVER_NUM};
+    use core::pin::pin;
+    use libtock_unittest::fake;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_select_resolves_on_first_upcall() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeEvents::default());
+        kernel.add_driver(&driver);
+
+        let a = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        let b = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 1);
+        let mut select = pin!(TockSubscribe::select(a, b));
+        assert_eq!(poll(select.as_mut()), Poll::Pending);
+
+        driver.share_ref.schedule_upcall(1, (7, 8, 9)).unwrap();
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(
+            poll(select.as_mut()),
+            Poll::Ready(Either::Second(Ok((7, 8, 9))))
+        );
+
+        // The losing subscription was replaced with the null upcall.
+        driver.share_ref.schedule_upcall(0, (1, 2, 3)).unwrap();
+        assert_eq!(
+            fake::Syscalls::yield_no_wait(),
+            libtock_platform::YieldNoWaitReturn::NoUpcall
+        );
+    }
+
+    #[test]
+    fn test_drop_before_upcall_unsubscribes() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeEvents::default());
+        kernel.add_driver(&driver);
+
+        let mut sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        assert_eq!(poll(sub.as_mut()), Poll::Pending);
+        drop(sub);
+
+        // The upcall is not delivered to the freed future.
+        driver.share_ref.schedule_upcall(0, (1, 2, 3)).unwrap();
+        assert_eq!(
+            fake::Syscalls::yield_no_wait(),
+            libtock_platform::YieldNoWaitReturn::NoUpcall
+        );
+
+        // A new subscription to the same upcall still works.
+        let mut sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        driver.share_ref.schedule_upcall(0, (4, 5, 6)).unwrap();
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(sub.as_mut()), Poll::Ready(Ok((4, 5, 6))));
+    }
+
+    #[test]
+    fn test_join_waits_for_both() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeEvents::default());
+        kernel.add_driver(&driver);
+
+        let a = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        let b = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 1);
+        let mut join = pin!(TockSubscribe::join(a, b));
+
+        driver.share_ref.schedule_upcall(0, (1, 2, 3)).unwrap();
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(join.as_mut()), Poll::Pending);
+
+        driver.share_ref.schedule_upcall(1, (4, 5, 6)).unwrap();
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(
+            poll(join.as_mut()),
+            Poll::Ready((Ok((1, 2, 3)), Ok((4, 5, 6))))
+        );
     }
 }
@@ -4,8 +4,12 @@
 
 extern crate alloc;
 
+#[cfg(test)]
+mod fake_events;
 mod future;
-pub use future::TockSubscribe;
+pub use future::{Either, SubscribeResult, TockSubscribe};
+mod timeout;
+pub use timeout::with_timeout;
 mod tock_executor;
 pub use tock_executor::TockExecutor;
 
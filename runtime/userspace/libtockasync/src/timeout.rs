@@ -0,0 +1,154 @@
+// Licensed under the Apache-2.0 license
+
+use crate::future::{Either, SubscribeResult, TockSubscribe};
+use alloc::boxed::Box;
+use core::pin::Pin;
+use libtock_alarm::{Alarm, Milliseconds};
+use libtock_platform::{ErrorCode, Syscalls};
+use portable_atomic::{AtomicBool, Ordering};
+
+/// Set while a [`with_timeout`] call owns the alarm upcall.
+static ALARM_IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// Releases the alarm upcall when [`with_timeout`] completes or is dropped.
+struct AlarmGuard;
+
+impl AlarmGuard {
+    fn acquire() -> Option<Self> {
+        // Tock is single threaded, and nothing yields between the load and the store.
+        if ALARM_IN_USE.load(Ordering::SeqCst) {
+            return None;
+        }
+        ALARM_IN_USE.store(true, Ordering::SeqCst);
+        Some(AlarmGuard)
+    }
+}
+
+impl Drop for AlarmGuard {
+    fn drop(&mut self) {
+        ALARM_IN_USE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Wait for `sub` to complete, or fail with `ErrorCode::Fail` if `time` elapses first.
+///
+/// The timeout is a one-shot alarm from libtock's alarm driver, which is stopped if `sub`
+/// completes first. A process has a single alarm upcall, so only one `with_timeout` can wait
+/// at a time; another call fails with `ErrorCode::Busy` while it does. Other alarm users in
+/// the process must not run concurrently with it. Whichever subscription loses the race is
+/// unsubscribed.
+///
+/// Use like:
+/// `with_timeout::<TockSyscalls>(TockSubscribe::subscribe::<TockSyscalls>(driver_num, 0), Milliseconds(100)).await`.
+pub async fn with_timeout<S: Syscalls>(
+    mut sub: Pin<Box<TockSubscribe>>,
+    time: Milliseconds,
+) -> SubscribeResult {
+    let Some(_guard) = AlarmGuard::acquire() else {
+        sub.unsubscribe();
+        return Err(ErrorCode::Busy);
+    };
+    let mut timer = TockSubscribe::subscribe::<S>(Alarm::<S>::DRIVER_NUM, Alarm::<S>::CALLBACK);
+    if let Err(err) = Alarm::<S>::set_relative(time) {
+        timer.unsubscribe();
+        sub.unsubscribe();
+        return Err(err);
+    }
+    match TockSubscribe::select(sub, timer).await {
+        Either::First(result) => {
+            // The alarm may already have fired, in which case there is nothing to stop.
+            let _ = Alarm::<S>::stop();
+            result
+        }
+        Either::Second(result) => {
+            // Free the alarm upcall for the next user.
+            S::unsubscribe(Alarm::<S>::DRIVER_NUM, Alarm::<S>::CALLBACK);
+            result.and(Err(ErrorCode::Fail))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_events::{poll, FakeEvents, DRIVER_NUM};
+    use core::pin::pin;
+    use core::task::Poll;
+    use libtock_unittest::fake;
+    use std::rc::Rc;
+    use std::sync::Mutex;
+
+    /// [`ALARM_IN_USE`] is process-wide, so these tests take turns with it.
+    static ALARM_TESTS: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_timeout_fires() {
+        let _lock = ALARM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let kernel = fake::Kernel::new();
+        let alarm = fake::Alarm::new(1_000_000);
+        let events = Rc::new(FakeEvents::default());
+        kernel.add_driver(&alarm);
+        kernel.add_driver(&events);
+
+        let sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        let mut timeout = pin!(with_timeout::<fake::Syscalls>(sub, Milliseconds(10)));
+        // The fake alarm schedules its upcall as soon as it is set.
+        assert_eq!(poll(timeout.as_mut()), Poll::Pending);
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(timeout.as_mut()), Poll::Ready(Err(ErrorCode::Fail)));
+
+        // The timed out subscription no longer receives upcalls.
+        events.share_ref.schedule_upcall(0, (1, 2, 3)).unwrap();
+        assert_eq!(
+            fake::Syscalls::yield_no_wait(),
+            libtock_platform::YieldNoWaitReturn::NoUpcall
+        );
+    }
+
+    #[test]
+    fn test_completes_before_timeout() {
+        let _lock = ALARM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let kernel = fake::Kernel::new();
+        let alarm = fake::Alarm::new(1_000_000);
+        let events = Rc::new(FakeEvents::default());
+        kernel.add_driver(&alarm);
+        kernel.add_driver(&events);
+
+        let sub = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        events.share_ref.schedule_upcall(0, (1, 2, 3)).unwrap();
+        let mut timeout = pin!(with_timeout::<fake::Syscalls>(sub, Milliseconds(10)));
+        assert_eq!(poll(timeout.as_mut()), Poll::Pending);
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(timeout.as_mut()), Poll::Ready(Ok((1, 2, 3))));
+    }
+
+    #[test]
+    fn test_alarm_in_use() {
+        let _lock = ALARM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let kernel = fake::Kernel::new();
+        let alarm = fake::Alarm::new(1_000_000);
+        let events = Rc::new(FakeEvents::default());
+        kernel.add_driver(&alarm);
+        kernel.add_driver(&events);
+
+        let first = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 0);
+        let mut first = pin!(with_timeout::<fake::Syscalls>(first, Milliseconds(10)));
+        assert_eq!(poll(first.as_mut()), Poll::Pending);
+
+        // A second timeout does not take the alarm upcall from the first
+        let second = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 1);
+        let mut second = pin!(with_timeout::<fake::Syscalls>(second, Milliseconds(10)));
+        assert_eq!(poll(second.as_mut()), Poll::Ready(Err(ErrorCode::Busy)));
+
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(first.as_mut()), Poll::Ready(Err(ErrorCode::Fail)));
+
+        // Once the first has finished, the alarm is free again
+        let third = TockSubscribe::subscribe::<fake::Syscalls>(DRIVER_NUM, 1);
+        events.share_ref.schedule_upcall(1, (4, 5, 6)).unwrap();
+        let mut third = pin!(with_timeout::<fake::Syscalls>(third, Milliseconds(10)));
+        assert_eq!(poll(third.as_mut()), Poll::Pending);
+        fake::Syscalls::yield_no_wait();
+        assert_eq!(poll(third.as_mut()), Poll::Ready(Ok((4, 5, 6))));
+    }
+}
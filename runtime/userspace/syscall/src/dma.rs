@@ -46,6 +46,17 @@ pub enum DMASource<'a> {
     Buffer(&'a [u8]),
 }
 
+/// A single AXI to AXI transfer within a scatter-gather list.
+#[derive(Debug, Clone, Copy)]
+pub struct DMASgDescriptor {
+    /// Source AXI address.
+    pub src_addr: AXIAddr,
+    /// Destination AXI address.
+    pub dest_addr: AXIAddr,
+    /// Number of bytes to transfer.
+    pub byte_count: u32,
+}
+
 impl<S: Syscalls> Default for DMA<S> {
     fn default() -> Self {
         Self::new()
@@ -79,10 +90,59 @@ impl<S: Syscalls> DMA<S> {
         }
     }
 
+    /// Do a scatter-gather DMA transfer.
+    ///
+    /// The descriptors are executed in order and a single completion is reported once all of
+    /// them have finished.
+    ///
+    /// # Arguments
+    /// * `descriptors` - Up to `MAX_SG_DESCRIPTORS` AXI to AXI transfers.
+    ///
+    /// # Returns
+    /// * `Ok(())` if every transfer completes successfully.
+    /// * `Err(ErrorCode)` if the list is invalid or a transfer fails. Descriptors after a
+    ///   failed one are not executed.
+    pub async fn xfer_sg(&self, descriptors: &[DMASgDescriptor]) -> Result<(), ErrorCode> {
+        if descriptors.is_empty() || descriptors.len() > MAX_SG_DESCRIPTORS {
+            return Err(ErrorCode::Invalid);
+        }
+
+        let mut list = [0u8; MAX_SG_DESCRIPTORS * SG_DESCRIPTOR_SIZE];
+        for (descriptor, raw) in descriptors
+            .iter()
+            .zip(list.chunks_exact_mut(SG_DESCRIPTOR_SIZE))
+        {
+            if descriptor.byte_count == 0 {
+                return Err(ErrorCode::Invalid);
+            }
+            raw[0..8].copy_from_slice(&descriptor.src_addr.to_le_bytes());
+            raw[8..16].copy_from_slice(&descriptor.dest_addr.to_le_bytes());
+            raw[16..20].copy_from_slice(&descriptor.byte_count.to_le_bytes());
+        }
+        let list = &list[..descriptors.len() * SG_DESCRIPTOR_SIZE];
+
+        let async_start = TockSubscribe::subscribe::<S>(self.driver_num, dma_subscribe::XFER_DONE);
+
+        share::scope::<AllowRo<_, DMA_DRIVER_NUM, { dma_ro_buffer::SG_LIST }>, _, _>(|handle| {
+            S::allow_ro::<DefaultConfig, DMA_DRIVER_NUM, { dma_ro_buffer::SG_LIST }>(handle, list)?;
+
+            S::command(
+                self.driver_num,
+                dma_cmd::XFER_SG_AXI_TO_AXI,
+                descriptors.len() as u32,
+                0,
+            )
+            .to_result::<(), ErrorCode>()?;
+            Ok(())
+        })?;
+
+        xfer_result(async_start.await?)
+    }
+
     async fn xfer_src_address(&self) -> Result<(), ErrorCode> {
         let async_start = TockSubscribe::subscribe::<S>(self.driver_num, dma_subscribe::XFER_DONE);
         S::command(self.driver_num, dma_cmd::XFER_AXI_TO_AXI, 0, 0).to_result::<(), ErrorCode>()?;
-        async_start.await.map(|_| ())
+        xfer_result(async_start.await?)
     }
 
     async fn xfer_src_buffer(&self, buffer: &[u8]) -> Result<(), ErrorCode> {
@@ -102,7 +162,7 @@ impl<S: Syscalls> DMA<S> {
             },
         )?;
 
-        async_start.await.map(|_| ())
+        xfer_result(async_start.await?)
     }
 
     fn setup(&self, config: &DMATransaction<'_>) -> Result<(), ErrorCode> {
@@ -143,6 +203,12 @@ impl<S: Syscalls> DMA<S> {
 // Driver number for the DMA interface
 pub const DMA_DRIVER_NUM: u32 = 0x9000_0000;
 
+/// Maximum number of descriptors accepted by `DMA::xfer_sg`.
+pub const MAX_SG_DESCRIPTORS: usize = 16;
+
+/// Size of an encoded scatter-gather descriptor.
+const SG_DESCRIPTOR_SIZE: usize = 20;
+
 /// Command IDs used by the DMA interface.
 mod dma_cmd {
     pub const SET_BYTE_XFER_COUNT: u32 = 0;
@@ -150,15 +216,32 @@ mod dma_cmd {
     pub const SET_DEST_ADDR: u32 = 2;
     pub const XFER_AXI_TO_AXI: u32 = 3;
     pub const XFER_LOCAL_TO_AXI: u32 = 4;
+    pub const XFER_SG_AXI_TO_AXI: u32 = 5;
 }
 
 /// Buffer IDs for DMA (read-only)
 mod dma_ro_buffer {
     /// Buffer ID for local buffers (read-only)
     pub const LOCAL_SOURCE: u32 = 0;
+    /// Buffer ID for the encoded scatter-gather list (read-only)
+    pub const SG_LIST: u32 = 0;
 }
 
 /// Subscription IDs for asynchronous notifications.
 mod dma_subscribe {
     pub const XFER_DONE: u32 = 0;
 }
+
+/// First argument of the `XFER_DONE` upcall.
+mod dma_xfer_result {
+    pub const SUCCESS: u32 = 0;
+}
+
+/// Map an `XFER_DONE` upcall to the result of the transfer.
+fn xfer_result((result, _detail, _): (u32, u32, u32)) -> Result<(), ErrorCode> {
+    if result == dma_xfer_result::SUCCESS {
+        Ok(())
+    } else {
+        Err(ErrorCode::Fail)
+    }
+}
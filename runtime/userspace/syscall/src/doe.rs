@@ -143,3 +143,38 @@ mod allow_rw {
     /// Read buffer for the message payload received
     pub const MESSAGE_READ: u32 = 0;
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use libtock_unittest::fake;
+    use libtock_unittest::fake::FakeDoeDriver;
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::vec;
+
+    #[test]
+    fn test_doe_loopback() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeDoeDriver::new());
+        kernel.add_driver(&driver);
+
+        let doe: Doe<fake::Syscalls> = Doe::new(driver_num::DOE_SPDM);
+        assert!(doe.exists());
+        assert_eq!(doe.max_message_size(), Ok(4096));
+
+        let object: [u8; 12] = [
+            0x01, 0x00, 0x01, 0x00, 0x03, 0x00, 0x00, 0x00, 0x10, 0x84, 0x00, 0x00,
+        ];
+        let ret = fake::wait_for_future_ready(Box::pin(doe.send_message(&object)));
+        assert_eq!(ret, Ok(()));
+        assert_eq!(driver.sent_messages(), vec![object.to_vec()]);
+
+        let mut buf = [0u8; 64];
+        let ret = fake::wait_for_future_ready(Box::pin(doe.receive_message(&mut buf)));
+        assert_eq!(ret, Ok(object.len() as u32));
+        assert_eq!(&buf[..object.len()], &object);
+    }
+}
@@ -15,6 +15,13 @@ pub struct SpiFlash<S: Syscalls = DefaultSyscalls> {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct FlashCapacity(pub u32);
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EraseVerifyError {
+    ErrorCode(ErrorCode),
+    /// The byte at this flash address was not `0xFF` after the erase.
+    NotErased(usize),
+}
+
 /// Represents an asynchronous SPI flash memory interface.
 ///
 /// This struct provides methods to interact with SPI flash memory in an asynchronous manner,
@@ -238,6 +245,91 @@ impl<S: Syscalls> SpiFlash<S> {
         .to_result::<(), ErrorCode>()?;
         async_erase_sub.await.map(|_| Ok(()))?
     }
+
+    /// Erases `len` bytes starting at `address` and reads them back to check that
+    /// every byte is `0xFF`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The starting address to erase from.
+    /// * `len` - The number of bytes to erase.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the region was erased.
+    /// * `Err(EraseVerifyError::NotErased(address))` with the first address that is not erased.
+    /// * `Err(EraseVerifyError::ErrorCode(ErrorCode))` if the erase or read fails.
+    pub async fn erase_and_verify(
+        &self,
+        address: usize,
+        len: usize,
+    ) -> Result<(), EraseVerifyError> {
+        self.erase(address, len)
+            .await
+            .map_err(EraseVerifyError::ErrorCode)?;
+
+        let mut buf = [0u8; VERIFY_BUF_SIZE];
+        let mut offset = 0;
+        while offset < len {
+            let chunk = core::cmp::min(len - offset, buf.len());
+            self.read(address + offset, chunk, &mut buf)
+                .await
+                .map_err(EraseVerifyError::ErrorCode)?;
+            if let Some(i) = buf[..chunk].iter().position(|&b| b != 0xFF) {
+                return Err(EraseVerifyError::NotErased(address + offset + i));
+            }
+            offset += chunk;
+        }
+        Ok(())
+    }
+}
+
+/// Size of the stack buffer `erase_and_verify` reads back into.
+const VERIFY_BUF_SIZE: usize = 256;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use libtock_unittest::fake;
+    use libtock_unittest::fake::FakeFlashDriver;
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::vec;
+
+    /// Driver number of the fake flash driver.
+    const DRIVER_NUM: u32 = 0x8000_0006;
+
+    fn setup() -> (fake::Kernel, Rc<FakeFlashDriver>, SpiFlash<fake::Syscalls>) {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeFlashDriver::new());
+        driver.set_capacity(0x1000);
+        driver.set_chunk_size(64);
+        driver.set_flash_content(vec![0x5A; 0x1000]);
+        kernel.add_driver(&driver);
+        let flash = SpiFlash::new(DRIVER_NUM);
+        (kernel, driver, flash)
+    }
+
+    #[test]
+    fn test_erase_and_verify() {
+        let (_kernel, driver, flash) = setup();
+        let ret = fake::wait_for_future_ready(Box::pin(flash.erase_and_verify(0x100, 0x300)));
+        assert_eq!(ret, Ok(()));
+        let content = driver.flash_content();
+        assert!(content[0x100..0x400].iter().all(|&b| b == 0xFF));
+        assert_eq!(content[0xFF], 0x5A);
+        assert_eq!(content[0x400], 0x5A);
+    }
+
+    #[test]
+    fn test_erase_and_verify_reports_first_mismatch() {
+        let (_kernel, driver, flash) = setup();
+        driver.set_stuck_byte(0x2A3, 0x00);
+        let ret = fake::wait_for_future_ready(Box::pin(flash.erase_and_verify(0x100, 0x300)));
+        assert_eq!(ret, Err(EraseVerifyError::NotErased(0x2A3)));
+    }
 }
 
 // -----------------------------------------------------------------------------
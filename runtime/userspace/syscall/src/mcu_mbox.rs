@@ -160,6 +160,156 @@ impl<S: Syscalls> McuMbox<S> {
         S::command(self.driver_num, command::FINISH_RESP, status.into(), 0)
             .to_result::<(), ErrorCode>()
     }
+
+    /// Handles one request end to end (receiver mode).
+    ///
+    /// Receives a request into `req`, passes the command code and payload to `handler` to fill
+    /// in `resp`, sends the response and finishes it with the status `handler` returned. The
+    /// driver only accepts `finish_response` once the response has been sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - A mutable byte slice to store the received request.
+    /// * `resp` - A mutable byte slice for `handler` to write the response into.
+    /// * `handler` - Returns the response length and the mailbox status to finish with.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((CmdCode, usize))` with the command code and the response length.
+    /// * `Err(ErrorCode)` if the operation fails.
+    pub async fn handle_request<F>(
+        &self,
+        req: &mut [u8],
+        resp: &mut [u8],
+        handler: F,
+    ) -> Result<(CmdCode, usize), ErrorCode>
+    where
+        F: FnOnce(CmdCode, &[u8], &mut [u8]) -> (usize, MbxCmdStatus),
+    {
+        let (cmd, req_len) = self.receive_command(req).await?;
+        let (resp_len, status) = handler(cmd, &req[..req_len], resp);
+        if resp_len > resp.len() {
+            return Err(ErrorCode::Size);
+        }
+        self.send_response(&resp[..resp_len]).await?;
+        self.finish_response(status)?;
+        Ok((cmd, resp_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use libtock_platform::CommandReturn;
+    use libtock_unittest::{fake, DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    /// Fake MCU mailbox driver that delivers one queued request and records the response.
+    #[derive(Default)]
+    struct FakeMcuMbox {
+        share_ref: DriverShareRef,
+        request_buffer: RefCell<RwAllowBuffer>,
+        response_buffer: RefCell<RoAllowBuffer>,
+        request: RefCell<Option<(CmdCode, Vec<u8>)>>,
+        response: RefCell<Option<Vec<u8>>>,
+        status: Cell<Option<u32>>,
+    }
+
+    impl fake::SyscallDriver for FakeMcuMbox {
+        fn info(&self) -> DriverInfo {
+            DriverInfo::new(MCU_MBOX0_DRIVER_NUM).upcall_count(2)
+        }
+
+        fn register(&self, share_ref: DriverShareRef) {
+            self.share_ref.replace(share_ref);
+        }
+
+        fn command(&self, command_num: u32, arg0: u32, _arg1: u32) -> CommandReturn {
+            match command_num {
+                command::EXISTS => libtock_unittest::command_return::success(),
+                command::RECEIVE_REQUEST => {
+                    let Some((cmd, data)) = self.request.take() else {
+                        return libtock_unittest::command_return::failure(ErrorCode::Busy);
+                    };
+                    self.request_buffer.borrow_mut()[..data.len()].copy_from_slice(&data);
+                    self.share_ref
+                        .schedule_upcall(subscribe::REQUEST_RECEIVED, (cmd, data.len() as u32, 0))
+                        .unwrap();
+                    libtock_unittest::command_return::success()
+                }
+                command::SEND_RESPONSE => {
+                    let data = self.response_buffer.borrow().to_vec();
+                    self.response.replace(Some(data));
+                    self.share_ref
+                        .schedule_upcall(subscribe::RESPONSE_SENT, (0, 0, 0))
+                        .unwrap();
+                    libtock_unittest::command_return::success()
+                }
+                command::FINISH_RESP => {
+                    if self.response.borrow().is_none() {
+                        return libtock_unittest::command_return::failure(ErrorCode::Fail);
+                    }
+                    self.status.set(Some(arg0));
+                    libtock_unittest::command_return::success()
+                }
+                _ => libtock_unittest::command_return::failure(ErrorCode::NoSupport),
+            }
+        }
+
+        fn allow_readwrite(
+            &self,
+            buffer_num: u32,
+            buffer: RwAllowBuffer,
+        ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+            if buffer_num == rw_allow::REQUEST {
+                Ok(self.request_buffer.replace(buffer))
+            } else {
+                Err((buffer, ErrorCode::Invalid))
+            }
+        }
+
+        fn allow_readonly(
+            &self,
+            buffer_num: u32,
+            buffer: RoAllowBuffer,
+        ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+            if buffer_num == ro_allow::RESPONSE {
+                Ok(self.response_buffer.replace(buffer))
+            } else {
+                Err((buffer, ErrorCode::Invalid))
+            }
+        }
+    }
+
+    #[test]
+    fn test_handle_request_loopback() {
+        let kernel = fake::Kernel::new();
+        let driver = Rc::new(FakeMcuMbox::default());
+        kernel.add_driver(&driver);
+        let payload: Vec<u8> = (0..37).collect();
+        driver.request.replace(Some((0x03, payload.clone())));
+
+        let mbox: McuMbox<fake::Syscalls> = McuMbox::default();
+        let mut req = [0u8; 64];
+        let mut resp = [0u8; 64];
+        let result = fake::wait_for_future_ready(Box::pin(mbox.handle_request(
+            &mut req,
+            &mut resp,
+            |_cmd, req, resp| {
+                resp[..req.len()].copy_from_slice(req);
+                (req.len(), MbxCmdStatus::Complete)
+            },
+        )));
+
+        assert_eq!(result, Ok((0x03, payload.len())));
+        assert_eq!(driver.response.borrow().as_deref(), Some(&payload[..]));
+        assert_eq!(driver.status.get(), Some(MbxCmdStatus::Complete.into()));
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -22,13 +22,15 @@ pub fn platform() -> &'static str {
 mod test {
     use caliptra_hw_model::BootParams;
     use caliptra_image_types::FwVerificationPqcKeyType;
-    use mcu_builder::{CaliptraBuilder, FirmwareBinaries, ImageCfg, TARGET};
+    use mcu_builder::{target_triple, CaliptraBuilder, FirmwareBinaries, ImageCfg};
     use mcu_config::McuMemoryMap;
     use mcu_hw_model::{DefaultHwModel, Fuses, InitParams, McuHwModel};
     use mcu_image_header::McuImageHeader;
-    use std::sync::atomic::AtomicU32;
+    use mcu_rom_common::fuse_svn_mask;
+    use mcu_testing_common::i3c_socket_server::I3C_PORT_IN_USE_EXIT_CODE;
     use std::sync::Mutex;
     use std::{
+        net::TcpListener,
         path::{Path, PathBuf},
         process::Command,
         sync::LazyLock,
@@ -47,7 +49,7 @@ mod test {
     fn target_binary(name: &str) -> PathBuf {
         PROJECT_ROOT
             .join("target")
-            .join(TARGET)
+            .join(target_triple())
             .join("release")
             .join(name)
     }
@@ -55,8 +57,16 @@ mod test {
     // only build the ROM once
     pub static ROM: LazyLock<PathBuf> = LazyLock::new(|| compile_rom(""));
 
-    pub static TEST_LOCK: LazyLock<Mutex<AtomicU32>> =
-        LazyLock::new(|| Mutex::new(AtomicU32::new(0)));
+    /// Serializes firmware builds and other writes to shared outputs under `target/`.
+    ///
+    /// Emulator runs don't take it: each run gets its own I3C port from
+    /// [`reserve_free_port`] and [`run_runtime`] hands the emulator private copies of
+    /// its inputs.
+    pub static BUILD_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Serializes tests that run the MCU hw model in-process, since they all share
+    /// `mcu_testing_common::MCU_RUNNING`.
+    pub static HW_MODEL_LOCK: Mutex<()> = Mutex::new(());
 
     // Compile the ROM for a given feature flag (empty string for default ROM).
     pub fn get_rom_with_feature(feature: &str) -> PathBuf {
@@ -80,6 +90,7 @@ mod test {
     }
 
     fn compile_rom(feature: &str) -> PathBuf {
+        let _build = BUILD_LOCK.lock().unwrap();
         let output: PathBuf = mcu_builder::rom_build(Some(platform()), feature)
             .expect("ROM build failed")
             .into();
@@ -95,6 +106,7 @@ mod test {
         let feature = feature.map(|f| format!("-{f}")).unwrap_or_default();
         let output = target_binary(&format!("runtime{}-{}.bin", feature, platform()));
         let output_name = format!("{}", output.display());
+        let _build = BUILD_LOCK.lock().unwrap();
         mcu_builder::runtime_build_with_apps_cached(
             &features,
             Some(&output_name),
@@ -152,6 +164,8 @@ mod test {
 
     fn build_test_binaries(feature: Option<&str>) -> TestBinaries {
         let mcu_runtime = compile_runtime(feature, false);
+        let mcu_rom = std::fs::read(&*ROM).unwrap();
+        let _build = BUILD_LOCK.lock().unwrap();
         let mut builder = CaliptraBuilder::new(
             cfg!(feature = "fpga_realtime"),
             None,
@@ -179,7 +193,6 @@ mod test {
         )
         .unwrap();
 
-        let mcu_rom = std::fs::read(&*ROM).unwrap();
         let soc_manifest = std::fs::read(
             builder
                 .get_soc_manifest(None)
@@ -200,7 +213,11 @@ mod test {
         }
     }
 
-    pub fn start_runtime_hw_model(feature: Option<&str>, i3c_port: Option<u16>) -> DefaultHwModel {
+    pub fn start_runtime_hw_model(
+        feature: Option<&str>,
+        i3c_port: Option<u16>,
+        i3c_dynamic_address: Option<u8>,
+    ) -> DefaultHwModel {
         let TestBinaries {
             vendor_pk_hash_u8,
             caliptra_rom,
@@ -235,6 +252,7 @@ mod test {
                 active_mode: true,
                 vendor_pqc_type: Some(FwVerificationPqcKeyType::LMS),
                 i3c_port,
+                i3c_dynamic_address,
                 enable_mcu_uart_log: true,
                 ..Default::default()
             },
@@ -263,6 +281,45 @@ mod test {
         }
     }
 
+    // Number of ports tried before giving up on finding a free one
+    const I3C_PORT_ATTEMPTS: usize = 5;
+
+    /// Reserves a free port for the emulator's I3C socket by binding `127.0.0.1:0` and
+    /// recording the port picked by the OS.
+    ///
+    /// The listener is closed before returning so that the emulator can bind the port, so
+    /// another process may grab it in between; pass the port to
+    /// [`run_with_i3c_port_retry`] to cover that window. Every emulator launch uses a
+    /// reserved port, so tests don't collide with each other or with other test processes.
+    pub fn reserve_free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .expect("Failed to reserve a free I3C port")
+    }
+
+    /// Calls `run` with `i3c_port`, moving on to the next port whenever the emulator
+    /// exits because it could not bind the I3C socket. Returns the exit code of the
+    /// last run together with the port it was given.
+    pub fn run_with_i3c_port_retry(
+        i3c_port: u16,
+        mut run: impl FnMut(String) -> i32,
+    ) -> (i32, u16) {
+        let mut port = i3c_port;
+        let mut code = run(port.to_string());
+        for _ in 1..I3C_PORT_ATTEMPTS {
+            if code != I3C_PORT_IN_USE_EXIT_CODE {
+                break;
+            }
+            // stay within the dynamic port range when wrapping around
+            let next = port.checked_add(1).unwrap_or(49152);
+            println!("I3C port {} is in use, retrying with port {}", port, next);
+            port = next;
+            code = run(port.to_string());
+        }
+        (code, port)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn run_runtime(
         feature: &str,
@@ -281,6 +338,13 @@ mod test {
         fuse_soc_manifest_max_svn: Option<u8>,
         fuse_vendor_hashes_prod_partition: Option<Vec<u8>>,
     ) -> i32 {
+        // The emulator reads copies of its inputs, taken under BUILD_LOCK, so that
+        // builds by concurrently running tests can't change them underneath it.
+        let run_dir = tempfile::tempdir().expect("Failed to create run directory");
+        let build = BUILD_LOCK.lock().unwrap();
+        let rom_path = copy_to_run_dir(&rom_path, run_dir.path(), "mcu-rom.bin");
+        let runtime_path = copy_to_run_dir(&runtime_path, run_dir.path(), "mcu-runtime.bin");
+
         let mut cargo_run_args = vec![
             "run",
             "-p",
@@ -381,6 +445,7 @@ mod test {
                 None,
                 None,
             )
+            .with_soc_manifest_path(run_dir.path().join("soc-manifest"))
         };
 
         let hw_revision_str;
@@ -393,19 +458,37 @@ mod test {
             if manufacturing_mode {
                 cargo_run_args.push("--manufacturing-mode");
             }
-            let caliptra_rom = caliptra_builder
-                .get_caliptra_rom()
-                .expect("Failed to build Caliptra ROM");
+            let vendor_pqc_type = match caliptra_builder.pqc_key_type() {
+                FwVerificationPqcKeyType::LMS => "lms",
+                FwVerificationPqcKeyType::MLDSA => "mldsa",
+            };
+            cargo_run_args.push("--vendor-pqc-type");
+            cargo_run_args.push(vendor_pqc_type);
+            let caliptra_rom = copy_to_run_dir(
+                &caliptra_builder
+                    .get_caliptra_rom()
+                    .expect("Failed to build Caliptra ROM"),
+                run_dir.path(),
+                "caliptra-rom.bin",
+            );
             cargo_run_args.push("--caliptra-rom");
             cargo_run_args.push(caliptra_rom.to_str().unwrap());
-            let caliptra_fw = caliptra_builder
-                .get_caliptra_fw()
-                .expect("Failed to build Caliptra firmware");
+            let caliptra_fw = copy_to_run_dir(
+                &caliptra_builder
+                    .get_caliptra_fw()
+                    .expect("Failed to build Caliptra firmware"),
+                run_dir.path(),
+                "caliptra-fw-bundle.bin",
+            );
             cargo_run_args.push("--caliptra-firmware");
             cargo_run_args.push(caliptra_fw.to_str().unwrap());
-            let soc_manifest = caliptra_builder
-                .get_soc_manifest(None)
-                .expect("Failed to build SoC manifest");
+            let soc_manifest = copy_to_run_dir(
+                &caliptra_builder
+                    .get_soc_manifest(None)
+                    .expect("Failed to build SoC manifest"),
+                run_dir.path(),
+                "soc-manifest.bin",
+            );
             cargo_run_args.push("--soc-manifest");
             cargo_run_args.push(soc_manifest.to_str().unwrap());
             let vendor_pk_hash = caliptra_builder
@@ -459,12 +542,14 @@ mod test {
                 fuse_vendor_hashes_prod_partition_str = hex_string;
                 cargo_run_args.push(fuse_vendor_hashes_prod_partition_str.as_str());
             }
+            drop(build);
 
             println!("Running test firmware {}", feature.replace("_", "-"));
             let mut cmd = Command::new("cargo");
             let cmd = cmd.args(&cargo_run_args).current_dir(&*PROJECT_ROOT);
             cmd.status().unwrap().code().unwrap_or(1)
         } else {
+            drop(build);
             println!("Running test firmware {}", feature.replace("_", "-"));
             let mut cmd = Command::new("cargo");
             let cmd = cmd.args(&cargo_run_args).current_dir(&*PROJECT_ROOT);
@@ -472,35 +557,37 @@ mod test {
         }
     }
 
-    fn run_test(feature: &str, example_app: bool) {
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    fn copy_to_run_dir(path: &Path, run_dir: &Path, name: &str) -> PathBuf {
+        let dest = run_dir.join(name);
+        std::fs::copy(path, &dest)
+            .unwrap_or_else(|e| panic!("Failed to copy {}: {}", path.display(), e));
+        dest
+    }
 
+    fn run_test(feature: &str, example_app: bool) {
         println!("Compiling test firmware {}", feature);
         let feature = feature.replace("_", "-");
         let test_runtime = compile_runtime(Some(&feature), example_app);
-        let i3c_port = "65534".to_string();
-        let test = run_runtime(
-            &feature,
-            ROM.to_path_buf(),
-            test_runtime,
-            i3c_port,
-            true,  // active mode is always true
-            false, //set this to true if you want to run in manufacturing mode
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        );
+        let (test, _) = run_with_i3c_port_retry(reserve_free_port(), |i3c_port| {
+            run_runtime(
+                &feature,
+                ROM.to_path_buf(),
+                test_runtime.clone(),
+                i3c_port,
+                true,  // active mode is always true
+                false, //set this to true if you want to run in manufacturing mode
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        });
         assert_eq!(0, test);
-
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     #[macro_export]
@@ -546,6 +633,7 @@ mod test {
     run_test!(test_caliptra_crypto, example_app);
     run_test!(test_caliptra_mailbox, example_app);
     run_test!(test_dma, example_app);
+    run_test!(test_dma_sg, example_app);
     run_test!(test_doe_transport_loopback, example_app);
     run_test!(test_doe_user_loopback, example_app);
     run_test!(test_doe_discovery, example_app);
@@ -576,70 +664,87 @@ mod test {
 
     run_test!(test_warm_reset, example_app);
 
-    /// This tests a full active mode boot run through with Caliptra, including
-    /// loading MCU's firmware from Caliptra over the recovery interface.
-    #[test]
-    fn test_active_mode_recovery_with_caliptra() {
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-
+    /// Runs a full active mode boot with Caliptra firmware and SoC manifest signed
+    /// with `pqc_key_type`, and the device provisioned for the same key type.
+    fn run_active_mode_recovery(pqc_key_type: FwVerificationPqcKeyType) {
         let feature = "test-exit-immediately".to_string();
         println!("Compiling test firmware {}", &feature);
         let test_runtime = compile_runtime(Some(&feature), false);
-        let i3c_port = "65534".to_string();
-        let test = run_runtime(
-            &feature,
-            ROM.to_path_buf(),
-            test_runtime,
-            i3c_port,
-            true,
+        let caliptra_builder = CaliptraBuilder::new(
             false,
             None,
             None,
             None,
             None,
+            Some(test_runtime.clone()),
             None,
             None,
             None,
             None,
             None,
-        );
+        )
+        .with_pqc_key_type(pqc_key_type);
+        let (test, _) = run_with_i3c_port_retry(reserve_free_port(), |i3c_port| {
+            run_runtime(
+                &feature,
+                ROM.to_path_buf(),
+                test_runtime.clone(),
+                i3c_port,
+                true,
+                false,
+                None,
+                None,
+                None,
+                None,
+                Some(caliptra_builder.clone()),
+                None,
+                None,
+                None,
+                None,
+            )
+        });
         assert_eq!(0, test);
+    }
 
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    /// This tests a full active mode boot run through with Caliptra, including
+    /// loading MCU's firmware from Caliptra over the recovery interface.
+    #[test]
+    fn test_active_mode_recovery_with_caliptra() {
+        run_active_mode_recovery(FwVerificationPqcKeyType::LMS);
     }
 
+    /// Same as `test_active_mode_recovery_with_caliptra`, but with MLDSA vendor keys.
     #[test]
-    fn test_mcu_rom_flash_access() {
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    fn test_active_mode_recovery_mldsa() {
+        run_active_mode_recovery(FwVerificationPqcKeyType::MLDSA);
+    }
 
+    #[test]
+    fn test_mcu_rom_flash_access() {
         let feature = "test-mcu-rom-flash-access".to_string();
         println!("Compiling test firmware {}", &feature);
         let test_runtime = compile_runtime(Some(&feature), false);
-        let i3c_port = "65534".to_string();
-        let test = run_runtime(
-            &feature,
-            get_rom_with_feature(&feature),
-            test_runtime,
-            i3c_port,
-            true,
-            false,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        );
+        let rom = get_rom_with_feature(&feature);
+        let (test, _) = run_with_i3c_port_retry(reserve_free_port(), |i3c_port| {
+            run_runtime(
+                &feature,
+                rom.clone(),
+                test_runtime.clone(),
+                i3c_port,
+                true,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        });
         assert_eq!(0, test);
-
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     fn test_mcu_svn(image_svn: u16, fuse_svn: u16) -> Option<i32> {
@@ -653,6 +758,7 @@ mod test {
 
         let test_runtime = target_binary(&format!("runtime-{}.bin", feature));
         let output_name = format!("{}", test_runtime.display());
+        let build = BUILD_LOCK.lock().unwrap();
         mcu_builder::runtime_build_with_apps_cached(
             &[feature],
             Some(&output_name),
@@ -673,61 +779,102 @@ mod test {
         )
         .expect("Runtime build failed");
         assert!(test_runtime.exists());
+        drop(build);
 
-        let fuse_vendor_hashes_prod_partition = {
-            let n = if fuse_svn > 128 { 128 } else { fuse_svn };
-            let val: u128 = if n == 0 {
-                0
-            } else if n == 128 {
-                u128::MAX
-            } else {
-                (1u128 << n) - 1
-            };
-
-            val.to_le_bytes()
-        };
+        let fuse_vendor_hashes_prod_partition = fuse_svn_mask(fuse_svn).to_le_bytes();
 
-        let i3c_port = "65534".to_string();
-        Some(run_runtime(
-            feature,
-            get_rom_with_feature(feature),
-            test_runtime,
-            i3c_port,
-            true,
-            false,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(fuse_vendor_hashes_prod_partition.to_vec()),
-        ))
+        let rom = get_rom_with_feature(feature);
+        let (test, _) = run_with_i3c_port_retry(reserve_free_port(), |i3c_port| {
+            run_runtime(
+                feature,
+                rom.clone(),
+                test_runtime.clone(),
+                i3c_port,
+                true,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(fuse_vendor_hashes_prod_partition.to_vec()),
+            )
+        });
+        Some(test)
     }
 
     #[test]
     fn test_mcu_svn_gt_fuse() {
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-
         let result = test_mcu_svn(100, 30);
         assert_eq!(0, result.unwrap_or_default());
-
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     #[test]
     fn test_mcu_svn_lt_fuse() {
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-
         let result = test_mcu_svn(25, 40);
         assert_ne!(0, result.unwrap_or_default());
+    }
+
+    #[test]
+    fn test_runtime_build_with_app_list() {
+        let build = |apps: &[&str], name: &str| {
+            let output = target_binary(&format!("{}-{}.bin", name, platform()));
+            let output_name = format!("{}", output.display());
+            let _build = BUILD_LOCK.lock().unwrap();
+            mcu_builder::runtime_build_with_app_list(
+                apps,
+                &[],
+                Some(&output_name),
+                Some(platform()),
+                Some(memory_map()),
+                false,
+                None,
+                None,
+                Some(&mcu_config_emulator::flash::LOGGING_FLASH_CONFIG),
+                None,
+            )
+            .expect("Runtime build failed");
+            std::fs::read(output).unwrap()
+        };
 
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // The listed app is bundled after the kernel; an empty list bundles none.
+        let no_apps = build(&[], "runtime-no-apps");
+        let example_app = build(&["example-app"], "runtime-example-app-list");
+        assert!(example_app.len() > no_apps.len());
+    }
+
+    #[test]
+    fn test_reserve_free_port() {
+        let port = reserve_free_port();
+        assert_ne!(0, port);
+        // the port is released again so the emulator can bind it
+        TcpListener::bind(("127.0.0.1", port)).unwrap();
+    }
+
+    #[test]
+    fn test_i3c_port_retry() {
+        let mut ports = vec![];
+        let (code, port) = run_with_i3c_port_retry(u16::MAX - 1, |i3c_port| {
+            ports.push(i3c_port);
+            if ports.len() < 3 {
+                I3C_PORT_IN_USE_EXIT_CODE
+            } else {
+                0
+            }
+        });
+        assert_eq!((0, 49152), (code, port));
+        assert_eq!(vec!["65534", "65535", "49152"], ports);
+
+        let mut attempts = 0;
+        let (code, port) = run_with_i3c_port_retry(u16::MAX - 1, |_| {
+            attempts += 1;
+            I3C_PORT_IN_USE_EXIT_CODE
+        });
+        assert_eq!(I3C_PORT_IN_USE_EXIT_CODE, code);
+        assert_eq!(49154, port);
+        assert_eq!(I3C_PORT_ATTEMPTS, attempts);
     }
 }
@@ -6,6 +6,8 @@ use caliptra_hw_model::BootParams;
 use mcu_hw_model::{new, InitParams, McuHwModel};
 use mcu_rom_common::McuBootMilestones;
 
+const MAX_CYCLES: u64 = 800_000_000;
+
 // TODO(zhalvorsen): Enable this test for emulator when it is supported
 #[cfg_attr(not(feature = "fpga_realtime"), ignore)]
 #[test]
@@ -35,20 +37,8 @@ fn test_hitless_update_flow() -> Result<()> {
     )?;
 
     println!("Waiting for flow to start");
-    hw.step_until(|hw| {
-        hw.mci_boot_milestones()
-            .contains(McuBootMilestones::CPTRA_BOOT_GO_ASSERTED)
-    });
+    hw.step_until_milestone(McuBootMilestones::CPTRA_BOOT_GO_ASSERTED, MAX_CYCLES)?;
 
     println!("Waiting for flow to finish");
-    hw.step_until(|hw| {
-        hw.mci_boot_milestones()
-            .contains(McuBootMilestones::FIRMWARE_BOOT_FLOW_COMPLETE)
-    });
-
-    assert!(hw
-        .mci_boot_milestones()
-        .contains(McuBootMilestones::FIRMWARE_BOOT_FLOW_COMPLETE));
-
-    Ok(())
+    hw.step_until_milestone(McuBootMilestones::FIRMWARE_BOOT_FLOW_COMPLETE, MAX_CYCLES)
 }
@@ -7,6 +7,8 @@ use mcu_hw_model::McuHwModel;
 use mcu_hw_model::{new, Fuses, InitParams};
 use mcu_rom_common::McuBootMilestones;
 
+const MAX_CYCLES: u64 = 800_000_000;
+
 // TODO(zhalvorsen): Enable this test for emulator when it is supported
 #[cfg_attr(not(feature = "fpga_realtime"), ignore)]
 #[test]
@@ -57,13 +59,5 @@ fn test_warm_reset_success() -> Result<()> {
 
     println!("Waiting for warm reset flow to complete");
 
-    hw.step_until(|hw| {
-        hw.mci_boot_milestones()
-            .contains(McuBootMilestones::WARM_RESET_FLOW_COMPLETE)
-    });
-
-    assert!(hw
-        .mci_boot_milestones()
-        .contains(McuBootMilestones::WARM_RESET_FLOW_COMPLETE));
-    Ok(())
+    hw.step_until_milestone(McuBootMilestones::WARM_RESET_FLOW_COMPLETE, MAX_CYCLES)
 }
@@ -2,7 +2,10 @@
 
 #[cfg(test)]
 mod test {
-    use crate::test::{compile_runtime, get_rom_with_feature, run_runtime, TEST_LOCK};
+    use crate::test::{
+        compile_runtime, get_rom_with_feature, reserve_free_port, run_runtime,
+        run_with_i3c_port_retry, BUILD_LOCK,
+    };
     use chrono::{TimeZone, Utc};
     use flash_image::{MCU_RT_IDENTIFIER, SOC_IMAGES_BASE_IDENTIFIER};
     use mcu_builder::{CaliptraBuilder, ImageCfg};
@@ -26,7 +29,7 @@ mod test {
         feature: &'static str,
         rom: PathBuf,
         runtime: PathBuf,
-        i3c_port: u32,
+        i3c_port: u16,
         soc_images: Vec<ImageCfg>,
         soc_images_paths: Vec<PathBuf>,
         primary_flash_image_path: Option<PathBuf>,
@@ -195,23 +198,26 @@ mod test {
         let _ = &opts.partition_table;
         let _ = &opts.flash_offset;
 
-        run_runtime(
-            opts.feature,
-            opts.rom.clone(),
-            opts.runtime.clone(),
-            opts.i3c_port.to_string(),
-            true,
-            false,
-            Some(opts.soc_images.clone()),
-            opts.pldm_fw_pkg_path.clone(),
-            opts.primary_flash_image_path.clone(),
-            opts.secondary_flash_image_path.clone(),
-            opts.builder.clone(),
-            Some("2.1.0".to_string()),
-            None,
-            None,
-            None,
-        )
+        let (test, _) = run_with_i3c_port_retry(opts.i3c_port, |i3c_port| {
+            run_runtime(
+                opts.feature,
+                opts.rom.clone(),
+                opts.runtime.clone(),
+                i3c_port,
+                true,
+                false,
+                Some(opts.soc_images.clone()),
+                opts.pldm_fw_pkg_path.clone(),
+                opts.primary_flash_image_path.clone(),
+                opts.secondary_flash_image_path.clone(),
+                opts.builder.clone(),
+                Some("2.1.0".to_string()),
+                None,
+                None,
+                None,
+            )
+        });
+        test
     }
 
     fn create_update_package() -> (PathBuf, PathBuf, PathBuf, String, PathBuf, Vec<PathBuf>) {
@@ -262,6 +268,7 @@ mod test {
             None,
             None,
         );
+        let build = BUILD_LOCK.lock().unwrap();
         let update_caliptra_fw = update_builder
             .get_caliptra_fw()
             .expect("Failed to build Caliptra firmware for update");
@@ -278,6 +285,7 @@ mod test {
 
         std::fs::copy(update_soc_manifest.clone(), temp_soc_manifest.clone())
             .expect("Failed to copy SOC manifest");
+        drop(build);
         let (_, update_flash_image_path) = create_flash_image(
             Some(update_caliptra_fw.clone()),
             Some(temp_soc_manifest.clone().into()),
@@ -446,7 +454,6 @@ mod test {
 
     // Common test function for both flash-based and streaming boot
     fn test_firmware_update_common(use_flash: bool) {
-        let lock = TEST_LOCK.lock().unwrap();
         let feature = if use_flash {
             "test-firmware-update-flash"
         } else {
@@ -459,7 +466,7 @@ mod test {
             format!("0x{:016x}", mci_base),
         );
 
-        let i3c_port = 65500;
+        let i3c_port = reserve_free_port();
         let soc_image_fw_1 = [0x55u8; 512]; // Example firmware data for SOC image 1
         let soc_image_fw_2 = [0xAAu8; 256]; // Example firmware data for SOC image 2
 
@@ -522,9 +529,16 @@ mod test {
             None,
             None,
             None,
+        )
+        .with_soc_manifest_path(
+            tempfile::NamedTempFile::new()
+                .expect("Failed to create temp file")
+                .path()
+                .to_path_buf(),
         );
 
         // Build Caliptra firmware
+        let build = BUILD_LOCK.lock().unwrap();
         let caliptra_fw = builder
             .get_caliptra_fw()
             .expect("Failed to build Caliptra firmware");
@@ -532,6 +546,7 @@ mod test {
         let soc_manifest = builder
             .get_soc_manifest(None)
             .expect("Failed to build SOC manifest");
+        drop(build);
 
         // Generate a flash image file to write to the primary flash
         let mut partition_table = PartitionTable {
@@ -595,8 +610,6 @@ mod test {
         run_test!(test_invalid_manifest, &pass_options.clone());
         run_test!(test_invalid_mcu_image, &pass_options.clone());
         run_test!(test_invalid_soc_image, &pass_options.clone());
-
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     #[test]
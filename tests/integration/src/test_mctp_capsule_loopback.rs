@@ -4,7 +4,9 @@
 
 #[cfg(test)]
 mod test {
-    use crate::test::{finish_runtime_hw_model, start_runtime_hw_model, TEST_LOCK};
+    use crate::test::{
+        finish_runtime_hw_model, reserve_free_port, start_runtime_hw_model, HW_MODEL_LOCK,
+    };
     use mcu_hw_model::McuHwModel;
     use mcu_testing_common::i3c_socket::{self, BufferedStream, MctpTestState, MctpTransportTest};
     use mcu_testing_common::mctp_util::common::MctpUtil;
@@ -13,12 +15,27 @@ mod test {
 
     #[test]
     fn test_mctp_capsule_loopback() {
+        run_mctp_capsule_loopback(None);
+    }
+
+    #[test]
+    fn test_mctp_capsule_loopback_fixed_dynamic_address() {
+        run_mctp_capsule_loopback(Some(0x20));
+    }
+
+    fn run_mctp_capsule_loopback(i3c_dynamic_address: Option<u8>) {
         let feature = "test-mctp-capsule-loopback";
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let _lock = HW_MODEL_LOCK.lock().unwrap();
 
         let feature = feature.replace("_", "-");
-        let mut hw = start_runtime_hw_model(Some(&feature), Some(65534));
+        let mut hw = start_runtime_hw_model(
+            Some(&feature),
+            Some(reserve_free_port()),
+            i3c_dynamic_address,
+        );
+        if let Some(addr) = i3c_dynamic_address {
+            assert_eq!(hw.i3c_address(), Some(addr));
+        }
 
         hw.start_i3c_controller();
 
@@ -33,9 +50,6 @@ mod test {
         let test = finish_runtime_hw_model(&mut hw);
 
         assert_eq!(0, test);
-
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     pub(crate) fn generate_tests() -> Vec<Box<dyn MctpTransportTest + Send>> {
@@ -4,12 +4,14 @@
 
 #[cfg(test)]
 mod test {
-    use crate::test::{finish_runtime_hw_model, start_runtime_hw_model, TEST_LOCK};
+    use crate::test::{
+        finish_runtime_hw_model, reserve_free_port, start_runtime_hw_model, HW_MODEL_LOCK,
+    };
     use chrono::{TimeZone, Utc};
     use lazy_static::lazy_static;
     use log::{error, LevelFilter};
     use mcu_hw_model::McuHwModel;
-    use mcu_testing_common::mctp_transport::{MctpPldmSocket, MctpTransport};
+    use mcu_testing_common::mctp_transport::{MctpPldmSocket, MctpTransport, MctpTransportConfig};
     use mcu_testing_common::{wait_for_runtime_start, MCU_RUNNING};
     use pldm_common::protocol::firmware_update::*;
     use pldm_fw_pkg::{
@@ -31,17 +33,32 @@ mod test {
 
     #[test]
     fn test_fw_update_e2e() {
+        run_fw_update_e2e(MctpTransportConfig::default());
+    }
+
+    #[test]
+    fn test_fw_update_e2e_fragmented() {
+        // Split every message into baseline-sized packets, so the firmware has to
+        // reassemble the firmware data responses from several fragments.
+        run_fw_update_e2e(MctpTransportConfig {
+            max_packet_payload: 64,
+        });
+    }
+
+    fn run_fw_update_e2e(transport_config: MctpTransportConfig) {
         let feature = "test-pldm-fw-update-e2e";
-        let lock = TEST_LOCK.lock().unwrap();
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let _lock = HW_MODEL_LOCK.lock().unwrap();
 
         let feature = feature.replace("_", "-");
-        let mut hw = start_runtime_hw_model(Some(&feature), Some(65534));
+        let mut hw = start_runtime_hw_model(Some(&feature), Some(reserve_free_port()), None);
 
         hw.start_i3c_controller();
 
-        let pldm_transport =
-            MctpTransport::new(hw.i3c_port().unwrap(), hw.i3c_address().unwrap().into());
+        let pldm_transport = MctpTransport::with_config(
+            hw.i3c_port().unwrap(),
+            hw.i3c_address().unwrap().into(),
+            transport_config,
+        );
         let pldm_socket = pldm_transport
             .create_socket(EndpointId(8), EndpointId(0))
             .unwrap();
@@ -50,9 +67,6 @@ mod test {
         let test = finish_runtime_hw_model(&mut hw);
 
         assert_eq!(0, test);
-
-        // force the compiler to keep the lock
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     pub const DEVICE_UUID: [u8; 16] = [
@@ -2,7 +2,10 @@
 
 #[cfg(test)]
 mod test {
-    use crate::test::{compile_runtime, get_rom_with_feature, run_runtime, TEST_LOCK};
+    use crate::test::{
+        compile_runtime, get_rom_with_feature, reserve_free_port, run_runtime,
+        run_with_i3c_port_retry, BUILD_LOCK,
+    };
     use chrono::{TimeZone, Utc};
     use mcu_builder::{CaliptraBuilder, ImageCfg};
     use mcu_config::boot::{PartitionId, PartitionStatus, RollbackEnable};
@@ -27,7 +30,7 @@ mod test {
         feature: &'static str,
         rom: PathBuf,
         runtime: PathBuf,
-        i3c_port: u32,
+        i3c_port: u16,
         soc_images: Vec<ImageCfg>,
         soc_images_paths: Vec<PathBuf>,
         primary_flash_image_path: Option<PathBuf>,
@@ -189,23 +192,26 @@ mod test {
     }
 
     fn run_runtime_with_options(opts: &TestOptions) -> i32 {
-        run_runtime(
-            opts.feature,
-            opts.rom.clone(),
-            opts.runtime.clone(),
-            opts.i3c_port.to_string(),
-            true,
-            opts.manufacturing_mode.unwrap_or(false),
-            Some(opts.soc_images.clone()),
-            opts.pldm_fw_pkg_path.clone(),
-            opts.primary_flash_image_path.clone(),
-            opts.secondary_flash_image_path.clone(),
-            opts.builder.clone(),
-            Some("2.1.0".to_string()),
-            opts.fuse_soc_manifest_svn,
-            opts.fuse_soc_manifest_max_svn,
-            None,
-        )
+        let (test, _) = run_with_i3c_port_retry(opts.i3c_port, |i3c_port| {
+            run_runtime(
+                opts.feature,
+                opts.rom.clone(),
+                opts.runtime.clone(),
+                i3c_port,
+                true,
+                opts.manufacturing_mode.unwrap_or(false),
+                Some(opts.soc_images.clone()),
+                opts.pldm_fw_pkg_path.clone(),
+                opts.primary_flash_image_path.clone(),
+                opts.secondary_flash_image_path.clone(),
+                opts.builder.clone(),
+                Some("2.1.0".to_string()),
+                opts.fuse_soc_manifest_svn,
+                opts.fuse_soc_manifest_max_svn,
+                None,
+            )
+        });
+        test
     }
 
     /// Test case: happy path
@@ -620,7 +626,6 @@ mod test {
 
     // Common test function for both flash-based and streaming boot
     fn test_soc_boot(is_flash_based_boot: bool) {
-        let lock = TEST_LOCK.lock().unwrap();
         env::set_var(
             "CPTRA_EMULATOR_SS_MCI_OFFSET",
             format!("0x{:016x}", MCI_BASE_AXI_ADDRESS),
@@ -631,7 +636,7 @@ mod test {
         } else {
             "test-pldm-streaming-boot"
         };
-        let i3c_port = 65500;
+        let i3c_port = reserve_free_port();
         let soc_image_fw_1 = [0x55u8; 512]; // Example firmware data for SOC image 1
         let soc_image_fw_2 = [0xAAu8; 256]; // Example firmware data for SOC image 2
 
@@ -674,9 +679,16 @@ mod test {
             None,
             None,
             None,
+        )
+        .with_soc_manifest_path(
+            tempfile::NamedTempFile::new()
+                .expect("Failed to create temp file")
+                .path()
+                .to_path_buf(),
         );
 
         // Build Caliptra firmware
+        let build = BUILD_LOCK.lock().unwrap();
         let caliptra_fw = builder
             .get_caliptra_fw()
             .expect("Failed to build Caliptra firmware");
@@ -684,6 +696,7 @@ mod test {
         let soc_manifest = builder
             .get_soc_manifest(None)
             .expect("Failed to build SOC manifest");
+        drop(build);
 
         // Generate a valid flash image file
         let mut partition_table = PartitionTable {
@@ -777,8 +790,6 @@ mod test {
                 &pass_options.clone()
             );
         }
-
-        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
     #[test]
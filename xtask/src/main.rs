@@ -179,6 +179,10 @@ enum Commands {
         /// Path to the PLDM manifest TOML file
         #[arg(short, long, value_name = "MANIFEST", required = false)]
         pldm_manifest: Option<String>,
+
+        /// Path to write a JSON manifest with the size and SHA-384 of every artifact
+        #[arg(long, required = false)]
+        manifest: Option<String>,
     },
     /// Commands related to flash images
     FlashImage {
@@ -207,6 +211,10 @@ enum Commands {
         #[arg(short, long, default_value_t = false)]
         check: bool,
 
+        /// Generate `Debug` impls for the firmware register read values
+        #[arg(long, default_value_t = false)]
+        debug_impls: bool,
+
         /// Extra RDL files to parse
         #[arg(short, long)]
         files: Vec<PathBuf>,
@@ -385,6 +393,7 @@ fn main() {
             soc_images,
             mcu_cfg,
             pldm_manifest,
+            manifest,
         } => mcu_builder::all_build(mcu_builder::AllBuildArgs {
             output: output.as_deref(),
             platform: platform.as_deref(),
@@ -397,6 +406,7 @@ fn main() {
             soc_images: soc_images.clone(),
             mcu_cfg: mcu_cfg.clone(),
             pldm_manifest: pldm_manifest.as_deref(),
+            manifest: manifest.as_deref(),
         }),
         Commands::Runtime { .. } => runtime::runtime_run(cli.xtask),
         Commands::RuntimeBuild {
@@ -466,9 +476,10 @@ fn main() {
         Commands::Test => test::test(),
         Commands::RegistersAutogen {
             check,
+            debug_impls,
             files,
             addrmap,
-        } => registers::autogen(*check, files, addrmap),
+        } => registers::autogen(*check, *debug_impls, files, addrmap),
         Commands::Deps => deps::check(),
         #[cfg(feature = "fpga_realtime")]
         Commands::Fpga { subcommand } => fpga::fpga_entry(subcommand),
@@ -36,6 +36,7 @@ static SKIP_TYPES: LazyLock<HashSet<&str>> = LazyLock::new(|| {
 
 pub(crate) fn autogen(
     check: bool,
+    debug_impls: bool,
     extra_files: &[PathBuf],
     extra_addrmap: &[String],
 ) -> Result<()> {
@@ -208,6 +209,7 @@ pub(crate) fn autogen(
 
     generate_fw_registers(
         check,
+        debug_impls,
         root_block.clone(),
         &scopes.clone(),
         header.clone(),
@@ -864,9 +866,11 @@ fn delete_rust_files(dir: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Generate read/write registers used by the firmware.
+/// Generate read/write registers used by the firmware, with `Debug` impls for the register
+/// read values if `debug_impls` is set.
 fn generate_fw_registers(
     check: bool,
+    debug_impls: bool,
     mut root_block: ValidatedRegisterBlock,
     scopes: &[ParentScope],
     header: String,
@@ -954,6 +958,7 @@ fn generate_fw_registers(
             false,
             register_types_to_crates,
             addr_only,
+            debug_impls,
         );
         root_submod_tokens += &format!("pub mod {module_ident};\n");
         file_action(
@@ -967,6 +972,7 @@ fn generate_fw_registers(
         true,
         register_types_to_crates,
         false,
+        debug_impls,
     );
     let recursion = "#![recursion_limit = \"2048\"]\n";
     let root_tokens = root_type_tokens;
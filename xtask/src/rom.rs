@@ -1,7 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use anyhow::Result;
-use mcu_builder::{PROJECT_ROOT, TARGET};
+use mcu_builder::{target_triple, PROJECT_ROOT};
 use std::process::Command;
 
 pub(crate) fn rom_run(trace: bool) -> Result<()> {
@@ -10,7 +10,7 @@ pub(crate) fn rom_run(trace: bool) -> Result<()> {
     mcu_builder::rom_build(platform, "")?;
     let rom_binary = PROJECT_ROOT
         .join("target")
-        .join(TARGET)
+        .join(target_triple())
         .join("release")
         .join(&platform_bin);
     let mut cargo_run_args = vec![
@@ -3,7 +3,7 @@
 use crate::Commands;
 use anyhow::Result;
 use mcu_builder::{rom_build, runtime_build_with_apps_cached, CaliptraBuilder, PROJECT_ROOT};
-use std::{path::PathBuf, process::Command};
+use std::{net::TcpListener, path::PathBuf, process::Command};
 
 /// Run the Runtime Tock kernel image for RISC-V in the emulator.
 pub(crate) fn runtime_run(args: Commands) -> Result<()> {
@@ -164,6 +164,15 @@ pub(crate) fn runtime_run(args: Commands) -> Result<()> {
     if no_stdin {
         cargo_run_args.push("--no-stdin-uart");
     }
+    // Streaming boot requires an I3C port; pick a free one when none was given.
+    let i3c_port = match i3c_port {
+        None if streaming_boot.is_some() => Some(
+            TcpListener::bind("127.0.0.1:0")
+                .and_then(|listener| listener.local_addr())
+                .map(|addr| addr.port())?,
+        ),
+        port => port,
+    };
     let port = format!("{}", i3c_port.unwrap_or(0));
     if i3c_port.is_some() {
         cargo_run_args.extend(["--i3c-port", &port]);
@@ -179,11 +188,6 @@ pub(crate) fn runtime_run(args: Commands) -> Result<()> {
             "--streaming-boot",
             streaming_boot.as_ref().unwrap().to_str().unwrap(),
         ]);
-
-        // Streaming boot requires i3c port to be set
-        if i3c_port.is_none() {
-            cargo_run_args.extend(["--i3c-port", "65534"]);
-        }
     }
     if flash_image.as_ref().is_some() {
         cargo_run_args.extend([
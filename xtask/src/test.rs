@@ -1,7 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use anyhow::{anyhow, bail, Result};
-use mcu_builder::{rom_build, PROJECT_ROOT, TARGET};
+use mcu_builder::{rom_build, target_triple, PROJECT_ROOT};
 use std::process::Command;
 
 use crate::emulator_cbinding;
@@ -36,7 +36,7 @@ fn build_hello_binary() -> Result<()> {
     let status = Command::new("cargo")
         .current_dir(&*PROJECT_ROOT)
         .env("RUSTFLAGS", "-C link-arg=-Ttests/hello/link.ld")
-        .args(["b", "-p", "test-hello", "--target", TARGET])
+        .args(["b", "-p", "test-hello", "--target", target_triple()])
         .status()?;
 
     if !status.success() {
@@ -56,7 +56,7 @@ fn get_emulator_args() -> [String; 10] {
         "--firmware".to_string(),
         "/dev/null".to_string(),
         "--rom".to_string(),
-        format!("target/{}/debug/hello", TARGET),
+        format!("target/{}/debug/hello", target_triple()),
     ]
 }
 
@@ -129,7 +129,7 @@ pub(crate) fn test_panic_missing() -> Result<()> {
     rom_build(None, "")?;
     let rom_elf = PROJECT_ROOT
         .join("target")
-        .join(TARGET)
+        .join(target_triple())
         .join("release")
         .join("mcu-rom-emulator");
     let rom_elf = std::fs::read(rom_elf)?;